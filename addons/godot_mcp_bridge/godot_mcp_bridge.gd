@@ -0,0 +1,81 @@
+@tool
+extends EditorPlugin
+
+# Newline-delimited JSON over TCP; see crates/mcp-server/src/editor_bridge.rs for the message types.
+
+const SETTING_HOST := "godot_mcp/bridge_host"
+const SETTING_PORT := "godot_mcp/bridge_port"
+const RECONNECT_SECONDS := 3.0
+
+var _peer := StreamPeerTCP.new()
+var _was_connected := false
+var _retry_in := 0.0
+
+func _enter_tree() -> void:
+	if not ProjectSettings.has_setting(SETTING_HOST):
+		ProjectSettings.set_setting(SETTING_HOST, "127.0.0.1")
+	if not ProjectSettings.has_setting(SETTING_PORT):
+		ProjectSettings.set_setting(SETTING_PORT, 8091)
+	scene_changed.connect(_on_scene_changed)
+	get_editor_interface().get_selection().selection_changed.connect(_on_selection_changed)
+
+func _exit_tree() -> void:
+	if scene_changed.is_connected(_on_scene_changed):
+		scene_changed.disconnect(_on_scene_changed)
+	var selection := get_editor_interface().get_selection()
+	if selection.selection_changed.is_connected(_on_selection_changed):
+		selection.selection_changed.disconnect(_on_selection_changed)
+	_peer.disconnect_from_host()
+
+func _process(delta: float) -> void:
+	_peer.poll()
+	var status := _peer.get_status()
+	if status == StreamPeerTCP.STATUS_CONNECTED:
+		if not _was_connected:
+			_was_connected = true
+			_send_hello()
+		return
+	_was_connected = false
+	if status == StreamPeerTCP.STATUS_CONNECTING:
+		return
+	_retry_in -= delta
+	if _retry_in <= 0.0:
+		_retry_in = RECONNECT_SECONDS
+		_peer.connect_to_host(ProjectSettings.get_setting(SETTING_HOST), int(ProjectSettings.get_setting(SETTING_PORT)))
+
+## Report a runtime error to the MCP server (callable from other editor tools).
+func send_runtime_error(message: String, file: String = "", line: int = -1) -> void:
+	var msg := {"type": "runtime_error", "message": message}
+	if file != "":
+		msg["file"] = file
+	if line >= 0:
+		msg["line"] = line
+	_send(msg)
+
+func _send_hello() -> void:
+	_send({
+		"type": "hello",
+		"project": ProjectSettings.get_setting("application/config/name"),
+		"godot_version": Engine.get_version_info().get("string", ""),
+	})
+	var root := get_editor_interface().get_edited_scene_root()
+	_on_scene_changed(root)
+	_on_selection_changed()
+
+func _on_scene_changed(root: Node) -> void:
+	var scene = null
+	if root != null and root.scene_file_path != "":
+		scene = root.scene_file_path
+	_send({"type": "scene_changed", "scene": scene})
+
+func _on_selection_changed() -> void:
+	var root := get_editor_interface().get_edited_scene_root()
+	var nodes: Array[String] = []
+	for node in get_editor_interface().get_selection().get_selected_nodes():
+		nodes.append(str(root.get_path_to(node)) if root != null else str(node.name))
+	_send({"type": "selection", "nodes": nodes})
+
+func _send(msg: Dictionary) -> void:
+	if _peer.get_status() != StreamPeerTCP.STATUS_CONNECTED:
+		return
+	_peer.put_data((JSON.stringify(msg) + "\n").to_utf8_buffer())
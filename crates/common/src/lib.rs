@@ -7,12 +7,87 @@
 
 use anyhow::Result;
 use serde::Deserialize;
+use std::{collections::BTreeMap, path::{Path, PathBuf}};
 use tracing::level_filters::LevelFilter;
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Default, Deserialize, Clone)]
 pub struct AppConfig {
     pub server: ServerConfig,
+    /// Additional Godot projects served alongside the workspace root.
+    #[serde(default)]
+    pub projects: Vec<ProjectConfig>,
+    #[serde(default)]
+    pub godot: GodotConfig,
+    #[serde(default)]
+    pub bundle: BundleConfig,
+    #[serde(default)]
+    pub index: IndexConfig,
+}
+
+/// Context bundle sizing and ranking; reloadable at runtime.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct BundleConfig {
+    /// `cap_bytes` used when a request omits it.
+    #[serde(default = "default_bundle_cap_bytes")]
+    pub default_cap_bytes: usize,
+    /// Largest `cap_bytes` a request may ask for.
+    #[serde(default = "default_bundle_max_cap_bytes")]
+    pub max_cap_bytes: usize,
+    /// Recency weight (0..=1) used when a request omits `recency`; 0 disables the boost.
+    #[serde(default)]
+    pub recency_weight: f32,
+    /// Extra synonyms for `expand` requests (`term: [alternatives]`), added to the built-in ones.
+    #[serde(default)]
+    pub synonyms: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for BundleConfig {
+    fn default() -> Self {
+        Self {
+            default_cap_bytes: default_bundle_cap_bytes(),
+            max_cap_bytes: default_bundle_max_cap_bytes(),
+            recency_weight: 0.0,
+            synonyms: BTreeMap::new(),
+        }
+    }
+}
+
+fn default_bundle_cap_bytes() -> usize { 64 * 1024 }
+fn default_bundle_max_cap_bytes() -> usize { 4 * 1024 * 1024 }
+
+/// Indexing rules; reloadable at runtime.
+#[derive(Debug, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct IndexConfig {
+    /// Extra path fragments to skip (e.g. "/exports/"), on top of the built-in rules.
+    #[serde(default)]
+    pub skip: Vec<String>,
+}
+
+/// Godot engine used for headless checks and exports.
+#[derive(Debug, Deserialize, Clone)]
+pub struct GodotConfig {
+    /// Path to the Godot executable; engine-backed routes are disabled when unset.
+    #[serde(default)]
+    pub binary: Option<PathBuf>,
+    /// Kill engine runs that exceed this many seconds.
+    #[serde(default = "default_godot_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for GodotConfig {
+    fn default() -> Self { Self { binary: None, timeout_secs: default_godot_timeout_secs() } }
+}
+
+fn default_godot_timeout_secs() -> u64 { 300 }
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ProjectConfig {
+    pub id: String,
+    pub root: PathBuf,
+    /// Defaults to <root>/.index_data
+    #[serde(default)]
+    pub data_dir: Option<PathBuf>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -21,9 +96,53 @@ pub struct ServerConfig {
     pub port: u16,
     #[serde(default = "default_auto_start_watchers")]
     pub auto_start_watchers: bool,
+    /// Listen on this Unix domain socket instead of host:port.
+    #[serde(default)]
+    pub socket_path: Option<PathBuf>,
+    /// TCP port for the editor plugin bridge (bound on `host`); disabled when unset.
+    #[serde(default)]
+    pub editor_port: Option<u16>,
+    /// TCP port for the gRPC services (bound on `host`); disabled when unset.
+    #[serde(default)]
+    pub grpc_port: Option<u16>,
+    /// http:// URLs notified with the changed paths after each watcher commit.
+    #[serde(default)]
+    pub webhooks: Vec<String>,
+    /// Allow /index/scan and /index/reset; disabled routes answer 403.
+    #[serde(default = "default_allow")]
+    pub allow_scan: bool,
+    /// Allow /index/watch/start and /index/watch/stop.
+    #[serde(default = "default_allow")]
+    pub allow_watch_control: bool,
+    /// Allow routes that write project files (structure-fix apply, autodoc apply, export).
+    #[serde(default = "default_allow")]
+    pub allow_fs_write: bool,
+    /// Allow /config/reload and adding or removing webhooks.
+    #[serde(default = "default_allow")]
+    pub allow_config_write: bool,
+}
+
+/// Matches config/default.yaml: 127.0.0.1:8080, watchers auto-started, every route allowed.
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".into(),
+            port: 8080,
+            auto_start_watchers: default_auto_start_watchers(),
+            socket_path: None,
+            editor_port: None,
+            grpc_port: None,
+            webhooks: Vec::new(),
+            allow_scan: default_allow(),
+            allow_watch_control: default_allow(),
+            allow_fs_write: default_allow(),
+            allow_config_write: default_allow(),
+        }
+    }
 }
 
 fn default_auto_start_watchers() -> bool { true }
+fn default_allow() -> bool { true }
 
 /// Initialize tracing subscriber with env filter.
 pub fn init_logging() {
@@ -37,9 +156,14 @@ pub fn init_logging() {
 
 /// Load configuration from config/{default,local}.yaml with env overrides.
 pub fn load_config() -> Result<AppConfig> {
+    load_config_from(Path::new("config"))
+}
+
+/// Load configuration from `<dir>/{default,local}.yaml` with env overrides.
+pub fn load_config_from(dir: &Path) -> Result<AppConfig> {
     let settings = config::Config::builder()
-        .add_source(config::File::with_name("config/default.yaml").required(false))
-        .add_source(config::File::with_name("config/local.yaml").required(false))
+        .add_source(config::File::from(dir.join("default.yaml")).required(false))
+        .add_source(config::File::from(dir.join("local.yaml")).required(false))
         .add_source(config::Environment::with_prefix("APP").separator("__"))
         .build()?;
     let cfg: AppConfig = settings.try_deserialize()?;
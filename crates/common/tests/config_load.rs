@@ -1,12 +1,12 @@
 // Basic test ensures common crate compiles and config type exists
 #[test]
 fn types_exist() {
-    let _ = godot_mcp_common_types();
+    godot_mcp_common_types();
 }
 
 fn godot_mcp_common_types() {
     use common::{AppConfig, ServerConfig};
-    let _cfg = AppConfig { server: ServerConfig { host: "127.0.0.1".into(), port: 8080, auto_start_watchers: true } };
+    let _cfg = AppConfig { server: ServerConfig { port: 8080, ..Default::default() }, ..Default::default() };
 }
 
 //EOF
\ No newline at end of file
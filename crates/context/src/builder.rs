@@ -0,0 +1,166 @@
+// Incremental bundles: items are loaded and admitted one at a time as the caller pulls them.
+use anyhow::Result;
+use index::SearchReader;
+
+use crate::{
+    boost_recent, chunk_items, chunk_terms, diversify, file_content, load_content, merged_items, query_label, Bundle, BundleCap, BundleItem,
+    BundleOptions, ContentMode, PackQueue, WeightedQuery, DEFAULT_BUNDLE_CAP,
+};
+
+/// Caller-side check on a loaded item; false leaves it out of the bundle.
+type ItemFilter<'a> = Box<dyn FnMut(&BundleItem) -> bool + Send + 'a>;
+
+/// A bundle assembled lazily: ranking happens up front in `new`, while each item's content is
+/// loaded and charged against the cap only when the iterator reaches it. Dropping the builder
+/// early (once a caller has enough context) skips the rest of the work; running it to the end
+/// yields exactly the items `bundle_queries` would.
+pub struct BundleBuilder<'a> {
+    idx: &'a SearchReader,
+    mode: ContentMode,
+    query: String,
+    generation: u64,
+    hits: usize,
+    cap: BundleCap,
+    pending: PackQueue,
+    filter: Option<ItemFilter<'a>>,
+    skipped: Vec<String>,
+    /// Content bytes and token estimate of the items yielded
+    totals: (usize, usize),
+}
+
+impl<'a> BundleBuilder<'a> {
+    /// Search, merge and re-rank the hits for `queries` (and their synonym variants, if
+    /// `opts.synonyms` is set) per `opts`; nothing is loaded yet.
+    pub fn new(idx: &'a SearchReader, queries: &[WeightedQuery], opts: &BundleOptions) -> Result<Self> {
+        let generation = idx.generation()?;
+        let expanded = match &opts.synonyms {
+            Some(synonyms) => synonyms.expand(queries),
+            None => queries.to_vec(),
+        };
+        let ranked = merged_items(idx, &expanded, opts.limit, opts.kind.as_deref(), &opts.exclude)?;
+        let hits = ranked.len();
+        let mut skipped = Vec::new();
+        let ranked = ranked.into_iter()
+            .filter(|it| {
+                let held = opts.known_files.contains(&it.path)
+                    && file_content(idx, &it.path).is_some_and(|full| opts.known_files.unchanged(&it.path, &full));
+                if held {
+                    skipped.push(it.path.clone());
+                }
+                !held
+            })
+            .collect();
+        let mut ranked = diversify(boost_recent(idx, ranked, opts.recency), opts.diversity);
+        if opts.content_mode == ContentMode::Chunks {
+            ranked = chunk_items(idx, ranked, &chunk_terms(&expanded));
+        }
+        Ok(Self {
+            idx,
+            mode: opts.content_mode,
+            query: query_label(queries),
+            generation,
+            hits,
+            cap: BundleCap::with_quotas(opts.cap_bytes.unwrap_or(DEFAULT_BUNDLE_CAP), &opts.quotas),
+            pending: PackQueue::new(ranked),
+            filter: None,
+            skipped,
+            totals: (0, 0),
+        })
+    }
+
+    /// Leave out loaded items for which `keep` is false (e.g. content a client already has);
+    /// they don't use up the cap and are listed by `skipped`. `keep` sees items trimmed and
+    /// truncated as they would be delivered.
+    pub fn with_filter(mut self, keep: impl FnMut(&BundleItem) -> bool + Send + 'a) -> Self {
+        self.filter = Some(Box::new(keep));
+        self
+    }
+
+    /// `query_label` of the queries the bundle is built for.
+    pub fn query(&self) -> &str { &self.query }
+
+    /// `SearchReader::generation` the hits came from.
+    pub fn generation(&self) -> u64 { self.generation }
+
+    /// Search hits before exclusion, re-ranking and chunking.
+    pub fn hits(&self) -> usize { self.hits }
+
+    /// Bytes charged so far for the items yielded.
+    pub fn size_bytes(&self) -> usize { self.cap.size_bytes() }
+
+    /// Content bytes of the items yielded so far.
+    pub fn content_bytes(&self) -> usize { self.totals.0 }
+
+    /// Summed `tokens_estimate` of the items yielded so far.
+    pub fn tokens_estimate(&self) -> usize { self.totals.1 }
+
+    /// Paths left out so far: files `known_files` holds unchanged, then items the filter dropped.
+    pub fn skipped(&self) -> &[String] { &self.skipped }
+
+    /// Take the remaining items and return the whole bundle.
+    pub fn finish(mut self) -> Bundle {
+        let items = self.by_ref().collect();
+        Bundle::new(self.query, items, self.cap.size_bytes(), self.generation)
+    }
+}
+
+impl Iterator for BundleBuilder<'_> {
+    type Item = BundleItem;
+
+    fn next(&mut self) -> Option<BundleItem> {
+        let (idx, mode) = (self.idx, self.mode);
+        let (filter, skipped) = (&mut self.filter, &mut self.skipped);
+        let prepare = |it, room| Some((load_content(idx, it, mode, room), ()));
+        let keep = |it: &BundleItem| {
+            if filter.as_mut().is_some_and(|keep| !keep(it)) {
+                skipped.push(it.path.clone());
+                return false;
+            }
+            true
+        };
+        let (it, ()) = self.pending.next_admitted_where(&mut self.cap, prepare, keep)?;
+        self.totals.0 += it.bytes.unwrap_or_default();
+        self.totals.1 += it.tokens_estimate.unwrap_or_default();
+        Some(it)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use index::{IndexPaths, SearchIndex};
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn yields_what_bundle_queries_packs() -> Result<()> {
+        let tmp = tempdir()?;
+        let root = tmp.path().join("root");
+        fs::create_dir_all(&root)?;
+        for i in 0..6 {
+            fs::write(root.join(format!("f{}.gd", i)), format!("func f{}(): pass # lantern {}\n", i, "lantern ".repeat(i)))?;
+        }
+        let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") })?;
+        idx.scan_and_index(&root)?;
+        let reader = idx.reader();
+        let queries = [WeightedQuery::new("lantern")];
+        let opts = BundleOptions { content_mode: ContentMode::Full, ..Default::default() };
+
+        let whole = BundleBuilder::new(&reader, &queries, &opts)?.finish();
+        assert_eq!(whole, crate::bundle_queries(&reader, &queries, &opts)?);
+        assert_eq!(whole.items.len(), 6);
+
+        // Stopping early leaves the rest unloaded and uncharged
+        let mut builder = BundleBuilder::new(&reader, &queries, &opts)?;
+        let first: Vec<_> = builder.by_ref().take(2).collect();
+        assert_eq!(first, whole.items[..2]);
+        assert!(builder.size_bytes() < whole.size_bytes);
+        assert_eq!(builder.content_bytes(), first.iter().map(|it| it.content.len()).sum::<usize>());
+        assert_eq!(whole.tokens_estimate, whole.items.iter().map(|it| it.tokens_estimate.unwrap()).sum::<usize>());
+
+        let filtered = BundleBuilder::new(&reader, &queries, &opts)?.with_filter(|it| it.path != whole.items[0].path);
+        let skipped_first = filtered.finish();
+        assert_eq!(skipped_first.items, whole.items[1..]);
+        Ok(())
+    }
+}
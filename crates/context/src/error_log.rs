@@ -0,0 +1,224 @@
+// Error-driven bundles: the files a Godot error or stack trace points at, around the lines it names.
+use std::{collections::BTreeSet, fs, path::Path};
+
+use anyhow::Result;
+use regex::Regex;
+
+use crate::{
+    pack, project_file, range_items, scene::root_node_name, truncate_content, Bundle, BundleCap, BundleItem, CHUNK_CONTEXT_LINES,
+    DEFAULT_BUNDLE_CAP, MAX_CHUNKS_PER_FILE,
+};
+
+/// Most files an error bundle pulls in, in order of first mention.
+pub const MAX_ERROR_FILES: usize = 8;
+
+/// A file an error log refers to, with the 1-based lines it names (empty for the whole file).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Referenced {
+    /// Root-relative, `/`-separated
+    path: String,
+    lines: Vec<usize>,
+}
+
+/// Bundle what a Godot runtime error needs to be debugged: every file the log names, in order
+/// of first mention. `res://` paths and `file.gd:42`-style locations (`SCRIPT ERROR ... at:`
+/// lines, `E` lines, `<Stack Trace>` frames) contribute ±`CHUNK_CONTEXT_LINES` around each line
+/// they name, overlaps merged; a file named without a line is packed whole. Node paths
+/// (`"/root/Main/Player"`) pull in the scene whose root node is the first node under `/root`.
+/// Bare file names are resolved under `root` directly, or by name when exactly one file
+/// matches. Files come from disk; missing ones and paths leading outside `root` are left out.
+/// Items carry score 0 and the bundle generation 0, as in `bundle_for_scene`; its `query` is
+/// the log's first line.
+pub fn bundle_for_error(root: &Path, log_text: &str, cap_bytes: Option<usize>) -> Result<Bundle> {
+    let refs = parse_log(root, log_text)?;
+    let mut items = Vec::new();
+    for r in refs {
+        let Some(full) = project_file(root, &r.path).and_then(|p| fs::read_to_string(p).ok()) else { continue };
+        let it = BundleItem {
+            kind: index::detect_kind(Path::new(&r.path)).to_string(),
+            path: format!("./{}", r.path),
+            score: 0,
+            content: String::new(),
+            start_line: None,
+            end_line: None,
+            hash: None,
+            bytes: None,
+            tokens_estimate: None,
+        };
+        if r.lines.is_empty() {
+            items.push(it);
+        } else {
+            items.extend(range_items(&it, &full, line_ranges(&r.lines, full.lines().count())));
+        }
+    }
+
+    let mut cap = BundleCap::new(cap_bytes.unwrap_or(DEFAULT_BUNDLE_CAP));
+    let mut packed = Vec::new();
+    let prepare = |mut it: BundleItem, room| {
+        // Line-range items already carry their lines; whole files are read now
+        if it.start_line.is_none() {
+            let full = fs::read_to_string(project_file(root, &it.path[2..])?).ok()?;
+            let (content, lines) = truncate_content(&full, &it.kind, room);
+            it.content = content;
+            it.start_line = Some(1);
+            it.end_line = Some(lines);
+        }
+        Some((it, ()))
+    };
+    pack(items, &mut cap, prepare, |it, ()| {
+        packed.push(it);
+        true
+    });
+    let query = log_text.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or_default().to_string();
+    Ok(Bundle::new(query, packed, cap.size_bytes(), 0))
+}
+
+/// Files the log refers to, in order of first mention, each with the lines it names.
+fn parse_log(root: &Path, log_text: &str) -> Result<Vec<Referenced>> {
+    let res_path = Regex::new(r#"res://([^\s:()"',]+)(?::(\d+))?"#)?;
+    let bare_path = Regex::new(r"(?:^|[\s(\[])((?:[\w.-]+/)*[\w.-]+\.(?:gd|tscn|tres|gdshader|cs)):(\d+)")?;
+    let node_path = Regex::new(r#""/root/([^"/]+)[^"]*""#)?;
+
+    // (byte offset, path, line) so mentions keep log order across the patterns
+    let mut mentions: Vec<(usize, String, Option<usize>)> = Vec::new();
+    for c in res_path.captures_iter(log_text) {
+        // A log is client text: paths that leave the project are dropped
+        if project_file(root, &c[1]).is_none() {
+            continue;
+        }
+        let line = c.get(2).and_then(|l| l.as_str().parse().ok());
+        mentions.push((c.get(0).map_or(0, |m| m.start()), c[1].to_string(), line));
+    }
+    for c in bare_path.captures_iter(log_text) {
+        let Some(path) = resolve_bare(root, &c[1]) else { continue };
+        mentions.push((c.get(1).map_or(0, |m| m.start()), path, c[2].parse().ok()));
+    }
+    let mut scenes = None;
+    for c in node_path.captures_iter(log_text) {
+        let scenes = scenes.get_or_insert_with(|| scene_roots(root));
+        if let Some((path, _)) = scenes.iter().find(|(_, name)| *name == c[1]) {
+            mentions.push((c.get(0).map_or(0, |m| m.start()), path.clone(), None));
+        }
+    }
+    mentions.sort_by_key(|(at, _, _)| *at);
+
+    let mut refs: Vec<Referenced> = Vec::new();
+    for (_, path, line) in mentions {
+        let i = match refs.iter().position(|r| r.path == path) {
+            Some(i) => i,
+            None if refs.len() < MAX_ERROR_FILES => {
+                refs.push(Referenced { path, lines: Vec::new() });
+                refs.len() - 1
+            }
+            None => continue,
+        };
+        if let Some(line) = line.filter(|&l| l > 0 && !refs[i].lines.contains(&l)) {
+            refs[i].lines.push(line);
+        }
+    }
+    Ok(refs)
+}
+
+/// `name` relative to `root` when it exists there, else the one indexable file with that file name.
+fn resolve_bare(root: &Path, name: &str) -> Option<String> {
+    let name = name.trim_start_matches("./");
+    if project_file(root, name).is_some() {
+        return Some(name.to_string());
+    }
+    let file_name = Path::new(name).file_name()?;
+    let mut found = project_files(root).filter(|p| Path::new(p).file_name() == Some(file_name));
+    let first = found.next()?;
+    found.next().is_none().then_some(first)
+}
+
+/// (root-relative .tscn path, root node name) for every scene under `root`.
+fn scene_roots(root: &Path) -> Vec<(String, String)> {
+    project_files(root)
+        .filter(|p| p.ends_with(".tscn"))
+        .filter_map(|p| {
+            let name = root_node_name(&fs::read_to_string(root.join(&p)).ok()?)?;
+            Some((p, name))
+        })
+        .collect()
+}
+
+/// Root-relative, `/`-separated paths of the files the index would take.
+fn project_files(root: &Path) -> impl Iterator<Item = String> + '_ {
+    index::collect_files(root).into_iter().filter(|p| !index::is_skipped(p)).filter_map(move |p| {
+        let rel = p.strip_prefix(root).ok()?;
+        Some(rel.to_string_lossy().replace('\\', "/"))
+    })
+}
+
+/// 0-based inclusive ranges of ±`CHUNK_CONTEXT_LINES` around `lines` (1-based), merged, in file
+/// order and at most `MAX_CHUNKS_PER_FILE`; lines past the end of the file are ignored.
+fn line_ranges(lines: &[usize], line_count: usize) -> Vec<(usize, usize)> {
+    let wanted: BTreeSet<usize> = lines.iter().filter(|&&l| l <= line_count).map(|l| l - 1).collect();
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for i in wanted {
+        let (start, end) = (i.saturating_sub(CHUNK_CONTEXT_LINES), (i + CHUNK_CONTEXT_LINES).min(line_count - 1));
+        if let Some(prev) = ranges.last_mut().filter(|prev| start <= prev.1 + 1) {
+            prev.1 = end;
+        } else if ranges.len() < MAX_CHUNKS_PER_FILE {
+            ranges.push((start, end));
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const LOG: &str = "\
+SCRIPT ERROR: Invalid get index 'hp' (on base: 'Nil').
+          at: take_hit (res://actors/player.gd:30)
+          at: _on_area_entered (res://actors/player.gd:12)
+E 0:00:02:0512   spikes.gd:4 @ _on_body_entered(): Node not found: \"Sprite\" (relative to \"/root/Level/Spikes\").
+";
+
+    #[test]
+    fn error_pulls_lines_frames_and_scene() -> Result<()> {
+        let tmp = tempdir()?;
+        let root = tmp.path();
+        fs::create_dir_all(root.join("actors"))?;
+        fs::create_dir_all(root.join("traps"))?;
+        let player: String = (1..=40).map(|i| format!("var line_{} = {}\n", i, i)).collect();
+        fs::write(root.join("actors/player.gd"), &player)?;
+        fs::write(root.join("traps/spikes.gd"), "extends Area2D\n\nfunc _on_body_entered(body):\n\t$Sprite.play()\n")?;
+        fs::write(root.join("level.tscn"), "[gd_scene format=3]\n\n[node name=\"Level\" type=\"Node2D\"]\n")?;
+        fs::write(root.join("other.tscn"), "[gd_scene format=3]\n\n[node name=\"Menu\" type=\"Control\"]\n")?;
+
+        let bundle = bundle_for_error(root, LOG, None)?;
+        let got: Vec<_> = bundle.items.iter().map(|it| (it.path.as_str(), it.start_line, it.end_line)).collect();
+        assert_eq!(
+            got,
+            [
+                ("./actors/player.gd", Some(7), Some(17)),
+                ("./actors/player.gd", Some(25), Some(35)),
+                ("./traps/spikes.gd", Some(1), Some(4)),
+                ("./level.tscn", Some(1), Some(3)),
+            ]
+        );
+        assert!(bundle.items[1].content.starts_with("var line_25 = 25\n"));
+        assert_eq!(bundle.query, "SCRIPT ERROR: Invalid get index 'hp' (on base: 'Nil').");
+        Ok(())
+    }
+
+    #[test]
+    fn paths_outside_the_root_are_dropped() -> Result<()> {
+        let tmp = tempdir()?;
+        let root = tmp.path().join("project");
+        fs::create_dir_all(&root)?;
+        fs::write(tmp.path().join("secret.txt"), "token\n")?;
+        fs::write(tmp.path().join("secret.gd"), "var token\n")?;
+        fs::write(root.join("main.gd"), "extends Node\n")?;
+
+        let log = "ERROR: res://../secret.txt:1\n   at: ../secret.gd:1\n   at: res://main.gd:1\n";
+        let bundle = bundle_for_error(&root, log, None)?;
+        let paths: Vec<_> = bundle.items.iter().map(|it| it.path.as_str()).collect();
+        assert_eq!(paths, ["./main.gd"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+// Exclude globs: drop matching paths before they reach the bundle budget.
+use std::str::FromStr;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+
+/// Globs over project-relative paths (`addons/**`, `*.md`); matching items are left out of a bundle.
+/// Deserializes from a list of patterns or, for query strings, a comma-separated string.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(try_from = "GlobSpec")]
+pub struct ExcludeGlobs {
+    patterns: Vec<String>,
+    set: GlobSet,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum GlobSpec {
+    List(Vec<String>),
+    Text(String),
+}
+
+impl TryFrom<GlobSpec> for ExcludeGlobs {
+    type Error = String;
+
+    fn try_from(spec: GlobSpec) -> Result<Self, Self::Error> {
+        match spec {
+            GlobSpec::List(patterns) => patterns.try_into(),
+            GlobSpec::Text(s) => s.parse(),
+        }
+    }
+}
+
+impl TryFrom<Vec<String>> for ExcludeGlobs {
+    type Error = String;
+
+    fn try_from(patterns: Vec<String>) -> Result<Self, Self::Error> {
+        let mut builder = GlobSetBuilder::new();
+        for p in &patterns {
+            builder.add(Glob::new(p).map_err(|e| format!("bad exclude pattern '{}': {}", p, e))?);
+        }
+        let set = builder.build().map_err(|e| e.to_string())?;
+        Ok(Self { patterns, set })
+    }
+}
+
+impl FromStr for ExcludeGlobs {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',').map(str::trim).filter(|p| !p.is_empty()).map(String::from).collect::<Vec<_>>().try_into()
+    }
+}
+
+impl PartialEq for ExcludeGlobs {
+    fn eq(&self, other: &Self) -> bool { self.patterns == other.patterns }
+}
+
+impl ExcludeGlobs {
+    pub fn is_empty(&self) -> bool { self.patterns.is_empty() }
+
+    /// Whether `path` (as stored in the index, e.g. `./addons/x.gd`) matches any pattern.
+    pub fn matches(&self, path: &str) -> bool {
+        self.set.is_match(path.strip_prefix("./").unwrap_or(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_relative_paths() {
+        let globs: ExcludeGlobs = "addons/**, *.md".parse().unwrap();
+        assert!(globs.matches("./addons/gut/gut.gd"));
+        assert!(globs.matches("./docs/guide.md"));
+        assert!(!globs.matches("./scripts/player.gd"));
+        assert!("[".parse::<ExcludeGlobs>().is_err());
+    }
+}
@@ -6,14 +6,53 @@
 // ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
 
 use anyhow::Result;
-use serde::Serialize;
-use std::path::Path;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::{Component, Path, PathBuf}, str::FromStr};
 
-use index::{SearchIndex, IndexPaths};
+use index::{SearchIndex, SearchReader, IndexPaths};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+mod builder;
+mod error_log;
+mod exclude;
+mod manifest;
+mod merge;
+mod pack;
+mod render;
+mod rerank;
+mod scene;
+mod symbol;
+mod synonyms;
+mod template;
+mod truncate;
+
+pub use builder::BundleBuilder;
+pub use error_log::{bundle_for_error, MAX_ERROR_FILES};
+pub use exclude::ExcludeGlobs;
+pub use manifest::{content_hash, BundleManifest, Delta, KnownFile, KnownFiles, ManifestEntry, ManifestIndex};
+pub use merge::{chunk_terms, merged_items, query_label, WeightedQuery};
+pub use pack::{pack, BundleCap, KindQuotas, Offer, PackQueue};
+pub use rerank::{boost_recent, diversify, RECENCY_HALF_LIFE_SECS};
+pub use scene::bundle_for_scene;
+pub use symbol::{bundle_for_symbol, is_symbol_name};
+pub use synonyms::{Synonyms, MAX_EXPANDED_QUERIES, SYNONYM_WEIGHT};
+pub use template::{is_template_name, load_template, render_template, uses_placeholder, PromptVars};
+pub use truncate::{truncate_content, TRUNCATED_MARKER};
+
 /// Max bundle size in bytes; default for v1.
 pub const DEFAULT_BUNDLE_CAP: usize = 64 * 1024; // 64KB
+/// Lines kept on each side of a matching line in `chunks` mode.
+pub const CHUNK_CONTEXT_LINES: usize = 5;
+/// Chunks packed per file in `chunks` mode, earliest first.
+pub const MAX_CHUNKS_PER_FILE: usize = 4;
+/// Rough bytes per LLM token for source and prose, as used by `estimate_tokens`.
+pub const BYTES_PER_TOKEN: usize = 4;
+
+/// Tokenizer-free estimate of how many tokens `text` costs a model: its length over
+/// `BYTES_PER_TOKEN`, rounded up. Good enough for budgeting; not for billing.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.len().div_ceil(BYTES_PER_TOKEN)
+}
 
 #[derive(Debug, Serialize, Clone, PartialEq, Eq)]
 pub struct BundleItem {
@@ -21,6 +60,38 @@ pub struct BundleItem {
     pub kind: String,
     pub score: i32, // quantized score for stable ordering
     pub content: String,
+    /// 1-based inclusive line range of `content`; unset for snippets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    /// Hex xxh3-64 of `content` (see `content_hash`); set once the item is packed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// Byte length of `content`; set once the item is packed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<usize>,
+    /// `estimate_tokens` of `content`; set once the item is packed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens_estimate: Option<usize>,
+}
+
+#[cfg(test)]
+impl BundleItem {
+    /// Unscored, unpacked item without a line range, for unit tests.
+    pub(crate) fn test(path: &str, kind: &str, content: &str) -> Self {
+        Self {
+            path: path.into(),
+            kind: kind.into(),
+            score: 0,
+            content: content.into(),
+            start_line: None,
+            end_line: None,
+            hash: None,
+            bytes: None,
+            tokens_estimate: None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Clone, PartialEq, Eq)]
@@ -28,6 +99,58 @@ pub struct Bundle {
     pub query: String,
     pub items: Vec<BundleItem>,
     pub size_bytes: usize,
+    /// `SearchReader::generation` the bundle was built from
+    pub generation: u64,
+    /// Content bytes over all items (`size_bytes` also charges per-item overhead)
+    pub content_bytes: usize,
+    /// Sum of the items' `tokens_estimate`
+    pub tokens_estimate: usize,
+}
+
+impl Bundle {
+    /// Bundle of packed `items`, with the content totals summed up.
+    pub fn new(query: String, items: Vec<BundleItem>, size_bytes: usize, generation: u64) -> Self {
+        let content_bytes = items.iter().map(|it| it.content.len()).sum();
+        let tokens_estimate = items.iter().map(|it| it.tokens_estimate.unwrap_or_else(|| estimate_tokens(&it.content))).sum();
+        Self { query, items, size_bytes, generation, content_bytes, tokens_estimate }
+    }
+}
+
+/// How much of each matching file a bundle item carries: `snippet` (default), `full`, `head(N)` lines,
+/// or `chunks` (line ranges around the matches, several items per file).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum ContentMode {
+    #[default]
+    Snippet,
+    Full,
+    Head(usize),
+    Chunks,
+}
+
+impl FromStr for ContentMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "snippet" => return Ok(ContentMode::Snippet),
+            "full" => return Ok(ContentMode::Full),
+            "chunks" => return Ok(ContentMode::Chunks),
+            _ => {}
+        }
+        s.strip_prefix("head(")
+            .and_then(|rest| rest.strip_suffix(')'))
+            .and_then(|n| n.parse().ok())
+            .filter(|&n| n > 0)
+            .map(ContentMode::Head)
+            .ok_or_else(|| format!("invalid content_mode '{}', expected snippet, full, head(N) or chunks", s))
+    }
+}
+
+impl TryFrom<String> for ContentMode {
+    type Error = String;
+
+    fn try_from(s: String) -> std::result::Result<Self, Self::Error> { s.parse() }
 }
 
 fn quantize_score(score: f32) -> i32 {
@@ -37,20 +160,195 @@ fn quantize_score(score: f32) -> i32 {
 
 // Note: helper removed to avoid dead_code warning; size is computed inline in bundle_query.
 
+/// Knobs for `bundle_query`; `Default` gives the v1 behaviour (10 hits, 64KB, snippets).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BundleOptions {
+    pub limit: usize,
+    /// Defaults to DEFAULT_BUNDLE_CAP
+    pub cap_bytes: Option<usize>,
+    pub kind: Option<String>,
+    pub content_mode: ContentMode,
+    /// MMR weight in 0..=1 (see `diversify`); 0 keeps pure relevance order
+    pub diversity: f32,
+    /// Recency weight in 0..=1 (see `boost_recent`); 0 leaves mtime to break near-ties only
+    pub recency: f32,
+    /// Per-kind shares of the cap; empty means one shared budget
+    pub quotas: KindQuotas,
+    /// Paths to leave out, matched before packing
+    pub exclude: ExcludeGlobs,
+    /// When set, also search synonym variants of the query terms (see `Synonyms::expand`)
+    pub synonyms: Option<Synonyms>,
+    /// Files the caller already holds; those still unchanged are skipped before packing
+    pub known_files: KnownFiles,
+}
+
+impl Default for BundleOptions {
+    fn default() -> Self {
+        Self {
+            limit: 10,
+            cap_bytes: None,
+            kind: None,
+            content_mode: ContentMode::Snippet,
+            diversity: 0.0,
+            recency: 0.0,
+            quotas: KindQuotas::default(),
+            exclude: ExcludeGlobs::default(),
+            synonyms: None,
+            known_files: KnownFiles::default(),
+        }
+    }
+}
+
 /// Create a bundle for a query using the provided index.
 /// Strategy v1:
-/// - Query top N (limit)
+/// - Query top N (limit), plus synonym variants of the terms when `synonyms` is set
 /// - Sort by quantized score desc, tie-break path asc for determinism
-/// - Cap total size to DEFAULT_BUNDLE_CAP (or provided cap)
-pub fn bundle_query(
-    idx: &SearchIndex,
-    query: &str,
-    limit: usize,
-    cap_bytes: Option<usize>,
-    kind: Option<&str>,
-) -> Result<Bundle> {
-    let cap = cap_bytes.unwrap_or(DEFAULT_BUNDLE_CAP);
-    let hits = idx.query_filtered(query, kind, limit, true)?;
+/// - Drop paths matching `exclude`, and files `known_files` holds unchanged
+/// - Optionally boost recently edited files by `recency`
+/// - Optionally re-rank for diversity (MMR) so near-duplicate files don't crowd out the rest
+/// - Cap total size to DEFAULT_BUNDLE_CAP (or provided cap), split per kind by `quotas` if given
+/// - Replace snippets per `content_mode`, each file cut to the budget left for it
+pub fn bundle_query(idx: &SearchReader, query: &str, opts: &BundleOptions) -> Result<Bundle> {
+    bundle_queries(idx, &[WeightedQuery::new(query)], opts)
+}
+
+/// `bundle_query` over several weighted queries, their hits merged by `merged_items`.
+/// The bundle's `query` is `query_label(queries)`. See `BundleBuilder` to take items one at a time.
+pub fn bundle_queries(idx: &SearchReader, queries: &[WeightedQuery], opts: &BundleOptions) -> Result<Bundle> {
+    Ok(BundleBuilder::new(idx, queries, opts)?.finish())
+}
+
+/// `bundle_query` without the items `old` already holds unchanged (same path, line range and hash),
+/// so a follow-up turn carries only new or changed content. Skipped items don't use up the cap.
+pub fn bundle_diff(idx: &SearchReader, old: &BundleManifest, query: &str, opts: &BundleOptions) -> Result<Bundle> {
+    let known = old.index();
+    Ok(BundleBuilder::new(idx, &[WeightedQuery::new(query)], opts)?.with_filter(|it| known.delta(it).is_some()).finish())
+}
+
+/// Stored copy of an indexed file, falling back to disk.
+fn file_content(idx: &SearchReader, path: &str) -> Option<String> {
+    match idx.stored_content(path) {
+        Ok(Some(c)) => Some(c),
+        _ => fs::read_to_string(idx.absolutize_path(path)).ok(),
+    }
+}
+
+/// Replace an item's snippet per `mode` with the stored copy of the file (falling back to disk),
+/// cut to fit `max_bytes` by `truncate_content`. Items whose file can't be read keep their snippet.
+/// `chunks` items are produced up front by `chunk_items` and pass through unchanged.
+pub fn load_content(idx: &SearchReader, mut it: BundleItem, mode: ContentMode, max_bytes: usize) -> BundleItem {
+    if matches!(mode, ContentMode::Snippet | ContentMode::Chunks) {
+        return it;
+    }
+    let Some(full) = file_content(idx, &it.path) else { return it };
+    let content = match mode {
+        ContentMode::Head(n) => head_lines(&full, n),
+        _ => &full,
+    };
+    let (content, lines) = truncate_content(content, &it.kind, max_bytes);
+    it.content = content;
+    it.start_line = Some(1);
+    it.end_line = Some(lines);
+    it
+}
+
+/// Split each item into line-range chunks of `CHUNK_CONTEXT_LINES` around its query matches,
+/// overlapping ranges merged and at most `MAX_CHUNKS_PER_FILE` per file. Chunks keep the
+/// file's score and stay together in rank order. Items without a readable file or a
+/// matching line are kept as they are.
+pub fn chunk_items(idx: &SearchReader, items: Vec<BundleItem>, query: &str) -> Vec<BundleItem> {
+    let terms: Vec<String> = query.split_whitespace().map(|t| t.to_lowercase()).collect();
+    let mut out = Vec::new();
+    for it in items {
+        let ranges = file_content(idx, &it.path)
+            .map(|full| (match_ranges(&full, |line| {
+                let line = line.to_lowercase();
+                terms.iter().any(|t| line.contains(t.as_str()))
+            }), full))
+            .filter(|(ranges, _)| !ranges.is_empty());
+        let Some((ranges, full)) = ranges else {
+            out.push(it);
+            continue;
+        };
+        out.extend(range_items(&it, &full, ranges));
+    }
+    out
+}
+
+/// One copy of `it` per 0-based inclusive line range of `full`.
+fn range_items(it: &BundleItem, full: &str, ranges: Vec<(usize, usize)>) -> Vec<BundleItem> {
+    let lines: Vec<&str> = full.split_inclusive('\n').collect();
+    ranges
+        .into_iter()
+        .map(|(start, end)| BundleItem {
+            content: lines[start..=end].concat(),
+            start_line: Some(start + 1),
+            end_line: Some(end + 1),
+            ..it.clone()
+        })
+        .collect()
+}
+
+/// 0-based inclusive line ranges around lines for which `is_match` holds.
+fn match_ranges(content: &str, is_match: impl Fn(&str) -> bool) -> Vec<(usize, usize)> {
+    let last = content.lines().count().saturating_sub(1);
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        if !is_match(line) {
+            continue;
+        }
+        let (start, end) = (i.saturating_sub(CHUNK_CONTEXT_LINES), (i + CHUNK_CONTEXT_LINES).min(last));
+        if let Some(prev) = ranges.last_mut().filter(|prev| start <= prev.1 + 1) {
+            prev.1 = end;
+        } else if ranges.len() == MAX_CHUNKS_PER_FILE {
+            break;
+        } else {
+            ranges.push((start, end));
+        }
+    }
+    ranges
+}
+
+/// First `n` lines of `s`, keeping the last line's newline.
+fn head_lines(s: &str, n: usize) -> &str {
+    if n == 0 {
+        return "";
+    }
+    match s.match_indices('\n').nth(n - 1) {
+        Some((i, _)) => &s[..=i],
+        None => s,
+    }
+}
+
+/// The file `rel` (root-relative) names inside `root`, or None when it is absolute, climbs out
+/// with `..`, is missing, or resolves (through symlinks) to somewhere outside the root. Paths
+/// that come from file contents go through this before they are read.
+pub(crate) fn project_file(root: &Path, rel: &str) -> Option<PathBuf> {
+    let rel = Path::new(rel);
+    if rel.components().any(|c| !matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    let root = root.canonicalize().ok()?;
+    let full = root.join(rel).canonicalize().ok()?;
+    (full.starts_with(&root) && full.is_file()).then_some(full)
+}
+
+/// Query, dedupe by file family and order items for bundling (no size cap applied).
+/// Paths matching `exclude` are dropped before the `limit` cut, fetching more hits as needed.
+pub fn ranked_items(idx: &SearchReader, query: &str, limit: usize, kind: Option<&str>, exclude: &ExcludeGlobs) -> Result<Vec<BundleItem>> {
+    let mut fetch = limit;
+    let hits = loop {
+        let mut hits = idx.query_filtered(query, kind, fetch, true)?;
+        let exhausted = hits.len() < fetch;
+        if !exclude.is_empty() {
+            hits.retain(|(_, path, _, _)| !exclude.matches(path));
+        }
+        if exhausted || hits.len() >= limit {
+            hits.truncate(limit);
+            break hits;
+        }
+        fetch *= 2;
+    };
 
     // Map to items, keep snippet as content for brevity
     let items_raw: Vec<BundleItem> = hits
@@ -60,6 +358,11 @@ pub fn bundle_query(
             kind,
             score: quantize_score(score),
             content: snippet.unwrap_or_default(),
+            start_line: None,
+            end_line: None,
+            hash: None,
+            bytes: None,
+            tokens_estimate: None,
         })
         .collect();
 
@@ -118,23 +421,7 @@ pub fn bundle_query(
         score_cmp.then_with(|| a.path.cmp(&b.path))
     });
 
-    // Enforce size cap
-    let mut acc: Vec<BundleItem> = Vec::new();
-    let mut total = 0usize;
-    for (mut it, _t) in items.into_iter() {
-        // Truncate content if single item exceeds cap
-        if it.content.len() > cap {
-            it.content.truncate(cap);
-        }
-        let next = total + it.content.len() + it.path.len() + it.kind.len() + 32;
-        if next > cap {
-            break;
-        }
-        total = next;
-        acc.push(it);
-    }
-
-    Ok(Bundle { query: query.to_string(), items: acc, size_bytes: total })
+    Ok(items.into_iter().map(|(it, _)| it).collect())
 }
 
 /// Convenience: open a temporary index over a root path and bundle a query.
@@ -142,7 +429,7 @@ pub fn bundle_from_root(root: &Path, data_dir: &Path, query: &str, limit: usize,
     let paths = IndexPaths { root: root.to_path_buf(), data_dir: data_dir.to_path_buf() };
     let mut idx = SearchIndex::open(&paths)?;
     let _ = idx.scan_and_index(root)?;
-    let bundle = bundle_query(&idx, query, limit, cap_bytes, None)?;
+    let bundle = bundle_query(&idx.reader(), query, &BundleOptions { limit, cap_bytes, ..Default::default() })?;
     Ok(bundle)
 }
 
@@ -175,6 +462,92 @@ mod tests {
         assert_eq!(bundle.items, sorted);
         Ok(())
     }
+
+    #[test]
+    fn content_mode_parses() {
+        assert_eq!("snippet".parse(), Ok(ContentMode::Snippet));
+        assert_eq!("full".parse(), Ok(ContentMode::Full));
+        assert_eq!("head(20)".parse(), Ok(ContentMode::Head(20)));
+        assert_eq!("chunks".parse(), Ok(ContentMode::Chunks));
+        assert!("head(0)".parse::<ContentMode>().is_err());
+        assert!("head".parse::<ContentMode>().is_err());
+    }
+
+    #[test]
+    fn full_and_head_modes_read_whole_files_under_cap() -> Result<()> {
+        let tmp = tempdir()?;
+        let root = tmp.path().join("root");
+        fs::create_dir_all(&root)?;
+        let body: String = (0..40).map(|i| format!("func f{}(): pass # banana\n", i)).collect();
+        fs::write(root.join("a.gd"), &body)?;
+        let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") })?;
+        idx.scan_and_index(&root)?;
+        let reader = idx.reader();
+
+        let full = bundle_query(&reader, "banana", &BundleOptions { content_mode: ContentMode::Full, ..Default::default() })?;
+        assert_eq!(full.items[0].content, body);
+        assert_eq!((full.items[0].start_line, full.items[0].end_line), (Some(1), Some(40)));
+
+        let head = bundle_query(&reader, "banana", &BundleOptions { content_mode: ContentMode::Head(3), ..Default::default() })?;
+        assert_eq!(head.items[0].content.lines().count(), 3);
+
+        // Over the cap the file is cut at a line boundary instead of being dropped, and marked
+        let capped = bundle_query(&reader, "banana", &BundleOptions { cap_bytes: Some(300), content_mode: ContentMode::Full, ..Default::default() })?;
+        assert!(capped.size_bytes <= 300);
+        let kept = capped.items[0].content.strip_suffix("[truncated]\n").expect("truncation marker");
+        assert!(body.starts_with(kept) && kept.ends_with('\n'));
+        assert_eq!(capped.items[0].end_line, Some(kept.lines().count()));
+        Ok(())
+    }
+
+    #[test]
+    fn diff_against_a_truncated_chunk_sends_nothing_new() -> Result<()> {
+        let tmp = tempdir()?;
+        let root = tmp.path().join("root");
+        fs::create_dir_all(&root)?;
+        let body: String = (0..40).map(|i| format!("func f{}(): pass # banana\n", i)).collect();
+        fs::write(root.join("a.gd"), &body)?;
+        let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") })?;
+        idx.scan_and_index(&root)?;
+        let reader = idx.reader();
+
+        // Every line matches, so the file is one chunk, cut to its kind's share when packed
+        let opts = BundleOptions {
+            cap_bytes: Some(300), content_mode: ContentMode::Chunks, quotas: "gdscript:60".parse().unwrap(), ..Default::default()
+        };
+        let capped = bundle_query(&reader, "banana", &opts)?;
+        assert!(capped.items[0].content.ends_with("[truncated]\n"));
+        let again = bundle_diff(&reader, &capped.manifest(), "banana", &opts)?;
+        assert!(again.items.is_empty(), "{:?}", again.items);
+
+        // A bigger cap delivers more of the file, which the client doesn't have yet
+        let wider = bundle_diff(&reader, &capped.manifest(), "banana", &BundleOptions { cap_bytes: Some(600), ..opts })?;
+        assert_eq!(wider.items.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn chunks_cover_match_neighbourhoods() -> Result<()> {
+        let tmp = tempdir()?;
+        let root = tmp.path().join("root");
+        fs::create_dir_all(&root)?;
+        let mut lines: Vec<String> = (1..=60).map(|i| format!("var line_{} = {}", i, i)).collect();
+        lines[9] = "var kiwi = 1".into(); // line 10
+        lines[12] = "var kiwi_two = 2".into(); // line 13, merges with line 10
+        lines[49] = "var KIWI = 3".into(); // line 50
+        fs::write(root.join("a.gd"), lines.join("\n"))?;
+        let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") })?;
+        idx.scan_and_index(&root)?;
+
+        let bundle = bundle_query(&idx.reader(), "kiwi", &BundleOptions { content_mode: ContentMode::Chunks, ..Default::default() })?;
+        let ranges: Vec<_> = bundle.items.iter().map(|it| (it.start_line, it.end_line)).collect();
+        assert_eq!(ranges, vec![(Some(5), Some(18)), (Some(45), Some(55))]);
+        assert!(bundle.items.iter().all(|it| it.path == "./a.gd"));
+        assert!(bundle.items[0].content.starts_with("var line_5 = 5\n"));
+        assert!(bundle.items[1].content.contains("var KIWI = 3\n"));
+        assert_eq!(bundle.items[1].content.lines().count(), 11);
+        Ok(())
+    }
 }
 
 //EOF
@@ -0,0 +1,150 @@
+// Bundle manifests: what a client already holds, so a follow-up bundle can send only what's new.
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::{Bundle, BundleItem};
+
+/// Hex xxh3-64 of an item's content, as recorded in manifests.
+pub fn content_hash(content: &str) -> String {
+    format!("{:x}", xxh3_64(content.as_bytes()))
+}
+
+/// One delivered item: its path, line range (chunks, full and head items) and content hash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    pub hash: String,
+}
+
+/// Items of a previously delivered bundle.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub items: Vec<ManifestEntry>,
+    /// Index generation the bundle was built from, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generation: Option<u64>,
+}
+
+/// Whether an item is missing from a manifest or differs from the delivered copy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Delta {
+    New,
+    Changed,
+}
+
+type EntryKey = (String, Option<usize>, Option<usize>);
+
+impl BundleManifest {
+    /// Item lookup by path and line range; see `delta`.
+    pub fn index(&self) -> ManifestIndex {
+        let known = self.items.iter().map(|e| ((e.path.clone(), e.start_line, e.end_line), e.hash.clone())).collect();
+        ManifestIndex { known }
+    }
+}
+
+impl Bundle {
+    pub fn manifest(&self) -> BundleManifest {
+        let items = self.items.iter()
+            .map(|it| ManifestEntry {
+                path: it.path.clone(),
+                start_line: it.start_line,
+                end_line: it.end_line,
+                hash: it.hash.clone().unwrap_or_else(|| content_hash(&it.content)),
+            })
+            .collect();
+        BundleManifest { items, generation: Some(self.generation) }
+    }
+}
+
+/// A manifest keyed for lookups while a bundle is packed.
+#[derive(Debug, Clone, Default)]
+pub struct ManifestIndex {
+    known: BTreeMap<EntryKey, String>,
+}
+
+impl ManifestIndex {
+    /// `None` when the manifest already has `it` with the same content.
+    pub fn delta(&self, it: &BundleItem) -> Option<Delta> {
+        match self.known.get(&(it.path.clone(), it.start_line, it.end_line)) {
+            None => Some(Delta::New),
+            Some(h) if *h == content_hash(&it.content) => None,
+            Some(_) => Some(Delta::Changed),
+        }
+    }
+}
+
+/// A whole file a client already holds, e.g. from earlier turns of a conversation: its path
+/// (`res://`, `./` or root-relative) and the `content_hash` of the file's full content.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KnownFile {
+    pub path: String,
+    pub hash: String,
+}
+
+/// Files a client already holds, keyed by index path (`./a/b.gd`). Unlike a manifest, entries
+/// cover the whole file, whatever parts of it earlier bundles carried.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(from = "Vec<KnownFile>")]
+pub struct KnownFiles(BTreeMap<String, String>);
+
+impl From<Vec<KnownFile>> for KnownFiles {
+    fn from(files: Vec<KnownFile>) -> Self {
+        Self(files.into_iter().map(|f| (index_path(&f.path), f.hash)).collect())
+    }
+}
+
+impl KnownFiles {
+    pub fn is_empty(&self) -> bool { self.0.is_empty() }
+
+    /// Whether the client has some copy of the file at `path` (an index path).
+    pub fn contains(&self, path: &str) -> bool { self.0.contains_key(path) }
+
+    /// Whether the client's copy of `path` matches `content`, the file as it is now.
+    pub fn unchanged(&self, path: &str, content: &str) -> bool {
+        self.0.get(path).is_some_and(|h| *h == content_hash(content))
+    }
+}
+
+/// `res://a.gd`, `a.gd` and `./a.gd` all become `./a.gd`.
+fn index_path(p: &str) -> String {
+    let p = p.strip_prefix("res://").unwrap_or(p).replace('\\', "/");
+    if p.starts_with("./") { p } else { format!("./{}", p) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delta_compares_hashes() {
+        let items = vec![BundleItem::test("./a.gd", "gdscript", "one"), BundleItem::test("./b.gd", "gdscript", "two")];
+        let bundle = Bundle::new("q".into(), items, 0, 0);
+        let known = bundle.manifest().index();
+        assert_eq!(known.delta(&BundleItem::test("./a.gd", "gdscript", "one")), None);
+        assert_eq!(known.delta(&BundleItem::test("./b.gd", "gdscript", "two!")), Some(Delta::Changed));
+        assert_eq!(known.delta(&BundleItem::test("./c.gd", "gdscript", "three")), Some(Delta::New));
+        // A chunk of a known file is a different item
+        let chunk = BundleItem { start_line: Some(1), end_line: Some(1), ..BundleItem::test("./a.gd", "gdscript", "one") };
+        assert_eq!(known.delta(&chunk), Some(Delta::New));
+    }
+
+    #[test]
+    fn known_files_match_whole_files_by_index_path() {
+        let files: KnownFiles = serde_json::from_str(&format!(
+            r#"[{{"path": "res://a.gd", "hash": "{}"}}, {{"path": "b.gd", "hash": "stale"}}]"#,
+            content_hash("one")
+        ))
+        .unwrap();
+        assert!(files.unchanged("./a.gd", "one"));
+        assert!(!files.unchanged("./a.gd", "one!"));
+        assert!(files.contains("./b.gd") && !files.unchanged("./b.gd", "two"));
+        assert!(!files.contains("./c.gd"));
+    }
+}
@@ -0,0 +1,84 @@
+// Multi-query bundles: run several weighted queries and merge their hits into one ranking.
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use index::SearchReader;
+use serde::Deserialize;
+
+use crate::{ranked_items, BundleItem, ExcludeGlobs};
+
+/// One query of a merged bundle; `weight` scales its scores. Deserializes from
+/// `{"q": "...", "weight": 2}` or a bare string (weight 1).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(from = "QuerySpec")]
+pub struct WeightedQuery {
+    pub q: String,
+    pub weight: f32,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum QuerySpec {
+    Text(String),
+    Weighted { q: String, weight: Option<f32> },
+}
+
+impl From<QuerySpec> for WeightedQuery {
+    fn from(spec: QuerySpec) -> Self {
+        match spec {
+            QuerySpec::Text(q) => WeightedQuery::new(q),
+            QuerySpec::Weighted { q, weight } => WeightedQuery { q, weight: weight.unwrap_or(1.0) },
+        }
+    }
+}
+
+impl WeightedQuery {
+    pub fn new(q: impl Into<String>) -> Self { Self { q: q.into(), weight: 1.0 } }
+}
+
+/// `Bundle::query` for a merged bundle: the queries joined with ` | `.
+pub fn query_label(queries: &[WeightedQuery]) -> String {
+    queries.iter().map(|wq| wq.q.as_str()).collect::<Vec<_>>().join(" | ")
+}
+
+/// Every query's terms, for matching lines in `chunk_items`.
+pub fn chunk_terms(queries: &[WeightedQuery]) -> String {
+    queries.iter().map(|wq| wq.q.as_str()).collect::<Vec<_>>().join(" ")
+}
+
+/// Top `limit` hits over all `queries`. A file found by several queries appears once, scored
+/// by the sum of its weighted scores and carrying the snippet of its best single match;
+/// ties break on path. A single query keeps `ranked_items` order as is. Paths matching
+/// `exclude` never take a place in the top `limit`.
+pub fn merged_items(idx: &SearchReader, queries: &[WeightedQuery], limit: usize, kind: Option<&str>, exclude: &ExcludeGlobs) -> Result<Vec<BundleItem>> {
+    if let [only] = queries {
+        return ranked_items(idx, &only.q, limit, kind, exclude);
+    }
+    // path -> (summed score, best weighted score, item carrying the best snippet)
+    let mut merged: BTreeMap<String, (f32, f32, BundleItem)> = BTreeMap::new();
+    for wq in queries {
+        for it in ranked_items(idx, &wq.q, limit, kind, exclude)? {
+            let score = it.score as f32 * wq.weight;
+            match merged.get_mut(&it.path) {
+                Some((sum, best, kept)) => {
+                    *sum += score;
+                    if score > *best {
+                        *best = score;
+                        *kept = it;
+                    }
+                }
+                None => { merged.insert(it.path.clone(), (score, score, it)); }
+            }
+        }
+    }
+    let mut items: Vec<BundleItem> = merged
+        .into_values()
+        .map(|(sum, _, mut it)| {
+            it.score = sum.round() as i32;
+            it
+        })
+        .collect();
+    items.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+    items.truncate(limit);
+    Ok(items)
+}
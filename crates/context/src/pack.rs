@@ -0,0 +1,423 @@
+// Byte budgeting: the running cap, optional per-kind shares and the packing loop that drives them.
+use std::{collections::{BTreeMap, HashMap, HashSet, VecDeque}, str::FromStr};
+
+use serde::Deserialize;
+
+use crate::{content_hash, estimate_tokens, truncate_content, BundleItem};
+
+/// Pool for kinds that have no share of their own.
+const OTHER_KINDS: &str = "*";
+
+/// Split of the bundle cap across kinds in percent, e.g. `{"gdscript": 60, "godot": 25, "docs": 15}`
+/// or, as a string, `gdscript:60,godot:25,docs:15`.
+/// Unlisted kinds share whatever percentage is left. Shares hold for a first pass over the
+/// ranked items; budget they leave unused then goes to the passed-over items in rank order.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(try_from = "QuotaSpec")]
+pub struct KindQuotas(BTreeMap<String, f32>);
+
+/// Accepted encodings: a JSON map, or a string for query strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum QuotaSpec {
+    Shares(BTreeMap<String, f32>),
+    Text(String),
+}
+
+impl TryFrom<QuotaSpec> for KindQuotas {
+    type Error = String;
+
+    fn try_from(spec: QuotaSpec) -> Result<Self, Self::Error> {
+        match spec {
+            QuotaSpec::Shares(shares) => shares.try_into(),
+            QuotaSpec::Text(s) => s.parse(),
+        }
+    }
+}
+
+impl FromStr for KindQuotas {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut shares = BTreeMap::new();
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let parsed = part.split_once(':').and_then(|(kind, pct)| {
+                let pct = pct.trim();
+                Some((kind.trim(), pct.strip_suffix('%').unwrap_or(pct).trim().parse::<f32>().ok()?))
+            });
+            match parsed {
+                Some((kind, pct)) if !kind.is_empty() => { shares.insert(kind.to_string(), pct); }
+                _ => return Err(format!("invalid quota '{}', expected kind:percent", part)),
+            }
+        }
+        shares.try_into()
+    }
+}
+
+impl TryFrom<BTreeMap<String, f32>> for KindQuotas {
+    type Error = String;
+
+    fn try_from(shares: BTreeMap<String, f32>) -> Result<Self, Self::Error> {
+        if let Some((kind, pct)) = shares.iter().find(|(_, pct)| !(0.0..=100.0).contains(*pct)) {
+            return Err(format!("quota for {} must be between 0 and 100 (got {})", kind, pct));
+        }
+        let total: f32 = shares.values().sum();
+        if total > 100.0 {
+            return Err(format!("quotas add up to {}%, more than 100%", total));
+        }
+        Ok(Self(shares))
+    }
+}
+
+impl KindQuotas {
+    pub fn is_empty(&self) -> bool { self.0.is_empty() }
+
+    fn pool<'a>(&self, kind: &'a str) -> &'a str {
+        if self.0.contains_key(kind) { kind } else { OTHER_KINDS }
+    }
+
+    /// Byte limit per pool for a bundle of `cap` bytes.
+    fn limits(&self, cap: usize) -> BTreeMap<String, usize> {
+        let bytes = |pct: f32| (cap as f64 * pct as f64 / 100.0) as usize;
+        let mut limits: BTreeMap<String, usize> = self.0.iter().map(|(k, &pct)| (k.clone(), bytes(pct))).collect();
+        limits.insert(OTHER_KINDS.to_string(), bytes(100.0 - self.0.values().sum::<f32>()));
+        limits
+    }
+}
+
+/// Outcome of offering an item to a `BundleCap`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Offer {
+    Admitted(BundleItem),
+    /// Fits the cap but not its kind's share; offer it again after `release_quotas`
+    OverQuota(BundleItem),
+    /// The cap is reached; stop offering
+    Full,
+    /// Repeats content already admitted (same hash, or lines of the same file); skip it
+    Duplicate,
+}
+
+/// Running size budget for a bundle. Items are admitted in order until the cap is hit,
+/// so callers can emit them one at a time (e.g. streaming) with the same result as `bundle_query`.
+#[derive(Debug, Clone)]
+pub struct BundleCap {
+    cap: usize,
+    total: usize,
+    /// Per-pool limits and usage while quotas apply
+    shares: Option<(KindQuotas, Pools)>,
+    /// Hashes of admitted content
+    hashes: HashSet<String>,
+    /// Path -> admitted 1-based inclusive line ranges
+    ranges: HashMap<String, Vec<(usize, usize)>>,
+}
+
+/// Pool -> (limit, used) bytes.
+type Pools = BTreeMap<String, (usize, usize)>;
+
+impl BundleCap {
+    pub fn new(cap: usize) -> Self { Self::with_quotas(cap, &KindQuotas::default()) }
+
+    /// Cap split per kind by `quotas`; empty quotas behave like `new`.
+    pub fn with_quotas(cap: usize, quotas: &KindQuotas) -> Self {
+        let shares = (!quotas.is_empty())
+            .then(|| (quotas.clone(), quotas.limits(cap).into_iter().map(|(k, limit)| (k, (limit, 0))).collect()));
+        Self { cap, total: 0, shares, hashes: HashSet::new(), ranges: HashMap::new() }
+    }
+
+    /// Offer the next item, truncating content larger than the cap or, while quotas apply,
+    /// than its kind's share (see `truncate_content`).
+    /// Lines an admitted item of the same file already covers are cut from either end of the
+    /// item, and an item left with nothing new, or whose content matches an admitted item's
+    /// byte for byte, is a `Duplicate` and costs nothing.
+    pub fn offer(&mut self, it: BundleItem) -> Offer {
+        let Some(mut it) = self.shape(it) else { return Offer::Duplicate };
+        let size = it.content.len() + overhead(&it);
+        if self.total + size > self.cap {
+            return Offer::Full;
+        }
+        if let Some((quotas, pools)) = &mut self.shares {
+            let (limit, used) = pools.get_mut(quotas.pool(&it.kind)).expect("every pool has a limit");
+            if *used + size > *limit {
+                return Offer::OverQuota(it);
+            }
+            *used += size;
+        }
+        self.total += size;
+        if let (Some(start), Some(end)) = (it.start_line, it.end_line) {
+            self.ranges.entry(it.path.clone()).or_default().push((start, end));
+        }
+        let hash = content_hash(&it.content);
+        self.hashes.insert(hash.clone());
+        it.hash = Some(hash);
+        it.bytes = Some(it.content.len());
+        it.tokens_estimate = Some(estimate_tokens(&it.content));
+        Offer::Admitted(it)
+    }
+
+    /// `it` as `offer` would admit it, overlap trimmed and content truncated, without charging
+    /// anything; `None` for a duplicate.
+    pub fn shape(&self, it: BundleItem) -> Option<BundleItem> {
+        let mut it = self.trim_overlap(it)?;
+        if self.hashes.contains(&content_hash(&it.content)) {
+            return None;
+        }
+        let max = self.max_content(&it);
+        if it.content.len() > max {
+            let (content, lines) = truncate_content(&it.content, &it.kind, max);
+            it.content = content;
+            it.end_line = it.start_line.map(|start| start + lines.max(1) - 1);
+        }
+        Some(it)
+    }
+
+    /// `it` without the leading and trailing lines admitted items of its file already cover;
+    /// `None` when they cover all of it. An admitted range strictly inside `it` is left in place.
+    fn trim_overlap(&self, mut it: BundleItem) -> Option<BundleItem> {
+        let (Some(first), Some(last)) = (it.start_line, it.end_line) else { return Some(it) };
+        let Some(ranges) = self.ranges.get(&it.path) else { return Some(it) };
+        let mut sorted = ranges.clone();
+        sorted.sort_unstable();
+        let (mut start, mut end) = (first, last);
+        for (s, e) in sorted {
+            if e < start || s > end {
+                continue;
+            }
+            if s <= start && e >= end {
+                return None;
+            }
+            if s <= start {
+                start = e + 1;
+            } else if e >= end {
+                end = s - 1;
+            }
+        }
+        if (start, end) != (first, last) {
+            let lines: Vec<&str> = it.content.split_inclusive('\n').collect();
+            let from = (start - first).min(lines.len());
+            // Keep anything past the range (a truncation marker) when the end stays put
+            let to = if end == last { lines.len() } else { (end - first + 1).min(lines.len()) };
+            it.content = lines[from..to].concat();
+            it.start_line = Some(start);
+            it.end_line = Some(end);
+        }
+        Some(it)
+    }
+
+    /// Most content bytes `it` can carry: the cap, or its pool's whole limit less the item's
+    /// overhead, so an item bigger than its share is cut to fit rather than turned away.
+    fn max_content(&self, it: &BundleItem) -> usize {
+        match &self.shares {
+            Some((quotas, pools)) => self.cap.min(pools[quotas.pool(&it.kind)].0.saturating_sub(overhead(it))),
+            None => self.cap,
+        }
+    }
+
+    /// Admit the next item, truncating content larger than the cap (see `truncate_content`).
+    /// Returns `None` for a duplicate or once the item no longer fits (stop at that point).
+    /// Only meaningful without quotas; use `offer` (or `pack`) otherwise.
+    pub fn admit(&mut self, it: BundleItem) -> Option<BundleItem> {
+        match self.offer(it) {
+            Offer::Admitted(it) => Some(it),
+            _ => None,
+        }
+    }
+
+    /// Content bytes `it` could still carry and be admitted.
+    pub fn remaining(&self, it: &BundleItem) -> usize {
+        let mut room = self.cap.saturating_sub(self.total);
+        if let Some((quotas, pools)) = &self.shares {
+            let (limit, used) = pools[quotas.pool(&it.kind)];
+            room = room.min(limit.saturating_sub(used));
+        }
+        room.saturating_sub(overhead(it))
+    }
+
+    /// Drop the per-kind shares so the rest of the cap is open to every kind.
+    /// Returns whether any were in force.
+    pub fn release_quotas(&mut self) -> bool {
+        self.shares.take().is_some()
+    }
+
+    pub fn size_bytes(&self) -> usize { self.total }
+}
+
+/// Per-item size charged on top of its content.
+fn overhead(it: &BundleItem) -> usize {
+    it.path.len() + it.kind.len() + 32
+}
+
+/// Ranked items still to be offered to a `BundleCap`, taken one admitted item at a time so
+/// callers can stop whenever they have enough. Items over their kind's quota are offered
+/// again, in rank order, once the first pass is through.
+#[derive(Debug, Clone, Default)]
+pub struct PackQueue {
+    queue: VecDeque<BundleItem>,
+    deferred: Vec<BundleItem>,
+    full: bool,
+}
+
+impl PackQueue {
+    pub fn new(items: Vec<BundleItem>) -> Self {
+        Self { queue: items.into(), deferred: Vec::new(), full: false }
+    }
+
+    /// Offer items to `cap` until one is admitted. `prepare` readies each item for the room it
+    /// has (e.g. `load_content`) or drops it by returning `None`, attaching any per-item data.
+    /// `None` once the queue is exhausted or the cap is full.
+    pub fn next_admitted<T>(
+        &mut self,
+        cap: &mut BundleCap,
+        prepare: impl FnMut(BundleItem, usize) -> Option<(BundleItem, T)>,
+    ) -> Option<(BundleItem, T)> {
+        self.next_admitted_where(cap, prepare, |_| true)
+    }
+
+    /// `next_admitted`, also dropping items `keep` rejects. `keep` sees each item as the cap
+    /// would admit it (`BundleCap::shape`), so it judges the content actually delivered, and a
+    /// dropped item costs nothing.
+    pub fn next_admitted_where<T>(
+        &mut self,
+        cap: &mut BundleCap,
+        mut prepare: impl FnMut(BundleItem, usize) -> Option<(BundleItem, T)>,
+        mut keep: impl FnMut(&BundleItem) -> bool,
+    ) -> Option<(BundleItem, T)> {
+        while !self.full {
+            let Some(it) = self.queue.pop_front() else {
+                if self.deferred.is_empty() || !cap.release_quotas() {
+                    return None;
+                }
+                self.queue.extend(self.deferred.drain(..));
+                continue;
+            };
+            let room = cap.remaining(&it);
+            let Some((it, extra)) = prepare(it, room) else { continue };
+            let Some(it) = cap.shape(it) else { continue };
+            if !keep(&it) {
+                continue;
+            }
+            match cap.offer(it) {
+                Offer::Admitted(it) => return Some((it, extra)),
+                Offer::OverQuota(it) => self.deferred.push(it),
+                Offer::Full => self.full = true,
+                Offer::Duplicate => {}
+            }
+        }
+        None
+    }
+}
+
+/// Offer ranked `items` to `cap` in order (see `PackQueue`); `emit` gets admitted items and
+/// returns false to stop early.
+pub fn pack<T>(
+    items: Vec<BundleItem>,
+    cap: &mut BundleCap,
+    mut prepare: impl FnMut(BundleItem, usize) -> Option<(BundleItem, T)>,
+    mut emit: impl FnMut(BundleItem, T) -> bool,
+) {
+    let mut queue = PackQueue::new(items);
+    while let Some((it, extra)) = queue.next_admitted(cap, &mut prepare) {
+        if !emit(it, extra) {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(path: &str, kind: &str, len: usize) -> BundleItem {
+        BundleItem::test(path, kind, &format!("{:<1$}", path, len))
+    }
+
+    fn lines(path: &str, start: usize, end: usize) -> BundleItem {
+        let content = (start..=end).map(|n| format!("line {}\n", n)).collect();
+        BundleItem { start_line: Some(start), end_line: Some(end), content, ..item(path, "gdscript", 0) }
+    }
+
+    fn quotas(pairs: &[(&str, f32)]) -> KindQuotas {
+        pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect::<BTreeMap<_, _>>().try_into().unwrap()
+    }
+
+    fn packed(items: Vec<BundleItem>, cap: &mut BundleCap) -> Vec<String> {
+        let mut out = Vec::new();
+        pack(items, cap, |it, _| Some((it, ())), |it, ()| { out.push(it.path); true });
+        out
+    }
+
+    #[test]
+    fn quotas_reject_bad_shares() {
+        let over: BTreeMap<String, f32> = [("gdscript".to_string(), 80.0), ("docs".to_string(), 30.0)].into();
+        assert!(KindQuotas::try_from(over).is_err());
+        let negative: BTreeMap<String, f32> = [("docs".to_string(), -1.0)].into();
+        assert!(KindQuotas::try_from(negative).is_err());
+    }
+
+    #[test]
+    fn quotas_parse_from_string() {
+        let parsed: KindQuotas = "gdscript:60%, godot:25,docs:15".parse().unwrap();
+        assert_eq!(parsed, quotas(&[("gdscript", 60.0), ("godot", 25.0), ("docs", 15.0)]));
+        assert!("gdscript".parse::<KindQuotas>().is_err());
+        assert!("gdscript:lots".parse::<KindQuotas>().is_err());
+    }
+
+    #[test]
+    fn shares_mix_kinds_then_leftovers_fill() {
+        // Each item costs 100 + overhead (~44); the cap fits six
+        let items = vec![
+            item("./a.gd", "gdscript", 100),
+            item("./b.gd", "gdscript", 100),
+            item("./c.gd", "gdscript", 100),
+            item("./d.gd", "gdscript", 100),
+            item("./e.gd", "gdscript", 100),
+            item("./s.tscn", "godot", 100),
+            item("./r.md", "docs", 100),
+        ];
+        let mut plain = BundleCap::new(900);
+        assert_eq!(packed(items.clone(), &mut plain), ["./a.gd", "./b.gd", "./c.gd", "./d.gd", "./e.gd", "./s.tscn"]);
+
+        let mut split = BundleCap::with_quotas(900, &quotas(&[("gdscript", 50.0), ("godot", 25.0), ("docs", 25.0)]));
+        // First pass: three scripts, the scene and the doc; the freed space then takes one more script
+        assert_eq!(packed(items, &mut split), ["./a.gd", "./b.gd", "./c.gd", "./s.tscn", "./r.md", "./d.gd"]);
+        assert!(split.size_bytes() <= 900);
+    }
+
+    #[test]
+    fn repeated_content_is_skipped_or_trimmed() {
+        let mut cap = BundleCap::new(10_000);
+        assert!(matches!(cap.offer(lines("./a.gd", 1, 10)), Offer::Admitted(_)));
+        assert_eq!(cap.offer(lines("./a.gd", 3, 8)), Offer::Duplicate);
+        // Same text under another path is still a repeat
+        assert_eq!(cap.offer(BundleItem { path: "./copy.gd".into(), ..lines("./a.gd", 1, 10) }), Offer::Duplicate);
+        let size = cap.size_bytes();
+
+        let Offer::Admitted(tail) = cap.offer(lines("./a.gd", 8, 14)) else { panic!("overlap should be trimmed, not dropped") };
+        assert_eq!((tail.start_line, tail.end_line), (Some(11), Some(14)));
+        assert_eq!(tail.content, "line 11\nline 12\nline 13\nline 14\n");
+        let Offer::Admitted(head) = cap.offer(lines("./b.gd", 1, 3)) else { panic!("other files are unaffected") };
+        assert_eq!(head.end_line, Some(3));
+        assert!(cap.size_bytes() > size);
+    }
+
+    #[test]
+    fn unlisted_kinds_share_the_rest() {
+        let mut cap = BundleCap::with_quotas(1000, &quotas(&[("gdscript", 80.0)]));
+        let items = vec![item("./x.json", "config", 100), item("./y.md", "docs", 100), item("./a.gd", "gdscript", 100)];
+        // Config and docs share the remaining 20%: only the first fits before the leftover pass
+        let mut first_pass = Vec::new();
+        for it in items {
+            if let Offer::Admitted(it) = cap.offer(it) {
+                first_pass.push(it.path);
+            }
+        }
+        assert_eq!(first_pass, ["./x.json", "./a.gd"]);
+    }
+
+    #[test]
+    fn content_over_its_share_is_cut_to_fit() {
+        let mut cap = BundleCap::with_quotas(400, &quotas(&[("docs", 50.0)]));
+        let Offer::Admitted(doc) = cap.offer(item("./guide.md", "docs", 300)) else { panic!("doc not admitted") };
+        assert!(doc.content.len() < 300);
+        assert!(cap.size_bytes() <= 200);
+    }
+}
@@ -0,0 +1,141 @@
+// Prompt-ready renderings of a bundle.
+use std::path::Path;
+
+use crate::{Bundle, BundleItem};
+
+/// Fenced-code language for an item, from its kind (config files by extension).
+fn language_hint(kind: &str, path: &str) -> &'static str {
+    match kind {
+        "gdscript" => "gdscript",
+        "rust" => "rust",
+        "docs" => "markdown",
+        // Scenes and resources are INI-like
+        "godot" => "ini",
+        "config" => match Path::new(path).extension().and_then(|e| e.to_str()) {
+            Some("toml") => "toml",
+            Some("yaml") | Some("yml") => "yaml",
+            Some("json") => "json",
+            _ => "",
+        },
+        _ => "",
+    }
+}
+
+/// Backtick fence longer than any run inside `content`, so the block can't end early.
+fn fence_for(content: &str) -> String {
+    let longest = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+/// Escape text for use inside a double-quoted XML attribute.
+fn xml_attr(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// ` (lines 5-18)` for items that carry a line range.
+fn line_suffix(it: &BundleItem) -> String {
+    match (it.start_line, it.end_line) {
+        (Some(start), Some(end)) => format!(" (lines {}-{})", start, end),
+        _ => String::new(),
+    }
+}
+
+/// ` start_line="5" end_line="18"` for items that carry a line range.
+fn line_attrs(it: &BundleItem) -> String {
+    match (it.start_line, it.end_line) {
+        (Some(start), Some(end)) => format!(" start_line=\"{}\" end_line=\"{}\"", start, end),
+        _ => String::new(),
+    }
+}
+
+impl Bundle {
+    /// Markdown with one `## path` header (plus its line range, if any) and fenced code block per item,
+    /// in bundle order.
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# Context: {}\n", self.query);
+        for it in &self.items {
+            let fence = fence_for(&it.content);
+            out.push_str(&format!(
+                "\n## `{}`{}\n\n{}{}\n{}",
+                it.path, line_suffix(it), fence, language_hint(&it.kind, &it.path), it.content,
+            ));
+            if !it.content.ends_with('\n') {
+                out.push('\n');
+            }
+            out.push_str(&fence);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// `<documents>` with one `<document index path kind>` per item, the prompt-context layout
+    /// most model providers document. Content goes in verbatim, so code isn't entity-escaped.
+    pub fn to_xml(&self) -> String {
+        let mut out = format!("<documents query=\"{}\">\n", xml_attr(&self.query));
+        for (i, it) in self.items.iter().enumerate() {
+            out.push_str(&format!(
+                "<document index=\"{}\" path=\"{}\" kind=\"{}\"{}>\n<document_content>\n{}",
+                i + 1, xml_attr(&it.path), xml_attr(&it.kind), line_attrs(it), it.content,
+            ));
+            if !it.content.ends_with('\n') {
+                out.push('\n');
+            }
+            out.push_str("</document_content>\n</document>\n");
+        }
+        out.push_str("</documents>\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Bundle, BundleItem};
+
+    #[test]
+    fn markdown_has_headers_and_language_hints() {
+        let bundle = Bundle::new(
+            "player".into(),
+            vec![
+                BundleItem::test("./player.gd", "gdscript", "func _ready():\n\tpass\n"),
+                BundleItem::test("./project.yaml", "config", "name: demo"),
+            ],
+            0,
+            0,
+        );
+        let md = bundle.to_markdown();
+        assert!(md.starts_with("# Context: player\n"));
+        assert!(md.contains("## `./player.gd`\n\n```gdscript\nfunc _ready():\n\tpass\n```\n"));
+        assert!(md.contains("## `./project.yaml`\n\n```yaml\nname: demo\n```\n"));
+    }
+
+    #[test]
+    fn xml_wraps_items_in_documents() {
+        let bundle = Bundle::new(
+            "a \"b\"".into(),
+            vec![BundleItem::test("./a&b.gd", "gdscript", "if x < 1:\n\tpass")],
+            0,
+            0,
+        );
+        assert_eq!(
+            bundle.to_xml(),
+            "<documents query=\"a &quot;b&quot;\">\n\
+             <document index=\"1\" path=\"./a&amp;b.gd\" kind=\"gdscript\">\n\
+             <document_content>\nif x < 1:\n\tpass\n</document_content>\n</document>\n\
+             </documents>\n"
+        );
+    }
+
+    #[test]
+    fn line_ranges_are_rendered() {
+        let chunk = BundleItem { start_line: Some(5), end_line: Some(7), ..BundleItem::test("./a.gd", "gdscript", "a\nb\nc\n") };
+        let bundle = Bundle::new("q".into(), vec![chunk], 0, 0);
+        assert!(bundle.to_markdown().contains("## `./a.gd` (lines 5-7)\n"));
+        assert!(bundle.to_xml().contains("<document index=\"1\" path=\"./a.gd\" kind=\"gdscript\" start_line=\"5\" end_line=\"7\">"));
+    }
+
+    #[test]
+    fn fence_outgrows_backticks_in_content() {
+        let bundle = Bundle::new("q".into(), vec![BundleItem::test("./a.md", "docs", "```gd\nx\n```")], 0, 0);
+        assert!(bundle.to_markdown().contains("````markdown\n```gd\nx\n```\n````\n"));
+    }
+}
@@ -0,0 +1,147 @@
+// Re-ranking after retrieval: maximal marginal relevance for coverage of more files/directories,
+// and a recency boost for recently edited files.
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::SystemTime;
+
+use index::SearchReader;
+
+use crate::BundleItem;
+
+/// A file edited this long ago gets half the recency boost of one edited just now.
+pub const RECENCY_HALF_LIFE_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Reorder ranked items by MMR. Each pick maximizes
+/// `(1 - diversity) * relevance - diversity * max_similarity_to_picked`, where relevance is the
+/// score relative to the best hit and similarity mixes directory overlap with content overlap.
+/// `diversity` is clamped to 0..=1; 0 keeps the input order.
+pub fn diversify(items: Vec<BundleItem>, diversity: f32) -> Vec<BundleItem> {
+    let diversity = diversity.clamp(0.0, 1.0);
+    if diversity == 0.0 || items.len() < 3 {
+        return items;
+    }
+    let best = items.iter().map(|it| it.score).max().unwrap_or(0).max(1) as f32;
+    let relevance: Vec<f32> = items.iter().map(|it| it.score.max(0) as f32 / best).collect();
+    let features: Vec<Features> = items.iter().map(Features::of).collect();
+
+    let mut remaining: Vec<usize> = (0..items.len()).collect();
+    let mut picked: Vec<usize> = Vec::with_capacity(items.len());
+    while !remaining.is_empty() {
+        // Strictly greater keeps the earlier (higher-ranked) item on ties
+        let mut best_pos = 0;
+        let mut best_value = f32::NEG_INFINITY;
+        for (pos, &i) in remaining.iter().enumerate() {
+            let redundancy = picked.iter().map(|&j| features[i].similarity(&features[j])).fold(0.0, f32::max);
+            let value = (1.0 - diversity) * relevance[i] - diversity * redundancy;
+            if value > best_value {
+                best_value = value;
+                best_pos = pos;
+            }
+        }
+        picked.push(remaining.remove(best_pos));
+    }
+
+    let mut slots: Vec<Option<BundleItem>> = items.into_iter().map(Some).collect();
+    picked.into_iter().filter_map(|i| slots[i].take()).collect()
+}
+
+/// Blend file mtime into scores: each item gains `weight * best_score * freshness`, where freshness
+/// is 1 for a file edited just now and halves every `RECENCY_HALF_LIFE_SECS`; items are then
+/// re-sorted by score (path asc on ties). `weight` is clamped to 0..=1; 0 keeps the input order.
+pub fn boost_recent(idx: &SearchReader, items: Vec<BundleItem>, weight: f32) -> Vec<BundleItem> {
+    let now = SystemTime::now();
+    boost_by_age(items, weight, |it| {
+        let modified = idx.absolutize_path(&it.path).metadata().and_then(|m| m.modified()).ok()?;
+        Some(now.duration_since(modified).unwrap_or_default().as_secs())
+    })
+}
+
+/// `boost_recent` with ages (seconds) from `age_of`; items of unknown age get no boost.
+fn boost_by_age(mut items: Vec<BundleItem>, weight: f32, age_of: impl Fn(&BundleItem) -> Option<u64>) -> Vec<BundleItem> {
+    let weight = weight.clamp(0.0, 1.0);
+    if weight == 0.0 || items.is_empty() {
+        return items;
+    }
+    let best = items.iter().map(|it| it.score).max().unwrap_or(0).max(1) as f32;
+    for it in &mut items {
+        let freshness = age_of(it).map_or(0.0, |age| 0.5f32.powf(age as f32 / RECENCY_HALF_LIFE_SECS as f32));
+        it.score += (weight * best * freshness).round() as i32;
+    }
+    items.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+    items
+}
+
+struct Features {
+    dirs: Vec<String>,
+    tokens: HashSet<String>,
+}
+
+impl Features {
+    fn of(it: &BundleItem) -> Self {
+        let dirs = Path::new(&it.path)
+            .parent()
+            .map(|p| p.components().map(|c| c.as_os_str().to_string_lossy().into_owned()).filter(|c| c != ".").collect())
+            .unwrap_or_default();
+        let tokens = it.content
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .filter(|t| t.len() > 2)
+            .map(str::to_lowercase)
+            .collect();
+        Self { dirs, tokens }
+    }
+
+    /// 0..=1: half shared leading directories, half content token overlap (Jaccard).
+    fn similarity(&self, other: &Self) -> f32 {
+        let depth = self.dirs.len().max(other.dirs.len());
+        let dir = if depth == 0 {
+            1.0
+        } else {
+            self.dirs.iter().zip(&other.dirs).take_while(|(a, b)| a == b).count() as f32 / depth as f32
+        };
+        let union = self.tokens.union(&other.tokens).count();
+        let content = if union == 0 { 0.0 } else { self.tokens.intersection(&other.tokens).count() as f32 / union as f32 };
+        0.5 * dir + 0.5 * content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{boost_by_age, diversify, RECENCY_HALF_LIFE_SECS};
+    use crate::BundleItem;
+
+    fn item(path: &str, score: i32, content: &str) -> BundleItem {
+        BundleItem { score, ..BundleItem::test(path, "gdscript", content) }
+    }
+
+    fn paths(items: &[BundleItem]) -> Vec<&str> {
+        items.iter().map(|it| it.path.as_str()).collect()
+    }
+
+    #[test]
+    fn zero_diversity_keeps_order() {
+        let items = vec![item("./a/x.gd", 900, "a"), item("./a/y.gd", 800, "a"), item("./b/z.gd", 100, "b")];
+        assert_eq!(paths(&diversify(items.clone(), 0.0)), paths(&items));
+    }
+
+    #[test]
+    fn near_duplicates_yield_to_other_directories() {
+        let dup = "func take_damage(amount): health -= amount";
+        let items = vec![
+            item("./enemies/goblin.gd", 1000, dup),
+            item("./enemies/orc.gd", 990, dup),
+            item("./enemies/troll.gd", 980, dup),
+            item("./ui/health_bar.gd", 700, "func set_value(health): bar.value = health"),
+        ];
+        let out = diversify(items, 0.5);
+        assert_eq!(paths(&out), vec!["./enemies/goblin.gd", "./ui/health_bar.gd", "./enemies/orc.gd", "./enemies/troll.gd"]);
+    }
+
+    #[test]
+    fn recent_files_overtake_stale_close_matches() {
+        let items = vec![item("./old.gd", 1000, "a"), item("./new.gd", 950, "a"), item("./far.gd", 400, "a")];
+        let age = |it: &BundleItem| Some(if it.path == "./old.gd" { 10 * RECENCY_HALF_LIFE_SECS } else { 0 });
+        assert_eq!(paths(&boost_by_age(items.clone(), 0.0, age)), paths(&items));
+        // A small weight settles near-ties without lifting weak matches past strong ones
+        assert_eq!(paths(&boost_by_age(items, 0.1, age)), vec!["./new.gd", "./old.gd", "./far.gd"]);
+    }
+}
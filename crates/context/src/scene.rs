@@ -0,0 +1,243 @@
+// Scene-centric bundles: one .tscn with the scripts and resources needed to reason about it.
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{bail, Result};
+
+use crate::{pack, project_file, truncate_content, Bundle, BundleCap, BundleItem, DEFAULT_BUNDLE_CAP};
+
+/// What a .tscn references, in file order.
+#[derive(Debug, Default)]
+struct SceneRefs {
+    /// ext_resource id -> res:// path
+    ext: HashMap<String, String>,
+    /// Declared ext_resource paths
+    resources: Vec<String>,
+    /// (node path, res:// script path)
+    scripts: Vec<(String, String)>,
+    /// node path -> res:// path of the scene it instances
+    instances: HashMap<String, String>,
+    /// `to` node of each connection
+    targets: Vec<String>,
+}
+
+/// Bundle a scene for an agent: the .tscn first, then the scripts attached to its nodes, the
+/// scripts of nodes its signals connect to (for instanced sub-scenes, their root script), then
+/// every other referenced resource. Files are read from disk under `root` in full and cut by
+/// `truncate_content` to fit `cap_bytes` (default `DEFAULT_BUNDLE_CAP`); missing or binary files,
+/// and references leading outside `root`, are left out. `scene_path` may be `res://` or root-relative. Items carry score 0 and the bundle
+/// generation 0, since nothing comes from the index.
+pub fn bundle_for_scene(root: &Path, scene_path: &str, cap_bytes: Option<usize>) -> Result<Bundle> {
+    let scene = scene_path.strip_prefix("res://").unwrap_or(scene_path).trim_start_matches("./");
+    if !scene.ends_with(".tscn") {
+        bail!("not a .tscn scene: {}", scene_path);
+    }
+    let Some(scene_file) = project_file(root, scene) else {
+        bail!("scene not found in the project: {}", scene_path);
+    };
+    let text = fs::read_to_string(scene_file)?;
+    let refs = parse_scene(&text);
+
+    let mut paths = vec![scene.to_string()];
+    let mut add = |res: &str| {
+        // The scene's own paths are file contents: only ones that stay in the project are read
+        if let Some(rel) = res.strip_prefix("res://").filter(|rel| project_file(root, rel).is_some()) {
+            if !paths.iter().any(|p| p == rel) {
+                paths.push(rel.to_string());
+            }
+        }
+    };
+    for (_, script) in &refs.scripts {
+        add(script);
+    }
+    for target in &refs.targets {
+        if let Some((_, script)) = refs.scripts.iter().find(|(node, _)| node == target) {
+            add(script);
+        } else if let Some(sub) = refs.instances.get(target).and_then(|s| s.strip_prefix("res://")) {
+            let sub_root = project_file(root, sub).and_then(|p| fs::read_to_string(p).ok()).map(|t| parse_scene(&t));
+            if let Some((_, script)) = sub_root.as_ref().and_then(|r| r.scripts.iter().find(|(node, _)| node == ".")) {
+                add(script);
+            }
+        }
+    }
+    for res in &refs.resources {
+        add(res);
+    }
+
+    let items = paths
+        .into_iter()
+        .map(|rel| BundleItem {
+            kind: index::detect_kind(Path::new(&rel)).to_string(),
+            path: format!("./{}", rel),
+            score: 0,
+            content: String::new(),
+            start_line: None,
+            end_line: None,
+            hash: None,
+            bytes: None,
+            tokens_estimate: None,
+        })
+        .collect();
+    let mut cap = BundleCap::new(cap_bytes.unwrap_or(DEFAULT_BUNDLE_CAP));
+    let mut packed = Vec::new();
+    let prepare = |mut it: BundleItem, room| {
+        let full = fs::read_to_string(project_file(root, &it.path[2..])?).ok()?;
+        let (content, lines) = truncate_content(&full, &it.kind, room);
+        it.content = content;
+        it.start_line = Some(1);
+        it.end_line = Some(lines);
+        Some((it, ()))
+    };
+    pack(items, &mut cap, prepare, |it, ()| {
+        packed.push(it);
+        true
+    });
+    Ok(Bundle::new(format!("res://{}", scene), packed, cap.size_bytes(), 0))
+}
+
+fn parse_scene(text: &str) -> SceneRefs {
+    let mut refs = SceneRefs::default();
+    let mut node: Option<String> = None;
+    for line in text.lines().map(str::trim) {
+        if line.starts_with("[ext_resource") {
+            if let (Some(id), Some(path)) = (attr(line, "id"), attr(line, "path")) {
+                refs.ext.insert(id.to_string(), path.to_string());
+                refs.resources.push(path.to_string());
+            }
+            node = None;
+        } else if line.starts_with("[node") {
+            let path = match (attr(line, "name"), attr(line, "parent")) {
+                (_, None) => ".".to_string(),
+                (Some(name), Some(".")) => name.to_string(),
+                (Some(name), Some(parent)) => format!("{}/{}", parent, name),
+                (None, Some(_)) => {
+                    node = None;
+                    continue;
+                }
+            };
+            if let Some(res) = attr(line, "instance").and_then(|v| ext_ref(&refs, v)) {
+                refs.instances.insert(path.clone(), res);
+            }
+            node = Some(path);
+        } else if line.starts_with("[connection") {
+            if let Some(to) = attr(line, "to") {
+                refs.targets.push(to.to_string());
+            }
+            node = None;
+        } else if line.starts_with('[') {
+            node = None;
+        } else if let (Some(path), Some(value)) = (&node, line.strip_prefix("script").and_then(|v| v.trim_start().strip_prefix('='))) {
+            if let Some(res) = ext_ref(&refs, value.trim()).or_else(|| unquote(value.trim()).map(String::from)) {
+                refs.scripts.push((path.clone(), res));
+            }
+        }
+    }
+    refs
+}
+
+/// Name of the scene's root node (the first `[node]` without a `parent`).
+pub(crate) fn root_node_name(text: &str) -> Option<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("[node") && attr(line, "parent").is_none())
+        .find_map(|line| attr(line, "name"))
+        .map(String::from)
+}
+
+/// Value of `key=...` in a section header, quotes stripped; `ExtResource(...)` values are kept whole.
+fn attr<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let mut rest = line;
+    loop {
+        let at = rest.find(key)?;
+        let before_ok = at == 0 || rest[..at].ends_with([' ', '[']);
+        let after = rest[at + key.len()..].trim_start();
+        rest = &rest[at + key.len()..];
+        let Some(value) = after.strip_prefix('=').filter(|_| before_ok) else { continue };
+        let value = value.trim_start();
+        if let Some(quoted) = value.strip_prefix('"') {
+            return quoted.find('"').map(|end| &quoted[..end]);
+        }
+        let end = if value.starts_with("ExtResource") {
+            value.find(')').map_or(value.len(), |i| i + 1)
+        } else {
+            value.find([' ', ']']).unwrap_or(value.len())
+        };
+        return Some(&value[..end]);
+    }
+}
+
+/// res:// path behind `ExtResource("id")` (Godot 4) or `ExtResource( 1 )` (Godot 3).
+fn ext_ref(refs: &SceneRefs, value: &str) -> Option<String> {
+    let id = value.strip_prefix("ExtResource")?.trim().strip_prefix('(')?.strip_suffix(')')?.trim().trim_matches('"');
+    refs.ext.get(id).cloned()
+}
+
+fn unquote(value: &str) -> Option<&str> {
+    value.strip_prefix('"')?.strip_suffix('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const LEVEL: &str = r#"[gd_scene load_steps=4 format=3]
+
+[ext_resource type="Script" path="res://level.gd" id="1_lv"]
+[ext_resource type="PackedScene" path="res://enemy.tscn" id="2_en"]
+[ext_resource type="Theme" path="res://ui/theme.tres" id="3_th"]
+[ext_resource type="Texture2D" path="res://icon.png" id="4_ic"]
+
+[node name="Level" type="Node2D"]
+script = ExtResource("1_lv")
+
+[node name="Enemy" parent="." instance=ExtResource("2_en")]
+
+[connection signal="died" from="Enemy" to="." method="_on_enemy_died"]
+[connection signal="ready" from="." to="Enemy" method="_on_level_ready"]
+"#;
+
+    #[test]
+    fn scene_pulls_scripts_targets_and_resources() -> Result<()> {
+        let tmp = tempdir()?;
+        let root = tmp.path();
+        fs::create_dir_all(root.join("ui"))?;
+        fs::write(root.join("level.tscn"), LEVEL)?;
+        fs::write(root.join("level.gd"), "extends Node2D\n")?;
+        fs::write(
+            root.join("enemy.tscn"),
+            "[ext_resource type=\"Script\" path=\"res://enemy.gd\" id=\"1\"]\n[node name=\"Enemy\" type=\"Node2D\"]\nscript = ExtResource(\"1\")\n",
+        )?;
+        fs::write(root.join("enemy.gd"), "signal died\n")?;
+        fs::write(root.join("ui/theme.tres"), "[gd_resource type=\"Theme\"]\n")?;
+        fs::write(root.join("icon.png"), [0x89u8, 0x50, 0xff, 0xfe])?;
+
+        let bundle = bundle_for_scene(root, "res://level.tscn", None)?;
+        let paths: Vec<&str> = bundle.items.iter().map(|it| it.path.as_str()).collect();
+        assert_eq!(paths, ["./level.tscn", "./level.gd", "./enemy.gd", "./enemy.tscn", "./ui/theme.tres"]);
+        assert_eq!(bundle.items[1].kind, "gdscript");
+        assert_eq!(bundle.items[0].content, LEVEL);
+
+        let small = bundle_for_scene(root, "level.tscn", Some(200))?;
+        assert!(small.size_bytes <= 200);
+        assert!(bundle_for_scene(root, "level.gd", None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn references_outside_the_root_are_left_out() -> Result<()> {
+        let tmp = tempdir()?;
+        let root = tmp.path().join("project");
+        fs::create_dir_all(&root)?;
+        fs::write(tmp.path().join("secret.txt"), "token\n")?;
+        fs::write(tmp.path().join("outside.tscn"), "[node name=\"Out\" type=\"Node\"]\n")?;
+        let scene = "[ext_resource type=\"Script\" path=\"res://main.gd\" id=\"1\"]\n[ext_resource type=\"Theme\" path=\"res://../secret.txt\" id=\"2\"]\n[ext_resource type=\"Script\" path=\"res:///etc/hostname\" id=\"3\"]\n[node name=\"Main\" type=\"Node\"]\nscript = ExtResource(\"1\")\n";
+        fs::write(root.join("main.tscn"), scene)?;
+        fs::write(root.join("main.gd"), "extends Node\n")?;
+
+        let bundle = bundle_for_scene(&root, "res://main.tscn", None)?;
+        let paths: Vec<&str> = bundle.items.iter().map(|it| it.path.as_str()).collect();
+        assert_eq!(paths, ["./main.tscn", "./main.gd"]);
+        assert!(bundle_for_scene(&root, "res://../outside.tscn", None).is_err());
+        Ok(())
+    }
+}
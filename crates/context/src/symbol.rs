@@ -0,0 +1,94 @@
+// Symbol-centric bundles: a function/class definition together with its call sites.
+use anyhow::{bail, Result};
+use index::SearchReader;
+use regex::Regex;
+
+use crate::{
+    file_content, load_content, match_ranges, pack, range_items, ranked_items, Bundle, BundleCap, BundleItem, BundleOptions,
+    ContentMode, DEFAULT_BUNDLE_CAP,
+};
+
+/// Keywords that introduce a named definition in GDScript or Rust.
+const DEFINITION_KEYWORDS: &str = "func|fn|class|class_name|struct|enum|trait|signal|type";
+
+/// Whether `s` is a plain identifier (`take_damage`, `Player`), as `bundle_for_symbol` expects.
+pub fn is_symbol_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Bundle what it takes to explain `name`: each file defining it, packed whole, then its call
+/// sites in other files as line-range chunks (as in `chunks` mode), both in search rank order.
+/// The index keeps no symbol field, so definitions are found by matching a
+/// `func`/`fn`/`class`/`class_name`/`struct`/`enum`/`trait`/`signal`/`type` line among the files a
+/// search for `name`'s words returns. `opts` supplies the limit, cap, kind and exclude globs.
+pub fn bundle_for_symbol(idx: &SearchReader, name: &str, opts: &BundleOptions) -> Result<Bundle> {
+    if !is_symbol_name(name) {
+        bail!("invalid symbol name: {}", name);
+    }
+    let escaped = regex::escape(name);
+    let definition = Regex::new(&format!(
+        r"^\s*(?:(?:pub(?:\([^)]*\))?|static|async|const|unsafe)\s+)*(?:{})\s+{}\b",
+        DEFINITION_KEYWORDS, escaped
+    ))?;
+    let usage = Regex::new(&format!(r"\b{}\b", escaped))?;
+
+    // The index tokenizer splits identifiers on `_`, so search the words
+    let words = name.split('_').filter(|w| !w.is_empty()).map(str::to_lowercase).collect::<Vec<_>>().join(" ");
+    let generation = idx.generation()?;
+    let hits = ranked_items(idx, &words, opts.limit, opts.kind.as_deref(), &opts.exclude)?;
+    let mut definitions = Vec::new();
+    let mut call_sites = Vec::new();
+    for it in hits {
+        let Some(full) = file_content(idx, &it.path) else { continue };
+        if full.lines().any(|line| definition.is_match(line)) {
+            definitions.push(it);
+        } else {
+            call_sites.extend(range_items(&it, &full, match_ranges(&full, |line| usage.is_match(line))));
+        }
+    }
+
+    let mut cap = BundleCap::with_quotas(opts.cap_bytes.unwrap_or(DEFAULT_BUNDLE_CAP), &opts.quotas);
+    let mut items = Vec::new();
+    let prepare = |it: BundleItem, room| {
+        // Call-site chunks already carry their lines; definitions get the whole file
+        let it = if it.start_line.is_some() { it } else { load_content(idx, it, ContentMode::Full, room) };
+        Some((it, ()))
+    };
+    pack(definitions.into_iter().chain(call_sites).collect(), &mut cap, prepare, |it, ()| {
+        items.push(it);
+        true
+    });
+    Ok(Bundle::new(name.to_string(), items, cap.size_bytes(), generation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use index::{IndexPaths, SearchIndex};
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn definition_file_then_call_sites() -> Result<()> {
+        let tmp = tempdir()?;
+        let root = tmp.path().join("root");
+        fs::create_dir_all(&root)?;
+        let player = "extends Node\n\nvar health = 10\n\nfunc take_damage(amount):\n\thealth -= amount\n";
+        fs::write(root.join("player.gd"), player)?;
+        let calls: String = (1..=20).map(|i| format!("var pad_{} = {}\n", i, i)).collect::<String>() + "func hit(p):\n\tp.take_damage(3)\n";
+        fs::write(root.join("enemy.gd"), calls)?;
+        fs::write(root.join("notes.md"), "Players take damage from spikes.\n")?;
+        let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") })?;
+        idx.scan_and_index(&root)?;
+
+        let bundle = bundle_for_symbol(&idx.reader(), "take_damage", &BundleOptions::default())?;
+        let got: Vec<_> = bundle.items.iter().map(|it| (it.path.as_str(), it.start_line, it.end_line)).collect();
+        assert_eq!(got, [("./player.gd", Some(1), Some(6)), ("./enemy.gd", Some(17), Some(22))]);
+        assert_eq!(bundle.items[0].content, player);
+        assert!(bundle.items[1].content.ends_with("\tp.take_damage(3)\n"));
+
+        assert!(bundle_for_symbol(&idx.reader(), "take damage", &BundleOptions::default()).is_err());
+        Ok(())
+    }
+}
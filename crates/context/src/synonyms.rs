@@ -0,0 +1,102 @@
+// Query expansion: Godot-domain synonyms searched alongside the terms a client used.
+use std::collections::BTreeMap;
+
+use crate::WeightedQuery;
+
+/// Weight of a synonym variant relative to the query it was derived from.
+pub const SYNONYM_WEIGHT: f32 = 0.5;
+/// Most queries an expanded request runs, originals included.
+pub const MAX_EXPANDED_QUERIES: usize = 16;
+
+/// Built-in term -> alternatives; `_` in an alternative separates words, as in identifiers.
+const BUILTIN: &[(&str, &[&str])] = &[
+    ("animation", &["tween", "animation_player"]),
+    ("camera", &["camera2d", "camera3d"]),
+    ("collision", &["collider", "hitbox", "area2d"]),
+    ("damage", &["hurt", "hit"]),
+    ("enemy", &["mob", "npc"]),
+    ("health", &["hp", "hitpoints"]),
+    ("hud", &["ui", "canvas_layer"]),
+    ("input", &["action", "keybind"]),
+    ("level", &["scene", "map", "stage"]),
+    ("menu", &["ui", "control"]),
+    ("music", &["audio", "bgm"]),
+    ("physics", &["rigidbody", "collision"]),
+    ("player", &["character", "hero"]),
+    ("save", &["persist", "serialize"]),
+    ("sound", &["audio", "sfx"]),
+    ("spawn", &["instantiate", "spawner"]),
+    ("timer", &["cooldown"]),
+    ("ui", &["control", "gui"]),
+];
+
+/// Term -> alternative terms used by `expand`. Keys are matched against lowercased query terms.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Synonyms(BTreeMap<String, Vec<String>>);
+
+impl Synonyms {
+    /// The built-in Godot dictionary (`hud` -> `ui`, `canvas_layer`; `enemy` -> `mob`, ...).
+    pub fn builtin() -> Self {
+        Self(BUILTIN.iter().map(|(term, alts)| (term.to_string(), alts.iter().map(|a| a.to_string()).collect())).collect())
+    }
+
+    /// Add `extra` entries (e.g. from config) after the existing alternatives for each term.
+    pub fn extended(mut self, extra: &BTreeMap<String, Vec<String>>) -> Self {
+        for (term, alts) in extra {
+            let known = self.0.entry(term.to_lowercase()).or_default();
+            for alt in alts {
+                let alt = alt.to_lowercase();
+                if !known.contains(&alt) {
+                    known.push(alt);
+                }
+            }
+        }
+        self
+    }
+
+    /// `queries` followed by one variant per synonym of each of their terms, that term
+    /// swapped for the synonym's words and the weight scaled by `SYNONYM_WEIGHT`. Variants
+    /// repeating an earlier query are dropped, and at most `MAX_EXPANDED_QUERIES` are returned.
+    pub fn expand(&self, queries: &[WeightedQuery]) -> Vec<WeightedQuery> {
+        let mut out = queries.to_vec();
+        for wq in queries {
+            let terms: Vec<String> = wq.q.split_whitespace().map(str::to_lowercase).collect();
+            for (i, term) in terms.iter().enumerate() {
+                for alt in self.0.get(term).into_iter().flatten() {
+                    if out.len() >= MAX_EXPANDED_QUERIES {
+                        return out;
+                    }
+                    let mut variant = terms.clone();
+                    variant[i] = alt.replace('_', " ");
+                    let q = variant.join(" ");
+                    if !out.iter().any(|o| o.q.to_lowercase() == q) {
+                        out.push(WeightedQuery { q, weight: wq.weight * SYNONYM_WEIGHT });
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variants_swap_one_term_at_lower_weight() {
+        let extra = BTreeMap::from([("HUD".to_string(), vec!["overlay".to_string(), "ui".to_string()])]);
+        let synonyms = Synonyms::builtin().extended(&extra);
+        let got: Vec<_> = synonyms.expand(&[WeightedQuery::new("Hud score")]).into_iter().map(|wq| (wq.q, wq.weight)).collect();
+        assert_eq!(
+            got,
+            [
+                ("Hud score".to_string(), 1.0),
+                ("ui score".to_string(), 0.5),
+                ("canvas layer score".to_string(), 0.5),
+                ("overlay score".to_string(), 0.5),
+            ]
+        );
+        assert_eq!(synonyms.expand(&[WeightedQuery::new("banana")]), [WeightedQuery::new("banana")]);
+    }
+}
@@ -0,0 +1,83 @@
+// Prompt templates: named text files with `{{bundle}}`, `{{query}}` and `{{issues}}` placeholders.
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+
+/// Values substituted for a template's placeholders.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PromptVars<'a> {
+    /// The rendered bundle (`{{bundle}}`)
+    pub bundle: &'a str,
+    /// The query the bundle was built for (`{{query}}`)
+    pub query: &'a str,
+    /// Issues reported for the bundled files (`{{issues}}`)
+    pub issues: &'a str,
+}
+
+/// Whether `name` can name a template: ASCII letters, digits, `-` and `_` only.
+pub fn is_template_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Read template `name` from `<config_dir>/templates/<name>.md`.
+pub fn load_template(config_dir: &Path, name: &str) -> Result<String> {
+    if !is_template_name(name) {
+        bail!("invalid template name: {}", name);
+    }
+    let path = config_dir.join("templates").join(format!("{}.md", name));
+    fs::read_to_string(&path).with_context(|| format!("reading template {}", path.display()))
+}
+
+/// Whether `template` uses placeholder `var` (spaces inside the braces allowed).
+pub fn uses_placeholder(template: &str, var: &str) -> bool {
+    placeholders(template).any(|(_, name)| name == var)
+}
+
+/// `template` with each `{{bundle}}`, `{{query}}` and `{{issues}}` (spaces inside the braces allowed)
+/// replaced in a single pass, so placeholders inside substituted text stay as they are. Unknown
+/// placeholders are left untouched.
+pub fn render_template(template: &str, vars: &PromptVars) -> String {
+    let mut out = String::with_capacity(template.len() + vars.bundle.len());
+    let mut last = 0;
+    for ((start, end), name) in placeholders(template) {
+        let value = match name {
+            "bundle" => vars.bundle,
+            "query" => vars.query,
+            "issues" => vars.issues,
+            _ => continue,
+        };
+        out.push_str(&template[last..start]);
+        out.push_str(value);
+        last = end;
+    }
+    out.push_str(&template[last..]);
+    out
+}
+
+/// Byte span and trimmed name of each `{{...}}` in `template`, in order.
+fn placeholders(template: &str) -> impl Iterator<Item = ((usize, usize), &str)> {
+    let mut from = 0;
+    std::iter::from_fn(move || {
+        let start = from + template[from..].find("{{")?;
+        let len = template[start + 2..].find("}}")?;
+        let end = start + 2 + len + 2;
+        from = end;
+        Some(((start, end), template[start + 2..end - 2].trim()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_known_placeholders_once() {
+        let vars = PromptVars { bundle: "code with {{query}}", query: "jump", issues: "" };
+        let out = render_template("Q: {{ query }}\n{{bundle}}\n{{other}}\nIssues:{{issues}}.", &vars);
+        assert_eq!(out, "Q: jump\ncode with {{query}}\n{{other}}\nIssues:.");
+        assert!(uses_placeholder("see {{ issues }}", "issues"));
+        assert!(!uses_placeholder("see {{bundle}}", "issues"));
+        assert!(!is_template_name("../secret"));
+        assert!(is_template_name("fix-bug_2"));
+    }
+}
@@ -0,0 +1,79 @@
+// Cutting item content to a byte budget at line, block or definition boundaries.
+
+/// Last line of an item whose content was cut to fit the cap.
+pub const TRUNCATED_MARKER: &str = "[truncated]";
+
+/// Bytes the marker line can add: itself, its newline and one to end a partly kept line.
+const MARKER_BYTES: usize = TRUNCATED_MARKER.len() + 2;
+
+/// `s` cut to fit `max` bytes, together with the number of lines of `s` kept. Content that fits
+/// is returned whole; otherwise the cut falls at the latest boundary in the back half of the
+/// budget, trying in turn the start of a top-level line (the next function or declaration,
+/// for `gdscript` and `rust` items), a blank line, then any line end, and a `[truncated]` line
+/// is appended. Only when no whole line fits is a line cut, at whitespace if possible; when
+/// not even the marker fits the result is empty.
+pub fn truncate_content(s: &str, kind: &str, max: usize) -> (String, usize) {
+    if s.len() <= max {
+        return (s.to_string(), s.lines().count());
+    }
+    if max < MARKER_BYTES {
+        return (String::new(), 0);
+    }
+    let kept = &s[..cut_point(s, kind, max - MARKER_BYTES)];
+    let lines = kept.lines().count();
+    let sep = if kept.is_empty() || kept.ends_with('\n') { "" } else { "\n" };
+    (format!("{}{}{}\n", kept, sep, TRUNCATED_MARKER), lines)
+}
+
+/// Byte offset to cut `s` at so the kept prefix is at most `budget` bytes.
+fn cut_point(s: &str, kind: &str, budget: usize) -> usize {
+    let mut end = budget;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    let Some(nl) = s[..end].rfind('\n') else {
+        return s[..end].rfind(char::is_whitespace).unwrap_or(end);
+    };
+    let line_end = nl + 1;
+    // Line starts a cut may fall on, latest first; the line at `line_end` is the first one dropped
+    let starts = || {
+        s[..line_end].match_indices('\n').map(|(i, _)| i + 1).rev().take_while(|&i| i >= line_end / 2)
+    };
+    let line_at = |i: usize| s[i..].lines().next().unwrap_or("");
+    if matches!(kind, "gdscript" | "rust") {
+        if let Some(at) = starts().find(|&i| is_top_level(line_at(i))) {
+            return at;
+        }
+    }
+    starts().find(|&i| line_at(i).trim().is_empty()).unwrap_or(line_end)
+}
+
+/// An unindented line that opens something new, rather than closing a block.
+fn is_top_level(line: &str) -> bool {
+    line.starts_with(|c: char| !c.is_whitespace() && !matches!(c, '}' | ')' | ']'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &str = "extends Node\n\nfunc one():\n\tpass\n\tpass\n\nfunc two():\n\tvar long_name = 1\n\tvar other = 2\n";
+
+    #[test]
+    fn cuts_before_the_function_that_does_not_fit() {
+        let (out, lines) = truncate_content(SCRIPT, "gdscript", SCRIPT.len() - 5);
+        assert_eq!(out, "extends Node\n\nfunc one():\n\tpass\n\tpass\n\n[truncated]\n");
+        assert_eq!(lines, 6);
+        assert_eq!(truncate_content(SCRIPT, "gdscript", SCRIPT.len()), (SCRIPT.to_string(), 9));
+    }
+
+    #[test]
+    fn falls_back_to_blank_lines_then_lines_then_words() {
+        let doc = "# Title\n\nFirst paragraph line.\nSecond line.\n\nNext paragraph.\n";
+        assert_eq!(truncate_content(doc, "docs", 58).0, "# Title\n\nFirst paragraph line.\nSecond line.\n[truncated]\n");
+        assert_eq!(truncate_content(doc, "docs", 50).0, "# Title\n\nFirst paragraph line.\n[truncated]\n");
+        assert_eq!(truncate_content(doc, "docs", 30).0, "# Title\n[truncated]\n");
+        assert_eq!(truncate_content("one two three four five", "docs", 21).0, "one two\n[truncated]\n");
+        assert_eq!(truncate_content("héllo wörld", "docs", 5), (String::new(), 0));
+    }
+}
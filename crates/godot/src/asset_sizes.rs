@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::orphan_assets::asset_kind;
+
+/// Files listed under `largest`.
+const LARGEST: usize = 10;
+
+/// Uncompressed formats with a smaller one Godot imports just as well: (extension, hint).
+const UNCOMPRESSED: &[(&str, &str)] = &[
+    ("wav", "use .ogg for music and long sounds"),
+    ("bmp", "use .png or .webp"),
+    ("tga", "use .png or .webp"),
+];
+
+/// Asset sizes of a project, for tracking binary bloat over time.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetSizeReport {
+    pub files: usize,
+    pub bytes: u64,
+    /// texture, audio, scene, script, resource, font, model, shader; largest first
+    pub by_kind: Vec<SizeBucket>,
+    /// Top-level folder (`.` for files at the root); largest first
+    pub by_dir: Vec<SizeBucket>,
+    pub largest: Vec<AssetSize>,
+    pub uncompressed: Vec<UncompressedAsset>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SizeBucket { pub name: String, pub files: usize, pub bytes: u64 }
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetSize {
+    /// Root-relative path
+    pub path: PathBuf,
+    pub kind: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UncompressedAsset {
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub hint: String,
+}
+
+/// Sum the sizes of the project's assets (the kinds `--orphan-assets` knows) by kind and by
+/// top-level folder, and list the largest files and those in uncompressed formats. Addons
+/// count, as they ship with the game; `.godot`, `.git` and fix backups don't.
+pub fn asset_sizes(root: &Path) -> AssetSizeReport {
+    let mut assets = Vec::new();
+    for entry in WalkDir::new(root).sort_by_file_name().into_iter().flatten() {
+        if !entry.file_type().is_file() { continue; }
+        let Ok(rel) = entry.path().strip_prefix(root) else { continue };
+        if rel.starts_with(".godot") || rel.starts_with(".git") || rel.starts_with(".structure_fix") || rel.starts_with(".lint_fix") { continue; }
+        let Some(kind) = asset_kind(rel.extension().and_then(|s| s.to_str()).unwrap_or("")) else { continue };
+        let bytes = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        assets.push(AssetSize { path: rel.to_path_buf(), kind: kind.to_string(), bytes });
+    }
+
+    let mut report = AssetSizeReport { files: assets.len(), bytes: assets.iter().map(|a| a.bytes).sum(), ..Default::default() };
+    let mut kinds: BTreeMap<String, (usize, u64)> = BTreeMap::new();
+    let mut dirs: BTreeMap<String, (usize, u64)> = BTreeMap::new();
+    for a in &assets {
+        let dir = if a.path.components().count() > 1 { a.path.components().next().unwrap().as_os_str().to_string_lossy().to_string() } else { ".".to_string() };
+        for (map, key) in [(&mut kinds, a.kind.clone()), (&mut dirs, dir)] {
+            let e = map.entry(key).or_default();
+            e.0 += 1;
+            e.1 += a.bytes;
+        }
+        let ext = a.path.extension().and_then(|s| s.to_str()).unwrap_or("").to_ascii_lowercase();
+        if let Some((_, hint)) = UNCOMPRESSED.iter().find(|(e, _)| *e == ext) {
+            report.uncompressed.push(UncompressedAsset { path: a.path.clone(), bytes: a.bytes, hint: hint.to_string() });
+        }
+    }
+    let buckets = |map: BTreeMap<String, (usize, u64)>| {
+        let mut v: Vec<SizeBucket> = map.into_iter().map(|(name, (files, bytes))| SizeBucket { name, files, bytes }).collect();
+        v.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+        v
+    };
+    report.by_kind = buckets(kinds);
+    report.by_dir = buckets(dirs);
+    report.uncompressed.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    assets.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    assets.truncate(LARGEST);
+    report.largest = assets;
+    report
+}
+
+/// Markdown rendering of a report: totals, the kind and folder tables, the largest files and
+/// the uncompressed ones.
+pub fn to_markdown(report: &AssetSizeReport) -> String {
+    let mut out = format!("# Asset sizes\n\n{} assets, {}\n", report.files, human_size(report.bytes));
+    let share = |bytes: u64| if report.bytes == 0 { 0.0 } else { bytes as f64 * 100.0 / report.bytes as f64 };
+    for (title, buckets) in [("By kind", &report.by_kind), ("By folder", &report.by_dir)] {
+        out.push_str(&format!("\n## {}\n\n| | Files | Size | Share |\n|---|---:|---:|---:|\n", title));
+        for b in buckets {
+            out.push_str(&format!("| {} | {} | {} | {:.1}% |\n", b.name, b.files, human_size(b.bytes), share(b.bytes)));
+        }
+    }
+    out.push_str("\n## Largest files\n\n| File | Kind | Size |\n|---|---|---:|\n");
+    for a in &report.largest {
+        out.push_str(&format!("| res://{} | {} | {} |\n", a.path.to_string_lossy().replace('\\', "/"), a.kind, human_size(a.bytes)));
+    }
+    out.push_str("\n## Uncompressed formats\n\n");
+    if report.uncompressed.is_empty() {
+        out.push_str("None.\n");
+    } else {
+        out.push_str("| File | Size | Hint |\n|---|---:|---|\n");
+        for u in &report.uncompressed {
+            out.push_str(&format!("| res://{} | {} | {} |\n", u.path.to_string_lossy().replace('\\', "/"), human_size(u.bytes), u.hint));
+        }
+    }
+    out
+}
+
+/// `1536` -> `1.5 KiB`.
+fn human_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB"];
+    if bytes < 1024 { return format!("{} B", bytes); }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
@@ -0,0 +1,69 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::gdscript;
+use crate::project_settings::ProjectSettings;
+use crate::scene_validate::SceneDocument;
+use crate::uid::UidMap;
+use crate::Issue;
+
+/// One entry of project.godot's `[autoload]` section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Autoload {
+    pub name: String,
+    /// `res://` or `uid://` path, without the leading `*`
+    pub path: String,
+    /// `*` prefix: registered as a global singleton, not just added to the tree
+    pub singleton: bool,
+    pub line: usize,
+}
+
+/// Autoloads declared in `<root>/project.godot`, in file order (empty if there is none).
+pub fn read_autoloads(root: &Path) -> Vec<Autoload> {
+    let Some(settings) = ProjectSettings::load(root) else { return vec![] };
+    settings.section("autoload").into_iter()
+        .map(|p| {
+            let raw = p.value.as_str().unwrap_or("");
+            Autoload { name: p.key.clone(), path: raw.trim_start_matches('*').to_string(), singleton: raw.starts_with('*'), line: p.line }
+        })
+        .collect()
+}
+
+/// Codes of autoload findings.
+pub const CODES: &[&str] = &["autoload-missing-file", "autoload-parse-error", "autoload-no-nodes", "autoload-name-collision"];
+
+/// Check each autoload: its file exists (uid:// resolved through `uids`), the script or scene
+/// parses, and its name doesn't collide with a `class_name` declared by some script. Issues
+/// point at project.godot, start with "Autoload '" and carry one of `CODES`.
+pub fn validate_autoloads(root: &Path, uids: &UidMap) -> Vec<Issue> {
+    let autoloads = read_autoloads(root);
+    if autoloads.is_empty() { return vec![]; }
+    let proj = Some(PathBuf::from("project.godot"));
+    let class_names = gdscript::class_registry(root);
+    let mut out = Vec::new();
+    for a in &autoloads {
+        let err = |code: &str, msg: String| Issue::error(format!("Autoload '{}' {}", a.name, msg), proj.clone()).at(a.line, None).with_code(code);
+        match uids.res_path(&a.path).and_then(|p| p.strip_prefix("res://")) {
+            Some(rel) if root.join(rel).is_file() => {
+                let target = root.join(rel);
+                if rel.ends_with(".gd") {
+                    let src = fs::read_to_string(&target).unwrap_or_default();
+                    if let Some((line, why)) = gdscript::logical_lines(&src).1.into_iter().next() {
+                        out.push(err("autoload-parse-error", format!("script does not parse: {} at {}:{}", why, a.path, line)));
+                    }
+                } else if rel.ends_with(".tscn") {
+                    let doc = SceneDocument::read(&target).unwrap_or_default();
+                    if doc.sections("node").next().is_none() {
+                        out.push(err("autoload-no-nodes", format!("scene has no nodes: {}", a.path)));
+                    }
+                }
+            }
+            _ if a.path.is_empty() => out.push(err("autoload-missing-file", "has no path".to_string())),
+            _ => out.push(err("autoload-missing-file", format!("missing file: {}", a.path))),
+        }
+        if let Some(script) = class_names.get(&a.name) {
+            out.push(err("autoload-name-collision", format!("collides with class_name {} in {}", a.name, script.display())));
+        }
+    }
+    out
+}
@@ -0,0 +1,96 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::{classify_rule_id, Issue};
+
+/// Issues recorded as known, so later runs only report new ones. Written by
+/// `godot-analyzer --write-baseline`, applied with `--baseline <file>`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub version: u32,
+    pub entries: Vec<BaselineEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    /// SARIF rule id (`scene-validator`, `script-lint`, ...)
+    pub rule: String,
+    /// Root-relative file with `/` separators; empty for project-wide issues
+    pub file: String,
+    /// See `fingerprint`
+    pub fingerprint: String,
+    /// The message when recorded, for people reading the file
+    pub message: String,
+}
+
+const VERSION: u32 = 1;
+
+/// Hash of an issue's rule, file and message with digit runs collapsed, so a finding keeps its
+/// fingerprint when lines shift (`at line 12` vs `at line 14`).
+pub fn fingerprint(issue: &Issue) -> String {
+    let mut normalized = String::with_capacity(issue.message.len());
+    for c in issue.message.chars() {
+        if c.is_ascii_digit() {
+            if !normalized.ends_with('#') { normalized.push('#'); }
+        } else {
+            normalized.push(c);
+        }
+    }
+    let mut h = Sha256::new();
+    h.update(classify_rule_id(issue).as_bytes());
+    h.update([0]);
+    h.update(file_key(issue).as_bytes());
+    h.update([0]);
+    h.update(normalized.as_bytes());
+    format!("{:x}", h.finalize())[..16].to_string()
+}
+
+fn file_key(issue: &Issue) -> String {
+    issue.file.as_ref().map(|p| p.to_string_lossy().replace('\\', "/")).unwrap_or_default()
+}
+
+impl Baseline {
+    /// A baseline recording `issues`, sorted for stable diffs.
+    pub fn from_issues(issues: &[Issue]) -> Self {
+        let mut entries: Vec<BaselineEntry> = issues.iter()
+            .map(|i| BaselineEntry { rule: classify_rule_id(i).to_string(), file: file_key(i), fingerprint: fingerprint(i), message: i.message.clone() })
+            .collect();
+        entries.sort_by(|a, b| a.file.cmp(&b.file).then(a.rule.cmp(&b.rule)).then(a.message.cmp(&b.message)));
+        Self { version: VERSION, entries }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path).with_context(|| format!("read baseline {}", path.display()))?;
+        let b: Self = serde_json::from_str(&text).with_context(|| format!("parse baseline {}", path.display()))?;
+        if b.version != VERSION { bail!("unsupported baseline version {} in {}", b.version, path.display()); }
+        Ok(b)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_vec_pretty(self)?).with_context(|| format!("write baseline {}", path.display()))
+    }
+
+    /// `issues` minus those the baseline records, and how many were suppressed. Each entry
+    /// absorbs one matching issue, so a second copy of a recorded finding is still reported.
+    pub fn filter(&self, issues: Vec<Issue>) -> (Vec<Issue>, usize) {
+        let mut budget: HashMap<(String, String, String), usize> = HashMap::new();
+        for e in &self.entries {
+            *budget.entry((e.rule.clone(), e.file.clone(), e.fingerprint.clone())).or_default() += 1;
+        }
+        let before = issues.len();
+        let kept: Vec<Issue> = issues.into_iter()
+            .filter(|i| {
+                match budget.get_mut(&(classify_rule_id(i).to_string(), file_key(i), fingerprint(i))) {
+                    Some(n) if *n > 0 => { *n -= 1; false }
+                    _ => true,
+                }
+            })
+            .collect();
+        let suppressed = before - kept.len();
+        (kept, suppressed)
+    }
+}
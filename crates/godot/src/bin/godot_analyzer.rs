@@ -1,7 +1,7 @@
 use clap::Parser;
 use std::path::PathBuf;
 use godot_analyzer::{
-    analyze_project, scene_issues_as_report_with, signal_graph_dot, signal_issues_as_report, structure_fix, GodotProjectReport, SceneCheckOptions, Severity, to_junit, to_sarif,
+    analyze_project, baseline::Baseline, config::AnalyzerConfig, scene_issues_as_report_with, signal_graph_dot, signal_graph_json, signal_issues_as_report, script_lint, structure_fix, GodotProjectReport, SceneCheckOptions, Severity, to_code_quality, to_html, to_junit, to_sarif,
 };
 
 #[derive(Parser, Debug)]
@@ -20,6 +20,16 @@ struct Args {
     /// Write JUnit XML output to this file
     #[arg(long)]
     junit_out: Option<PathBuf>,
+    /// Write a standalone HTML report to this file
+    #[arg(long)]
+    html_out: Option<PathBuf>,
+    /// Write a GitLab Code Quality (Code Climate) JSON report to this file
+    #[arg(long)]
+    code_quality_out: Option<PathBuf>,
+    /// Override a rule's severity, e.g. `--severity missing-preload=warn` or
+    /// `--severity "Duplicate connection=info"` (repeatable; wins over godot-analyzer.toml)
+    #[arg(long = "severity")]
+    severity_overrides: Vec<String>,
     /// Exit with code 2 if any issue meets or exceeds this severity (info|warn|error)
     #[arg(long)]
     fail_on: Option<String>,
@@ -30,8 +40,26 @@ struct Args {
     #[arg(long)]
     validate_signals: bool,
     /// Lint GDScript files and include findings in outputs
-    #[arg(long)]
+    #[arg(long, visible_alias = "lint-scripts", alias = "lint_gd")]
     lint_gd: bool,
+    /// Report assets (textures, audio, scenes, scripts, ...) that nothing references
+    #[arg(long)]
+    orphan_assets: bool,
+    /// Report asset sizes by kind and top-level folder, the largest files and uncompressed formats; prints JSON unless --asset-sizes-json-out is given
+    #[arg(long)]
+    asset_sizes: bool,
+    /// Write the asset size report JSON to this file instead of stdout
+    #[arg(long)]
+    asset_sizes_json_out: Option<PathBuf>,
+    /// Also write the asset size report as Markdown to this file
+    #[arg(long)]
+    asset_sizes_md_out: Option<PathBuf>,
+    /// Suppress issues recorded in this baseline file (see --write-baseline)
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+    /// Record the current issues as the baseline (at --baseline, default godot-analyzer.baseline.json) and exit
+    #[arg(long)]
+    write_baseline: bool,
     /// Optionally write scene findings as a standalone JSON file
     #[arg(long)]
     scene_json_out: Option<PathBuf>,
@@ -41,6 +69,9 @@ struct Args {
     /// Optionally write a DOT graph of signal connections across scenes
     #[arg(long)]
     signal_dot_out: Option<PathBuf>,
+    /// Optionally write the signal graph as JSON (lines, flags, endpoint status)
+    #[arg(long)]
+    signal_json_out: Option<PathBuf>,
     /// Structure fix: plan (dry-run) only; prints JSON to stdout unless --json-out provided
     #[arg(long)]
     structure_fix: bool,
@@ -50,16 +81,68 @@ struct Args {
     /// Apply structure fix (implies --structure_fix). Prints JSON summary.
     #[arg(long)]
     structure_fix_apply: bool,
+    /// Limit the structure fix plan/apply to moves whose source or destination matches this glob (repeatable), e.g. "scripts/**"
+    #[arg(long = "structure-fix-only")]
+    structure_fix_only: Vec<String>,
+    /// Leave out structure fix moves whose source or destination matches this glob (repeatable)
+    #[arg(long = "structure-fix-exclude")]
+    structure_fix_exclude: Vec<String>,
+    /// Structure fix grouping: by-type (scripts/, scenes/, assets/) or by-feature (features/<scene>/); overrides [structure] strategy
+    #[arg(long = "structure-strategy")]
+    structure_strategy: Option<structure_fix::Strategy>,
+    /// Undo the latest --structure_fix_apply from .structure_fix/manifest.json. Prints JSON summary.
+    #[arg(long, alias = "structure_fix_rollback")]
+    structure_fix_rollback: bool,
+    /// Apply the safe GDScript lint fixes (backups under .lint_fix/backup). Prints JSON summary.
+    #[arg(long)]
+    lint_fix: bool,
+    /// Limit --lint-fix to these rules (repeatable). Options: debug-print,tab-indentation,missing-extends.
+    #[arg(long = "lint-fix-rule")]
+    lint_fix_rules: Vec<String>,
 }
 
 fn main() {
     let args = Args::parse();
     let root = args.root.unwrap_or_else(|| std::env::current_dir().unwrap());
+    let mut config = AnalyzerConfig::load(&root).expect("read godot-analyzer.toml");
+    for spec in &args.severity_overrides {
+        config.set_severity(spec).expect("--severity");
+    }
+    // Script lint fixes
+    if args.lint_fix {
+        let sum = script_lint::lint_gd_scripts_fix(&root, &args.lint_fix_rules).expect("apply lint fixes");
+        println!("{}", serde_json::to_string_pretty(&sum).unwrap());
+        return;
+    }
+    if args.structure_fix_rollback {
+        let sum = structure_fix::rollback_structure_fix(&root).expect("roll back structure fix");
+        println!("{}", serde_json::to_string_pretty(&sum).unwrap());
+        return;
+    }
+
+    if args.asset_sizes {
+        let sizes = godot_analyzer::asset_sizes::asset_sizes(&root);
+        if let Some(p) = args.asset_sizes_md_out.as_ref() {
+            std::fs::write(p, godot_analyzer::asset_sizes::to_markdown(&sizes)).expect("write asset size markdown");
+        }
+        let s = serde_json::to_string_pretty(&sizes).unwrap();
+        if let Some(p) = args.asset_sizes_json_out.as_ref() {
+            std::fs::write(p, s).expect("write asset size json");
+        } else {
+            println!("{}", s);
+        }
+        return;
+    }
+
     let mut report = analyze_project(&root).expect("analyze");
 
     // Structure fix planning/apply
     if args.structure_fix || args.structure_fix_apply {
-        let plan = structure_fix::plan_structure_fix(&root);
+        let selection = structure_fix::MoveSelection { include: args.structure_fix_only.clone(), exclude: args.structure_fix_exclude.clone(), ..Default::default() };
+        let mut layout = structure_fix::Layout::configured(&root).expect("read [structure] in godot-analyzer.toml");
+        if let Some(strategy) = args.structure_strategy { layout.strategy = strategy; }
+        let mut plan = selection.select(&structure_fix::plan_structure_fix_with(&root, &layout)).expect("--structure-fix-only/--structure-fix-exclude");
+        if !selection.is_empty() { plan.edits = structure_fix::preview_edits(&root, &plan); }
         if args.structure_fix_apply {
             let sum = structure_fix::apply_structure_fix(&root, &plan).expect("apply structure fix");
             println!("{}", serde_json::to_string_pretty(&sum).unwrap());
@@ -74,7 +157,7 @@ fn main() {
         return;
     }
 
-    if args.validate_scenes {
+    if args.validate_scenes || config.enables("scene-validator") {
         let mut opts = SceneCheckOptions::default();
         if !args.scene_checks.is_empty() {
             // Disable all, then enable selected
@@ -99,23 +182,52 @@ fn main() {
     report.issues.sort_by(|a, b| a.severity.cmp(&b.severity).then(a.message.cmp(&b.message)));
     }
 
-    if args.validate_signals {
+    if args.validate_signals || config.enables("signal-validator") {
         let sig_issues = signal_issues_as_report(&root);
         report.issues.extend(sig_issues);
         report.issues.sort_by(|a, b| a.severity.cmp(&b.severity).then(a.message.cmp(&b.message)));
     }
 
-    if args.lint_gd {
+    if args.lint_gd || config.enables("script-lint") {
         let lint_issues = godot_analyzer::lint_gd(&root);
         report.issues.extend(lint_issues);
         report.issues.sort_by(|a, b| a.severity.cmp(&b.severity).then(a.message.cmp(&b.message)));
     }
 
+    if args.orphan_assets || config.enables("orphan-assets") {
+        report.issues.extend(godot_analyzer::orphan_assets_as_report(&root));
+        report.issues.sort_by(|a, b| a.severity.cmp(&b.severity).then(a.message.cmp(&b.message)));
+    }
+
+    // --severity overrides come on top of what the library applied from the config file
+    if !args.severity_overrides.is_empty() {
+        config.regrade(&mut report.issues);
+        report.issues.sort_by(|a, b| a.severity.cmp(&b.severity).then(a.message.cmp(&b.message)));
+    }
+
+    // Baseline: record everything found so far, or drop what was recorded
+    if args.write_baseline {
+        let path = args.baseline.clone().unwrap_or_else(|| PathBuf::from("godot-analyzer.baseline.json"));
+        Baseline::from_issues(&report.issues).save(&path).expect("write baseline");
+        println!("baseline: recorded {} issue(s) in {}", report.issues.len(), path.display());
+        return;
+    }
+    let mut suppressed = None;
+    if let Some(p) = args.baseline.as_ref() {
+        let baseline = Baseline::load(p).expect("read baseline");
+        let (kept, n) = baseline.filter(std::mem::take(&mut report.issues));
+        report.issues = kept;
+        suppressed = Some(n);
+    }
+
     // Optional DOT graph export for signals
     if let Some(p) = args.signal_dot_out.as_ref() {
         let dot = signal_graph_dot(&root);
         std::fs::write(p, dot).expect("write signal dot");
     }
+    if let Some(p) = args.signal_json_out.as_ref() {
+        std::fs::write(p, serde_json::to_vec_pretty(&signal_graph_json(&root)).unwrap()).expect("write signal json");
+    }
 
     // Optional filtering by minimum severity for outputs
     let mut filtered: Option<GodotProjectReport> = None;
@@ -134,6 +246,7 @@ fn main() {
         println!("addons: {}", if out_ref.addons.is_empty() { "none".into() } else { out_ref.addons.join(", ") });
         println!("export presets: {}", if out_ref.export_presets.is_empty() { "none".into() } else { out_ref.export_presets.iter().map(|p| format!("{} ({})", p.name, p.platform)).collect::<Vec<_>>().join(", ") });
         println!("issues: {}", out_ref.issues.len());
+        if let Some(n) = suppressed { println!("suppressed by baseline: {}", n); }
     }
 
     if let Some(p) = args.sarif_out.as_ref() {
@@ -144,12 +257,21 @@ fn main() {
         let s = to_junit(out_ref);
         std::fs::write(p, s).expect("write junit");
     }
+    if let Some(p) = args.html_out.as_ref() {
+        std::fs::write(p, to_html(out_ref)).expect("write html");
+    }
+    if let Some(p) = args.code_quality_out.as_ref() {
+        std::fs::write(p, serde_json::to_vec_pretty(&to_code_quality(out_ref)).unwrap()).expect("write code quality");
+    }
 
-    if let Some(th) = args.fail_on.as_deref().and_then(parse_severity) {
+    if let Some(th) = args.fail_on.as_deref().and_then(parse_severity).or(config.thresholds.fail_on) {
         if report.issues.iter().any(|i| i.severity >= th) {
             std::process::exit(2);
         }
     }
+    if config.thresholds.max_issues.is_some_and(|max| report.issues.len() > max) {
+        std::process::exit(2);
+    }
 }
 
 fn parse_severity(s: &str) -> Option<Severity> {
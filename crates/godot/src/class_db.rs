@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Godot's built-in classes with their parent and the signals each declares (with their
+/// argument count, when given), bundled from `data/classes.txt`, and the properties of some.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClassDb {
+    classes: HashMap<String, (Option<String>, Vec<SignalDecl>)>,
+    properties: HashMap<String, Vec<String>>,
+}
+
+/// A signal's name and argument count.
+type SignalDecl = (String, Option<usize>);
+
+impl ClassDb {
+    /// The bundled database.
+    pub fn builtin() -> &'static ClassDb {
+        static DB: OnceLock<ClassDb> = OnceLock::new();
+        DB.get_or_init(|| ClassDb::parse(include_str!("../data/classes.txt")))
+    }
+
+    /// Lines of `Class < Parent : signal/args signal ... | property ...`; the parent, signal
+    /// list, argument counts and property list are optional and `#` starts a comment line.
+    pub fn parse(text: &str) -> Self {
+        let mut classes = HashMap::new();
+        let mut properties = HashMap::new();
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let (line, props) = match line.split_once('|') {
+                Some((l, p)) => (l.trim(), Some(p)),
+                None => (line, None),
+            };
+            let (head, signals) = line.split_once(':').unwrap_or((line, ""));
+            let (name, parent) = match head.split_once('<') {
+                Some((n, p)) => (n.trim(), Some(p.trim().to_string())),
+                None => (head.trim(), None),
+            };
+            let signals = signals.split_whitespace().map(|s| match s.split_once('/') {
+                Some((s, args)) => (s.to_string(), args.parse().ok()),
+                None => (s.to_string(), None),
+            });
+            if let Some(props) = props { properties.insert(name.to_string(), props.split_whitespace().map(str::to_string).collect()); }
+            classes.insert(name.to_string(), (parent, signals.collect()));
+        }
+        Self { classes, properties }
+    }
+
+    pub fn contains(&self, class: &str) -> bool { self.classes.contains_key(class) }
+
+    pub fn parent(&self, class: &str) -> Option<&str> {
+        self.classes.get(class)?.0.as_deref()
+    }
+
+    /// `class` followed by its ancestors up to Object; empty for an unknown class.
+    pub fn ancestors<'a>(&'a self, class: &'a str) -> Vec<&'a str> {
+        let mut out = Vec::new();
+        let mut cur = Some(class).filter(|c| self.contains(c));
+        while let Some(c) = cur.filter(|c| !out.contains(c)) {
+            out.push(c);
+            cur = self.parent(c);
+        }
+        out
+    }
+
+    /// True if `class` is `base` or derives from it.
+    pub fn inherits(&self, class: &str, base: &str) -> bool {
+        self.ancestors(class).contains(&base)
+    }
+
+    /// Signals of `class`, inherited ones included.
+    pub fn signals(&self, class: &str) -> Vec<&str> {
+        self.ancestors(class).into_iter()
+            .flat_map(|c| self.classes[c].1.iter().map(|(s, _)| s.as_str()))
+            .collect()
+    }
+
+    /// Number of arguments `class`'s `signal` passes (the nearest declaration wins); None when
+    /// the signal or its argument count is unknown.
+    pub fn signal_args(&self, class: &str, signal: &str) -> Option<usize> {
+        self.ancestors(class).into_iter()
+            .find_map(|c| self.classes[c].1.iter().find(|(s, _)| s == signal))
+            .and_then(|(_, args)| *args)
+    }
+
+    /// Whether `class` has `property`, inherited ones and `metadata/...` included; None when a
+    /// class up the chain doesn't list its properties, as only some are bundled.
+    pub fn has_property(&self, class: &str, property: &str) -> Option<bool> {
+        let chain = self.ancestors(class);
+        if chain.is_empty() { return None; }
+        if property.starts_with("metadata/") || (property.starts_with("theme_override_") && chain.contains(&"Control")) { return Some(true); }
+        let mut known = true;
+        for c in chain {
+            match self.properties.get(c) {
+                Some(props) if props.iter().any(|p| p == property) => return Some(true),
+                Some(_) => {}
+                None => known = false,
+            }
+        }
+        known.then_some(false)
+    }
+
+    pub fn has_signal(&self, class: &str, signal: &str) -> bool {
+        self.signals(class).contains(&signal)
+    }
+
+    /// Every class name, sorted.
+    pub fn class_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.classes.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Closest known class to a misspelled `name`: a case-insensitive match, else one within
+    /// two single-character edits.
+    pub fn suggest(&self, name: &str) -> Option<&str> {
+        let lower = name.to_lowercase();
+        if let Some(c) = self.class_names().into_iter().find(|c| c.to_lowercase() == lower) { return Some(c); }
+        self.class_names().into_iter()
+            .map(|c| (edit_distance(&lower, &c.to_lowercase()), c))
+            .filter(|&(d, _)| d <= 2)
+            .min()
+            .map(|(_, c)| c)
+    }
+}
+
+/// Levenshtein distance over chars.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            cur.push((prev[j] + usize::from(ca != *cb)).min(prev[j + 1] + 1).min(cur[j] + 1));
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
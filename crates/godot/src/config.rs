@@ -0,0 +1,176 @@
+use anyhow::{bail, Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::custom_rules::{validate_custom_rules, CustomRule};
+use crate::structure_fix::{MoveRule, RenameStyle, Strategy};
+use crate::{classify_rule_id, Issue, Severity};
+
+/// Name of the config file read from the project root.
+pub const CONFIG_FILE: &str = "godot-analyzer.toml";
+
+/// Team policy from `godot-analyzer.toml`. Rules are named by code (`debug-print`,
+/// `missing-script`, ...) or by SARIF rule id (`script-lint`, `scene-validator`, ...).
+///
+/// ```toml
+/// [rules]
+/// enable = ["script-lint", "signal-validator"]   # optional passes, as if their CLI flag was given
+/// disable = ["debug-print", "orphan-assets"]
+///
+/// [severity]
+/// missing-preload = "warn"
+/// "Duplicate connection" = "info"   # message prefix
+///
+/// [paths]
+/// exclude = ["addons/**", "prototypes/**/*.tscn"]
+///
+/// [thresholds]
+/// fail_on = "error"
+/// max_issues = 50
+/// max_function_lines = 80
+///
+/// [structure]
+/// rules = ["ui/**.gd => res://ui/scripts/", "**/*.gd => res://scripts/<filename>"]
+/// rename = "snake_case"
+/// strategy = "by-feature"
+///
+/// [[custom]]
+/// id = "absolute-get-node"
+/// pattern = 'get_node\("/root/'
+/// message = "Absolute /root path"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnalyzerConfig {
+    pub rules: RulesConfig,
+    /// Rule code, message prefix or rule id -> severity, tried in that order
+    pub severity: BTreeMap<String, Severity>,
+    pub paths: PathsConfig,
+    pub thresholds: Thresholds,
+    /// Project-specific regex rules, run by `analyze_project`
+    pub custom: Vec<CustomRule>,
+    pub structure: StructureConfig,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RulesConfig {
+    /// Optional passes to run (`scene-validator`, `signal-validator`, `script-lint`,
+    /// `orphan-assets`) and opt-in lint rules (`typed-gdscript` or its codes)
+    pub enable: Vec<String>,
+    pub disable: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PathsConfig {
+    /// Globs over root-relative paths (`/` separators) whose issues are dropped
+    pub exclude: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StructureConfig {
+    /// Layout rules for the structure fix, `<glob> => res://<destination>`, first match wins;
+    /// replace the built-in scripts/scenes/assets rules when set
+    pub rules: Vec<String>,
+    /// `none` (default) keeps file names; `snake_case` renames files to the style guide's names
+    pub rename: RenameStyle,
+    /// `by-type` (default) sorts files by the rules; `by-feature` groups each scene with its
+    /// scripts and assets under res://features/<scene>/
+    pub strategy: Strategy,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Thresholds {
+    /// Fail (exit 2) when an issue reaches this severity; `--fail-on` takes precedence
+    pub fail_on: Option<Severity>,
+    /// Fail when more issues than this remain after filtering
+    pub max_issues: Option<usize>,
+    /// Lines a function may span before `function-length` fires (default 50)
+    pub max_function_lines: Option<usize>,
+    /// Blocks a function body may nest before `nesting-depth` fires (default 4)
+    pub max_nesting_depth: Option<usize>,
+    /// Cyclomatic complexity a function may reach before `cyclomatic-complexity` fires (default 10)
+    pub max_complexity: Option<usize>,
+}
+
+impl AnalyzerConfig {
+    /// `<root>/godot-analyzer.toml`, or the defaults when there is none.
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(CONFIG_FILE);
+        if !path.exists() { return Ok(Self::default()); }
+        let text = fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("parse {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut config: Self = toml::from_str(text)?;
+        // `gd-linter`, the lint pass's earlier rule id, still names it
+        for r in config.rules.enable.iter_mut().chain(config.rules.disable.iter_mut()).filter(|r| *r == "gd-linter") {
+            *r = "script-lint".into();
+        }
+        if let Some(s) = config.severity.remove("gd-linter") { config.severity.entry("script-lint".into()).or_insert(s); }
+        config.exclude_set()?;
+        validate_custom_rules(&config.custom)?;
+        for rule in &config.structure.rules { MoveRule::parse(rule)?; }
+        Ok(config)
+    }
+
+    /// Whether `[rules] enable` turns on the optional pass `rule`.
+    pub fn enables(&self, rule: &str) -> bool {
+        self.rules.enable.iter().any(|r| r == rule)
+    }
+
+    /// Drop issues of disabled rules or excluded paths and apply severity overrides.
+    pub fn apply(&self, issues: &mut Vec<Issue>) {
+        let excluded = self.exclude_set().unwrap_or_else(|_| GlobSet::empty());
+        issues.retain(|i| {
+            let disabled = self.rules.disable.iter().any(|r| i.code.as_deref() == Some(r.as_str()) || r == classify_rule_id(i));
+            let skipped = i.file.as_ref().is_some_and(|f| excluded.is_match(f.to_string_lossy().replace('\\', "/")));
+            !disabled && !skipped
+        });
+        self.regrade(issues);
+    }
+
+    /// Apply the severity overrides alone.
+    pub fn regrade(&self, issues: &mut [Issue]) {
+        for i in issues.iter_mut() {
+            if let Some(sev) = self.severity_for(i) { i.severity = sev; }
+        }
+    }
+
+    /// Configured severity for an issue: by its code, else by the longest key its message
+    /// starts with (`Preload missing file`), else by its rule id.
+    pub fn severity_for(&self, issue: &Issue) -> Option<Severity> {
+        issue.code.as_deref().and_then(|c| self.severity.get(c))
+            .or_else(|| self.severity.iter().filter(|(k, _)| issue.message.starts_with(k.as_str())).max_by_key(|(k, _)| k.len()).map(|(_, s)| s))
+            .or_else(|| self.severity.get(classify_rule_id(issue)))
+            .copied()
+    }
+
+    /// Add a `rule=level` override (as given to `--severity`), replacing the configured one.
+    pub fn set_severity(&mut self, spec: &str) -> Result<()> {
+        let (rule, level) = spec.split_once('=').with_context(|| format!("expected <rule>=<level>, got '{}'", spec))?;
+        let sev = match level.trim().to_ascii_lowercase().as_str() {
+            "info" => Severity::Info,
+            "warn" | "warning" => Severity::Warn,
+            "error" => Severity::Error,
+            other => bail!("unknown severity '{}' in '{}' (info|warn|error)", other, spec),
+        };
+        self.severity.insert(rule.trim().to_string(), sev);
+        Ok(())
+    }
+
+    fn exclude_set(&self) -> Result<GlobSet> {
+        let mut b = GlobSetBuilder::new();
+        for pattern in &self.paths.exclude {
+            b.add(Glob::new(pattern).with_context(|| format!("invalid exclude glob '{}'", pattern))?);
+        }
+        Ok(b.build()?)
+    }
+}
@@ -0,0 +1,91 @@
+use anyhow::{bail, Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use regex::Regex;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::{Issue, Severity};
+
+/// A project-specific check declared in `godot-analyzer.toml`: every match of `pattern` in
+/// the files selected by `files` is reported with `message`.
+///
+/// ```toml
+/// [[custom]]
+/// id = "absolute-get-node"
+/// pattern = 'get_node\("/root/'
+/// files = ["**/*.gd"]
+/// message = "Absolute /root path — reach autoloads by name instead"
+/// severity = "warn"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomRule {
+    /// Rule code, as used by `[rules] disable`, `[severity]` and suppression comments
+    pub id: String,
+    /// Regex matched against the whole file (`(?m)` for `^`/`$` per line)
+    pub pattern: String,
+    /// Globs over root-relative paths; scripts, scenes and resources when empty
+    #[serde(default)]
+    pub files: Vec<String>,
+    pub message: String,
+    #[serde(default = "default_severity")]
+    pub severity: Severity,
+}
+
+fn default_severity() -> Severity { Severity::Warn }
+
+const DEFAULT_FILES: &[&str] = &["**/*.gd", "**/*.tscn", "**/*.tres"];
+
+impl CustomRule {
+    /// The compiled pattern and file set, or why they don't compile.
+    fn compile(&self) -> Result<(Regex, GlobSet)> {
+        if self.id.trim().is_empty() { bail!("custom rule without an id"); }
+        let re = Regex::new(&self.pattern).with_context(|| format!("custom rule '{}': invalid pattern", self.id))?;
+        let mut b = GlobSetBuilder::new();
+        let files: Vec<&str> = if self.files.is_empty() { DEFAULT_FILES.to_vec() } else { self.files.iter().map(String::as_str).collect() };
+        for f in files {
+            b.add(Glob::new(f).with_context(|| format!("custom rule '{}': invalid files glob '{}'", self.id, f))?);
+        }
+        Ok((re, b.build()?))
+    }
+}
+
+/// Check that every rule compiles.
+pub fn validate_custom_rules(rules: &[CustomRule]) -> Result<()> {
+    rules.iter().try_for_each(|r| r.compile().map(|_| ()))
+}
+
+/// Matches of `rules` across the project (`.godot` and `.git` skipped), one issue per match
+/// at its line and column, tagged with the rule's id. Matches on lines a suppression comment
+/// covers are dropped.
+pub fn run_custom_rules(root: &Path, rules: &[CustomRule]) -> Result<Vec<Issue>> {
+    if rules.is_empty() { return Ok(vec![]); }
+    let compiled: Vec<(&CustomRule, Regex, GlobSet)> = rules.iter()
+        .map(|r| r.compile().map(|(re, files)| (r, re, files)))
+        .collect::<Result<_>>()?;
+    let mut out = Vec::new();
+    for entry in WalkDir::new(root).into_iter().flatten() {
+        if !entry.file_type().is_file() { continue; }
+        let path = entry.path();
+        let rel = path.strip_prefix(root).unwrap_or(path);
+        if rel.starts_with(".godot") || rel.starts_with(".git") { continue; }
+        let rel_str = rel.to_string_lossy().replace('\\', "/");
+        let applicable: Vec<_> = compiled.iter().filter(|(_, _, files)| files.is_match(&rel_str)).collect();
+        if applicable.is_empty() { continue; }
+        let Ok(text) = fs::read_to_string(path) else { continue };
+        let supp = crate::suppress::Suppressions::read(path);
+        for (rule, re, _) in applicable {
+            for m in re.find_iter(&text) {
+                let before = &text[..m.start()];
+                let line = before.matches('\n').count() + 1;
+                let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+                if supp.suppresses(&rule.id, Some(line)) { continue; }
+                let issue = Issue { severity: rule.severity, ..Issue::warn(rule.message.clone(), Some(rel.to_path_buf())) };
+                out.push(issue.at(line, Some(column)).with_code(rule.id.clone()));
+            }
+        }
+    }
+    Ok(out)
+}
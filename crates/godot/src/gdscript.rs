@@ -0,0 +1,568 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+// --- Lexing ---
+
+/// One logical line of source: continuation lines (open brackets, trailing `\`) joined,
+/// comments removed. Blank and comment-only lines are dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    /// 1-based line the logical line starts on
+    pub line: usize,
+    /// Leading whitespace characters on that line
+    pub indent: usize,
+    /// Trimmed text; triple-quoted strings may still contain newlines
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind { Ident, Number, Str, Op }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    /// Source text; strings keep their quotes and any `&`/`^`/`r` prefix
+    pub text: &'a str,
+}
+
+impl Token<'_> {
+    pub fn is(&self, text: &str) -> bool { self.text == text }
+
+    /// Contents of a string token without prefix or quotes (escapes are left as written).
+    pub fn string_value(&self) -> Option<&str> {
+        if self.kind != TokenKind::Str { return None; }
+        let s = self.text.trim_start_matches(['&', '^', 'r']);
+        let q = if s.starts_with("\"\"\"") || s.starts_with("'''") { 3 } else { 1 };
+        s.get(q..s.len().checked_sub(q)?)
+    }
+}
+
+const OPERATORS: &[&str] = &[
+    "**=", "<<=", ">>=", "...", ":=", "->", "==", "!=", "<=", ">=", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=", "**", "&&", "||", "<<", ">>", "..",
+];
+
+/// Split one logical line into tokens. Whitespace is dropped; unknown characters become
+/// single-character `Op` tokens.
+pub fn tokens(text: &str) -> Vec<Token<'_>> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let start = i;
+        let kind = if b.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        } else if matches!(b, b'"' | b'\'') || (matches!(b, b'&' | b'^' | b'r') && matches!(bytes.get(i + 1), Some(b'"' | b'\''))) {
+            if b != b'"' && b != b'\'' { i += 1; }
+            i = string_end(bytes, i);
+            TokenKind::Str
+        } else if b.is_ascii_digit() || (b == b'.' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)) {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || bytes[i] == b'.') { i += 1; }
+            TokenKind::Number
+        } else if b.is_ascii_alphabetic() || b == b'_' || b >= 0x80 {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || bytes[i] >= 0x80) { i += 1; }
+            TokenKind::Ident
+        } else {
+            i += OPERATORS.iter().find(|op| text[i..].starts_with(*op)).map_or(1, |op| op.len());
+            TokenKind::Op
+        };
+        out.push(Token { kind, text: &text[start..i] });
+    }
+    out
+}
+
+/// Byte offset just past the string starting with the quote at `i` (or the end of input).
+fn string_end(bytes: &[u8], i: usize) -> usize {
+    let q = bytes[i];
+    let triple = bytes.get(i + 1) == Some(&q) && bytes.get(i + 2) == Some(&q);
+    let mut j = i + if triple { 3 } else { 1 };
+    while j < bytes.len() {
+        match bytes[j] {
+            b'\\' => j += 2,
+            c if c == q && (!triple || bytes[j..].starts_with(&[q, q, q])) => return j + if triple { 3 } else { 1 },
+            _ => j += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Logical lines of `src`, plus structural errors (unterminated strings, unbalanced brackets)
+/// as (1-based line, description).
+pub fn logical_lines(src: &str) -> (Vec<Line>, Vec<(usize, String)>) {
+    let bytes = src.as_bytes();
+    let mut lines = Vec::new();
+    let mut errors = Vec::new();
+    let mut brackets: Vec<(u8, usize)> = Vec::new();
+    let mut text: Vec<u8> = Vec::new();
+    let (mut line, mut start, mut indent) = (1, 1, 0);
+    let mut at_line_start = true;
+    let mut i = 0;
+    let flush = |text: &mut Vec<u8>, lines: &mut Vec<Line>, start: usize, indent: usize| {
+        let t = String::from_utf8_lossy(text).trim().to_string();
+        if !t.is_empty() { lines.push(Line { line: start, indent, text: t }); }
+        text.clear();
+    };
+    while i < bytes.len() {
+        let b = bytes[i];
+        if at_line_start {
+            if b == b' ' || b == b'\t' { indent += 1; i += 1; continue; }
+            at_line_start = false;
+            start = line;
+        }
+        match b {
+            b'\n' => {
+                line += 1;
+                i += 1;
+                let continued = !brackets.is_empty() || text.last() == Some(&b'\\');
+                if continued {
+                    if text.last() == Some(&b'\\') { text.pop(); }
+                    text.push(b' ');
+                    at_line_start = false;
+                } else {
+                    flush(&mut text, &mut lines, start, indent);
+                    indent = 0;
+                    at_line_start = true;
+                }
+                continue;
+            }
+            b'#' => {
+                while i < bytes.len() && bytes[i] != b'\n' { i += 1; }
+                continue;
+            }
+            b'"' | b'\'' => {
+                let end = string_end(bytes, i);
+                let s = &bytes[i..end];
+                let triple = s.len() >= 3 && s[..3].iter().all(|&c| c == b);
+                let closed = if triple { s.len() >= 6 && s.ends_with(&[b, b, b]) } else { s.len() >= 2 && s.ends_with(&[b]) && !s.contains(&b'\n') };
+                if !closed {
+                    errors.push((line, "unterminated string".to_string()));
+                    let stop = s.iter().position(|&c| c == b'\n').map_or(end, |p| i + p);
+                    text.extend_from_slice(&bytes[i..stop]);
+                    i = stop;
+                    continue;
+                }
+                line += s.iter().filter(|&&c| c == b'\n').count();
+                text.extend_from_slice(s);
+                i = end;
+                continue;
+            }
+            b'(' | b'[' | b'{' => brackets.push((b, line)),
+            b')' | b']' | b'}' => {
+                let open = match b { b')' => b'(', b']' => b'[', _ => b'{' };
+                match brackets.last() {
+                    Some(&(o, _)) if o == open => { brackets.pop(); }
+                    _ => errors.push((line, format!("unmatched '{}'", b as char))),
+                }
+            }
+            _ => {}
+        }
+        text.push(b);
+        i += 1;
+    }
+    for (b, l) in brackets.into_iter().rev() {
+        errors.push((l, format!("unclosed '{}'", b as char)));
+    }
+    flush(&mut text, &mut lines, start, indent);
+    errors.sort();
+    (lines, errors)
+}
+
+// --- Syntax tree ---
+
+/// A parsed GDScript file (or inner class): its header, member declarations and functions.
+/// The parser is lightweight: it follows indentation and declaration keywords and keeps
+/// expressions as text, tokenizable with `tokens`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Script {
+    pub extends: Option<String>,
+    pub class_name: Option<String>,
+    /// Annotations not attached to a declaration (`@tool`, `@icon(...)`)
+    pub annotations: Vec<Annotation>,
+    pub signals: Vec<Signal>,
+    /// Member `var`s and `const`s
+    pub vars: Vec<Var>,
+    pub enums: Vec<Enum>,
+    pub functions: Vec<Function>,
+    pub classes: Vec<InnerClass>,
+    /// Lines at class level that aren't declarations
+    pub statements: Vec<Stmt>,
+    /// Every logical line of the file, in order (top-level `Script` only)
+    pub lines: Vec<Line>,
+    /// Structural errors from lexing (top-level `Script` only)
+    pub errors: Vec<(usize, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    /// Without the `@`
+    pub name: String,
+    /// Text between the parentheses, if any
+    pub args: Option<String>,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signal {
+    pub name: String,
+    pub params: Vec<Param>,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Var {
+    pub name: String,
+    pub type_hint: Option<String>,
+    /// Declared with `:=`
+    pub inferred: bool,
+    pub value: Option<String>,
+    pub is_const: bool,
+    pub is_static: bool,
+    pub annotations: Vec<Annotation>,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Enum {
+    pub name: Option<String>,
+    pub values: Vec<String>,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Param {
+    pub name: String,
+    pub type_hint: Option<String>,
+    pub inferred: bool,
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<Param>,
+    pub return_type: Option<String>,
+    pub is_static: bool,
+    pub annotations: Vec<Annotation>,
+    pub line: usize,
+    /// Last line of the body
+    pub end_line: usize,
+    pub body: Vec<Stmt>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InnerClass {
+    pub name: String,
+    pub line: usize,
+    pub script: Script,
+}
+
+/// A statement in a function body (or a stray class-level line).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stmt {
+    pub line: usize,
+    /// Block nesting below the function body (0 = directly in it)
+    pub depth: usize,
+    pub kind: StmtKind,
+    /// The whole logical line
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StmtKind {
+    Var { name: String, type_hint: Option<String>, inferred: bool, value: Option<String>, is_const: bool },
+    For { var: String, iter: String },
+    If,
+    Elif,
+    Else,
+    While,
+    Match,
+    /// A pattern line directly under `match`
+    Branch,
+    Return,
+    Pass,
+    Break,
+    Continue,
+    Expr,
+}
+
+impl Script {
+    /// Read and parse the file at `path`.
+    pub fn read(path: &Path) -> Option<Self> {
+        fs::read_to_string(path).ok().map(|s| parse(&s))
+    }
+
+    pub fn function(&self, name: &str) -> Option<&Function> {
+        self.functions.iter().find(|f| f.name == name)
+    }
+
+    /// Names declared at class level: member vars and consts, signals, enums and their
+    /// values, functions and inner classes.
+    pub fn member_names(&self) -> Vec<&str> {
+        let mut out: Vec<&str> = self.vars.iter().map(|v| v.name.as_str()).collect();
+        out.extend(self.signals.iter().map(|s| s.name.as_str()));
+        for e in &self.enums {
+            out.extend(e.name.as_deref());
+            out.extend(e.values.iter().map(String::as_str));
+        }
+        out.extend(self.functions.iter().map(|f| f.name.as_str()));
+        out.extend(self.classes.iter().map(|c| c.name.as_str()));
+        out
+    }
+}
+
+/// class_name -> declaring script (root-relative) across the project's .gd files; the first
+/// script found wins when a name is declared twice.
+pub fn class_registry(root: &Path) -> HashMap<String, PathBuf> {
+    let mut out = HashMap::new();
+    for entry in WalkDir::new(root).into_iter().flatten() {
+        let path = entry.path();
+        if !entry.file_type().is_file() || path.extension().and_then(|s| s.to_str()) != Some("gd") { continue; }
+        let Some(name) = Script::read(path).and_then(|s| s.class_name) else { continue };
+        out.entry(name).or_insert_with(|| path.strip_prefix(root).unwrap_or(path).to_path_buf());
+    }
+    out
+}
+
+/// Parse GDScript source. Never fails: lines that don't fit a declaration become statements,
+/// and lexing problems are collected in `errors`.
+pub fn parse(src: &str) -> Script {
+    let (lines, errors) = logical_lines(src);
+    let mut script = parse_block(&lines);
+    script.lines = lines;
+    script.errors = errors;
+    script
+}
+
+/// Class-level declarations of `lines`, which all belong to one class body.
+fn parse_block(lines: &[Line]) -> Script {
+    let mut script = Script::default();
+    let base = lines.first().map_or(0, |l| l.indent);
+    let mut pending: Vec<Annotation> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let l = &lines[i];
+        // Body of the declaration on this line: the following lines indented deeper
+        let body_end = lines[i + 1..].iter().position(|n| n.indent <= l.indent).map_or(lines.len(), |p| i + 1 + p);
+        let body = &lines[i + 1..body_end];
+        i = body_end;
+        if l.indent > base {
+            // Stray indented line outside any block
+            script.statements.extend(statements(std::slice::from_ref(l)));
+            continue;
+        }
+        let (mut annotations, rest) = leading_annotations(&l.text, l.line);
+        pending.append(&mut annotations);
+        let rest = rest.trim();
+        if rest.is_empty() { continue; }
+        let (is_static, rest) = match rest.strip_prefix("static ") { Some(r) => (true, r.trim_start()), None => (false, rest) };
+        let word = rest.split(|c: char| !(c.is_alphanumeric() || c == '_')).next().unwrap_or("");
+        let after = rest[word.len()..].trim_start();
+        match word {
+            "extends" => {
+                script.annotations.append(&mut pending);
+                script.extends = Some(after.trim_end_matches(':').trim().to_string());
+            }
+            "class_name" => {
+                script.annotations.append(&mut pending);
+                let (name, ext) = match after.split_once(" extends ") { Some((n, e)) => (n, Some(e)), None => (after, None) };
+                script.class_name = Some(name.trim().to_string());
+                if let Some(e) = ext { script.extends = Some(e.trim().to_string()); }
+            }
+            "signal" => {
+                let (name, params) = match after.split_once('(') {
+                    Some((n, p)) => (n.trim(), split_params(p.rsplit_once(')').map_or(p, |(p, _)| p))),
+                    None => (after.trim(), Vec::new()),
+                };
+                script.signals.push(Signal { name: name.to_string(), params, line: l.line });
+                pending.clear();
+            }
+            "var" | "const" => {
+                let (name, type_hint, inferred, value) = split_decl(after);
+                script.vars.push(Var { name, type_hint, inferred, value, is_const: word == "const", is_static, annotations: std::mem::take(&mut pending), line: l.line });
+            }
+            "enum" => {
+                let (name, values) = after.split_once('{').unwrap_or((after, ""));
+                let values = values.trim_end().trim_end_matches('}');
+                let values = split_top_level(values, ',').into_iter()
+                    .map(|v| v.split('=').next().unwrap_or("").trim().to_string())
+                    .filter(|v| !v.is_empty())
+                    .collect();
+                let name = Some(name.trim()).filter(|n| !n.is_empty()).map(str::to_string);
+                script.enums.push(Enum { name, values, line: l.line });
+                pending.clear();
+            }
+            "func" => {
+                let mut f = parse_func(after, l, body);
+                f.is_static = is_static;
+                f.annotations = std::mem::take(&mut pending);
+                script.functions.push(f);
+            }
+            "class" => {
+                let name = after.split([' ', ':']).next().unwrap_or("").to_string();
+                let mut inner = parse_block(body);
+                if let Some((_, ext)) = after.trim_end_matches(':').split_once(" extends ") {
+                    inner.extends = Some(ext.trim().to_string());
+                }
+                script.classes.push(InnerClass { name, line: l.line, script: inner });
+                pending.clear();
+            }
+            _ => {
+                script.annotations.append(&mut pending);
+                script.statements.extend(statements(&lines[body_end - body.len() - 1..body_end]));
+            }
+        }
+    }
+    script.annotations.append(&mut pending);
+    script
+}
+
+/// `func` declaration text after the keyword, with its line and body lines.
+fn parse_func(after: &str, l: &Line, body: &[Line]) -> Function {
+    let name = after.split(|c: char| c == '(' || c.is_whitespace()).next().unwrap_or("").to_string();
+    let open = after.find('(').unwrap_or(after.len());
+    let close = matching_close(after, open).unwrap_or(after.len());
+    let params = split_params(after.get(open + 1..close).unwrap_or(""));
+    let tail = after.get(close + 1..).unwrap_or("");
+    // `-> Type:` then an optional one-line body after the colon
+    let colon = find_top_level(tail, |s| s.starts_with(':')).unwrap_or(tail.len());
+    let return_type = tail[..colon].trim().strip_prefix("->").map(|t| t.trim().to_string());
+    let inline = tail.get(colon + 1..).unwrap_or("").trim();
+    let mut body_stmts = Vec::new();
+    if !inline.is_empty() {
+        body_stmts.extend(statements(&[Line { line: l.line, indent: l.indent + 1, text: inline.to_string() }]));
+    }
+    body_stmts.extend(statements(body));
+    let end_line = body.last().map_or(l.line, |b| b.line + b.text.matches('\n').count());
+    Function { name, params, return_type, is_static: false, annotations: Vec::new(), line: l.line, end_line, body: body_stmts }
+}
+
+/// Statements of a block of lines, with nesting depth from indentation.
+fn statements(lines: &[Line]) -> Vec<Stmt> {
+    let mut out = Vec::new();
+    // (indent, the block opener was a `match`)
+    let mut stack: Vec<(usize, bool)> = Vec::new();
+    for l in lines {
+        while stack.last().is_some_and(|&(ind, _)| l.indent < ind) { stack.pop(); }
+        if stack.last().is_none_or(|&(ind, _)| l.indent > ind) {
+            let under_match = out.last().is_some_and(|s: &Stmt| s.kind == StmtKind::Match);
+            stack.push((l.indent, under_match));
+        }
+        let depth = stack.len() - 1;
+        let under_match = stack.last().is_some_and(|&(_, m)| m);
+        out.push(Stmt { line: l.line, depth, kind: stmt_kind(&l.text, under_match), text: l.text.clone() });
+    }
+    out
+}
+
+fn stmt_kind(text: &str, under_match: bool) -> StmtKind {
+    if under_match { return StmtKind::Branch; }
+    let word = text.split(|c: char| !(c.is_alphanumeric() || c == '_')).next().unwrap_or("");
+    let after = text[word.len()..].trim_start();
+    match word {
+        "var" | "const" => {
+            let (name, type_hint, inferred, value) = split_decl(after);
+            StmtKind::Var { name, type_hint, inferred, value, is_const: word == "const" }
+        }
+        "for" => {
+            let var = after.split(|c: char| !(c.is_alphanumeric() || c == '_')).next().unwrap_or("").to_string();
+            let iter = after.split_once(" in ").map_or("", |(_, it)| it).trim().trim_end_matches(':').trim().to_string();
+            StmtKind::For { var, iter }
+        }
+        "if" => StmtKind::If,
+        "elif" => StmtKind::Elif,
+        "else" => StmtKind::Else,
+        "while" => StmtKind::While,
+        "match" => StmtKind::Match,
+        "return" => StmtKind::Return,
+        "pass" => StmtKind::Pass,
+        "break" => StmtKind::Break,
+        "continue" => StmtKind::Continue,
+        _ => StmtKind::Expr,
+    }
+}
+
+/// `@name(args)` annotations at the start of `text`, and what follows them.
+fn leading_annotations(text: &str, line: usize) -> (Vec<Annotation>, &str) {
+    let mut out = Vec::new();
+    let mut rest = text;
+    while let Some(r) = rest.strip_prefix('@') {
+        let name_len = r.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(r.len());
+        let name = r[..name_len].to_string();
+        let mut after = &r[name_len..];
+        let mut args = None;
+        if after.starts_with('(') {
+            let close = matching_close(after, 0).unwrap_or(after.len() - 1);
+            args = Some(after[1..close].trim().to_string());
+            after = &after[(close + 1).min(after.len())..];
+        }
+        out.push(Annotation { name, args, line });
+        rest = after.trim_start();
+    }
+    (out, rest)
+}
+
+/// Declaration text after `var`/`const`: (name, type, declared with `:=`, value). A trailing
+/// `:` opening a setter/getter block is dropped from the type.
+fn split_decl(after: &str) -> (String, Option<String>, bool, Option<String>) {
+    let name_len = after.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(after.len());
+    let name = after[..name_len].to_string();
+    let rest = after[name_len..].trim_start();
+    if let Some(v) = rest.strip_prefix(":=") {
+        return (name, None, true, Some(v.trim().to_string()));
+    }
+    let (type_part, value) = match find_top_level(rest, |s| s.starts_with('=') && !s.starts_with("==")) {
+        Some(eq) => (&rest[..eq], Some(rest[eq + 1..].trim().to_string())),
+        None => (rest, None),
+    };
+    let type_hint = type_part.trim().strip_prefix(':').map(|t| t.trim().trim_end_matches(':').trim().to_string()).filter(|t| !t.is_empty());
+    (name, type_hint, false, value)
+}
+
+fn split_params(text: &str) -> Vec<Param> {
+    split_top_level(text, ',').into_iter()
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| {
+            let (name, type_hint, inferred, default) = split_decl(p.trim());
+            Param { name, type_hint, inferred, default }
+        })
+        .collect()
+}
+
+/// Byte offset of the bracket closing the one at `open`.
+fn matching_close(s: &str, open: usize) -> Option<usize> {
+    let rest = s.get(open + 1..)?;
+    find_top_level(rest, |t| t.starts_with([')', ']', '}'])).map(|p| open + 1 + p)
+}
+
+/// First byte offset outside strings and brackets where `pred` holds for the rest of `s`.
+fn find_top_level(s: &str, pred: impl Fn(&str) -> bool) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if depth == 0 && pred(&s[i..]) { return Some(i); }
+        match b {
+            b'"' | b'\'' => { i = string_end(bytes, i); continue; }
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+fn split_top_level(s: &str, sep: char) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut rest = s;
+    while let Some(p) = find_top_level(rest, |t| t.starts_with(sep)) {
+        out.push(&rest[..p]);
+        rest = &rest[p + sep.len_utf8()..];
+    }
+    out.push(rest);
+    out
+}
@@ -0,0 +1,59 @@
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+use crate::{Issue, Severity};
+
+/// Arguments for a headless engine run against `root`.
+/// With a script: `--check-only --script <script>` (parse check of one file);
+/// otherwise `--import`, which loads and imports the whole project and exits.
+pub fn headless_args(root: &Path, script: Option<&str>) -> Vec<String> {
+    let mut args = vec!["--headless".to_string(), "--path".to_string(), root.to_string_lossy().to_string()];
+    match script {
+        Some(s) => args.extend(["--check-only".to_string(), "--script".to_string(), s.to_string()]),
+        None => args.push("--import".to_string()),
+    }
+    args
+}
+
+/// Arguments for a headless export of `preset` to `target`
+/// (`--export-release`, or `--export-debug` when `debug`).
+pub fn export_args(root: &Path, preset: &str, debug: bool, target: &Path) -> Vec<String> {
+    vec![
+        "--headless".to_string(),
+        "--path".to_string(),
+        root.to_string_lossy().to_string(),
+        if debug { "--export-debug" } else { "--export-release" }.to_string(),
+        preset.to_string(),
+        target.to_string_lossy().to_string(),
+    ]
+}
+
+/// Map Godot's console output to Issues. Recognizes the engine's
+/// `SCRIPT ERROR:` / `ERROR:` / `USER ERROR:` / `WARNING:` headers and takes the file and line
+/// from the following `at: ... (res://file.gd:12)` line when present.
+pub fn parse_godot_output(output: &str) -> Vec<Issue> {
+    let re_head = Regex::new(r"^(SCRIPT ERROR|USER SCRIPT ERROR|USER ERROR|ERROR|WARNING|USER WARNING): (.*)$").unwrap();
+    let re_at = Regex::new(r"^\s*at: .*\((res://[^):]+)(?::(\d+))?\)\s*$").unwrap();
+    let mut out: Vec<Issue> = Vec::new();
+    let lines: Vec<&str> = output.lines().collect();
+    let mut i = 0;
+    while i < lines.len() {
+        let Some(cap) = re_head.captures(lines[i].trim_end()) else { i += 1; continue };
+        let severity = if cap[1].contains("WARNING") { Severity::Warn } else { Severity::Error };
+        let mut message = cap[2].trim().to_string();
+        let mut file: Option<PathBuf> = None;
+        let mut line_no = None;
+        if let Some(at) = lines.get(i + 1).and_then(|l| re_at.captures(l)) {
+            let res = &at[1];
+            file = Some(PathBuf::from(res.strip_prefix("res://").unwrap_or(res)));
+            if let Some(line) = at.get(2) {
+                message = format!("{} (line {})", message, line.as_str());
+                line_no = line.as_str().parse().ok();
+            }
+            i += 1;
+        }
+        out.push(Issue { severity, message, file, line: line_no, column: None, code: None });
+        i += 1;
+    }
+    out
+}
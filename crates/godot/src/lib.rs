@@ -1,19 +1,32 @@
 use anyhow::Result;
-use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+pub mod asset_sizes;
+pub mod autoload;
+pub mod baseline;
+pub mod class_db;
+pub mod config;
+pub mod custom_rules;
+pub mod gdscript;
+pub mod headless;
+pub mod orphan_assets;
+pub mod project_settings;
 pub mod scene_validate;
 pub mod signal_validate;
 pub mod structure_fix;
+pub mod suppress;
 pub mod script_lint;
+pub mod uid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct GodotProjectReport {
     pub project_path: PathBuf,
     pub project_format_version: Option<i32>,
+    /// `application/config/features` (engine version, renderer)
+    pub features: Vec<String>,
     pub addons: Vec<String>,
     pub export_presets: Vec<ExportPreset>,
     pub issues: Vec<Issue>,
@@ -34,42 +47,68 @@ impl Default for SceneCheckOptions {
     }
 }
 
+impl SceneCheckOptions {
+    /// Enable only the named checks (script, properties, subresource, preload, load).
+    /// An empty list enables all checks; an unknown name is returned as the error.
+    pub fn from_names<S: AsRef<str>>(names: &[S]) -> std::result::Result<Self, String> {
+        if names.is_empty() { return Ok(Self::default()); }
+        let mut opts = Self { script: false, properties: false, subresource: false, preload: false, load: false };
+        for n in names {
+            match n.as_ref() {
+                "script" => opts.script = true,
+                "properties" => opts.properties = true,
+                "subresource" => opts.subresource = true,
+                "preload" => opts.preload = true,
+                "load" => opts.load = true,
+                other => return Err(other.to_string()),
+            }
+        }
+        Ok(opts)
+    }
+}
+
+/// Project-level checks (project.godot, autoloads, addons, export presets, ext_resource
+/// paths, custom rules), filtered and re-graded by the project's `godot-analyzer.toml`.
 pub fn analyze_project(root: &Path) -> Result<GodotProjectReport> {
-    let mut report = GodotProjectReport::default();
-    report.project_path = root.to_path_buf();
+    let config = config::AnalyzerConfig::load(root)?;
+    let mut report = GodotProjectReport { project_path: root.to_path_buf(), ..Default::default() };
+    let uids = uid::UidMap::load(root);
 
-    // Detect engine version from project.godot
+    // Engine version, features, icon and main scene from project.godot
     let proj = root.join("project.godot");
-    if let Ok(s) = fs::read_to_string(&proj) {
-        for line in s.lines() {
-            if let Some(v) = line.strip_prefix("config_version=") {
-                let v = v.trim().trim_matches('\'');
-                if let Ok(n) = v.parse::<i32>() { report.project_format_version = Some(n); }
+    let proj_rel = Some(PathBuf::from("project.godot"));
+    if let Some(settings) = project_settings::ProjectSettings::load(root) {
+        report.project_format_version = settings.config_version().and_then(|n| i32::try_from(n).ok());
+        report.features = settings.features();
+        // config_version 5 is written by Godot 4, 4 by Godot 3
+        if let (Some(n), Some(v)) = (report.project_format_version, settings.engine_version()) {
+            let major = v.split('.').next().unwrap_or_default();
+            if matches!((n, major), (5, "3") | (4, "4")) {
+                report.issues.push(Issue::warn(format!("config/features engine version {} doesn't match config_version {}", v, n), proj_rel.clone()));
             }
         }
-        // Application icon and main scene checks (heuristic INI parsing)
-        let icon = find_ini_kv(&s, "config/icon");
-        if let Some(v) = icon {
-            if let Some(p) = v.strip_prefix("res://") {
-                let t = root.join(p);
-                if !t.exists() { report.issues.push(Issue::warn(format!("Missing application icon: {}", v), Some(proj.strip_prefix(root).unwrap_or(&proj).to_path_buf()))); }
-            }
-        } else {
-            report.issues.push(Issue::info("No application icon configured (config/icon)", Some(proj.strip_prefix(root).unwrap_or(&proj).to_path_buf())));
+        // uid:// values (written by 4.4+) resolve through the project's uid table; a dangling one counts as missing
+        let missing = |v: &str| {
+            let target = uids.res_path(v).and_then(|r| r.strip_prefix("res://"));
+            (v.starts_with("res://") || v.starts_with("uid://")) && !target.is_some_and(|p| root.join(p).exists())
+        };
+        match settings.icon() {
+            Some(v) if missing(v) => report.issues.push(Issue::warn(format!("Missing application icon: {}", v), proj_rel.clone())),
+            Some(_) => {}
+            None => report.issues.push(Issue::info("No application icon configured (config/icon)", proj_rel.clone())),
         }
-        let main_scene = find_ini_kv(&s, "run/main_scene");
-        if let Some(v) = main_scene {
-            if let Some(p) = v.strip_prefix("res://") {
-                let t = root.join(p);
-                if !t.exists() { report.issues.push(Issue::warn(format!("Missing main scene: {}", v), Some(proj.strip_prefix(root).unwrap_or(&proj).to_path_buf()))); }
-            }
-        } else {
-            report.issues.push(Issue::info("No main scene configured (run/main_scene)", Some(proj.strip_prefix(root).unwrap_or(&proj).to_path_buf())));
+        match settings.main_scene() {
+            Some(v) if missing(v) => report.issues.push(Issue::warn(format!("Missing main scene: {}", v), proj_rel.clone())),
+            Some(_) => {}
+            None => report.issues.push(Issue::info("No main scene configured (run/main_scene)", proj_rel.clone())),
         }
     } else {
         report.issues.push(Issue::warn("Missing project.godot", Some(proj.strip_prefix(root).unwrap_or(&proj).to_path_buf())));
     }
 
+    // Autoload singletons
+    report.issues.extend(autoload::validate_autoloads(root, &uids));
+
     // List addons
     let addons_dir = root.join("addons");
     if addons_dir.exists() {
@@ -112,7 +151,11 @@ pub fn analyze_project(root: &Path) -> Result<GodotProjectReport> {
     }
 
     // Scan .tscn and .tres for broken ext_resource paths
-    report.issues.extend(scan_broken_ext_resources(root)?);
+    report.issues.extend(scan_broken_ext_resources(root, &uids)?);
+
+    // Regex rules declared in godot-analyzer.toml
+    report.issues.extend(custom_rules::run_custom_rules(root, &config.custom)?);
+    config.apply(&mut report.issues);
 
     // Deterministic ordering for stable JSON
     report.addons.sort();
@@ -124,17 +167,24 @@ pub fn analyze_project(root: &Path) -> Result<GodotProjectReport> {
 
 /// Run GDScript lint and convert to Issue entries (warning severity by default)
 pub fn lint_gd(root: &Path) -> Vec<Issue> {
-    script_lint::lint_gd_scripts(root).into_iter()
-        .map(|f| match f.severity {
-            Severity::Info => Issue::info(f.message, Some(f.file)),
-            Severity::Warn => Issue::warn(f.message, Some(f.file)),
-            Severity::Error => Issue::error(f.message, Some(f.file)),
-        })
-        .collect()
+    with_config(root, script_lint::lint_gd_scripts(root).into_iter().map(lint_finding_as_issue).collect())
+}
+
+/// `issues` filtered and re-graded by the project's `godot-analyzer.toml`; an unreadable
+/// config is reported by `analyze_project` and treated as empty here.
+fn with_config(root: &Path, mut issues: Vec<Issue>) -> Vec<Issue> {
+    config::AnalyzerConfig::load(root).unwrap_or_default().apply(&mut issues);
+    issues
+}
+
+/// Convert a single lint finding into a report Issue, keeping its severity.
+pub fn lint_finding_as_issue(f: script_lint::LintFinding) -> Issue {
+    Issue { severity: f.severity, message: f.message, file: Some(f.file), line: f.line, column: f.column, code: Some(f.code) }
 }
 
 /// Run signal validation across .tscn files and convert to Issue entries.
 pub fn signal_issues_as_report(root: &Path) -> Vec<Issue> {
+    let uids = uid::UidMap::load(root);
     let mut out = Vec::new();
     for entry in WalkDir::new(root).into_iter().flatten() {
         let path = entry.path();
@@ -142,35 +192,73 @@ pub fn signal_issues_as_report(root: &Path) -> Vec<Issue> {
         let is_scene = matches!(path.extension().and_then(|s| s.to_str()), Some("tscn"));
         if !is_scene { continue; }
         let rel = path.strip_prefix(root).unwrap_or(path);
-        let sig_issues = signal_validate::validate_scene_signals(root, rel);
+        let sig_issues = signal_validate::validate_scene_signals_with(root, rel, &uids);
         for si in sig_issues {
             let mut msg = si.message.clone();
             if let Some(np) = si.node_path.as_ref() {
                 msg = format!("{} [node: {}]", msg, np);
             }
-            out.push(Issue::error(msg, Some(rel.to_path_buf())));
+            let issue = if signal_validate::FLAG_WARNINGS.contains(&si.code.as_str()) { Issue::warn(msg, Some(rel.to_path_buf())) } else { Issue::error(msg, Some(rel.to_path_buf())) };
+            out.push(issue.at(si.line, None).with_code(&si.code));
         }
     }
-    out
+    with_config(root, out)
+}
+
+/// Report assets nothing in the project references as info Issue entries.
+pub fn orphan_assets_as_report(root: &Path) -> Vec<Issue> {
+    let issues = orphan_assets::find_orphan_assets(root, &uid::UidMap::load(root)).into_iter()
+        .map(|a| {
+            let res = format!("res://{}", a.path.to_string_lossy().replace('\\', "/"));
+            Issue::info(format!("Unused asset: {} ({}, {} bytes)", res, a.kind, a.bytes), Some(a.path)).with_code("unused-asset")
+        })
+        .collect();
+    with_config(root, issues)
 }
 
 /// Build a DOT graph of signal connections across all .tscn files under root.
 pub fn signal_graph_dot(root: &Path) -> String {
-    let mut edges: Vec<signal_validate::ConnectionEdge> = Vec::new();
+    let edges: Vec<signal_validate::SignalGraphEdge> = signal_graph(root).into_iter()
+        .filter(signal_validate::SignalGraphEdge::is_resolved)
+        .collect();
+    signal_validate::signal_graph_to_dot(&edges)
+}
+
+/// Every connection in the project — scenes' `[connection]`s and scripts' `connect()` calls —
+/// with its line, flags and endpoint status, sorted. Scripts no scene attaches contribute
+/// edges of their own.
+pub fn signal_graph(root: &Path) -> Vec<signal_validate::SignalGraphEdge> {
+    let uids = uid::UidMap::load(root);
+    let mut edges = Vec::new();
+    let mut scripts = Vec::new();
     for entry in WalkDir::new(root).into_iter().flatten() {
         let path = entry.path();
         if !entry.file_type().is_file() { continue; }
-        if matches!(path.extension().and_then(|s| s.to_str()), Some("tscn")) {
-            let rel = path.strip_prefix(root).unwrap_or(path);
-            edges.extend(signal_validate::extract_scene_connections(root, rel));
+        let rel = path.strip_prefix(root).unwrap_or(path);
+        match path.extension().and_then(|s| s.to_str()) {
+            Some("tscn") => edges.extend(signal_validate::scene_signal_graph_with(root, rel, &uids)),
+            Some("gd") => scripts.push(rel.to_path_buf()),
+            _ => {}
         }
     }
+    // Attached scripts' calls are already in their scenes' edges
+    let attached: std::collections::HashSet<PathBuf> = edges.iter().filter_map(|e| e.edge.script.clone()).collect();
+    for script in scripts.iter().filter(|s| !attached.contains(*s)) {
+        edges.extend(signal_validate::script_signal_graph(root, script));
+    }
     // Sort once more globally to ensure deterministic output across files
     edges.sort();
-    signal_validate::connections_to_dot(&edges)
+    edges
 }
 
-/// Run scene validation across .tscn files and convert to Issue entries.
+/// The signal graph as JSON: `{ "edges": [{ scene, from, to, signal, method, line, flags,
+/// script?, origin, deferred, one_shot, from_status, to_status, from_id, to_id }] }`, origins being `scene` or
+/// `code` and statuses `resolved`, `missing` or `unknown`.
+pub fn signal_graph_json(root: &Path) -> serde_json::Value {
+    serde_json::json!({ "edges": signal_graph(root) })
+}
+
+/// Run scene validation across .tscn and .tres files and convert to Issue entries.
 /// Skips generic ext_resource path issues to avoid duplication with scan_broken_ext_resources.
 pub fn scene_issues_as_report(root: &Path) -> Vec<Issue> {
     scene_issues_as_report_with(root, &SceneCheckOptions::default())
@@ -179,59 +267,47 @@ pub fn scene_issues_as_report(root: &Path) -> Vec<Issue> {
 /// Same as scene_issues_as_report but allows selecting which checks to keep.
 pub fn scene_issues_as_report_with(root: &Path, opts: &SceneCheckOptions) -> Vec<Issue> {
     let mut out = Vec::new();
-    for entry in WalkDir::new(root).into_iter().flatten() {
-        let path = entry.path();
-        if !entry.file_type().is_file() { continue; }
-        let is_scene = matches!(path.extension().and_then(|s| s.to_str()), Some("tscn"));
-        if !is_scene { continue; }
-        let rel = path.strip_prefix(root).unwrap_or(path);
-        let scene_issues = scene_validate::validate_scene(root, rel);
-        for si in scene_issues {
-            // Avoid duplicating the broad ext_resource missing messages already emitted by scan_broken_ext_resources
-            if si.message.starts_with("Missing ext_resource path:") { continue; }
-
-            // Filter by selected checks
-            match scene_issue_kind(&si.message) {
-                SceneIssueKind::MissingScript | SceneIssueKind::ScriptExtResourceMissing | SceneIssueKind::UnknownExtResource => {
-                    if !opts.script { continue; }
-                }
-                SceneIssueKind::PropertyExtMissing => {
-                    if !opts.properties { continue; }
-                }
-                SceneIssueKind::UnknownSubResource => {
-                    if !opts.subresource { continue; }
-                }
-                SceneIssueKind::PreloadMissing => {
-                    if !opts.preload { continue; }
-                }
-                SceneIssueKind::LoadMissing => {
-                    if !opts.load { continue; }
-                }
-                SceneIssueKind::Other => {}
-            }
-            let mut msg = si.message.clone();
-            if let Some(np) = si.node_path.as_ref() {
-                msg = format!("{} [node: {}]", msg, np);
-            }
-            // Map all scene validator findings to Error for now
-            out.push(Issue::error(msg, Some(rel.to_path_buf())));
+    for si in validate_scenes_with(root, None, opts) {
+        // Avoid duplicating the broad ext_resource missing messages already emitted by scan_broken_ext_resources
+        if matches!(si.code.as_str(), "missing-ext-resource" | "dangling-uid") { continue; }
+        let mut msg = si.message.clone();
+        if let Some(np) = si.node_path.as_ref() {
+            msg = format!("{} [node: {}]", msg, np);
         }
+        // Map all scene validator findings to Error for now
+        out.push(Issue::error(msg, Some(si.file)).at(si.line, None).with_code(&si.code));
+    }
+    with_config(root, out)
+}
+
+/// Validate every .tscn and .tres under root (or only `scene`, relative to root) and keep the
+/// findings selected by `opts`. Unlike the report helpers this keeps line and node path.
+pub fn validate_scenes_with(root: &Path, scene: Option<&Path>, opts: &SceneCheckOptions) -> Vec<scene_validate::SceneIssue> {
+    let scenes: Vec<PathBuf> = match scene {
+        Some(rel) => vec![rel.to_path_buf()],
+        None => WalkDir::new(root).into_iter().flatten()
+            .filter(|e| e.file_type().is_file())
+            .filter(|e| matches!(e.path().extension().and_then(|s| s.to_str()), Some("tscn" | "tres")))
+            .map(|e| e.path().strip_prefix(root).unwrap_or(e.path()).to_path_buf())
+            .collect(),
+    };
+    let uids = uid::UidMap::load(root);
+    let mut out = Vec::new();
+    for rel in scenes {
+        out.extend(scene_validate::validate_scene_with_uids(root, &rel, &uids).into_iter().filter(|si| scene_check_enabled(&si.code, opts)));
     }
     out
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum SceneIssueKind { MissingScript, ScriptExtResourceMissing, UnknownExtResource, PropertyExtMissing, UnknownSubResource, PreloadMissing, LoadMissing, Other }
-
-fn scene_issue_kind(msg: &str) -> SceneIssueKind {
-    if msg.starts_with("Missing script:") { return SceneIssueKind::MissingScript; }
-    if msg.starts_with("Script ExtResource(") { return SceneIssueKind::ScriptExtResourceMissing; }
-    if msg.starts_with("Unknown ExtResource id:") { return SceneIssueKind::UnknownExtResource; }
-    if msg.starts_with("Property '") { return SceneIssueKind::PropertyExtMissing; }
-    if msg.starts_with("Unknown SubResource id:") { return SceneIssueKind::UnknownSubResource; }
-    if msg.starts_with("Preload missing file:") { return SceneIssueKind::PreloadMissing; }
-    if msg.starts_with("Load missing file:") { return SceneIssueKind::LoadMissing; }
-    SceneIssueKind::Other
+fn scene_check_enabled(code: &str, opts: &SceneCheckOptions) -> bool {
+    match code {
+        "missing-script" | "unknown-ext-resource" => opts.script,
+        "missing-property-resource" => opts.properties,
+        "unknown-sub-resource" => opts.subresource,
+        "missing-preload" => opts.preload,
+        "missing-load" => opts.load,
+        _ => true,
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
@@ -239,15 +315,49 @@ pub struct ExportPreset { pub name: String, pub platform: String, pub export_pat
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "lowercase")]
-pub enum Severity { Info, Warn, Error }
+pub enum Severity { Info, #[serde(alias = "warning")] Warn, Error }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-pub struct Issue { pub severity: Severity, pub message: String, pub file: Option<PathBuf> }
+pub struct Issue {
+    pub severity: Severity,
+    pub message: String,
+    pub file: Option<PathBuf>,
+    /// 1-based line in `file`, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// 1-based column on `line`, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    /// Rule code (`debug-print`, `missing-script`, ...), for checks that have one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
 
 impl Issue {
-    pub fn info<M: Into<String>>(msg: M, file: Option<PathBuf>) -> Self { Self { severity: Severity::Info, message: msg.into(), file } }
-    pub fn warn<M: Into<String>>(msg: M, file: Option<PathBuf>) -> Self { Self { severity: Severity::Warn, message: msg.into(), file } }
-    pub fn error<M: Into<String>>(msg: M, file: Option<PathBuf>) -> Self { Self { severity: Severity::Error, message: msg.into(), file } }
+    pub fn info<M: Into<String>>(msg: M, file: Option<PathBuf>) -> Self { Self { severity: Severity::Info, message: msg.into(), file, line: None, column: None, code: None } }
+    pub fn warn<M: Into<String>>(msg: M, file: Option<PathBuf>) -> Self { Self { severity: Severity::Warn, message: msg.into(), file, line: None, column: None, code: None } }
+    pub fn error<M: Into<String>>(msg: M, file: Option<PathBuf>) -> Self { Self { severity: Severity::Error, message: msg.into(), file, line: None, column: None, code: None } }
+
+    /// The same issue located at `line` (and `column`) of its file.
+    pub fn at(self, line: usize, column: Option<usize>) -> Self { Self { line: Some(line), column, ..self } }
+
+    /// The same issue tagged with a rule code.
+    pub fn with_code<C: Into<String>>(self, code: C) -> Self { Self { code: Some(code.into()), ..self } }
+
+    /// `file:line:column`, as far as known.
+    pub fn location(&self) -> String {
+        let mut s = self.file.as_ref().map(|p| p.display().to_string()).unwrap_or_default();
+        if let Some(l) = self.line { s.push_str(&format!(":{}", l)); }
+        if let (Some(_), Some(c)) = (self.line, self.column) { s.push_str(&format!(":{}", c)); }
+        s
+    }
+}
+
+/// Presets declared in `<root>/export_presets.cfg` (empty if the file is missing).
+pub fn read_export_presets(root: &Path) -> Result<Vec<ExportPreset>> {
+    let path = root.join("export_presets.cfg");
+    if !path.exists() { return Ok(Vec::new()); }
+    parse_export_presets(&path)
 }
 
 fn parse_export_presets(path: &Path) -> Result<Vec<ExportPreset>> {
@@ -272,43 +382,44 @@ fn parse_export_presets(path: &Path) -> Result<Vec<ExportPreset>> {
     Ok(out)
 }
 
-fn trim_value(v: &str) -> String { v.trim().trim_matches('\'').to_string() }
+fn trim_value(v: &str) -> String { v.trim().trim_matches(|c| c == '\'' || c == '"').to_string() }
 
-fn scan_broken_ext_resources(root: &Path) -> Result<Vec<Issue>> {
+fn scan_broken_ext_resources(root: &Path, uids: &uid::UidMap) -> Result<Vec<Issue>> {
     let mut out = Vec::new();
-    let re = Regex::new(r#"^\[ext_resource\s+[^\]]*path=\"([^\"]+)\""#).unwrap();
     for entry in WalkDir::new(root).into_iter().flatten() {
         let path = entry.path();
         if !entry.file_type().is_file() { continue; }
         let is_scene = matches!(path.extension().and_then(|s| s.to_str()), Some("tscn" | "tres"));
         if !is_scene { continue; }
         let Ok(content) = fs::read_to_string(path) else { continue };
-        for line in content.lines() {
-            if let Some(caps) = re.captures(line) {
-                let p = caps.get(1).map(|m| m.as_str()).unwrap_or("");
-                if p.starts_with("uid://") { continue; }
-                if p.starts_with("res://") {
-                    let rel = &p[6..];
-                    let target = root.join(rel);
-                    if !target.exists() {
-                        out.push(Issue::error(format!("Missing ext_resource path: {}", p), Some(path.strip_prefix(root).unwrap_or(path).to_path_buf())));
-                    }
+        let doc = scene_validate::SceneDocument::parse(&content);
+        let file = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+        let supp = suppress::Suppressions::parse(&content, ';');
+        let start = out.len();
+        for section in doc.sections("ext_resource") {
+            let Some(p) = section.attr_str("path") else { continue };
+            let Some(res) = uids.res_path(p) else {
+                out.push(Issue::error(format!("Dangling uid: {}", p), Some(file.clone())).at(section.line, None).with_code("dangling-uid"));
+                continue;
+            };
+            if let Some(rel) = res.strip_prefix("res://") {
+                let target = root.join(rel);
+                if !target.exists() {
+                    let shown = if res == p { p.to_string() } else { format!("{} ({})", res, p) };
+                    out.push(Issue::error(format!("Missing ext_resource path: {}", shown), Some(file.clone())).at(section.line, None).with_code("missing-ext-resource"));
                 }
             }
+            // A uid attribute Godot can't resolve makes it fall back to the text path
+            if let Some(u) = section.attr_str("uid").filter(|u| u.starts_with("uid://") && uids.resolve(u).is_none()) {
+                out.push(Issue::warn(format!("Dangling uid: {} (falls back to path {})", u, p), Some(file.clone())).at(section.line, None).with_code("dangling-uid"));
+            }
         }
+        let kept: Vec<Issue> = out.drain(start..).filter(|i| !supp.suppresses(i.code.as_deref().unwrap_or_default(), i.line)).collect();
+        out.extend(kept);
     }
     Ok(out)
 }
 
-fn find_ini_kv(contents: &str, key: &str) -> Option<String> {
-    // Search for lines like key="res://..." possibly with section headers above
-    for line in contents.lines() {
-        let line = line.trim();
-        if let Some(v) = line.strip_prefix(&format!("{key}=")) { return Some(trim_value(v)); }
-    }
-    None
-}
-
 // --- Outputs ---
 pub fn to_sarif(report: &GodotProjectReport) -> serde_json::Value {
     let results: Vec<serde_json::Value> = report.issues.iter().map(|i| {
@@ -318,7 +429,7 @@ pub fn to_sarif(report: &GodotProjectReport) -> serde_json::Value {
             "ruleId": rule_id,
             "level": level,
             "message": {"text": i.message},
-            "locations": [{ "physicalLocation": { "artifactLocation": { "uri": i.file.as_ref().map(|p| p.to_string_lossy().to_string()).unwrap_or_default() } } }]
+            "locations": [{ "physicalLocation": sarif_location(i) }]
         })
     }).collect();
     serde_json::json!({
@@ -331,7 +442,9 @@ pub fn to_sarif(report: &GodotProjectReport) -> serde_json::Value {
                     {"id": "godot-analyzer", "name": "godot-analyzer", "shortDescription": {"text": "Godot project configuration checks"}},
                     {"id": "scene-validator", "name": "scene-validator", "shortDescription": {"text": "Godot scene (.tscn) validation checks"}},
                     {"id": "signal-validator", "name": "signal-validator", "shortDescription": {"text": "Godot scene signal connection checks"}},
-                    {"id": "gd-linter", "name": "gd-linter", "shortDescription": {"text": "GDScript code style and safety checks"}}
+                    {"id": "script-lint", "name": "script-lint", "shortDescription": {"text": "GDScript code style and safety checks"}},
+                    {"id": "autoload-validator", "name": "autoload-validator", "shortDescription": {"text": "project.godot autoload singleton checks"}},
+                    {"id": "orphan-assets", "name": "orphan-assets", "shortDescription": {"text": "Assets nothing in the project references"}}
                 ]
             }},
             "results": results
@@ -339,37 +452,28 @@ pub fn to_sarif(report: &GodotProjectReport) -> serde_json::Value {
     })
 }
 
+/// SARIF physicalLocation: the artifact, plus a region when the line (and column) is known.
+fn sarif_location(i: &Issue) -> serde_json::Value {
+    let uri = i.file.as_ref().map(|p| p.to_string_lossy().replace('\\', "/")).unwrap_or_default();
+    let mut loc = serde_json::json!({ "artifactLocation": { "uri": uri } });
+    if let Some(line) = i.line {
+        loc["region"] = serde_json::json!({ "startLine": line });
+        if let Some(col) = i.column { loc["region"]["startColumn"] = col.into(); }
+    }
+    loc
+}
+
+/// SARIF rule id (and JUnit classname) of an issue, by the module its code comes from.
 fn classify_rule_id(i: &Issue) -> &'static str {
-    // Heuristic mapping: known scene validator signatures get a distinct rule id
-    let msg = i.message.as_str();
-    if msg.starts_with("Missing script:")
-        || msg.starts_with("Script ExtResource(")
-    || msg.starts_with("Unknown ExtResource id:")
-    || msg.starts_with("Property '")
-    || msg.starts_with("Unknown SubResource id:")
-    || msg.starts_with("Preload missing file:")
-    || msg.starts_with("Load missing file:")
-    {
-        "scene-validator"
-    } else if msg.starts_with("Unknown connection '")
-        || msg.starts_with("Connection missing ")
-        || msg.starts_with("Duplicate connection:")
-        || msg.starts_with("Invalid method name:")
-        || msg.starts_with("Target method not found:")
-    {
-        "signal-validator"
-    } else if msg.starts_with("Class name mismatch:")
-        || msg == "Debug print found"
-        || msg == "Tab indentation used"
-        || msg == "Missing extends declaration"
-        || msg.starts_with("GDScript preload missing file:")
-        || msg.starts_with("GDScript load missing file:")
-        || msg.starts_with("GDScript ") && msg.contains(" missing file:")
-    {
-        "gd-linter"
-    } else {
-        // Default to the core analyzer
-        "godot-analyzer"
+    match i.code.as_deref() {
+        // The core ext_resource scan reports these; the scene validator's copies are dropped
+        Some("missing-ext-resource" | "dangling-uid") | None => "godot-analyzer",
+        Some(c) if scene_validate::CODES.contains(&c) => "scene-validator",
+        Some(c) if signal_validate::CODES.contains(&c) => "signal-validator",
+        Some(c) if script_lint::CODES.contains(&c) => "script-lint",
+        Some(c) if autoload::CODES.contains(&c) => "autoload-validator",
+        Some("unused-asset") => "orphan-assets",
+        Some(_) => "godot-analyzer",
     }
 }
 
@@ -378,10 +482,10 @@ pub fn to_junit(report: &GodotProjectReport) -> String {
     s.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
     s.push_str(&format!("<testsuite name=\"godot-analyzer\" tests=\"{}\">\n", report.issues.len()));
     for i in &report.issues {
-        let name = format!("{}", i.message);
-    let class_name = match classify_rule_id(i) { "scene-validator" => "scene-validator", "signal-validator" => "signal-validator", "gd-linter" => "gd-linter", _ => "godot-analyzer" };
+        let name = i.message.clone();
+    let class_name = match classify_rule_id(i) { "scene-validator" => "scene-validator", "signal-validator" => "signal-validator", "script-lint" => "script-lint", "autoload-validator" => "autoload-validator", "orphan-assets" => "orphan-assets", _ => "godot-analyzer" };
     s.push_str(&format!("  <testcase name=\"{}\" classname=\"{}\">\n", xml_escape(&name), class_name));
-        s.push_str(&format!("    <failure message=\"{:?}\">{}</failure>\n", i.severity, xml_escape(&i.file.as_ref().map(|p| p.display().to_string()).unwrap_or_default())));
+        s.push_str(&format!("    <failure message=\"{:?}\">{}</failure>\n", i.severity, xml_escape(&i.location())));
         s.push_str("  </testcase>\n");
     }
     s.push_str("</testsuite>\n");
@@ -389,3 +493,86 @@ pub fn to_junit(report: &GodotProjectReport) -> String {
 }
 
 fn xml_escape(input: &str) -> String { input.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;") }
+
+/// GitLab Code Quality (Code Climate) report: one entry per issue, located at its file and
+/// line (project-wide issues on project.godot, line 1). Fingerprints are the baseline
+/// fingerprints, so they survive line moves; repeats of a finding get `-2`, `-3`, ...
+pub fn to_code_quality(report: &GodotProjectReport) -> serde_json::Value {
+    let mut seen: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+    let entries: Vec<serde_json::Value> = report.issues.iter().map(|i| {
+        let rule = classify_rule_id(i);
+        let base = baseline::fingerprint(i);
+        let n = seen.entry(base.clone()).or_default();
+        *n += 1;
+        let fingerprint = if *n == 1 { base } else { format!("{}-{}", base, n) };
+        let severity = match i.severity { Severity::Info => "info", Severity::Warn => "minor", Severity::Error => "major" };
+        let category = match rule {
+            _ if i.code.as_deref().is_some_and(|c| c.starts_with("perf-")) => "Performance",
+            "script-lint" => "Style",
+            "orphan-assets" => "Clarity",
+            _ => "Bug Risk",
+        };
+        let path = i.file.as_ref().map(|p| p.to_string_lossy().replace('\\', "/")).unwrap_or_else(|| "project.godot".into());
+        serde_json::json!({
+            "type": "issue",
+            "check_name": rule,
+            "description": i.message,
+            "categories": [category],
+            "severity": severity,
+            "fingerprint": fingerprint,
+            "location": { "path": path, "lines": { "begin": i.line.unwrap_or(1) } }
+        })
+    }).collect();
+    serde_json::Value::Array(entries)
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;margin:2em;color:#222}h1{font-size:1.4em}\
+.counts span{margin-right:1.5em}.filters label{margin-right:1em}details{margin:.5em 0;border:1px solid #ddd;border-radius:4px}\
+summary{padding:.4em .6em;background:#f6f6f6;cursor:pointer;font-family:monospace}table{border-collapse:collapse;width:100%}\
+td{padding:.25em .6em;border-top:1px solid #eee;vertical-align:top}td.loc{font-family:monospace;white-space:nowrap}\
+.sev{font-weight:bold;text-transform:uppercase;font-size:.8em}.error .sev{color:#b00020}.warn .sev{color:#a86400}.info .sev{color:#1a5fb4}\
+.rule{color:#777;font-size:.85em}";
+
+const HTML_SCRIPT: &str = "function applyFilters(){var on={};document.querySelectorAll('.filters input').forEach(function(c){on[c.value]=c.checked});\
+document.querySelectorAll('tr.issue').forEach(function(r){r.hidden=!on[r.dataset.severity]});\
+document.querySelectorAll('details.file').forEach(function(d){var n=d.querySelectorAll('tr.issue:not([hidden])').length;d.hidden=n===0;d.querySelector('.shown').textContent=n})}\
+document.querySelectorAll('.filters input').forEach(function(c){c.addEventListener('change',applyFilters)});";
+
+/// Standalone HTML page for the report: issue counts by severity, checkboxes filtering by
+/// severity, and issues grouped per file (project-wide issues under "(project)"), errors first.
+pub fn to_html(report: &GodotProjectReport) -> String {
+    let esc = |s: &str| xml_escape(s).replace('"', "&quot;");
+    let sev_name = |s: Severity| match s { Severity::Info => "info", Severity::Warn => "warn", Severity::Error => "error" };
+    let mut files: std::collections::BTreeMap<String, Vec<&Issue>> = std::collections::BTreeMap::new();
+    for i in &report.issues {
+        let file = i.file.as_ref().map(|p| p.to_string_lossy().replace('\\', "/")).unwrap_or_else(|| "(project)".into());
+        files.entry(file).or_default().push(i);
+    }
+    let count = |s: Severity| report.issues.iter().filter(|i| i.severity == s).count();
+    let title = format!("godot-analyzer: {}", report.project_path.display());
+
+    let mut s = String::new();
+    s.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    s.push_str(&format!("<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{}</h1>\n", esc(&title), HTML_STYLE, esc(&title)));
+    s.push_str(&format!("<p class=\"counts\"><span>issues: <b>{}</b></span><span class=\"error\">errors: <b>{}</b></span><span class=\"warn\">warnings: <b>{}</b></span><span class=\"info\">info: <b>{}</b></span><span>files: <b>{}</b></span></p>\n",
+        report.issues.len(), count(Severity::Error), count(Severity::Warn), count(Severity::Info), files.len()));
+    s.push_str("<p class=\"filters\">");
+    for (value, label) in [("error", "Errors"), ("warn", "Warnings"), ("info", "Info")] {
+        s.push_str(&format!("<label><input type=\"checkbox\" value=\"{}\" checked> {}</label>", value, label));
+    }
+    s.push_str("</p>\n");
+    if files.is_empty() { s.push_str("<p>No issues found.</p>\n"); }
+    for (file, mut issues) in files {
+        issues.sort_by(|a, b| b.severity.cmp(&a.severity).then(a.line.cmp(&b.line)).then(a.message.cmp(&b.message)));
+        s.push_str(&format!("<details class=\"file\" open>\n<summary>{} (<span class=\"shown\">{}</span>)</summary>\n<table>\n", esc(&file), issues.len()));
+        for i in issues {
+            let loc = match (i.line, i.column) { (Some(l), Some(c)) => format!("{}:{}", l, c), (Some(l), None) => l.to_string(), _ => String::new() };
+            let sev = sev_name(i.severity);
+            s.push_str(&format!("<tr class=\"issue {}\" data-severity=\"{}\"><td class=\"sev\">{}</td><td class=\"loc\">{}</td><td>{} <span class=\"rule\">{}</span></td></tr>\n",
+                sev, sev, sev, loc, esc(&i.message), classify_rule_id(i)));
+        }
+        s.push_str("</table>\n</details>\n");
+    }
+    s.push_str(&format!("<script>{}</script>\n</body>\n</html>\n", HTML_SCRIPT));
+    s
+}
@@ -0,0 +1,91 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::gdscript::{self, TokenKind};
+use crate::uid::UidMap;
+
+/// An asset that no scene, resource, script or project setting references.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrphanAsset {
+    /// Root-relative path
+    pub path: PathBuf,
+    /// texture, audio, scene, script, resource, font, model or shader
+    pub kind: String,
+    pub bytes: u64,
+}
+
+/// Asset kind by extension; None for files that aren't checked.
+pub(crate) fn asset_kind(ext: &str) -> Option<&'static str> {
+    Some(match ext.to_ascii_lowercase().as_str() {
+        "png" | "jpg" | "jpeg" | "webp" | "svg" | "bmp" | "tga" | "exr" | "hdr" | "ktx" => "texture",
+        "wav" | "ogg" | "mp3" => "audio",
+        "tscn" | "scn" => "scene",
+        "gd" | "cs" => "script",
+        "tres" | "res" => "resource",
+        "ttf" | "otf" | "woff" | "woff2" | "fnt" => "font",
+        "glb" | "gltf" | "obj" | "fbx" | "blend" => "model",
+        "gdshader" | "gdshaderinc" | "shader" => "shader",
+        _ => return None,
+    })
+}
+
+/// Files Godot loads without a reference (the default audio bus layout).
+const IMPLICIT: &[&str] = &["default_bus_layout.tres"];
+
+/// Build the set of `res://` paths referenced from project.godot, export presets, scenes,
+/// resources, scripts and GDExtension files (uid:// resolved through `uids`; a file's
+/// references to itself don't count), then list assets outside it. Scripts declaring a
+/// `class_name` count as referenced when another script or scene names that class. Addons,
+/// `.godot` and `.git` are skipped.
+pub fn find_orphan_assets(root: &Path, uids: &UidMap) -> Vec<OrphanAsset> {
+    let re_ref = Regex::new(r#"(?:res|uid)://[^"'\s)\]]+"#).unwrap();
+    let mut files: Vec<(String, PathBuf)> = Vec::new();
+    let mut referenced: HashSet<String> = IMPLICIT.iter().map(|p| format!("res://{}", p)).collect();
+    // Identifiers used per file, for class_name lookups: (res path, identifiers)
+    let mut idents: Vec<(String, HashSet<String>)> = Vec::new();
+    for entry in WalkDir::new(root).into_iter().flatten() {
+        let path = entry.path();
+        if !entry.file_type().is_file() { continue; }
+        let rel = path.strip_prefix(root).unwrap_or(path);
+        if rel.starts_with(".godot") || rel.starts_with(".git") { continue; }
+        let res = format!("res://{}", rel.to_string_lossy().replace('\\', "/"));
+        let ext = rel.extension().and_then(|s| s.to_str()).unwrap_or("");
+        let is_text_source = matches!(ext, "tscn" | "tres" | "gd" | "godot" | "cfg" | "gdextension" | "gdshader" | "gdshaderinc");
+        if is_text_source {
+            let Ok(text) = fs::read_to_string(path) else { continue };
+            for m in re_ref.find_iter(&text) {
+                let target = uids.res_path(m.as_str()).unwrap_or(m.as_str());
+                if target != res { referenced.insert(target.to_string()); }
+            }
+            if matches!(ext, "gd" | "tscn" | "tres") {
+                let used = gdscript::logical_lines(&text).0.iter()
+                    .flat_map(|l| gdscript::tokens(&l.text).into_iter().filter(|t| t.kind == TokenKind::Ident).map(|t| t.text.to_string()).collect::<Vec<_>>())
+                    .collect();
+                idents.push((res.clone(), used));
+            }
+        }
+        if rel.starts_with("addons") { continue; }
+        if asset_kind(ext).is_some() { files.push((res, rel.to_path_buf())); }
+    }
+
+    let mut out = Vec::new();
+    for (res, rel) in files {
+        if referenced.contains(&res) { continue; }
+        if rel.extension().and_then(|s| s.to_str()) == Some("gd") {
+            let class_name = gdscript::Script::read(&root.join(&rel)).and_then(|s| s.class_name);
+            if let Some(name) = class_name {
+                if idents.iter().any(|(other, used)| *other != res && used.contains(&name)) { continue; }
+            }
+        }
+        let ext = rel.extension().and_then(|s| s.to_str()).unwrap_or("");
+        let kind = asset_kind(ext).unwrap_or("asset").to_string();
+        let bytes = fs::metadata(root.join(&rel)).map(|m| m.len()).unwrap_or(0);
+        out.push(OrphanAsset { path: rel, kind, bytes });
+    }
+    out.sort_by(|a, b| a.path.cmp(&b.path));
+    out
+}
@@ -0,0 +1,68 @@
+use std::fs;
+use std::path::Path;
+
+use crate::scene_validate::{Property, SceneDocument, Value};
+
+/// project.godot read as a ConfigFile: settings are addressed the way Godot names them,
+/// `<section>/<key>` (`application/config/icon`, `autoload/Game`), with typed `Value`s.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectSettings {
+    doc: SceneDocument,
+}
+
+impl ProjectSettings {
+    pub fn parse(text: &str) -> Self {
+        Self { doc: SceneDocument::parse(text) }
+    }
+
+    /// `<root>/project.godot`; None if it can't be read.
+    pub fn load(root: &Path) -> Option<Self> {
+        fs::read_to_string(root.join("project.godot")).ok().map(|s| Self::parse(&s))
+    }
+
+    /// `config_version`: a top-level key, also accepted inside a section as older hand-written
+    /// files have it.
+    pub fn config_version(&self) -> Option<i64> {
+        let in_sections = self.doc.sections.iter().flat_map(|s| &s.properties);
+        self.doc.preamble.iter().chain(in_sections).find(|p| p.key == "config_version")?.value.as_int()
+    }
+
+    /// The property behind `setting` (`section/key`, where the key may itself contain `/`).
+    pub fn property(&self, setting: &str) -> Option<&Property> {
+        let (section, key) = setting.split_once('/')?;
+        self.doc.sections.iter().filter(|s| s.kind == section).find_map(|s| s.property(key))
+    }
+
+    pub fn get(&self, setting: &str) -> Option<&Value> {
+        self.property(setting).map(|p| &p.value)
+    }
+
+    pub fn get_str(&self, setting: &str) -> Option<&str> {
+        self.get(setting)?.as_str()
+    }
+
+    /// Properties of every `[name]` section, in file order.
+    pub fn section(&self, name: &str) -> Vec<&Property> {
+        self.doc.sections.iter().filter(|s| s.kind == name).flat_map(|s| &s.properties).collect()
+    }
+
+    /// `application/config/name`
+    pub fn name(&self) -> Option<&str> { self.get_str("application/config/name") }
+
+    /// `application/config/icon`
+    pub fn icon(&self) -> Option<&str> { self.get_str("application/config/icon") }
+
+    /// `application/run/main_scene`
+    pub fn main_scene(&self) -> Option<&str> { self.get_str("application/run/main_scene") }
+
+    /// `application/config/features`, e.g. `["4.5", "GL Compatibility"]`.
+    pub fn features(&self) -> Vec<String> {
+        let features = self.get("application/config/features").and_then(Value::as_strings);
+        features.unwrap_or_default().into_iter().map(str::to_string).collect()
+    }
+
+    /// Engine version listed in `features` (`4.5`), if any.
+    pub fn engine_version(&self) -> Option<String> {
+        self.features().into_iter().find(|f| f.split('.').all(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit())))
+    }
+}
@@ -1,156 +1,843 @@
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::class_db::ClassDb;
+use crate::gdscript;
+use crate::suppress::Suppressions;
+use crate::uid::UidMap;
+
+/// Codes of every finding `validate_scene` reports.
+pub const CODES: &[&str] = &[
+    "malformed-header", "missing-resource-header", "dangling-uid", "missing-ext-resource", "unknown-node-type",
+    "missing-script", "missing-instance", "recursive-instance", "missing-property-resource", "unknown-ext-resource",
+    "unknown-sub-resource", "missing-preload", "missing-load", "orphaned-override", "override-type-mismatch",
+    "script-override-mismatch", "animation-missing-node", "animation-missing-property", "dangling-node-path",
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct SceneIssue {
     pub file: PathBuf,
     pub line: usize,
     pub node_path: Option<String>,
+    /// Code naming the finding in suppression comments and severity overrides
+    pub code: String,
     pub message: String,
 }
 
-// Minimal validator: flags missing script for nodes with script="res://..." in .tscn
+// --- Text scene model ---
+
+/// A Godot text scene or resource (.tscn/.tres), or any file in the same ConfigFile syntax
+/// (project.godot, .import), parsed into its `[section]` headers and the `key = value`
+/// properties under each. Parsing is lenient: a line that can't be read as a header or
+/// property is skipped, headers being noted in `malformed`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SceneDocument {
+    /// Properties before the first header (project.godot's `config_version`)
+    pub preamble: Vec<Property>,
+    pub sections: Vec<Section>,
+    /// `[...]` lines that couldn't be parsed as a header, as (1-based line, trimmed text)
+    pub malformed: Vec<(usize, String)>,
+}
+
+/// One `[kind attr=value ...]` header with the properties that follow it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    /// `gd_scene`, `ext_resource`, `sub_resource`, `node`, `connection`, ...; in config files
+    /// the section name (`application`, `preset.0.options`)
+    pub kind: String,
+    /// 1-based line of the header
+    pub line: usize,
+    pub attrs: Vec<(String, Value)>,
+    pub properties: Vec<Property>,
+}
+
+/// A `key = value` line; keys may contain `/` (`tracks/0/path`, `metadata/foo`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property {
+    pub key: String,
+    pub value: Value,
+    /// 1-based line the property starts on; the value may continue over later lines
+    pub line: usize,
+}
+
+/// A Variant literal as written in the file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// `"text"`, also `&"StringName"` and `^"NodePath"`, escapes resolved
+    Str(String),
+    /// Number as written (`3`, `-0.5`, `1e-05`)
+    Number(String),
+    /// Bare word: `true`, `false`, `null`, `inf`, or an unquoted attribute value
+    Ident(String),
+    Array(Vec<Value>),
+    Dict(Vec<(Value, Value)>),
+    /// Constructor such as `Vector2(1, 2)`, `ExtResource("1_abc")` or `Array[int]([1])`; the
+    /// `"prop": value` arguments of `Object(Type, ...)` are kept as one-entry `Dict`s
+    Call { name: String, args: Vec<Value> },
+}
+
+impl SceneDocument {
+    pub fn parse(text: &str) -> Self {
+        let mut p = Parser { src: text, pos: 0, line: 1 };
+        let mut preamble = Vec::new();
+        let mut malformed = Vec::new();
+        let mut sections: Vec<Section> = Vec::new();
+        loop {
+            p.skip_ws();
+            let Some(b) = p.peek() else { break };
+            let (pos, line) = (p.pos, p.line);
+            if b == b';' {
+                p.skip_line();
+                continue;
+            }
+            if b == b'[' {
+                match p.section() {
+                    Some(s) => sections.push(s),
+                    None => {
+                        p.pos = pos;
+                        p.line = line;
+                        let text = text[pos..].lines().next().unwrap_or_default().trim();
+                        malformed.push((line, text.to_string()));
+                        p.skip_line();
+                    }
+                }
+                continue;
+            }
+            match p.property() {
+                Some(prop) => match sections.last_mut() {
+                    Some(s) => s.properties.push(prop),
+                    None => preamble.push(prop),
+                },
+                None => { p.pos = pos; p.line = line; p.skip_line(); }
+            }
+        }
+        Self { preamble, sections, malformed }
+    }
+
+    /// Parse the file at `path`; None if it can't be read.
+    pub fn read(path: &Path) -> Option<Self> {
+        fs::read_to_string(path).ok().map(|s| Self::parse(&s))
+    }
+
+    pub fn sections<'a>(&'a self, kind: &'a str) -> impl Iterator<Item = &'a Section> + 'a {
+        self.sections.iter().filter(move |s| s.kind == kind)
+    }
+
+    /// ext_resource id -> (path, header line). Godot 3 numeric and Godot 4 string ids alike.
+    pub fn ext_resources(&self) -> HashMap<String, (String, usize)> {
+        self.sections("ext_resource")
+            .filter_map(|s| Some((s.attr_str("id")?.to_string(), (s.attr_str("path")?.to_string(), s.line))))
+            .collect()
+    }
+
+    pub fn sub_resource_ids(&self) -> HashSet<String> {
+        self.sections("sub_resource").filter_map(|s| s.attr_str("id")).map(str::to_string).collect()
+    }
+
+    /// Every node of the scene with `instance=ExtResource(...)` nodes expanded into the
+    /// instanced scene's tree, recursively; a root node with `instance` is an inherited scene.
+    /// Nodes re-declared here (overrides, editable children) are merged with the instanced
+    /// ones. Instances that can't be read or that recurse contribute only their own node.
+    pub fn node_tree(&self, root: &Path, uids: &UidMap) -> Vec<SceneNode> {
+        let mut out = Vec::new();
+        expand_nodes(self, root, uids, None, None, &mut Vec::new(), &mut out);
+        out
+    }
+}
+
+fn expand_nodes(doc: &SceneDocument, root: &Path, uids: &UidMap, prefix: Option<&str>, source: Option<&str>, stack: &mut Vec<String>, out: &mut Vec<SceneNode>) {
+    let ext: HashMap<String, (String, usize)> = doc.ext_resources().into_iter()
+        .map(|(id, (p, line))| (id, (uids.res_path(&p).unwrap_or(&p).to_string(), line)))
+        .collect();
+    for node in doc.sections("node") {
+        let Some(local) = node.node_path() else { continue };
+        let path = match prefix {
+            None | Some(".") => local,
+            Some(pre) if local == "." => pre.to_string(),
+            Some(pre) => format!("{}/{}", pre, local),
+        };
+        let instance = node.attr("instance").and_then(|v| ext.get(v.resource_id("ExtResource")?)).map(|(p, _)| p.clone());
+        if let Some(inst) = instance.as_deref().filter(|p| !stack.iter().any(|s| s == p)) {
+            let inst_doc = inst.strip_prefix("res://").and_then(|rel| SceneDocument::read(&root.join(rel)));
+            if let Some(inst_doc) = inst_doc {
+                stack.push(inst.to_string());
+                expand_nodes(&inst_doc, root, uids, Some(&path), Some(inst), stack, out);
+                stack.pop();
+            }
+        }
+        let properties = node.properties.iter().map(|p| (p.key.clone(), p.value.clone())).collect();
+        let declared = SceneNode { path, ty: node.attr_str("type").map(str::to_string), script: node.script_path(&ext), instance, source: source.map(str::to_string), line: node.line, properties };
+        match out.iter_mut().find(|n| n.path == declared.path) {
+            Some(n) => {
+                n.ty = declared.ty.or(n.ty.take());
+                n.script = declared.script.or(n.script.take());
+                n.instance = declared.instance.or(n.instance.take());
+                n.source = declared.source;
+                n.line = declared.line;
+                for (key, value) in declared.properties {
+                    match n.properties.iter_mut().find(|(k, _)| *k == key) {
+                        Some(slot) => slot.1 = value,
+                        None => n.properties.push((key, value)),
+                    }
+                }
+            }
+            None => out.push(declared),
+        }
+    }
+}
+
+/// A node of a scene's full tree: the scene's own `[node]`s plus those brought in by
+/// `instance=` (instanced and inherited scenes), at their path in this scene.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneNode {
+    /// Path relative to the scene root (`.` for the root)
+    pub path: String,
+    /// `type`, from the outermost declaration that sets it
+    pub ty: Option<String>,
+    /// `res://` script, the outermost override winning
+    pub script: Option<String>,
+    /// `res://` scene this node instances
+    pub instance: Option<String>,
+    /// `res://` scene of the outermost declaration; None for the scene itself
+    pub source: Option<String>,
+    /// Line of that declaration in `source`
+    pub line: usize,
+    /// Effective property values (overrides replace instanced values)
+    pub properties: Vec<(String, Value)>,
+}
+
+/// Path of the node `rel` (`$`/`get_node` syntax, `.` and `..` allowed) reached from the node
+/// at `base`, both relative to the scene root; None when it leaves the scene.
+pub(crate) fn resolve_node_path(base: &str, rel: &str) -> Option<String> {
+    let mut parts: Vec<&str> = if base == "." { vec![] } else { base.split('/').collect() };
+    for part in rel.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => { parts.pop()?; }
+            p => parts.push(p),
+        }
+    }
+    Some(if parts.is_empty() { ".".to_string() } else { parts.join("/") })
+}
+
+/// Whether `path` lies under an instance whose scene couldn't be expanded into `tree`, where
+/// the tree can't tell if the node exists.
+pub(crate) fn under_unexpanded_instance(tree: &[SceneNode], path: &str) -> bool {
+    let parts: Vec<&str> = path.split('/').collect();
+    (1..parts.len()).map(|k| parts[..k].join("/")).any(|prefix| {
+        tree.iter().any(|n| n.path == prefix && n.instance.is_some()) && !tree.iter().any(|n| n.path.starts_with(&format!("{}/", prefix)))
+    })
+}
+
+impl Section {
+    pub fn attr(&self, key: &str) -> Option<&Value> {
+        self.attrs.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    pub fn attr_str(&self, key: &str) -> Option<&str> {
+        self.attr(key)?.as_str()
+    }
+
+    pub fn property(&self, key: &str) -> Option<&Property> {
+        self.properties.iter().find(|p| p.key == key)
+    }
+
+    /// Header attributes then properties, as (key, value, line).
+    pub fn assignments(&self) -> impl Iterator<Item = (&str, &Value, usize)> {
+        let attrs = self.attrs.iter().map(|(k, v)| (k.as_str(), v, self.line));
+        attrs.chain(self.properties.iter().map(|p| (p.key.as_str(), &p.value, p.line)))
+    }
+
+    /// Path of a `[node]` relative to the scene root: `.` for the root (no `parent`), `name`
+    /// under `parent="."`, `parent/name` otherwise. An explicit `path` attribute wins.
+    pub fn node_path(&self) -> Option<String> {
+        if self.kind != "node" { return None; }
+        if let Some(p) = self.attr_str("path") { return Some(p.to_string()); }
+        let name = self.attr_str("name")?;
+        Some(match self.attr_str("parent") {
+            None => ".".to_string(),
+            Some(".") => name.to_string(),
+            Some(parent) => format!("{}/{}", parent, name),
+        })
+    }
+
+    /// The node's script as a `res://` path: `script="res://..."` or `script = ExtResource(id)`
+    /// resolved through `ext`.
+    pub fn script_path(&self, ext: &HashMap<String, (String, usize)>) -> Option<String> {
+        let v = self.attr("script").or_else(|| self.property("script").map(|p| &p.value))?;
+        let path = match v {
+            Value::Str(s) => s.as_str(),
+            v => ext.get(v.resource_id("ExtResource")?).map(|(p, _)| p.as_str())?,
+        };
+        path.starts_with("res://").then(|| path.to_string())
+    }
+}
+
+impl Value {
+    /// Text of a string, number or bare word.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(s) | Value::Number(s) | Value::Ident(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Value::Number(n) => n.parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Ident(w) if w == "true" => Some(true),
+            Value::Ident(w) if w == "false" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Strings of a `PackedStringArray(...)` or an array; None for anything else or if an
+    /// element isn't a string.
+    pub fn as_strings(&self) -> Option<Vec<&str>> {
+        let items = match self {
+            Value::Array(items) => items.as_slice(),
+            v => v.call_args("PackedStringArray")?,
+        };
+        items.iter().map(|v| match v { Value::Str(s) => Some(s.as_str()), _ => None }).collect()
+    }
+
+    /// Arguments of a `name(...)` call.
+    pub fn call_args(&self, name: &str) -> Option<&[Value]> {
+        match self {
+            Value::Call { name: n, args } if n == name => Some(args),
+            _ => None,
+        }
+    }
+
+    /// Id in `ExtResource(id)` / `SubResource(id)` (pass the constructor name).
+    pub fn resource_id(&self, ctor: &str) -> Option<&str> {
+        self.call_args(ctor)?.first()?.as_str()
+    }
+
+    /// This value and every value nested in it, depth first.
+    pub fn nested(&self) -> Vec<&Value> {
+        let mut out = vec![self];
+        match self {
+            Value::Array(items) | Value::Call { args: items, .. } => out.extend(items.iter().flat_map(Value::nested)),
+            Value::Dict(entries) => out.extend(entries.iter().flat_map(|(k, v)| k.nested().into_iter().chain(v.nested()))),
+            _ => {}
+        }
+        out
+    }
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    line: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> { self.src.as_bytes().get(self.pos).copied() }
+
+    fn bump(&mut self) -> Option<u8> {
+        let b = self.peek()?;
+        self.pos += 1;
+        if b == b'\n' { self.line += 1; }
+        Some(b)
+    }
+
+    fn eat(&mut self, b: u8) -> bool {
+        if self.peek() == Some(b) { self.bump(); true } else { false }
+    }
+
+    fn skip_ws(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\r' | b'\n')) { self.bump(); }
+    }
+
+    fn skip_inline_ws(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\r')) { self.bump(); }
+    }
+
+    fn skip_line(&mut self) {
+        while let Some(b) = self.bump() { if b == b'\n' { break; } }
+    }
+
+    fn take_while(&mut self, f: impl Fn(u8) -> bool) -> &str {
+        let start = self.pos;
+        while self.peek().is_some_and(&f) { self.bump(); }
+        &self.src[start..self.pos]
+    }
+
+    fn word(&mut self) -> Option<String> {
+        let w = self.take_while(|b| b.is_ascii_alphanumeric() || b == b'_');
+        (!w.is_empty()).then(|| w.to_string())
+    }
+
+    /// `[kind key=value ...]`; attribute values may span lines (e.g. `binds=[...]`).
+    fn section(&mut self) -> Option<Section> {
+        let line = self.line;
+        self.eat(b'[');
+        let kind = self.take_while(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'.' | b'/' | b'-')).to_string();
+        if kind.is_empty() { return None; }
+        let mut attrs = Vec::new();
+        loop {
+            self.skip_ws();
+            if self.eat(b']') { break; }
+            let key = self.word()?;
+            self.skip_inline_ws();
+            if !self.eat(b'=') { return None; }
+            attrs.push((key, self.value()?));
+        }
+        self.rest_of_line_blank();
+        Some(Section { kind, line, attrs, properties: Vec::new() })
+    }
+
+    /// `key = value` where the value may continue over following lines.
+    fn property(&mut self) -> Option<Property> {
+        let line = self.line;
+        let key = self.take_while(|b| b != b'=' && b != b'\n').trim().to_string();
+        if key.is_empty() || !self.eat(b'=') { return None; }
+        let value = self.value()?;
+        self.rest_of_line_blank();
+        Some(Property { key, value, line })
+    }
+
+    /// Consume the rest of the current line, tolerating trailing junk after a complete item.
+    fn rest_of_line_blank(&mut self) {
+        self.skip_inline_ws();
+        if !matches!(self.peek(), None | Some(b'\n')) { self.skip_line(); }
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        self.skip_inline_ws();
+        match self.peek()? {
+            b'"' => self.string().map(Value::Str),
+            b'&' | b'^' => { self.bump(); self.string().map(Value::Str) }
+            b'[' => self.list(b']').map(Value::Array),
+            b'{' => self.dict(),
+            b'-' | b'+' | b'.' | b'0'..=b'9' => {
+                let n = self.take_while(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'+'));
+                Some(Value::Number(n.to_string()))
+            }
+            b if b.is_ascii_alphabetic() || b == b'_' => {
+                let mut name = self.word()?;
+                if self.peek() == Some(b'[') {
+                    // Typed container: keep the element type as part of the name
+                    let start = self.pos;
+                    self.list(b']')?;
+                    name.push_str(&self.src[start..self.pos]);
+                }
+                self.skip_inline_ws();
+                if self.peek() == Some(b'(') {
+                    let args = self.list(b')')?;
+                    return Some(Value::Call { name, args });
+                }
+                Some(Value::Ident(name))
+            }
+            _ => None,
+        }
+    }
+
+    /// Comma-separated values from the opening bracket at `pos` up to `close`.
+    fn list(&mut self, close: u8) -> Option<Vec<Value>> {
+        self.bump();
+        let mut items = Vec::new();
+        loop {
+            self.skip_ws();
+            if self.eat(close) { return Some(items); }
+            let item = self.value()?;
+            self.skip_ws();
+            if self.eat(b':') {
+                let v = self.value()?;
+                items.push(Value::Dict(vec![(item, v)]));
+                self.skip_ws();
+            } else {
+                items.push(item);
+            }
+            if !self.eat(b',') {
+                self.skip_ws();
+                return self.eat(close).then_some(items);
+            }
+        }
+    }
+
+    fn dict(&mut self) -> Option<Value> {
+        self.bump();
+        let mut entries = Vec::new();
+        loop {
+            self.skip_ws();
+            if self.eat(b'}') { return Some(Value::Dict(entries)); }
+            let key = self.value()?;
+            self.skip_ws();
+            if !self.eat(b':') { return None; }
+            entries.push((key, self.value()?));
+            self.skip_ws();
+            if !self.eat(b',') {
+                self.skip_ws();
+                return self.eat(b'}').then_some(Value::Dict(entries));
+            }
+        }
+    }
+
+    /// A double-quoted string, which may contain raw newlines.
+    fn string(&mut self) -> Option<String> {
+        if !self.eat(b'"') { return None; }
+        let mut out = Vec::new();
+        loop {
+            match self.bump()? {
+                b'"' => return Some(String::from_utf8_lossy(&out).into_owned()),
+                b'\\' => match self.bump()? {
+                    b'n' => out.push(b'\n'),
+                    b't' => out.push(b'\t'),
+                    b'r' => out.push(b'\r'),
+                    b => out.push(b),
+                },
+                b => out.push(b),
+            }
+        }
+    }
+}
+
+// --- Validation ---
+
+/// Check a .tscn or .tres: header lines that don't parse (and, for a .tres, a missing
+/// `[gd_resource]` header), then its references: ext_resource paths, `script` (path or ExtResource), properties
+/// assigned an ExtResource, SubResource ids anywhere in a value, and `preload`/`load` of
+/// `res://` paths, whether written as values or inside embedded script text. `uid://` paths
+/// are resolved through the project's `UidMap`; ones it doesn't know are reported as dangling.
 pub fn validate_scene(root: &Path, scene_rel: &Path) -> Vec<SceneIssue> {
-    let path = root.join(scene_rel);
-    let Ok(text) = fs::read_to_string(&path) else { return vec![] };
+    validate_scene_with_uids(root, scene_rel, &UidMap::load(root))
+}
+
+/// The chain of instanced scenes leading from `start` back to `target` (`target -> start ->
+/// ... -> target`), if `start` instances `target` directly or through other scenes.
+fn instance_cycle(root: &Path, uids: &UidMap, target: &str, start: &str) -> Option<Vec<String>> {
+    let mut stack = vec![vec![target.to_string(), start.to_string()]];
+    let mut seen = HashSet::new();
+    while let Some(chain) = stack.pop() {
+        let last = chain.last()?.clone();
+        if last == target { return Some(chain); }
+        if !seen.insert(last.clone()) { continue; }
+        let Some(doc) = last.strip_prefix("res://").and_then(|rel| SceneDocument::read(&root.join(rel))) else { continue };
+        let ext = doc.ext_resources();
+        for node in doc.sections("node") {
+            let Some((p, _)) = node.attr("instance").and_then(|v| ext.get(v.resource_id("ExtResource")?)) else { continue };
+            let mut next = chain.clone();
+            next.push(uids.res_path(p).unwrap_or(p).to_string());
+            stack.push(next);
+        }
+    }
+    None
+}
+
+/// `validate_scene` with an already loaded uid table, for validating many scenes.
+pub fn validate_scene_with_uids(root: &Path, scene_rel: &Path, uids: &UidMap) -> Vec<SceneIssue> {
+    let Some(doc) = SceneDocument::read(&root.join(scene_rel)) else { return vec![] };
     let mut out = Vec::new();
-    let mut current_node_path: Option<String> = None;
-    let re_ext_line = Regex::new(r#"^\s*\[ext_resource\b"#).unwrap();
-    let re_ext_id = Regex::new(r#"id\s*=\s*(\d+)"#).unwrap();
-    let re_ext_path_attr = Regex::new(r#"path\s*=\s*\"([^\"]+)\""#).unwrap();
-    let re_sub_line = Regex::new(r#"^\s*\[sub_resource\b"#).unwrap();
-    let re_sub_id = Regex::new(r#"id\s*=\s*(\d+)"#).unwrap();
-    let re_script_ext = Regex::new(r#"script\s*=\s*ExtResource\(\"(\d+)\"\)"#).unwrap();
-    let re_prop_ext = Regex::new(r#"(?P<prop>[A-Za-z0-9_]+)\s*=\s*ExtResource\(\"(?P<id>\d+)\"\)"#).unwrap();
-    let re_prop_sub = Regex::new(r#"(?P<prop>[A-Za-z0-9_]+)\s*=\s*SubResource\(\"?(?P<id>\d+)\"?\)"#).unwrap();
-    let re_any_sub = Regex::new(r#"SubResource\(\"?(?P<id>\d+)\"?\)"#).unwrap();
+    let issue = |line: usize, node_path: Option<String>, code: &str, message: String| SceneIssue { file: scene_rel.to_path_buf(), line, node_path, code: code.to_string(), message };
+    let missing = |p: &str| p.strip_prefix("res://").is_some_and(|res| !root.join(res).exists());
     let re_preload = Regex::new(r#"preload\(\"(res://[^\"]+)\"\)"#).unwrap();
     let re_load = Regex::new(r#"\bload\(\"(res://[^\"]+)\"\)"#).unwrap();
-    let mut ext_map: HashMap<String, (String, usize)> = HashMap::new();
-    let mut sub_ids: HashMap<String, usize> = HashMap::new();
-    for (i, line) in text.lines().enumerate() {
-        let lno = i + 1;
-        // ext_resource declarations
-        if re_ext_line.is_match(line) {
-            let id = re_ext_id
-                .captures(line)
-                .and_then(|c| c.get(1).map(|m| m.as_str().to_string()));
-            let p = re_ext_path_attr
-                .captures(line)
-                .and_then(|c| c.get(1).map(|m| m.as_str().to_string()));
-            if let (Some(id), Some(p)) = (id, p) {
-                ext_map.insert(id.clone(), (p.clone(), lno));
-                if let Some(res) = p.strip_prefix("res://") {
-                    let target = root.join(res);
-                    if !target.exists() {
-                        out.push(SceneIssue { file: scene_rel.to_path_buf(), line: lno, node_path: None, message: format!("Missing ext_resource path: {}", p) });
-                    }
+    // ext_resource paths with uid:// resolved where the uid is known
+    let ext_map: HashMap<String, (String, usize)> = doc.ext_resources().into_iter()
+        .map(|(id, (p, line))| (id, (uids.res_path(&p).unwrap_or(&p).to_string(), line)))
+        .collect();
+    let sub_ids = doc.sub_resource_ids();
+    let db = ClassDb::builtin();
+    let mut class_names = None;
+    let self_res = format!("res://{}", scene_rel.to_string_lossy().replace('\\', "/"));
+
+    for (lno, text) in &doc.malformed {
+        out.push(issue(*lno, None, "malformed-header", format!("Malformed section header: {}", text)));
+    }
+    let is_tres = scene_rel.extension().and_then(|s| s.to_str()) == Some("tres");
+    if is_tres && doc.sections.first().is_none_or(|s| s.kind != "gd_resource") {
+        let lno = doc.sections.first().map_or(1, |s| s.line);
+        out.push(issue(lno, None, "missing-resource-header", "Missing gd_resource header".to_string()));
+    }
+
+    for section in &doc.sections {
+        if section.kind == "ext_resource" {
+            let Some(p) = section.attr_str("path") else { continue };
+            match uids.res_path(p) {
+                None => out.push(issue(section.line, None, "dangling-uid", format!("Dangling uid: {}", p))),
+                Some(res) if missing(res) => {
+                    let shown = if res == p { p.to_string() } else { format!("{} ({})", res, p) };
+                    out.push(issue(section.line, None, "missing-ext-resource", format!("Missing ext_resource path: {}", shown)));
                 }
+                Some(_) => {}
             }
             continue;
         }
+        let node_path = section.node_path();
+        // type= must name a built-in class or a project class_name (looked up only when needed)
+        if let Some(ty) = section.attr_str("type").filter(|_| section.kind == "node") {
+            if !db.contains(ty) && !class_names.get_or_insert_with(|| gdscript::class_registry(root)).contains_key(ty) {
+                let hint = db.suggest(ty).map(|c| format!(" — did you mean '{}'?", c)).unwrap_or_default();
+                out.push(issue(section.line, node_path.clone(), "unknown-node-type", format!("Unknown node type: {}{}", ty, hint)));
+            }
+        }
+        for (key, value, lno) in section.assignments() {
+            // script="res://..." form
+            if let (true, Value::Str(script)) = (key == "script", value) {
+                if missing(script) {
+                    out.push(issue(lno, node_path.clone(), "missing-script", format!("Missing script: {}", script)));
+                }
+            }
 
-        // sub_resource declarations (collect ids for validation of SubResource references)
-        if re_sub_line.is_match(line) {
-            if let Some(id) = re_sub_id
-                .captures(line)
-                .and_then(|c| c.get(1).map(|m| m.as_str().to_string()))
-            {
-                sub_ids.insert(id, lno);
-            }
-            // continue scanning subsequent lines for other issues as well
-        }
-
-        // track node path from [node ... path="..."] lines
-        if line.trim_start().starts_with("[node ") {
-            current_node_path = extract_attr(line, "path").map(|s| s.to_string());
-        }
-        // detect script attribute and resolve
-        if let Some(script) = extract_attr(line, "script") {
-            if let Some(p) = script.strip_prefix("res://") {
-                let target = root.join(p);
-                if !target.exists() {
-                    out.push(SceneIssue {
-                        file: scene_rel.to_path_buf(),
-                        line: lno,
-                        node_path: current_node_path.clone(),
-                        message: format!("Missing script: {}", script),
-                    });
+            // key = ExtResource("id"); the script property gets its own message
+            if let Some(id) = value.resource_id("ExtResource") {
+                match ext_map.get(id) {
+                    Some((path_str, _)) if missing(path_str) => {
+                        let (code, message) = if key == "script" {
+                            ("missing-script", format!("Script ExtResource({}) missing file {}", id, path_str))
+                        } else if key == "instance" {
+                            ("missing-instance", format!("Instanced scene missing: ExtResource({}) {}", id, path_str))
+                        } else {
+                            ("missing-property-resource", format!("Property '{}' ExtResource({}) missing file {}", key, id, path_str))
+                        };
+                        out.push(issue(lno, node_path.clone(), code, message));
+                    }
+                    Some((path_str, _)) if key == "instance" => {
+                        if let Some(cycle) = instance_cycle(root, uids, &self_res, path_str) {
+                            out.push(issue(lno, node_path.clone(), "recursive-instance", format!("Recursive scene instance: {}", cycle.join(" -> "))));
+                        }
+                    }
+                    Some(_) => {}
+                    None => out.push(issue(lno, node_path.clone(), "unknown-ext-resource", format!("Unknown ExtResource id: {}", id))),
                 }
             }
-        }
 
-        // script = ExtResource("id") form (explicit special-case for message clarity)
-        if let Some(caps) = re_script_ext.captures(line) {
-            let id = caps.get(1).unwrap().as_str();
-            if let Some((path_str, _decl_line)) = ext_map.get(id) {
-                if let Some(res) = path_str.strip_prefix("res://") {
-                    let target = root.join(res);
-                    if !target.exists() {
-                        out.push(SceneIssue { file: scene_rel.to_path_buf(), line: lno, node_path: current_node_path.clone(), message: format!("Script ExtResource({}) missing file {}", id, path_str) });
+            for v in value.nested() {
+                // SubResource("id") anywhere (e.g. inside dictionaries)
+                if let Some(id) = v.resource_id("SubResource").filter(|id| !sub_ids.contains(*id)) {
+                    out.push(issue(lno, node_path.clone(), "unknown-sub-resource", format!("Unknown SubResource id: {}", id)));
+                }
+                // preload("res://...") / load("res://...") as values
+                for (ctor, label, code) in [("preload", "Preload", "missing-preload"), ("load", "Load", "missing-load")] {
+                    let Some(path_str) = v.call_args(ctor).and_then(|a| a.first()).and_then(Value::as_str) else { continue };
+                    if path_str.starts_with("res://") && missing(path_str) {
+                        out.push(issue(lno, None, code, format!("{} missing file: {}", label, path_str)));
+                    }
+                }
+                // ... and inside embedded script text, on the line they appear
+                let Value::Str(text) = v else { continue };
+                for (re, label, code) in [(&re_preload, "Preload", "missing-preload"), (&re_load, "Load", "missing-load")] {
+                    for caps in re.captures_iter(text) {
+                        let m = caps.get(1).unwrap();
+                        if missing(m.as_str()) {
+                            let at = lno + text[..m.start()].matches('\n').count();
+                            out.push(issue(at, None, code, format!("{} missing file: {}", label, m.as_str())));
+                        }
                     }
                 }
-            } else {
-                out.push(SceneIssue { file: scene_rel.to_path_buf(), line: lno, node_path: current_node_path.clone(), message: format!("Unknown ExtResource id: {}", id) });
             }
         }
+    }
+    let checks = override_issues(&doc, root, uids, &self_res).into_iter()
+        .chain(animation_issues(&doc, root, uids))
+        .chain(node_path_issues(&doc, root, uids));
+    for (lno, node_path, code, message) in checks {
+        out.push(issue(lno, Some(node_path), code, message));
+    }
+    let supp = Suppressions::read(&root.join(scene_rel));
+    out.retain(|i| !supp.suppresses(&i.code, Some(i.line)));
+    out
+}
 
-        // Generic property = ExtResource("id") form
-        if let Some(caps) = re_prop_ext.captures(line) {
-            let prop = caps.name("prop").map(|m| m.as_str()).unwrap_or("");
-            let id = caps.name("id").map(|m| m.as_str()).unwrap_or("");
-            // If it's the script property, the script-specific handler above already covered it; skip duplicate
-            if prop == "script" { continue; }
-            if let Some((path_str, _decl_line)) = ext_map.get(id) {
-                if let Some(res) = path_str.strip_prefix("res://") {
-                    let target = root.join(res);
-                    if !target.exists() {
-                        out.push(SceneIssue { file: scene_rel.to_path_buf(), line: lno, node_path: current_node_path.clone(), message: format!("Property '{}' ExtResource({}) missing file {}", prop, id, path_str) });
+/// Checks of the Animation sub_resources the scene's AnimationPlayers play (Godot 4
+/// `libraries`, Godot 3 `anims/<name>`): each track's node must exist, resolved from the
+/// player's `root_node` (`..` by default) with instanced nodes included, and in Godot 4 scenes
+/// a value or bezier track's property must exist where the node's class and script chain
+/// are known. Tracks through `%Unique` names or absolute paths aren't checked. As (line,
+/// player path, code, message), on the track's `path` line.
+fn animation_issues(doc: &SceneDocument, root: &Path, uids: &UidMap) -> Vec<(usize, String, &'static str, String)> {
+    let subs: HashMap<&str, &Section> = doc.sections("sub_resource").filter_map(|s| Some((s.attr_str("id")?, s))).collect();
+    let sub = |v: &Value| v.resource_id("SubResource").and_then(|id| subs.get(id)).copied();
+    let node_path_arg = |v: &Value| v.call_args("NodePath").and_then(|a| a.first()).and_then(Value::as_str).map(str::to_string);
+    // (player path, root_node, [(animation name, Animation section)])
+    let mut players = Vec::new();
+    for node in doc.sections("node") {
+        let Some(player) = node.node_path() else { continue };
+        let mut anims = Vec::new();
+        for p in &node.properties {
+            if let (true, Value::Dict(libraries)) = (p.key == "libraries", &p.value) {
+                for (lib, v) in libraries {
+                    let Some(Value::Dict(data)) = sub(v).and_then(|s| s.property("_data")).map(|p| &p.value) else { continue };
+                    let prefix = lib.as_str().filter(|l| !l.is_empty()).map(|l| format!("{}/", l)).unwrap_or_default();
+                    for (name, a) in data {
+                        if let (Some(name), Some(anim)) = (name.as_str(), sub(a)) { anims.push((format!("{}{}", prefix, name), anim)); }
                     }
                 }
-            } else {
-                out.push(SceneIssue { file: scene_rel.to_path_buf(), line: lno, node_path: current_node_path.clone(), message: format!("Unknown ExtResource id: {}", id) });
+            } else if let (Some(name), Some(anim)) = (p.key.strip_prefix("anims/"), sub(&p.value)) {
+                anims.push((name.to_string(), anim));
             }
         }
+        if anims.is_empty() { continue; }
+        let root_node = node.property("root_node").and_then(|p| node_path_arg(&p.value)).unwrap_or_else(|| "..".to_string());
+        players.push((player, root_node, anims));
+    }
+    if players.is_empty() { return vec![]; }
 
-        // Generic property = SubResource("id") form — ensure id exists
-        if let Some(caps) = re_prop_sub.captures(line) {
-            let id = caps.name("id").map(|m| m.as_str()).unwrap_or("");
-            if !sub_ids.contains_key(id) {
-                out.push(SceneIssue { file: scene_rel.to_path_buf(), line: lno, node_path: current_node_path.clone(), message: format!("Unknown SubResource id: {}", id) });
+    let tree = doc.node_tree(root, uids);
+    let godot4 = doc.sections.first().and_then(|s| s.attr("format")).and_then(Value::as_int).is_some_and(|f| f >= 3);
+    let classes = gdscript::class_registry(root);
+    let mut out = Vec::new();
+    for (player, root_node, anims) in players {
+        let Some(base) = resolve_node_path(&player, &root_node) else { continue };
+        for (name, anim) in anims {
+            for p in &anim.properties {
+                let Some(track) = p.key.strip_prefix("tracks/").and_then(|k| k.strip_suffix("/path")) else { continue };
+                let Some(path) = node_path_arg(&p.value) else { continue };
+                let (node_part, property) = path.split_once(':').map_or((path.as_str(), None), |(n, p)| (n, Some(p)));
+                if node_part.starts_with('/') || node_part.split('/').any(|s| s.starts_with('%')) { continue; }
+                let Some(target) = resolve_node_path(&base, node_part) else { continue };
+                let at = format!("'{}' in animation '{}' (track {})", path, name, track);
+                let Some(node) = tree.iter().find(|n| n.path == target) else {
+                    out.push((p.line, player.clone(), "animation-missing-node", format!("Animation track node missing: {}{}", at, moved_hint(&tree, &target))));
+                    continue;
+                };
+                let kind = anim.property(&format!("tracks/{}/type", track)).and_then(|t| t.value.as_str()).unwrap_or("value");
+                let Some(property) = property.filter(|_| godot4 && matches!(kind, "value" | "bezier")) else { continue };
+                // `position:x` animates a part of `position`
+                let property = property.split(':').next().unwrap_or(property);
+                if node_has_property(root, node, property, &classes) == Some(false) {
+                    let ty = node.ty.as_deref().unwrap_or("the node");
+                    out.push((p.line, player.clone(), "animation-missing-property", format!("Animation track property missing: {}; {} has no property '{}'", at, ty, property)));
+                }
             }
         }
-        // SubResource("id") found anywhere (e.g., inside dictionaries)
-        for caps in re_any_sub.captures_iter(line) {
-            let id = caps.name("id").map(|m| m.as_str()).unwrap_or("");
-            if !sub_ids.contains_key(id) {
-                out.push(SceneIssue { file: scene_rel.to_path_buf(), line: lno, node_path: current_node_path.clone(), message: format!("Unknown SubResource id: {}", id) });
+    }
+    out
+}
+
+/// ` — 'Name' is at 'Other/Name'` when a node named like the missing `target` exists
+/// elsewhere in the tree, as a renamed or moved node often does; empty otherwise.
+fn moved_hint(tree: &[SceneNode], target: &str) -> String {
+    let leaf = target.rsplit('/').next().unwrap_or(target);
+    tree.iter().find(|n| n.path != "." && n.path.rsplit('/').next() == Some(leaf))
+        .map(|n| format!(" — '{}' is at '{}'", leaf, n.path))
+        .unwrap_or_default()
+}
+
+/// `NodePath("...")` values (arrays of them too) the scene's own `[node]`s assign, such as
+/// exported `Node`/`NodePath` properties, checked against the node tree from the node that
+/// holds them. Paths leaving the scene, absolute and `%Unique` paths aren't checked; a
+/// `:property` suffix is ignored. As (line, node path, code, message).
+fn node_path_issues(doc: &SceneDocument, root: &Path, uids: &UidMap) -> Vec<(usize, String, &'static str, String)> {
+    let mut tree = None;
+    let mut out = Vec::new();
+    for node in doc.sections("node") {
+        let Some(base) = node.node_path() else { continue };
+        for p in &node.properties {
+            for v in p.value.nested() {
+                let Some(path) = v.call_args("NodePath").and_then(|a| a.first()).and_then(Value::as_str) else { continue };
+                let node_part = path.split(':').next().unwrap_or(path);
+                if node_part.is_empty() || node_part.starts_with('/') || node_part.split('/').any(|s| s.starts_with('%')) { continue; }
+                let Some(target) = resolve_node_path(&base, node_part) else { continue };
+                let tree = tree.get_or_insert_with(|| doc.node_tree(root, uids));
+                if tree.iter().any(|n| n.path == target) || under_unexpanded_instance(tree, &target) { continue; }
+                out.push((p.line, base.clone(), "dangling-node-path", format!("Dangling NodePath: {} = '{}' points at '{}', which doesn't exist{}", p.key, path, target, moved_hint(tree, &target))));
             }
         }
+    }
+    out
+}
 
-        // preload("res://...") occurrences in embedded script text or properties
-        if let Some(caps) = re_preload.captures(line) {
-            if let Some(path_str) = caps.get(1).map(|m| m.as_str()) {
-                if let Some(res) = path_str.strip_prefix("res://") {
-                    let target = root.join(res);
-                    if !target.exists() {
-                        out.push(SceneIssue { file: scene_rel.to_path_buf(), line: lno, node_path: None, message: format!("Preload missing file: {}", path_str) });
-                    }
+/// Whether `node` has `property`: a `var` along its script's `extends` chain, else a property
+/// of its built-in class. None when that can't be known (an unreadable script, an unknown
+/// base, a script with `_get`/`_set`/`_get_property_list`, a class without bundled properties).
+fn node_has_property(root: &Path, node: &SceneNode, property: &str, classes: &HashMap<String, PathBuf>) -> Option<bool> {
+    let db = ClassDb::builtin();
+    let mut ty = node.ty.clone().filter(|t| db.contains(t));
+    let mut next = node.script.as_deref().and_then(|s| s.strip_prefix("res://")).map(|s| root.join(s));
+    let mut seen = HashSet::new();
+    while let Some(path) = next.take() {
+        if !seen.insert(path.clone()) { return None; }
+        let parsed = gdscript::Script::read(&path)?;
+        if parsed.vars.iter().any(|v| v.name == property && !v.is_const) { return Some(true); }
+        if parsed.functions.iter().any(|f| matches!(f.name.as_str(), "_get" | "_set" | "_get_property_list")) { return None; }
+        let Some(base) = parsed.extends else { break };
+        if let Some(res) = base.trim_matches('"').strip_prefix("res://") {
+            next = Some(root.join(res));
+        } else if let Some(p) = classes.get(&base) {
+            next = Some(root.join(p));
+        } else if db.contains(&base) {
+            ty = ty.or(Some(base));
+        } else {
+            return None;
+        }
+    }
+    db.has_property(ty.as_deref()?, property)
+}
+
+/// Checks of the nodes a scene re-declares on top of instanced (or inherited) scenes: the
+/// node must still exist in the instanced scene, an overridden value should keep the kind of
+/// value the source has (`Vector2` stays `Vector2`), and an overriding script should extend a
+/// class the node's type derives from. As (line, node path, code, message).
+fn override_issues(doc: &SceneDocument, root: &Path, uids: &UidMap, self_res: &str) -> Vec<(usize, String, &'static str, String)> {
+    let ext: HashMap<String, (String, usize)> = doc.ext_resources().into_iter()
+        .map(|(id, (p, line))| (id, (uids.res_path(&p).unwrap_or(&p).to_string(), line)))
+        .collect();
+    let db = ClassDb::builtin();
+    // What the instances bring in, before this scene's overrides: path -> (instanced scene, node)
+    let mut base: HashMap<String, (String, SceneNode)> = HashMap::new();
+    let mut prefixes: Vec<(String, String)> = Vec::new();
+    for node in doc.sections("node") {
+        let Some(path) = node.node_path() else { continue };
+        let Some((inst, _)) = node.attr("instance").and_then(|v| ext.get(v.resource_id("ExtResource")?)) else { continue };
+        if inst == self_res { continue; }
+        let Some(inst_doc) = inst.strip_prefix("res://").and_then(|rel| SceneDocument::read(&root.join(rel))) else { continue };
+        for n in inst_doc.node_tree(root, uids) {
+            let full = match (path.as_str(), n.path.as_str()) {
+                (".", p) => p.to_string(),
+                (pre, ".") => pre.to_string(),
+                (pre, p) => format!("{}/{}", pre, p),
+            };
+            base.insert(full, (inst.clone(), n));
+        }
+        prefixes.push((path, inst.clone()));
+    }
+    if prefixes.is_empty() { return vec![]; }
+
+    let mut out = Vec::new();
+    for node in doc.sections("node") {
+        let Some(path) = node.node_path() else { continue };
+        if node.attr("type").is_some() { continue; }
+        let Some((inst, src)) = base.get(&path) else {
+            if node.attr("instance").is_some() { continue; }
+            // Longest instanced prefix the node sits under names the scene it was expected in
+            let owner = prefixes.iter()
+                .filter(|(pre, _)| pre == "." || path.starts_with(&format!("{}/", pre)))
+                .max_by_key(|(pre, _)| pre.len());
+            let message = match owner {
+                Some((_, inst)) => format!("Orphaned override: '{}' no longer exists in {}", path, inst),
+                None => format!("Orphaned override: '{}' has no type and no instanced scene provides it", path),
+            };
+            out.push((node.line, path, "orphaned-override", message));
+            continue;
+        };
+        if let Some(script) = node.script_path(&ext) {
+            let base_class = script.strip_prefix("res://")
+                .and_then(|rel| gdscript::Script::read(&root.join(rel)))
+                .and_then(|s| s.extends);
+            if let (Some(ty), Some(base_class)) = (src.ty.as_deref(), base_class) {
+                if db.contains(ty) && db.contains(&base_class) && !db.inherits(ty, &base_class) {
+                    out.push((node.line, path.clone(), "script-override-mismatch", format!("Script override on '{}' extends {} but the node is a {} — hint: {}", path, base_class, ty, script)));
                 }
             }
         }
-
-        // load("res://...") occurrences
-        if let Some(caps) = re_load.captures(line) {
-            if let Some(path_str) = caps.get(1).map(|m| m.as_str()) {
-                if let Some(res) = path_str.strip_prefix("res://") {
-                    let target = root.join(res);
-                    if !target.exists() {
-                        out.push(SceneIssue { file: scene_rel.to_path_buf(), line: lno, node_path: None, message: format!("Load missing file: {}", path_str) });
-                    }
+        for p in &node.properties {
+            let Some((_, was)) = src.properties.iter().find(|(k, _)| *k == p.key) else { continue };
+            let (now_kind, was_kind) = (value_kind(&p.value), value_kind(was));
+            if let (Some(now_kind), Some(was_kind)) = (now_kind, was_kind) {
+                if now_kind != was_kind {
+                    out.push((p.line, path.clone(), "override-type-mismatch", format!("Override type mismatch: '{}' sets {} to {} but {} has {}", path, p.key, now_kind, inst, was_kind)));
                 }
             }
         }
@@ -158,11 +845,18 @@ pub fn validate_scene(root: &Path, scene_rel: &Path) -> Vec<SceneIssue> {
     out
 }
 
-fn extract_attr<'a>(line: &'a str, key: &str) -> Option<&'a str> {
-    // naive parse: key="value" anywhere in line
-    let pat = format!("{}=\"", key);
-    let idx = line.find(&pat)? + pat.len();
-    let rest = &line[idx..];
-    let end = rest.find('"')?;
-    Some(&rest[..end])
+/// Kind of a value for comparing an override with its source: the constructor name, with
+/// resource references and plain literals grouped; None for `null`, which fits anything.
+fn value_kind(v: &Value) -> Option<&str> {
+    Some(match v {
+        Value::Str(_) => "String",
+        Value::Number(_) => "number",
+        Value::Ident(i) if i == "null" => return None,
+        Value::Ident(i) if i == "true" || i == "false" => "bool",
+        Value::Ident(_) => "number",
+        Value::Array(_) => "Array",
+        Value::Dict(_) => "Dictionary",
+        Value::Call { name, .. } if name == "ExtResource" || name == "SubResource" => "Resource",
+        Value::Call { name, .. } => name.split('[').next().unwrap_or(name),
+    })
 }
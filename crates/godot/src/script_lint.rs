@@ -1,32 +1,56 @@
+use anyhow::{bail, Result};
 use regex::Regex;
-use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
+use crate::class_db::ClassDb;
+use crate::gdscript::{self, tokens, Function, Script, StmtKind, Token, TokenKind};
+use crate::config::AnalyzerConfig;
+use crate::project_settings::ProjectSettings;
+use crate::scene_validate::{resolve_node_path, under_unexpanded_instance, SceneDocument};
+use crate::suppress::Suppressions;
+use crate::uid::UidMap;
 use crate::Severity;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LintFinding {
     pub code: String,
     pub message: String,
     pub file: PathBuf,
     pub severity: Severity,
+    /// 1-based line of the (first) occurrence, when the rule points at one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
 }
 
 /// Lint GDScript files under root and return findings.
 pub fn lint_gd_scripts(root: &Path) -> Vec<LintFinding> {
-    let re_class = Regex::new(r#"(?m)^\s*class_name\s+([A-Za-z_][A-Za-z0-9_]*)\b"#).unwrap();
-    let re_debug = Regex::new(r#"(?m)^\s*(print|prints|printt)\s*\("#).unwrap();
-    let re_tabs = Regex::new(r#"(?m)^\t+"#).unwrap();
-    let re_ext = Regex::new(r#"(?m)\b(preload|load)\s*\(\s*\"(res://[^\"]+)\"\s*\)"#).unwrap();
-
     let mut out: Vec<LintFinding> = Vec::new();
+    // Controls of each linted file, for findings reported after the per-file pass
+    let mut controls: HashMap<PathBuf, (HashSet<String>, Severity, Suppressions)> = HashMap::new();
+    let config = AnalyzerConfig::load(root).unwrap_or_default();
+    let thresholds = &config.thresholds;
+    // Opt-in rules run when `[rules] enable` names them or their group
+    let godot4 = ProjectSettings::load(root).and_then(|p| p.config_version()).is_some_and(|v| v >= 5);
+    let classes = gdscript::class_registry(root);
+    let uids = UidMap::load(root);
+    let opted_in = |code: &str| config.rules.enable.iter().any(|r| r == code || (r == "typed-gdscript" && TYPED_RULES.contains(&code)));
+    let limits = FunctionLimits {
+        lines: thresholds.max_function_lines.unwrap_or(50),
+        depth: thresholds.max_nesting_depth.unwrap_or(4),
+        complexity: thresholds.max_complexity.unwrap_or(10),
+    };
 
     for entry in WalkDir::new(root).into_iter().flatten() {
         let path = entry.path();
         if !entry.file_type().is_file() { continue; }
         if path.extension().and_then(|s| s.to_str()).map(|s| s.eq_ignore_ascii_case("gd")).unwrap_or(false) {
             let rel = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+            if rel.starts_with(FIX_BACKUP_DIR) { continue; }
             let Ok(contents) = fs::read_to_string(path) else { continue };
 
             // Parse suppression directives and severity override
@@ -37,52 +61,127 @@ pub fn lint_gd_scripts(root: &Path) -> Vec<LintFinding> {
             let (disable_all, disabled, level) = parse_controls(&contents);
             let sev = level.unwrap_or(Severity::Warn);
             if disable_all { continue; }
+            let script = gdscript::parse(&contents);
+            // `# analyzer:` suppression comments, shared with the scene validators
+            let supp = Suppressions::parse(&contents, '#');
+            controls.insert(rel.clone(), (disabled.clone(), sev, supp.clone()));
+            let mut push = |code: &str, message: String, at: Option<(usize, Option<usize>)>| {
+                if !disabled.contains(code) && !supp.suppresses(code, at.map(|a| a.0)) {
+                    let (line, column) = at.map_or((None, None), |(l, c)| (Some(l), c));
+                    out.push(LintFinding { code: code.into(), message, file: rel.clone(), severity: sev, line, column });
+                }
+            };
 
             // class_name vs filename
-            if let Some(cap) = re_class.captures(&contents) {
-                let cls = cap.get(1).map(|m| m.as_str()).unwrap_or("");
+            if let Some(cls) = script.class_name.as_deref().filter(|c| !c.is_empty()) {
                 let fname = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
-                if !cls.is_empty() && !fname.eq(cls) {
-                    if !disabled.contains("class-name-mismatch") {
-                        out.push(LintFinding { code: "class-name-mismatch".into(), message: format!("Class name mismatch: class_name {} but file is {}.gd", cls, fname), file: rel.clone(), severity: sev });
-                    }
+                if fname != cls {
+                    let at = script.lines.iter().find(|l| l.text.starts_with("class_name")).map(|l| (l.line, column_of(&contents, l.line, "class_name")));
+                    push("class-name-mismatch", format!("Class name mismatch: class_name {} but file is {}.gd", cls, fname), at);
                 }
             }
 
-            // debug prints
-            if re_debug.is_match(&contents) {
-                if !disabled.contains("debug-print") {
-                    out.push(LintFinding { code: "debug-print".into(), message: "Debug print found".into(), file: rel.clone(), severity: sev });
-                }
-            }
+            // debug prints: calls to the global print functions, not methods named print
+            let is_debug_call = |t: &[Token]| t[1].is("(") && matches!(t[0].text, "print" | "prints" | "printt") && t[0].kind == TokenKind::Ident;
+            let first_debug = script.lines.iter().find_map(|l| {
+                let toks = tokens(&l.text);
+                let i = toks.windows(2).enumerate().position(|(i, w)| is_debug_call(w) && (i == 0 || !matches!(toks[i - 1].text, "." | "func")))?;
+                Some((l.line, column_of(&contents, l.line, toks[i].text)))
+            });
+            if first_debug.is_some() { push("debug-print", "Debug print found".into(), first_debug); }
 
             // tabs indentation
-            if re_tabs.is_match(&contents) {
-                if !disabled.contains("tab-indentation") {
-                    out.push(LintFinding { code: "tab-indentation".into(), message: "Tab indentation used".into(), file: rel.clone(), severity: sev });
-                }
+            if let Some(i) = contents.lines().position(|l| l.starts_with('\t')) {
+                push("tab-indentation", "Tab indentation used".into(), Some((i + 1, Some(1))));
             }
 
             // missing extends
-            if !contents.lines().any(|l| l.trim_start().starts_with("extends ")) {
-                if !disabled.contains("missing-extends") {
-                    out.push(LintFinding { code: "missing-extends".into(), message: "Missing extends declaration".into(), file: rel.clone(), severity: sev });
-                }
+            if script.extends.is_none() {
+                push("missing-extends", "Missing extends declaration".into(), None);
             }
 
             // load/preload missing files
-            for cap in re_ext.captures_iter(&contents) {
-                if let Some(p) = cap.get(2).map(|m| m.as_str()) {
-                    if p.starts_with("res://") {
-                        let target = root.join(&p[6..]);
-                        if !target.exists() {
-                            if !disabled.contains("missing-resource-ref") {
-                                out.push(LintFinding { code: "missing-resource-ref".into(), message: format!("GDScript {} missing file: {}", cap.get(1).unwrap().as_str(), p), file: rel.clone(), severity: sev });
-                            }
+            for l in &script.lines {
+                for w in tokens(&l.text).windows(4) {
+                    if !(matches!(w[0].text, "preload" | "load") && w[1].is("(") && w[3].is(")")) { continue; }
+                    let Some(p) = w[2].string_value() else { continue };
+                    if let Some(res) = p.strip_prefix("res://") {
+                        if !root.join(res).exists() {
+                            push("missing-resource-ref", format!("GDScript {} missing file: {}", w[0].text, p), Some((l.line, column_of(&contents, l.line, w[2].text))));
                         }
                     }
                 }
             }
+
+            // locals and parameters named like a member variable or constant
+            for (func, name, line, member) in shadowed_variables(&script) {
+                push("shadowed-variable", format!("Shadowed variable: '{}' in {}() at line {} shadows the member declared at line {}", name, func, line, member), Some((line, None)));
+            }
+
+            // locals and parameters never read ('_'-prefixed names are intentional)
+            for (code, message, line, name) in unused_names(&script) {
+                push(code, message, Some((line, column_of(&contents, line, &name))));
+            }
+
+            // functions too long, too deeply nested or with too many paths
+            for (code, message, line, name) in function_metrics(&script, &limits) {
+                push(code, message, Some((line, column_of(&contents, line, &name))));
+            }
+
+            // per-frame node lookups and awaits, string building in loops
+            for (code, message, line, needle) in perf_issues(&script) {
+                push(code, message, Some((line, column_of(&contents, line, &needle))));
+            }
+
+            // @export types, @tool placement, Godot 3 keywords in a Godot 4 project
+            for (code, message, line, needle) in annotation_issues(root, &script, &classes, godot4) {
+                push(code, message, Some((line, column_of(&contents, line, &needle))));
+            }
+
+            // typed GDScript (opt-in): return types, typed declarations, `:=` on untyped calls
+            for (code, message, line, name) in typed_issues(&script).into_iter().filter(|t| opted_in(t.0)) {
+                push(code, message, Some((line, column_of(&contents, line, &name))));
+            }
+
+            // naming conventions of the GDScript style guide
+            for (code, message, line, name) in naming_issues(&script) {
+                push(code, message, Some((line, column_of(&contents, line, &name))));
+            }
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+            // A stem equal to the class_name is what class-name-mismatch asks for
+            if !is_snake_case(stem) && script.class_name.as_deref() != Some(stem) {
+                push("file-name", format!("File name not snake_case: {}.gd — hint: {}.gd", stem, to_snake_case(stem)), None);
+            }
+        }
+    }
+
+    // preload cycles, reported once, on the first script of the cycle
+    for cycle in preload_cycles(&preload_graph(root, &uids)) {
+        let Some(rel) = cycle[0].strip_prefix("res://").map(PathBuf::from) else { continue };
+        let Some((disabled, sev, supp)) = controls.get(&rel) else { continue };
+        let (code, message) = if cycle.len() == 2 {
+            ("self-preload", format!("Script preloads itself: {}", cycle[0]))
+        } else {
+            ("cyclic-preload", format!("Cyclic preload: {}", cycle.join(" -> ")))
+        };
+        if !disabled.contains(code) && !supp.suppresses(code, None) {
+            out.push(LintFinding { code: code.into(), message, file: rel, severity: *sev, line: None, column: None });
+        }
+    }
+
+    // signals nothing uses, and emit_signal/connect calls naming signals the class lacks
+    for (rel, code, message, line) in signal_lint(root) {
+        let Some((disabled, sev, supp)) = controls.get(&rel) else { continue };
+        if !disabled.contains(code) && !supp.suppresses(code, Some(line)) {
+            out.push(LintFinding { code: code.into(), message, file: rel, severity: *sev, line: Some(line), column: None });
+        }
+    }
+
+    // @onready node paths missing from the scenes the script is attached in
+    for (rel, code, message, line) in onready_paths(root, &uids) {
+        let Some((disabled, sev, supp)) = controls.get(&rel) else { continue };
+        if !disabled.contains(code) && !supp.suppresses(code, Some(line)) {
+            out.push(LintFinding { code: code.into(), message, file: rel, severity: *sev, line: Some(line), column: None });
         }
     }
 
@@ -91,6 +190,795 @@ pub fn lint_gd_scripts(root: &Path) -> Vec<LintFinding> {
     out
 }
 
+/// Codes of every finding `lint_gd_scripts` reports.
+pub const CODES: &[&str] = &[
+    "class-name-mismatch", "debug-print", "tab-indentation", "missing-extends", "missing-resource-ref",
+    "shadowed-variable", "unused-variable", "unused-parameter", "function-length", "nesting-depth",
+    "cyclomatic-complexity", "perf-node-lookup", "perf-await", "perf-string-concat", "export-type",
+    "export-untyped", "tool-placement", "legacy-annotation", "missing-return-type", "untyped-variable",
+    "untyped-parameter", "inferred-untyped-call", "function-name", "variable-name", "constant-name",
+    "class-name-case", "file-name", "self-preload", "cyclic-preload", "unused-signal",
+    "undeclared-script-signal", "onready-missing-node",
+];
+
+/// Rules `lint_gd_scripts_fix` can fix.
+pub const FIXABLE_RULES: &[&str] = &["debug-print", "tab-indentation", "missing-extends"];
+
+/// Where `lint_gd_scripts_fix` keeps the original of each script it edits.
+const FIX_BACKUP_DIR: &str = ".lint_fix";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintFixSummary {
+    pub fixes: Vec<LintFix>,
+    pub backed_up: usize,
+}
+
+/// Edits one rule made in one file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintFix { pub file: PathBuf, pub code: String, pub count: usize }
+
+/// Apply the safe fixes for `rules` (all of `FIXABLE_RULES` when empty) to the scripts under
+/// root, skipping files, lines and rules the linter's controls, suppression comments and
+/// `[rules] disable` turn off:
+///
+/// - `debug-print`: removes statements that are only a `print`/`prints`/`printt` call,
+///   leaving `pass` when that empties a block
+/// - `tab-indentation`: turns leading tabs into the project's space indent (the most common
+///   among space-indented scripts, else 4)
+/// - `missing-extends`: adds `extends RefCounted`, the implicit base, below any `@tool`,
+///   `class_name` or header comment lines
+///
+/// Originals are copied to `.lint_fix/backup/<path>` before a file is rewritten.
+pub fn lint_gd_scripts_fix(root: &Path, rules: &[String]) -> Result<LintFixSummary> {
+    if let Some(r) = rules.iter().find(|r| !FIXABLE_RULES.contains(&r.as_str())) {
+        bail!("no fix for rule '{}' (fixable: {})", r, FIXABLE_RULES.join(", "));
+    }
+    let disabled_by_config = AnalyzerConfig::load(root)?.rules.disable;
+    let wanted = |code: &str| (rules.is_empty() || rules.iter().any(|r| r == code)) && !disabled_by_config.iter().any(|r| r == code || r == "script-lint");
+    let unit = " ".repeat(indent_unit(root));
+    let mut summary = LintFixSummary::default();
+    let mut files: Vec<PathBuf> = WalkDir::new(root).into_iter().flatten()
+        .filter(|e| e.file_type().is_file() && e.path().extension().and_then(|s| s.to_str()).is_some_and(|s| s.eq_ignore_ascii_case("gd")))
+        .map(|e| e.into_path())
+        .collect();
+    files.sort();
+    for path in files {
+        let rel = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+        // Vendored addons and the structure fixer's backups are never rewritten
+        if rel.starts_with(".godot") || rel.starts_with(".git") || rel.starts_with(FIX_BACKUP_DIR)
+            || rel.starts_with("addons") || rel.starts_with(".structure_fix") { continue; }
+        let Ok(contents) = fs::read_to_string(&path) else { continue };
+        let (off, disabled, _) = parse_controls(&contents);
+        if off { continue; }
+        let supp = Suppressions::parse(&contents, '#');
+        let fixing = |code: &str| wanted(code) && !disabled.contains(code) && !supp.off && !supp.disabled.contains(code);
+        let script = gdscript::parse(&contents);
+        let mut lines: Vec<String> = contents.split('\n').map(str::to_string).collect();
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+
+        if fixing("debug-print") {
+            // Logical line index and (first, last) physical line index of each print statement
+            let mut prints: Vec<(usize, usize, usize)> = Vec::new();
+            for (i, l) in script.lines.iter().enumerate() {
+                let toks = tokens(&l.text);
+                let whole_call = toks.len() >= 3 && matches!(toks[0].text, "print" | "prints" | "printt") && toks[0].kind == TokenKind::Ident
+                    && toks[1].is("(") && closing_paren(&toks, 1) == Some(toks.len() - 1);
+                if !whole_call || supp.suppresses("debug-print", Some(l.line)) { continue; }
+                let first = l.line - 1;
+                // The call ends where its parentheses balance again
+                let mut last = first;
+                let mut depth = 0i64;
+                for (k, text) in lines.iter().enumerate().skip(first) {
+                    depth += tokens(text).iter().map(|t| match t.text { "(" | "[" | "{" => 1, ")" | "]" | "}" => -1, _ => 0 }).sum::<i64>();
+                    last = k;
+                    if depth <= 0 { break; }
+                }
+                prints.push((i, first, last));
+            }
+            // A block left empty keeps a `pass` where its first print was
+            let removed: HashSet<usize> = prints.iter().map(|p| p.0).collect();
+            let removals: Vec<(usize, usize, Option<String>)> = prints.iter().map(|&(i, first, last)| {
+                let indent = script.lines[i].indent;
+                let opens_block = i > 0 && script.lines[i - 1].indent < indent;
+                let empties_block = opens_block && script.lines[i..].iter().enumerate()
+                    .take_while(|(_, n)| n.indent >= indent).all(|(k, _)| removed.contains(&(i + k)));
+                let ws: String = lines[first].chars().take_while(|c| c.is_whitespace()).collect();
+                (first, last, empties_block.then(|| format!("{}pass", ws)))
+            }).collect();
+            for (first, last, replacement) in removals.iter().rev() {
+                lines.splice(*first..=*last, replacement.clone());
+            }
+            if !removals.is_empty() { counts.push(("debug-print", removals.len())); }
+        }
+
+        if fixing("tab-indentation") {
+            let mut n = 0;
+            for l in lines.iter_mut().filter(|l| l.starts_with('\t')) {
+                let ws = l.len() - l.trim_start_matches([' ', '\t']).len();
+                *l = format!("{}{}", l[..ws].replace('\t', &unit), &l[ws..]);
+                n += 1;
+            }
+            if n > 0 { counts.push(("tab-indentation", n)); }
+        }
+
+        if fixing("missing-extends") && script.extends.is_none() {
+            // Below the leading run of annotations, class_name and comments
+            let header = |t: &str| t.starts_with('#') || t.starts_with("@tool") || t.starts_with("@icon") || t.starts_with("@static_unload") || t.starts_with("class_name");
+            let at = lines.iter().take_while(|l| l.trim().is_empty() || header(l.trim()))
+                .enumerate().filter(|(_, l)| !l.trim().is_empty()).last().map_or(0, |(k, _)| k + 1);
+            lines.insert(at, "extends RefCounted".into());
+            counts.push(("missing-extends", 1));
+        }
+
+        if counts.is_empty() { continue; }
+        let backup = root.join(FIX_BACKUP_DIR).join("backup").join(&rel);
+        if let Some(parent) = backup.parent() { fs::create_dir_all(parent)?; }
+        fs::copy(&path, &backup)?;
+        summary.backed_up += 1;
+        fs::write(&path, lines.join("\n"))?;
+        summary.fixes.extend(counts.into_iter().map(|(code, count)| LintFix { file: rel.clone(), code: code.into(), count }));
+    }
+    Ok(summary)
+}
+
+/// Index of the `)` closing the `(` at `open`.
+fn closing_paren(toks: &[Token], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (k, t) in toks.iter().enumerate().skip(open) {
+        match t.text {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => { depth -= 1; if depth == 0 { return Some(k); } }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Spaces per indent level in the project's space-indented scripts: the most common smallest
+/// indent, 4 when no script indents with spaces.
+fn indent_unit(root: &Path) -> usize {
+    let mut seen: HashMap<usize, usize> = HashMap::new();
+    for entry in WalkDir::new(root).into_iter().flatten() {
+        let path = entry.path();
+        if !entry.file_type().is_file() || path.extension().and_then(|s| s.to_str()) != Some("gd") { continue; }
+        let rel = path.strip_prefix(root).unwrap_or(path);
+        if rel.starts_with(".godot") || rel.starts_with(".git") || rel.starts_with(FIX_BACKUP_DIR) { continue; }
+        let Ok(contents) = fs::read_to_string(path) else { continue };
+        let smallest = contents.lines()
+            .filter(|l| l.starts_with(' ') && !l.trim().is_empty())
+            .map(|l| l.len() - l.trim_start_matches(' ').len())
+            .min();
+        if let Some(n) = smallest { *seen.entry(n).or_default() += 1; }
+    }
+    seen.into_iter().max_by_key(|&(n, count)| (count, std::cmp::Reverse(n))).map_or(4, |(n, _)| n)
+}
+
+/// (function, name, line, member's line) for each parameter, local or `for` variable that
+/// reuses the name of a member var/const of its class, inner classes included.
+fn shadowed_variables(script: &Script) -> Vec<(String, String, usize, usize)> {
+    let mut out = Vec::new();
+    let member = |name: &str| script.vars.iter().find(|v| v.name == name).map(|v| v.line);
+    for f in &script.functions {
+        for p in &f.params {
+            if let Some(m) = member(&p.name) { out.push((f.name.clone(), p.name.clone(), f.line, m)); }
+        }
+        for st in &f.body {
+            let name = match &st.kind {
+                StmtKind::Var { name, .. } | StmtKind::For { var: name, .. } => name,
+                _ => continue,
+            };
+            if let Some(m) = member(name) { out.push((f.name.clone(), name.clone(), st.line, m)); }
+        }
+    }
+    for c in &script.classes {
+        out.extend(shadowed_variables(&c.script));
+    }
+    out
+}
+
+/// Per-function maxima, from `[thresholds]` in godot-analyzer.toml.
+struct FunctionLimits { lines: usize, depth: usize, complexity: usize }
+
+/// Approximate cyclomatic complexity of a function: 1, plus one per `if`/`elif`/`while`/`for`,
+/// `match` branch, conditional expression (`a if c else b`) and `and`/`or`/`&&`/`||`.
+fn complexity(f: &Function) -> usize {
+    1 + f.body.iter().map(|st| {
+        let decision = matches!(st.kind, StmtKind::If | StmtKind::Elif | StmtKind::While | StmtKind::For { .. } | StmtKind::Branch) as usize;
+        let toks = tokens(&st.text);
+        // The leading keyword of an if/elif/while is the decision counted above
+        let skip = usize::from(matches!(st.kind, StmtKind::If | StmtKind::Elif | StmtKind::While));
+        let inline = toks.iter().skip(skip).filter(|t| matches!(t.text, "if" | "and" | "or" | "&&" | "||") && t.kind != TokenKind::Str).count();
+        decision + inline
+    }).sum::<usize>()
+}
+
+/// (code, message, line, name) for functions over `limits`, inner classes included.
+fn function_metrics(script: &Script, limits: &FunctionLimits) -> Vec<(&'static str, String, usize, String)> {
+    let mut out = Vec::new();
+    for f in &script.functions {
+        let lines = f.end_line.saturating_sub(f.line) + 1;
+        if lines > limits.lines {
+            out.push(("function-length", format!("Function too long: {}() spans {} lines (max {}) — hint: split it into smaller functions", f.name, lines, limits.lines), f.line, f.name.clone()));
+        }
+        // A statement at depth d sits inside d blocks of the body
+        if let Some(deepest) = f.body.iter().max_by_key(|st| st.depth).filter(|st| st.depth > limits.depth) {
+            out.push(("nesting-depth", format!("Nesting too deep: {}() nests {} blocks at line {} (max {}) — hint: return early or extract the inner blocks", f.name, deepest.depth, deepest.line, limits.depth), f.line, f.name.clone()));
+        }
+        let c = complexity(f);
+        if c > limits.complexity {
+            out.push(("cyclomatic-complexity", format!("Complexity too high: {}() has cyclomatic complexity {} (max {}) — hint: split the branches into functions", f.name, c, limits.complexity), f.line, f.name.clone()));
+        }
+    }
+    for c in &script.classes {
+        out.extend(function_metrics(&c.script, limits));
+    }
+    out
+}
+
+/// Variant types `@export` accepts besides enums, Resources and Nodes.
+const EXPORTABLE_BUILTINS: &[&str] = &[
+    "bool", "int", "float", "String", "StringName", "NodePath", "Color", "Vector2", "Vector2i", "Vector3", "Vector3i", "Vector4", "Vector4i",
+    "Rect2", "Rect2i", "Transform2D", "Transform3D", "Plane", "Quaternion", "AABB", "Basis", "Projection", "Dictionary", "Array",
+    "PackedByteArray", "PackedInt32Array", "PackedInt64Array", "PackedFloat32Array", "PackedFloat64Array", "PackedStringArray",
+    "PackedVector2Array", "PackedVector3Array", "PackedVector4Array", "PackedColorArray",
+];
+
+/// (code, message, line, needle) for annotation misuse: `@export` vars whose type the
+/// inspector can't edit or that have neither a type nor a default, `@tool` below
+/// `extends`/`class_name`, and Godot 3's `onready`/`export`/`tool` keywords when `godot4`.
+fn annotation_issues(root: &Path, script: &Script, classes: &HashMap<String, PathBuf>, godot4: bool) -> Vec<(&'static str, String, usize, String)> {
+    let mut out = Vec::new();
+    let is_export = |a: &gdscript::Annotation| a.name.starts_with("export") && !matches!(a.name.as_str(), "export_group" | "export_subgroup" | "export_category");
+    for v in script.vars.iter().filter(|v| !v.is_const && v.annotations.iter().any(is_export)) {
+        match v.type_hint.as_deref() {
+            None if !v.inferred && v.value.is_none() => {
+                out.push(("export-untyped", format!("Export without type: '{}' — hint: give it a type or a default value", v.name), v.line, v.name.clone()));
+            }
+            Some(ty) if exportable(root, script, classes, ty) == Some(false) => {
+                out.push(("export-type", format!("Export type not exportable: '{}' is {} — hint: export built-in types, enums, Resources or Nodes", v.name, ty), v.line, v.name.clone()));
+            }
+            _ => {}
+        }
+    }
+    // Top-level script only: @tool has to precede extends and class_name
+    if let Some(i) = script.lines.iter().position(|l| l.indent == 0 && l.text.starts_with("@tool")) {
+        if script.lines[..i].iter().any(|l| l.indent == 0 && !l.text.starts_with('@')) {
+            out.push(("tool-placement", "Misplaced @tool: it must come before extends and class_name — hint: move it to the top of the script".into(), script.lines[i].line, "@tool".into()));
+        }
+    }
+    if godot4 {
+        for l in &script.lines {
+            let toks = tokens(&l.text);
+            let Some(kw) = toks.first().map(|t| t.text).filter(|k| matches!(*k, "onready" | "export" | "tool")) else { continue };
+            let legacy = if kw == "tool" { toks.len() == 1 } else { toks.iter().any(|t| t.is("var")) };
+            if legacy {
+                out.push(("legacy-annotation", format!("Legacy keyword: '{}' is Godot 3 syntax — hint: use @{} in Godot 4", kw, kw), l.line, kw.to_string()));
+            }
+        }
+    }
+    out
+}
+
+/// Whether `@export` takes a var of type `ty`: built-in Variant types, typed arrays and
+/// dictionaries of those, enums, and classes deriving from Resource or Node (class_names
+/// followed to their built-in base). None when the type can't be resolved.
+fn exportable(root: &Path, script: &Script, classes: &HashMap<String, PathBuf>, ty: &str) -> Option<bool> {
+    if let Some((base, args)) = ty.split_once('[') {
+        if !matches!(base.trim(), "Array" | "Dictionary") { return Some(false); }
+        let mut all = Some(true);
+        for arg in args.trim_end_matches(']').split(',') {
+            match exportable(root, script, classes, arg.trim())? { true => {}, false => all = Some(false) }
+        }
+        return all;
+    }
+    if EXPORTABLE_BUILTINS.contains(&ty) { return Some(true); }
+    if matches!(ty, "Callable" | "Signal" | "RID" | "Object" | "Variant") { return Some(false); }
+    // Enums of this class, or `Class.Enum` of another
+    if ty.contains('.') || script.enums.iter().any(|e| e.name.as_deref() == Some(ty)) { return Some(true); }
+    let db = ClassDb::builtin();
+    let base = if db.contains(ty) { ty.to_string() } else { builtin_base(root, classes.get(ty)?, classes)? };
+    Some(db.inherits(&base, "Resource") || db.inherits(&base, "Node"))
+}
+
+/// Built-in class the script at root-relative `path` ends up extending, following res://
+/// paths and class_names.
+fn builtin_base(root: &Path, path: &Path, classes: &HashMap<String, PathBuf>) -> Option<String> {
+    let db = ClassDb::builtin();
+    let mut next = root.join(path);
+    let mut seen = HashSet::new();
+    while seen.insert(next.clone()) {
+        let base = Script::read(&next)?.extends.unwrap_or_else(|| "RefCounted".into());
+        if let Some(res) = base.trim_matches('"').strip_prefix("res://") {
+            next = root.join(res);
+        } else if let Some(p) = classes.get(&base) {
+            next = root.join(p);
+        } else {
+            return db.contains(&base).then_some(base);
+        }
+    }
+    None
+}
+
+/// (script, code, message, line) for `@onready` vars initialised with `$Path` or
+/// `get_node("Path")` where some scene attaching the script has no node at that path
+/// relative to the node. Paths leaving the scene or entering an instance that can't be
+/// read aren't judged.
+fn onready_paths(root: &Path, uids: &UidMap) -> Vec<(PathBuf, &'static str, String, usize)> {
+    let re = Regex::new(r#"^(?:\$(?:"([^"]+)"|([A-Za-z0-9_/.]+))|get_node\(\s*\^?"([^"]+)"\s*\))"#).unwrap();
+    let mut scripts: HashMap<String, Option<Script>> = HashMap::new();
+    let mut out = Vec::new();
+    let mut scenes: Vec<PathBuf> = WalkDir::new(root).into_iter().flatten()
+        .filter(|e| e.file_type().is_file() && e.path().extension().and_then(|s| s.to_str()) == Some("tscn"))
+        .map(|e| e.into_path())
+        .collect();
+    scenes.sort();
+    for path in scenes {
+        let rel = path.strip_prefix(root).unwrap_or(&path);
+        if rel.starts_with(".godot") || rel.starts_with(".git") || rel.starts_with(FIX_BACKUP_DIR) { continue; }
+        let Some(doc) = SceneDocument::read(&path) else { continue };
+        let scene_res = format!("res://{}", rel.to_string_lossy().replace('\\', "/"));
+        let tree = doc.node_tree(root, uids);
+        for node in tree.iter().filter(|n| n.source.is_none()) {
+            let Some(script_res) = node.script.as_deref() else { continue };
+            let Some(script_rel) = script_res.strip_prefix("res://") else { continue };
+            let script = scripts.entry(script_res.to_string()).or_insert_with(|| Script::read(&root.join(script_rel)));
+            let Some(script) = script.as_ref() else { continue };
+            for v in script.vars.iter().filter(|v| v.annotations.iter().any(|a| a.name == "onready")) {
+                let Some(caps) = v.value.as_deref().and_then(|val| re.captures(val)) else { continue };
+                let Some(target) = caps.get(1).or(caps.get(2)).or(caps.get(3)).map(|m| m.as_str().trim_end_matches('.')) else { continue };
+                if target.starts_with('/') || target.starts_with('%') { continue; }
+                let Some(resolved) = resolve_node_path(&node.path, target) else { continue };
+                if tree.iter().any(|n| n.path == resolved) || under_unexpanded_instance(&tree, &resolved) { continue; }
+                let shown = if caps.get(3).is_some() { format!("get_node(\"{}\")", target) } else { format!("${}", target) };
+                out.push((PathBuf::from(script_rel), "onready-missing-node", format!("Missing @onready node: {} (var {}) not found from '{}' in {}", shown, v.name, node.path, scene_res), v.line));
+            }
+        }
+    }
+    out
+}
+
+/// Opt-in rules of the `typed-gdscript` group.
+pub const TYPED_RULES: &[&str] = &["missing-return-type", "untyped-variable", "untyped-parameter", "inferred-untyped-call"];
+
+/// (code, message, line, name) for declarations typed GDScript wants annotated: functions
+/// without `-> Type`, member and local `var`s with neither a type nor `:=`, untyped
+/// parameters, and `:=` on a call to a function of the same class that declares no return
+/// type (the variable ends up Variant).
+fn typed_issues(script: &Script) -> Vec<(&'static str, String, usize, String)> {
+    let untyped_call = |value: &str| -> Option<String> {
+        let toks = tokens(value);
+        let start = if toks.len() > 2 && toks[0].is("self") && toks[1].is(".") { 2 } else { 0 };
+        let (callee, open) = (toks.get(start)?, start + 1);
+        if callee.kind != TokenKind::Ident || !toks.get(open)?.is("(") || closing_paren(&toks, open) != Some(toks.len() - 1) { return None; }
+        let f = script.function(callee.text)?;
+        matches!(f.return_type.as_deref(), None | Some("Variant")).then(|| f.name.clone())
+    };
+    let mut out = Vec::new();
+    let mut check_var = |name: &str, type_hint: &Option<String>, inferred: bool, value: &Option<String>, line: usize, func: Option<&str>| {
+        let place = func.map(|f| format!(" in {}()", f)).unwrap_or_default();
+        if type_hint.is_none() && !inferred {
+            out.push(("untyped-variable", format!("Untyped variable: '{}'{} — hint: declare its type (`var {}: int`) or infer it with `:=`", name, place, name), line, name.to_string()));
+        } else if let Some(callee) = value.as_deref().filter(|_| inferred).and_then(untyped_call) {
+            out.push(("inferred-untyped-call", format!("Inferred from untyped call: '{}'{} := {}() is Variant — hint: give {}() a return type or declare '{}' with a type", name, place, callee, callee, name), line, name.to_string()));
+        }
+    };
+    for v in script.vars.iter().filter(|v| !v.is_const) {
+        check_var(&v.name, &v.type_hint, v.inferred, &v.value, v.line, None);
+    }
+    for f in &script.functions {
+        for st in &f.body {
+            if let StmtKind::Var { name, type_hint, inferred, value, is_const: false } = &st.kind {
+                check_var(name, type_hint, *inferred, value, st.line, Some(&f.name));
+            }
+        }
+    }
+    for f in &script.functions {
+        if f.return_type.is_none() {
+            out.push(("missing-return-type", format!("Missing return type: {}() — hint: declare it, e.g. `func {}() -> void`", f.name, f.name), f.line, f.name.clone()));
+        }
+        for p in f.params.iter().filter(|p| p.type_hint.is_none() && !p.inferred) {
+            out.push(("untyped-parameter", format!("Untyped parameter: '{}' in {}() — hint: declare its type (`{}: int`)", p.name, f.name, p.name), f.line, p.name.clone()));
+        }
+    }
+    for c in &script.classes {
+        out.extend(typed_issues(&c.script));
+    }
+    out
+}
+
+/// Callbacks the engine runs every frame.
+const FRAME_CALLBACKS: &[&str] = &["_process", "_physics_process"];
+
+/// (code, message, line, needle) for performance anti-patterns, codes prefixed `perf-`:
+/// node lookups (`get_node()`, `$Path`, `find_child()`, ...) and `await` in per-frame
+/// callbacks, and strings grown with `+=`/`s = s + ...` inside `for`/`while` loops.
+fn perf_issues(script: &Script) -> Vec<(&'static str, String, usize, String)> {
+    let mut out = Vec::new();
+    for f in &script.functions {
+        let per_frame = FRAME_CALLBACKS.contains(&f.name.as_str());
+        // Depths of the loops enclosing the current statement
+        let mut loops: Vec<usize> = Vec::new();
+        for st in &f.body {
+            while loops.last().is_some_and(|&d| d >= st.depth) { loops.pop(); }
+            let toks = tokens(&st.text);
+            if per_frame {
+                let lookup = toks.iter().enumerate().find(|(k, t)| {
+                    let called = toks.get(k + 1).is_some_and(|n| n.is("("));
+                    (called && matches!(t.text, "get_node" | "get_node_or_null" | "find_child" | "find_children" | "find_node") && t.kind == TokenKind::Ident)
+                        || (t.is("$") && toks.get(k + 1).is_some_and(|n| matches!(n.kind, TokenKind::Ident | TokenKind::Str)))
+                });
+                if let Some((k, t)) = lookup {
+                    let what = if t.is("$") { format!("${}", toks[k + 1].text) } else { format!("{}()", t.text) };
+                    out.push(("perf-node-lookup", format!("Performance: {} in {}() looks the node up every frame — hint: cache it in an @onready var", what, f.name), st.line, t.text.to_string()));
+                }
+                if toks.iter().any(|t| t.is("await") && t.kind == TokenKind::Ident) {
+                    out.push(("perf-await", format!("Performance: await in {}() suspends a per-frame callback — hint: start the wait from a separate function or a Timer", f.name), st.line, "await".into()));
+                }
+            }
+            if !loops.is_empty() {
+                let grown = match toks.iter().position(|t| t.is("+=") || t.is("=")) {
+                    Some(k) if toks[k].is("+=") => Some(&toks[k + 1..]),
+                    // `s = s + ...`
+                    Some(k) if toks[..k].iter().map(|t| t.text).eq(toks[k + 1..].iter().take(k).map(|t| t.text)) && toks.get(2 * k + 1).is_some_and(|t| t.is("+")) => Some(&toks[k + 1..]),
+                    _ => None,
+                };
+                let stringy = grown.is_some_and(|rhs| rhs.iter().enumerate().any(|(k, t)| t.kind == TokenKind::Str || (t.is("str") && rhs.get(k + 1).is_some_and(|n| n.is("(")))));
+                if stringy {
+                    out.push(("perf-string-concat", format!("Performance: string concatenation in a loop in {}() copies the string every iteration — hint: append to a PackedStringArray and join() it", f.name), st.line, toks[0].text.to_string()));
+                }
+            }
+            if matches!(st.kind, StmtKind::For { .. } | StmtKind::While) { loops.push(st.depth); }
+        }
+    }
+    for c in &script.classes {
+        out.extend(perf_issues(&c.script));
+    }
+    out
+}
+
+/// (code, message, line, name) for local variables and parameters no statement reads. A
+/// local is looked for in the statements after it down to the end of its block; assigning to
+/// it (`x = ...`) isn't a read, `x += ...` is. Names starting with `_` are skipped.
+fn unused_names(script: &Script) -> Vec<(&'static str, String, usize, String)> {
+    let reads = |text: &str, name: &str| {
+        let toks = tokens(text);
+        toks.iter().enumerate().any(|(k, t)| {
+            t.kind == TokenKind::Ident && t.text == name
+                && !(k > 0 && toks[k - 1].is("."))
+                && !(k == 0 && toks.get(1).is_some_and(|n| n.is("=")))
+        })
+    };
+    let mut out = Vec::new();
+    for f in &script.functions {
+        for p in f.params.iter().filter(|p| !p.name.starts_with('_')) {
+            if !f.body.iter().any(|st| reads(&st.text, &p.name)) {
+                out.push(("unused-parameter", format!("Unused parameter: '{}' in {}() — hint: prefix it with '_' if the signature needs it", p.name, f.name), f.line, p.name.clone()));
+            }
+        }
+        for (i, st) in f.body.iter().enumerate() {
+            let StmtKind::Var { name, is_const, .. } = &st.kind else { continue };
+            if name.starts_with('_') || name.is_empty() { continue; }
+            let mut scope = f.body[i + 1..].iter().take_while(|s| s.depth >= st.depth);
+            if !scope.any(|s| reads(&s.text, name)) {
+                let what = if *is_const { "constant" } else { "variable" };
+                out.push(("unused-variable", format!("Unused {}: '{}' in {}() — hint: remove it or prefix it with '_'", what, name, f.name), st.line, name.clone()));
+            }
+        }
+    }
+    for c in &script.classes {
+        out.extend(unused_names(&c.script));
+    }
+    out
+}
+
+/// (code, message, line, name) for declarations breaking the style guide's naming: snake_case
+/// functions, variables and parameters, SCREAMING_SNAKE_CASE constants (PascalCase allowed
+/// for preloaded classes), PascalCase `class_name` and inner classes. Leading underscores
+/// (private names, virtual callbacks) are ignored.
+fn naming_issues(script: &Script) -> Vec<(&'static str, String, usize, String)> {
+    let mut out = Vec::new();
+    let variable = |name: &str, scope: &str, line: usize, out: &mut Vec<_>| {
+        if !is_snake_case(name) {
+            out.push(("variable-name", format!("Variable name not snake_case: '{}'{} — hint: {}", name, scope, to_snake_case(name)), line, name.to_string()));
+        }
+    };
+    let constant = |name: &str, value: Option<&str>, line: usize, out: &mut Vec<_>| {
+        let preloaded = value.is_some_and(|v| v.trim_start().starts_with("preload(") || v.trim_start().starts_with("load(")) && is_pascal_case(name);
+        if !is_screaming_snake_case(name) && !preloaded {
+            out.push(("constant-name", format!("Constant name not SCREAMING_SNAKE_CASE: '{}' — hint: {}", name, to_snake_case(name).to_uppercase()), line, name.to_string()));
+        }
+    };
+    if let Some(cls) = script.class_name.as_deref().filter(|c| !is_pascal_case(c)) {
+        let line = script.lines.iter().find(|l| l.text.starts_with("class_name")).map_or(1, |l| l.line);
+        out.push(("class-name-case", format!("Class name not PascalCase: '{}' — hint: {}", cls, to_pascal_case(cls)), line, cls.to_string()));
+    }
+    for v in &script.vars {
+        if v.is_const { constant(&v.name, v.value.as_deref(), v.line, &mut out); } else { variable(&v.name, "", v.line, &mut out); }
+    }
+    for f in &script.functions {
+        if !is_snake_case(&f.name) {
+            out.push(("function-name", format!("Function name not snake_case: '{}' — hint: {}", f.name, to_snake_case(&f.name)), f.line, f.name.clone()));
+        }
+        let scope = format!(" in {}()", f.name);
+        for p in &f.params { variable(&p.name, &scope, f.line, &mut out); }
+        for st in &f.body {
+            match &st.kind {
+                StmtKind::Var { name, value, is_const: true, .. } => constant(name, value.as_deref(), st.line, &mut out),
+                StmtKind::Var { name, .. } | StmtKind::For { var: name, .. } => variable(name, &scope, st.line, &mut out),
+                _ => {}
+            }
+        }
+    }
+    for c in &script.classes {
+        if !is_pascal_case(&c.name) {
+            out.push(("class-name-case", format!("Class name not PascalCase: '{}' — hint: {}", c.name, to_pascal_case(&c.name)), c.line, c.name.clone()));
+        }
+        out.extend(naming_issues(&c.script));
+    }
+    out
+}
+
+pub(crate) fn is_snake_case(name: &str) -> bool {
+    let n = name.trim_start_matches('_');
+    n.is_empty() || (!n.starts_with(|c: char| c.is_ascii_digit()) && n.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'))
+}
+
+fn is_screaming_snake_case(name: &str) -> bool {
+    let n = name.trim_start_matches('_');
+    !n.is_empty() && !n.starts_with(|c: char| c.is_ascii_digit()) && n.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn is_pascal_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase()) && name.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// `playerSpeed` -> `player_speed`, `HTTPRequest` -> `http_request`, `My-Scene` -> `my_scene`.
+pub(crate) fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() {
+            let prev = i.checked_sub(1).map(|j| chars[j]);
+            let next = chars.get(i + 1);
+            let boundary = prev.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit())
+                || (prev.is_some_and(|p| p.is_ascii_uppercase()) && next.is_some_and(|n| n.is_ascii_lowercase()));
+            if boundary && !out.ends_with('_') { out.push('_'); }
+            out.push(c.to_ascii_lowercase());
+        } else if c == '-' || c == ' ' {
+            if !out.ends_with('_') { out.push('_'); }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// `player_controller` -> `PlayerController`.
+fn to_pascal_case(name: &str) -> String {
+    to_snake_case(name).split('_').filter(|p| !p.is_empty())
+        .map(|p| { let mut c = p.chars(); c.next().map(|f| f.to_ascii_uppercase().to_string() + c.as_str()).unwrap_or_default() })
+        .collect()
+}
+
+/// (file, code, message, line) for signals declared in a script but never emitted or
+/// connected anywhere in the project (`unused-signal`), and for `emit_signal("x")` /
+/// `connect("x", ...)` calls on the script itself where neither the script, its inner classes
+/// nor its base classes declare `x` (`undeclared-script-signal`). Use is matched by name:
+/// `emit_signal("x")`, `x.emit(...)`, `connect("x", ...)`, `x.connect(...)`, `await x` in any
+/// script and `[connection signal="x"]` in any scene or resource.
+fn signal_lint(root: &Path) -> Vec<(PathBuf, &'static str, String, usize)> {
+    let mut declared: Vec<(PathBuf, String, usize)> = Vec::new();
+    let (mut emitted, mut connected) = (HashSet::new(), HashSet::new());
+    // (file, path, call, signal, line) of emit_signal/connect with no receiver or `self`
+    let mut self_calls: Vec<(PathBuf, PathBuf, String, String, usize)> = Vec::new();
+    for entry in WalkDir::new(root).into_iter().flatten() {
+        let path = entry.path();
+        if !entry.file_type().is_file() { continue; }
+        let rel = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+        if rel.starts_with(".godot") || rel.starts_with(".git") || rel.starts_with(FIX_BACKUP_DIR) { continue; }
+        match rel.extension().and_then(|s| s.to_str()) {
+            Some("gd") => {
+                let Some(script) = Script::read(path) else { continue };
+                let mut stack = vec![&script];
+                while let Some(s) = stack.pop() {
+                    declared.extend(s.signals.iter().map(|sig| (rel.clone(), sig.name.clone(), sig.line)));
+                    stack.extend(s.classes.iter().map(|c| &c.script));
+                }
+                for l in &script.lines {
+                    let toks = tokens(&l.text);
+                    for (k, w) in toks.windows(2).enumerate() {
+                        if w[0].is("await") && w[1].kind == TokenKind::Ident {
+                            let awaited = toks[k + 1..].windows(2).take_while(|p| p[0].is(".") || p[0].kind == TokenKind::Ident).filter(|p| p[0].kind == TokenKind::Ident && !p[1].is(".")).map(|p| p[0].text).next();
+                            connected.insert(awaited.unwrap_or(w[1].text).to_string());
+                        }
+                    }
+                    for (k, w) in toks.windows(4).enumerate() {
+                        if w[0].kind == TokenKind::Ident && matches!(w[0].text, "emit_signal" | "connect") && w[1].is("(") {
+                            let Some(name) = w[2].string_value() else { continue };
+                            if w[0].is("emit_signal") { emitted.insert(name.to_string()); } else { connected.insert(name.to_string()); }
+                            let on_self = k == 0 || !toks[k - 1].is(".") || (k >= 2 && toks[k - 2].is("self"));
+                            if on_self { self_calls.push((rel.clone(), path.to_path_buf(), w[0].text.to_string(), name.to_string(), l.line)); }
+                        } else if w[0].kind == TokenKind::Ident && w[1].is(".") && matches!(w[2].text, "emit" | "connect") && w[3].is("(") {
+                            if w[2].is("emit") { emitted.insert(w[0].text.to_string()); } else { connected.insert(w[0].text.to_string()); }
+                        }
+                    }
+                }
+            }
+            Some("tscn" | "tres") => {
+                let Some(doc) = SceneDocument::read(path) else { continue };
+                connected.extend(doc.sections("connection").filter_map(|c| c.attr_str("signal")).map(str::to_string));
+            }
+            _ => {}
+        }
+    }
+
+    let mut out = Vec::new();
+    for (rel, name, line) in declared {
+        if emitted.contains(&name) { continue; }
+        let message = if connected.contains(&name) {
+            format!("Signal never emitted: '{}' is connected but nothing emits it", name)
+        } else {
+            format!("Unused signal: '{}' is never emitted or connected", name)
+        };
+        out.push((rel, "unused-signal", message, line));
+    }
+    let classes = if self_calls.is_empty() { HashMap::new() } else { gdscript::class_registry(root) };
+    let mut known: HashMap<PathBuf, Option<HashSet<String>>> = HashMap::new();
+    for (rel, path, call, name, line) in self_calls {
+        let signals = known.entry(path.clone()).or_insert_with(|| {
+            let mut s = crate::signal_validate::script_signals(root, &path, &classes)?;
+            let script = Script::read(&path)?;
+            let mut stack: Vec<&Script> = script.classes.iter().map(|c| &c.script).collect();
+            while let Some(inner) = stack.pop() {
+                s.extend(inner.signals.iter().map(|sig| sig.name.clone()));
+                stack.extend(inner.classes.iter().map(|c| &c.script));
+            }
+            Some(s)
+        });
+        if signals.as_ref().is_some_and(|s| !s.contains(&name)) {
+            out.push((rel, "undeclared-script-signal", format!("Undeclared signal in script: {}('{}') — no signal '{}' in this script or its base classes", call, name, name), line));
+        }
+    }
+    out
+}
+
+/// What each file loads when it is loaded, as `res://` path -> `res://` paths: a script's
+/// `preload(...)` targets and `extends "path"`, a scene's or resource's ext_resources (uid://
+/// resolved through `uids`). Runtime `load(...)` calls aren't edges.
+fn preload_graph(root: &Path, uids: &UidMap) -> HashMap<String, Vec<String>> {
+    let mut graph: HashMap<String, Vec<String>> = HashMap::new();
+    for entry in WalkDir::new(root).into_iter().flatten() {
+        let path = entry.path();
+        if !entry.file_type().is_file() { continue; }
+        let rel = path.strip_prefix(root).unwrap_or(path);
+        if rel.starts_with(".godot") || rel.starts_with(".git") || rel.starts_with(FIX_BACKUP_DIR) { continue; }
+        let res = format!("res://{}", rel.to_string_lossy().replace('\\', "/"));
+        let targets: Vec<String> = match rel.extension().and_then(|s| s.to_str()) {
+            Some("gd") => {
+                let Some(script) = Script::read(path) else { continue };
+                let mut t: Vec<String> = script.lines.iter()
+                    .flat_map(|l| {
+                        let toks = tokens(&l.text);
+                        toks.windows(4)
+                            .filter(|w| w[0].is("preload") && w[1].is("(") && w[3].is(")"))
+                            .filter_map(|w| w[2].string_value().map(str::to_string))
+                            .collect::<Vec<_>>()
+                    })
+                    .collect();
+                t.extend(script.extends.as_deref().and_then(|e| e.strip_prefix('"')?.strip_suffix('"')).map(str::to_string));
+                t
+            }
+            Some("tscn" | "tres") => {
+                let Some(doc) = SceneDocument::read(path) else { continue };
+                doc.ext_resources().into_values().map(|(p, _)| p).collect()
+            }
+            _ => continue,
+        };
+        let targets = targets.iter().filter_map(|t| uids.res_path(t)).map(str::to_string).collect();
+        graph.insert(res, targets);
+    }
+    graph
+}
+
+/// One cycle per strongly connected group of `graph` that contains a script, as the path from
+/// the group's first script back to itself (`[a, b, a]`; `[a, a]` for a file loading itself).
+fn preload_cycles(graph: &HashMap<String, Vec<String>>) -> Vec<Vec<String>> {
+    let mut nodes: Vec<&String> = graph.keys().collect();
+    nodes.sort();
+    // Tarjan's algorithm, recursive: projects are far shallower than the stack
+    struct Tarjan<'a> {
+        graph: &'a HashMap<String, Vec<String>>,
+        index: HashMap<&'a str, usize>,
+        low: HashMap<&'a str, usize>,
+        stack: Vec<&'a str>,
+        on_stack: HashSet<&'a str>,
+        groups: Vec<Vec<&'a str>>,
+    }
+    impl<'a> Tarjan<'a> {
+        fn visit(&mut self, v: &'a str) {
+            let i = self.index.len();
+            self.index.insert(v, i);
+            self.low.insert(v, i);
+            self.stack.push(v);
+            self.on_stack.insert(v);
+            for w in self.graph.get(v).into_iter().flatten() {
+                let w = w.as_str();
+                if !self.graph.contains_key(w) { continue; }
+                if !self.index.contains_key(w) {
+                    self.visit(w);
+                    let lw = self.low[w];
+                    let lv = self.low.get_mut(v).unwrap();
+                    *lv = (*lv).min(lw);
+                } else if self.on_stack.contains(w) {
+                    let iw = self.index[w];
+                    let lv = self.low.get_mut(v).unwrap();
+                    *lv = (*lv).min(iw);
+                }
+            }
+            if self.low[v] == self.index[v] {
+                let mut group = Vec::new();
+                while let Some(w) = self.stack.pop() {
+                    self.on_stack.remove(w);
+                    group.push(w);
+                    if w == v { break; }
+                }
+                self.groups.push(group);
+            }
+        }
+    }
+    let mut t = Tarjan { graph, index: HashMap::new(), low: HashMap::new(), stack: Vec::new(), on_stack: HashSet::new(), groups: Vec::new() };
+    for v in nodes {
+        if !t.index.contains_key(v.as_str()) { t.visit(v); }
+    }
+
+    let mut out = Vec::new();
+    for group in t.groups {
+        let Some(start) = group.iter().filter(|g| g.ends_with(".gd")).min().copied() else { continue };
+        let members: HashSet<&str> = group.iter().copied().collect();
+        if group.len() == 1 {
+            if graph[start].iter().any(|t| t == start) { out.push(vec![start.to_string(), start.to_string()]); }
+            continue;
+        }
+        // Shortest way around: BFS from start until an edge leads back to it
+        let mut prev: HashMap<&str, &str> = HashMap::new();
+        let mut queue = std::collections::VecDeque::from([start]);
+        let mut last = None;
+        while let Some(v) = queue.pop_front() {
+            let mut next: Vec<&str> = graph[v].iter().map(String::as_str).filter(|w| members.contains(w)).collect();
+            next.sort();
+            if next.contains(&start) { last = Some(v); break; }
+            for w in next {
+                if w != start && !prev.contains_key(w) { prev.insert(w, v); queue.push_back(w); }
+            }
+        }
+        let Some(mut v) = last else { continue };
+        let mut path = vec![start.to_string()];
+        while v != start {
+            path.push(v.to_string());
+            v = prev[v];
+        }
+        path.reverse();
+        path.insert(0, start.to_string());
+        out.push(path);
+    }
+    out
+}
+
+/// 1-based char column of `needle` on 1-based `line` of `contents`, skipping matches inside
+/// a longer identifier (`a` isn't found in `var`).
+fn column_of(contents: &str, line: usize, needle: &str) -> Option<usize> {
+    let text = contents.lines().nth(line.checked_sub(1)?)?;
+    let ident = |c: char| c.is_alphanumeric() || c == '_';
+    let b = text.match_indices(needle).map(|(b, _)| b).find(|&b| {
+        let before = text[..b].chars().next_back();
+        let after = text[b + needle.len()..].chars().next();
+        let joined_before = needle.starts_with(ident) && before.is_some_and(ident);
+        let joined_after = needle.ends_with(ident) && after.is_some_and(ident);
+        !joined_before && !joined_after
+    })?;
+    Some(text[..b].chars().count() + 1)
+}
+
 fn parse_controls(contents: &str) -> (bool, HashSet<String>, Option<Severity>) {
     let mut disabled: HashSet<String> = HashSet::new();
     let mut off = false;
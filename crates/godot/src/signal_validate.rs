@@ -1,157 +1,136 @@
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use std::fs;
 use std::path::{Path, PathBuf};
 
-use crate::scene_validate::SceneIssue;
+use crate::class_db::ClassDb;
+use crate::gdscript::{self, Script};
+use crate::scene_validate::{resolve_node_path, SceneDocument, SceneIssue, SceneNode, Value};
+use crate::suppress::Suppressions;
+use crate::uid::UidMap;
+
+/// CONNECT_DEFERRED, CONNECT_PERSIST, CONNECT_ONE_SHOT, CONNECT_REFERENCE_COUNTED and
+/// CONNECT_APPEND_SOURCE_OBJECT (Godot 4.5)
+const CONNECT_FLAGS: i64 = 1 | 2 | 4 | 8 | 16;
+const CONNECT_DEFERRED: i64 = 1;
+const CONNECT_ONE_SHOT: i64 = 4;
+
+/// Control signals a player triggers again and again, where a one-shot connection is
+/// almost always a mistake.
+const REPEATED_UI_SIGNALS: &[&str] = &[
+    "pressed", "button_down", "button_up", "toggled", "value_changed", "text_changed", "text_submitted",
+    "item_selected", "item_activated", "item_clicked", "id_pressed", "tab_changed", "tab_selected",
+    "gui_input", "mouse_entered", "mouse_exited", "focus_entered", "focus_exited", "resized",
+];
+
+/// Codes of every finding `validate_scene_signals` reports.
+pub const CODES: &[&str] = &[
+    "incomplete-connection", "unknown-connection-node", "unknown-signal", "undeclared-signal", "duplicate-connection",
+    "invalid-method-name", "missing-target-method", "method-arity-mismatch", "invalid-connection-flags",
+    "one-shot-ui-signal", "deferred-free",
+];
+
+/// Codes of the flag checks that flag likely mistakes rather than broken connections;
+/// reported as warnings.
+pub const FLAG_WARNINGS: &[&str] = &["one-shot-ui-signal", "deferred-free"];
 
 /// Validate [connection] entries in a .tscn file.
-/// - Checks that `from` and `to` node paths exist in the scene's node tree
+/// - Checks that `from` and `to` node paths exist in the scene's node tree (instanced and
+///   inherited scenes expanded)
 /// - Checks presence of `signal` and `method` fields
 /// - Flags duplicate connections (same signal/from/to/method)
+/// - Checks `flags=`: undefined bits, one-shot UI signals, deferred calls to freeing methods
+/// - Checks the target method takes the signal's arguments plus `binds` less `unbinds`
 pub fn validate_scene_signals(root: &Path, scene_rel: &Path) -> Vec<SceneIssue> {
-    let path = root.join(scene_rel);
-    let Ok(text) = fs::read_to_string(&path) else { return vec![] };
+    validate_scene_signals_with(root, scene_rel, &UidMap::load(root))
+}
+
+/// `validate_scene_signals` with an already loaded uid table, for validating many scenes.
+pub fn validate_scene_signals_with(root: &Path, scene_rel: &Path, uids: &UidMap) -> Vec<SceneIssue> {
+    let Some(doc) = SceneDocument::read(&root.join(scene_rel)) else { return vec![] };
     let mut out = Vec::new();
+    let re_method_name = Regex::new(r#"^[A-Za-z_]\w*$"#).unwrap();
 
-    let re_node_line = Regex::new(r#"^\s*\[node\b"#).unwrap();
-    let re_conn_line = Regex::new(r#"^\s*\[connection\b"#).unwrap();
-    let re_ext_line = Regex::new(r#"^\s*\[ext_resource\b"#).unwrap();
-    let re_ext_id = Regex::new(r#"id\s*=\s*(\d+)"#).unwrap();
-    let re_ext_path_attr = Regex::new(r#"path\s*=\s*\"([^\"]+)\""#).unwrap();
-    let re_script_ext = Regex::new(r#"script\s*=\s*ExtResource\(\"(\d+)\"\)"#).unwrap();
-    let re_script_path = Regex::new(r#"script\s*=\s*\"(res://[^\"]+)\""#).unwrap();
-
-    // First pass: build ext_resource id -> path map
-    let mut ext_map: HashMap<String, String> = HashMap::new();
-    for line in text.lines() {
-        let line_trim = line.trim_start();
-        if re_ext_line.is_match(line_trim) {
-            let id = re_ext_id
-                .captures(line_trim)
-                .and_then(|c| c.get(1).map(|m| m.as_str().to_string()));
-            let p = re_ext_path_attr
-                .captures(line_trim)
-                .and_then(|c| c.get(1).map(|m| m.as_str().to_string()));
-            if let (Some(id), Some(p)) = (id, p) {
-                ext_map.insert(id, p);
-            }
-        }
-    }
+    // Known node paths and node -> res:// script path / type
+    let tree = doc.node_tree(root, uids);
+    let node_paths = scene_node_paths(&tree);
+    let node_scripts: HashMap<String, String> = tree.iter().filter_map(|n| Some((n.path.clone(), n.script.clone()?))).collect();
+    let node_types: HashMap<String, String> = tree.iter().filter_map(|n| Some((n.path.clone(), n.ty.clone()?))).collect();
+    let root_node_path = tree.first().map(|n| n.path.clone());
+    let classes = gdscript::class_registry(root);
 
-    // Second pass: build known node paths and node->script mapping
-    let mut node_paths: HashSet<String> = HashSet::new();
-    node_paths.insert(".".to_string());
-    let mut node_scripts: HashMap<String, String> = HashMap::new(); // node path -> res:// script path
-    let mut current_node_path: Option<String> = None;
-    let mut root_node_path: Option<String> = None;
-    for line in text.lines() {
-        let line_trim = line.trim_start();
-        if re_node_line.is_match(line_trim) {
-            // Entering a new node header line
-            if let Some(p) = extract_attr(line_trim, "path") {
-                let p = p.to_string();
-                if root_node_path.is_none() && p == "." { root_node_path = Some(p.clone()); }
-                node_paths.insert(p.clone());
-                current_node_path = Some(p.clone());
-                // Capture script attribute if present on the same line
-                if let Some(caps) = re_script_ext.captures(line_trim) {
-                    let id = caps.get(1).unwrap().as_str();
-                    if let Some(path_str) = ext_map.get(id) {
-                        if path_str.starts_with("res://") {
-                            node_scripts.insert(p.clone(), path_str.clone());
-                        }
-                    }
-                } else if let Some(caps) = re_script_path.captures(line_trim) {
-                    let sp = caps.get(1).unwrap().as_str();
-                    node_scripts.insert(p.clone(), sp.to_string());
-                }
-                continue;
-            }
-            let name = extract_attr(line_trim, "name");
-            let parent = extract_attr(line_trim, "parent").unwrap_or(".");
-            if let Some(n) = name {
-                let full = if parent == "." { n.to_string() } else { format!("{}/{}", parent, n) };
-                if root_node_path.is_none() && parent == "." { root_node_path = Some(full.clone()); }
-                node_paths.insert(full.clone());
-                current_node_path = Some(full.clone());
-                // Capture script attribute if present on the same line
-                if let Some(caps) = re_script_ext.captures(line_trim) {
-                    let id = caps.get(1).unwrap().as_str();
-                    if let Some(path_str) = ext_map.get(id) {
-                        if path_str.starts_with("res://") {
-                            node_scripts.insert(full.clone(), path_str.clone());
-                        }
-                    }
-                } else if let Some(caps) = re_script_path.captures(line_trim) {
-                    let sp = caps.get(1).unwrap().as_str();
-                    node_scripts.insert(full.clone(), sp.to_string());
-                }
-            }
-            continue;
-        }
-        // If inside a node block, attempt to capture script assignment
-        if let Some(cur) = current_node_path.as_ref() {
-            if let Some(caps) = re_script_ext.captures(line_trim) {
-                let id = caps.get(1).unwrap().as_str();
-                if let Some(path_str) = ext_map.get(id) {
-                    if path_str.starts_with("res://") {
-                        node_scripts.insert(cur.clone(), path_str.clone());
-                    }
-                }
-            } else if let Some(caps) = re_script_path.captures(line_trim) {
-                let p = caps.get(1).unwrap().as_str();
-                node_scripts.insert(cur.clone(), p.to_string());
-            }
-        }
-    }
+    // Every signal name declared by a built-in class or a project script, when needed
+    let mut declared: Option<HashSet<String>> = None;
 
     // detect duplicate connections
     let mut seen: HashMap<(String,String,String,String), usize> = HashMap::new();
 
-    for (i, line) in text.lines().enumerate() {
-        let lno = i + 1;
-        let line_trim = line.trim_start();
-        if !re_conn_line.is_match(line_trim) { continue; }
-
-        let signal = extract_attr(line_trim, "signal");
-        let from = extract_attr(line_trim, "from");
-        let to = extract_attr(line_trim, "to");
-        let method = extract_attr(line_trim, "method");
+    for conn in doc.sections("connection") {
+        let lno = conn.line;
+        let signal = conn.attr_str("signal");
+        let from = conn.attr_str("from");
+        let to = conn.attr_str("to");
+        let method = conn.attr_str("method");
+        // The source node's path in `tree` ("." is the root's own path)
+        let from_node = from.and_then(|f| if f == "." { root_node_path.clone() } else { Some(f.to_string()) });
 
-    if signal.is_none() { out.push(issue(scene_rel, lno, None, "Connection missing signal field — hint: set signal=\"<name>\" in [connection]")); }
-    if method.is_none() { out.push(issue(scene_rel, lno, None, "Connection missing method field — hint: set method=\"<func>\" and ensure the target node's script defines it")); }
+    if signal.is_none() { out.push(issue(scene_rel, lno, None, "incomplete-connection", "Connection missing signal field — hint: set signal=\"<name>\" in [connection]")); }
+    if method.is_none() { out.push(issue(scene_rel, lno, None, "incomplete-connection", "Connection missing method field — hint: set method=\"<func>\" and ensure the target node's script defines it")); }
 
         if let Some(f) = from {
-            if f != "." && !node_paths.contains(f) {
-                out.push(issue(scene_rel, lno, None, &format!("Unknown connection 'from' node: {} — hint: create node or correct the 'from' path", f)));
+            if !node_paths.contains(f) {
+                out.push(issue(scene_rel, lno, None, "unknown-connection-node", &format!("Unknown connection 'from' node: {} — hint: create node or correct the 'from' path", f)));
+            } else if let Some(s) = signal {
+                // Signal must exist on the source node's class or be declared by its script
+                let ty = from_node.as_ref().and_then(|n| node_types.get(n));
+                let script = from_node.as_ref().and_then(|n| node_scripts.get(n));
+                match ty.and_then(|ty| Some((ty, node_signals(root, ty, script.map(String::as_str), &classes)?))) {
+                    Some((ty, signals)) => if !signals.contains(s) {
+                        out.push(issue(scene_rel, lno, None, "unknown-signal", &format!("Unknown signal: '{}' on from='{}' ({}) — hint: check the signal name or declare 'signal {}' in the node's script", s, f, ty, s)));
+                    }
+                    // The node's signals aren't known, but a name nothing declares can't be right
+                    None => if !declared.get_or_insert_with(|| declared_signals(root)).contains(s) {
+                        out.push(issue(scene_rel, lno, None, "undeclared-signal", &format!("Undeclared signal: '{}' on from='{}' — no script or built-in class declares it", s, f)));
+                    }
+                }
             }
         } else {
-            out.push(issue(scene_rel, lno, None, "Connection missing from field — hint: set from=\"<node_path>\" (use '.' for the scene root)"));
+            out.push(issue(scene_rel, lno, None, "incomplete-connection", "Connection missing from field — hint: set from=\"<node_path>\" (use '.' for the scene root)"));
         }
         if let Some(t) = to {
-            if t != "." && !node_paths.contains(t) {
-                out.push(issue(scene_rel, lno, None, &format!("Unknown connection 'to' node: {} — hint: create node or correct the 'to' path", t)));
+            if !node_paths.contains(t) {
+                out.push(issue(scene_rel, lno, None, "unknown-connection-node", &format!("Unknown connection 'to' node: {} — hint: create node or correct the 'to' path", t)));
             }
         } else {
-            out.push(issue(scene_rel, lno, None, "Connection missing to field — hint: set to=\"<node_path>\" (use '.' for the scene root)"));
+            out.push(issue(scene_rel, lno, None, "incomplete-connection", "Connection missing to field — hint: set to=\"<node_path>\" (use '.' for the scene root)"));
+        }
+
+        let flags = conn.attr("flags").and_then(|v| v.as_int()).unwrap_or(0);
+        if flags < 0 || flags & !CONNECT_FLAGS != 0 {
+            out.push(issue(scene_rel, lno, None, "invalid-connection-flags", &format!("Invalid connection flags: flags={} sets bits Godot doesn't define — hint: combine CONNECT_DEFERRED (1), CONNECT_PERSIST (2), CONNECT_ONE_SHOT (4), CONNECT_REFERENCE_COUNTED (8), CONNECT_APPEND_SOURCE_OBJECT (16)", flags)));
+        }
+        if let (Some(s), Some(f)) = (signal, from) {
+            let ty = from_node.as_ref().and_then(|n| node_types.get(n));
+            if let Some(ty) = ty.filter(|ty| flags & CONNECT_ONE_SHOT != 0 && ClassDb::builtin().inherits(ty, "Control") && REPEATED_UI_SIGNALS.contains(&s)) {
+                out.push(issue(scene_rel, lno, None, "one-shot-ui-signal", &format!("One-shot UI connection: '{}' on from='{}' ({}) disconnects after the first emission — hint: drop CONNECT_ONE_SHOT (4) unless the handler must run only once", s, f, ty)));
+            }
         }
 
         if let (Some(s), Some(f), Some(t), Some(m)) = (signal, from, to, method) {
             let key = (s.to_string(), f.to_string(), t.to_string(), m.to_string());
             if let Some(_prev) = seen.insert(key.clone(), lno) {
-                out.push(issue(scene_rel, lno, None, &format!("Duplicate connection: signal={} from={} to={} method={} — hint: remove the duplicate [connection] line", key.0, key.1, key.2, key.3)));
+                out.push(issue(scene_rel, lno, None, "duplicate-connection", &format!("Duplicate connection: signal={} from={} to={} method={} — hint: remove the duplicate [connection] line", key.0, key.1, key.2, key.3)));
             }
 
             // Method existence checks (GDScript only)
             // Validate method name format first
             let method_name = m.trim();
-            if method_name.is_empty() || !Regex::new(r#"^[A-Za-z_]\w*$"#).unwrap().is_match(method_name) {
-                out.push(issue(scene_rel, lno, None, &format!("Invalid method name: '{}' — hint: use letters/numbers/underscore and start with a letter/underscore", m)));
+            if method_name.is_empty() || !re_method_name.is_match(method_name) {
+                out.push(issue(scene_rel, lno, None, "invalid-method-name", &format!("Invalid method name: '{}' — hint: use letters/numbers/underscore and start with a letter/underscore", m)));
             } else {
                 // Resolve target node path -> script
                 let target_node_lookup = if t == "." {
-                    // Prefer explicit mapping for '.', otherwise use root node computed path
+                    // Prefer explicit mapping for '.', otherwise use the root node's own path
                     if node_scripts.contains_key(".") { Some(".".to_string()) } else { root_node_path.clone() }
                 } else { Some(t.to_string()) };
                 if let Some(tnp) = target_node_lookup {
@@ -160,14 +139,38 @@ pub fn validate_scene_signals(root: &Path, scene_rel: &Path) -> Vec<SceneIssue>
                         if script_res_path.ends_with(".gd") {
                             if let Some(res) = script_res_path.strip_prefix("res://") {
                                 let script_fs_path = root.join(res);
-                                if let Ok(src) = fs::read_to_string(&script_fs_path) {
-                                    let pattern = format!(r#"(?m)^\s*func\s+{}\s*\("#, regex::escape(method_name));
-                                    let re_func = Regex::new(&pattern).unwrap();
-                                    if !re_func.is_match(&src) {
-                                        out.push(issue(scene_rel, lno, None, &format!(
+                                if let Some(script) = Script::read(&script_fs_path) {
+                                    match script.function(method_name) {
+                                        None => out.push(issue(scene_rel, lno, None, "missing-target-method", &format!(
                                             "Target method not found: method='{}' to='{}' — hint: define 'func {}(...)' in {}",
                                             method_name, t, method_name, script_res_path
-                                        )));
+                                        ))),
+                                        Some(func) => {
+                                            if flags & CONNECT_DEFERRED != 0 && frees_self(func) {
+                                                out.push(issue(scene_rel, lno, None, "deferred-free", &format!(
+                                                    "Deferred connection to freeing method: method='{}' to='{}' frees its node — hint: queued calls can run after the node is freed (e.g. the signal fires twice in a frame); connect without CONNECT_DEFERRED (1) or guard with is_queued_for_deletion()",
+                                                    method_name, t
+                                                )));
+                                            }
+                                            let ty = from_node.as_ref().and_then(|n| node_types.get(n)).map(String::as_str);
+                                            let from_script = from_node.as_ref().and_then(|n| node_scripts.get(n)).map(String::as_str);
+                                            if let Some(args) = signal_args(root, ty, from_script, s, &classes) {
+                                                let binds = match conn.attr("binds") { Some(Value::Array(items)) => items.len(), _ => 0 };
+                                                let unbinds = conn.attr("unbinds").and_then(|v| v.as_int()).map_or(0, |n| n.max(0) as usize);
+                                                let passed = args.saturating_sub(unbinds) + binds;
+                                                let required = func.params.iter().filter(|p| p.default.is_none() && !p.name.starts_with("...")).count();
+                                                let variadic = func.params.iter().any(|p| p.name.starts_with("..."));
+                                                let max = if variadic { usize::MAX } else { func.params.len() };
+                                                if passed < required || passed > max {
+                                                    let takes = if required == max { required.to_string() } else if variadic { format!("{}+", required) } else { format!("{}-{}", required, max) };
+                                                    let extra = if binds + unbinds > 0 { format!(" ({} from the signal, {} bound, {} unbound)", args, binds, unbinds) } else { String::new() };
+                                                    out.push(issue(scene_rel, lno, None, "method-arity-mismatch", &format!(
+                                                        "Target method argument mismatch: method='{}' to='{}' takes {} argument(s) but '{}' passes {}{} — hint: match func {}(...) to the signal's arguments, or adjust binds/unbinds",
+                                                        method_name, t, takes, s, passed, extra, method_name
+                                                    )));
+                                                }
+                                            }
+                                        }
                                     }
                                 }
                             }
@@ -180,88 +183,455 @@ pub fn validate_scene_signals(root: &Path, scene_rel: &Path) -> Vec<SceneIssue>
         }
     }
 
+    // connect() calls of the scripts on this scene's nodes, repeating a [connection] or each other
+    let mut in_code: HashMap<(String, String, String, String), String> = HashMap::new();
+    for (node_line, e) in code_edges(root, scene_rel, &tree) {
+        if e.from_status == EndpointStatus::Unknown || e.to_status == EndpointStatus::Unknown { continue; }
+        let e = e.edge;
+        let at = format!("{}:{}", e.script.as_deref().unwrap_or(Path::new("")).display(), e.line);
+        let key = (e.signal, e.from, e.to, e.method);
+        let what = format!("signal={} from={} to={} method={}", key.0, key.1, key.2, key.3);
+        if let Some(&lno) = seen.get(&key) {
+            out.push(issue(scene_rel, lno, None, "duplicate-connection", &format!("Duplicate connection: {} — hint: {} also connects it in code; keep one of them", what, at)));
+        } else if let Some(prev) = in_code.get(&key) {
+            out.push(issue(scene_rel, node_line, None, "duplicate-connection", &format!("Duplicate connection: {} — hint: connected in code at both {} and {}; keep one of them", what, prev, at)));
+        } else {
+            in_code.insert(key, at);
+        }
+    }
+
+    let supp = Suppressions::read(&root.join(scene_rel));
+    out.retain(|i| !supp.suppresses(&i.code, Some(i.line)));
     out
 }
 
-fn extract_attr<'a>(line: &'a str, key: &str) -> Option<&'a str> {
-    let pat = format!("{}=\"", key);
-    let idx = line.find(&pat)? + pat.len();
-    let rest = &line[idx..];
-    let end = rest.find('"')?;
-    Some(&rest[..end])
+/// Signals a node of built-in type `ty` with `script` attached can emit: the class's own and
+/// inherited signals plus those declared along the script's `extends` chain. None when
+/// something in the chain is unknown (a custom type, an unreadable script or base), as the
+/// signal set can't be known then.
+fn node_signals(root: &Path, ty: &str, script: Option<&str>, classes: &HashMap<String, PathBuf>) -> Option<HashSet<String>> {
+    let db = ClassDb::builtin();
+    if !db.contains(ty) { return None; }
+    let mut out: HashSet<String> = db.signals(ty).into_iter().map(str::to_string).collect();
+    if let Some(s) = script {
+        out.extend(script_signals(root, &root.join(s.strip_prefix("res://")?), classes)?);
+    }
+    Some(out)
 }
 
-fn issue(scene_rel: &Path, line: usize, node_path: Option<String>, message: &str) -> SceneIssue {
-    SceneIssue { file: scene_rel.to_path_buf(), line, node_path, message: message.to_string() }
+/// Signals an instance of the script at `path` has: those declared along its `extends` chain
+/// (res:// paths and class_names from `classes`) plus the built-in base class's. None when
+/// something in the chain can't be read or resolved.
+pub(crate) fn script_signals(root: &Path, path: &Path, classes: &HashMap<String, PathBuf>) -> Option<HashSet<String>> {
+    let db = ClassDb::builtin();
+    let mut out = HashSet::new();
+    let mut next = Some(path.to_path_buf());
+    let mut seen = HashSet::new();
+    while let Some(path) = next.take() {
+        if !seen.insert(path.clone()) { break; }
+        let parsed = Script::read(&path)?;
+        out.extend(parsed.signals.into_iter().map(|s| s.name));
+        let Some(base) = parsed.extends else { break };
+        if let Some(res) = base.trim_matches('"').strip_prefix("res://") {
+            next = Some(root.join(res));
+        } else if let Some(p) = classes.get(&base) {
+            next = Some(root.join(p));
+        } else if db.contains(&base) {
+            out.extend(db.signals(&base).into_iter().map(str::to_string));
+        } else {
+            return None;
+        }
+    }
+    Some(out)
+}
+
+/// Number of arguments `signal` passes on a node of type `ty` with `script` attached: the
+/// parameters of the first declaration along the script's `extends` chain, else the built-in
+/// class's. None when it can't be known.
+fn signal_args(root: &Path, ty: Option<&str>, script: Option<&str>, signal: &str, classes: &HashMap<String, PathBuf>) -> Option<usize> {
+    let db = ClassDb::builtin();
+    let mut next = script.and_then(|s| s.strip_prefix("res://")).map(|s| root.join(s));
+    let mut seen = HashSet::new();
+    while let Some(path) = next.take() {
+        if !seen.insert(path.clone()) { break; }
+        let parsed = Script::read(&path)?;
+        if let Some(sig) = parsed.signals.iter().find(|s| s.name == signal) { return Some(sig.params.len()); }
+        let Some(base) = parsed.extends else { break };
+        if let Some(res) = base.trim_matches('"').strip_prefix("res://") {
+            next = Some(root.join(res));
+        } else if let Some(p) = classes.get(&base) {
+            next = Some(root.join(p));
+        } else if db.contains(&base) {
+            return db.signal_args(&base, signal);
+        } else {
+            return None;
+        }
+    }
+    db.signal_args(ty?, signal)
+}
+
+/// Names of all signals built-in classes and the project's scripts (inner classes included)
+/// declare.
+pub(crate) fn declared_signals(root: &Path) -> HashSet<String> {
+    let db = ClassDb::builtin();
+    let mut out: HashSet<String> = db.class_names().into_iter().flat_map(|c| db.signals(c)).map(str::to_string).collect();
+    for entry in walkdir::WalkDir::new(root).into_iter().flatten() {
+        if entry.path().extension().and_then(|s| s.to_str()) != Some("gd") { continue; }
+        let Some(script) = Script::read(entry.path()) else { continue };
+        let mut stack = vec![&script];
+        while let Some(s) = stack.pop() {
+            out.extend(s.signals.iter().map(|sig| sig.name.clone()));
+            stack.extend(s.classes.iter().map(|c| &c.script));
+        }
+    }
+    out
+}
+
+/// Whether `f` calls `free()`/`queue_free()` on its own node (bare or through `self.`).
+fn frees_self(f: &gdscript::Function) -> bool {
+    f.body.iter().any(|st| {
+        let toks = gdscript::tokens(&st.text);
+        toks.iter().enumerate().any(|(i, t)| {
+            let call = matches!(t.text, "free" | "queue_free") && t.kind == gdscript::TokenKind::Ident && toks.get(i + 1).is_some_and(|n| n.is("("));
+            let on_self = i == 0 || !toks[i - 1].is(".") || (i >= 2 && toks[i - 2].is("self"));
+            call && on_self
+        })
+    })
+}
+
+/// Paths of every node in the scene tree, plus "." for the root.
+fn scene_node_paths(tree: &[SceneNode]) -> HashSet<String> {
+    let mut node_paths: HashSet<String> = tree.iter().map(|n| n.path.clone()).collect();
+    node_paths.insert(".".to_string());
+    node_paths
+}
+
+fn issue(scene_rel: &Path, line: usize, node_path: Option<String>, code: &str, message: &str) -> SceneIssue {
+    SceneIssue { file: scene_rel.to_path_buf(), line, node_path, code: code.to_string(), message: message.to_string() }
 }
 
 // --- Graph (DOT) Export ---
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct ConnectionEdge {
     pub scene: PathBuf,
     pub from: String,
     pub to: String,
     pub signal: String,
     pub method: String,
+    /// 1-based line of the `[connection]` header
+    pub line: usize,
+    /// `flags=` as saved: CONNECT_DEFERRED (1), CONNECT_PERSIST (2), CONNECT_ONE_SHOT (4), ...
+    pub flags: i64,
+    /// Root-relative script whose `connect()` call makes the connection (`line` is then the
+    /// script's); None for a `[connection]` of the scene
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<PathBuf>,
+}
+
+/// Whether a connection endpoint names a node of the scene's tree; `unknown` for code
+/// connections on an expression that isn't a node path (`timer.timeout.connect(...)`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EndpointStatus { Resolved, Missing, Unknown }
+
+/// Where a connection is made: a scene's `[connection]` or a script's `connect()` call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionOrigin { Scene, Code }
+
+/// A connection of the signal graph with its flags decoded and the status of both ends.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SignalGraphEdge {
+    #[serde(flatten)]
+    pub edge: ConnectionEdge,
+    pub origin: ConnectionOrigin,
+    pub deferred: bool,
+    pub one_shot: bool,
+    pub from_status: EndpointStatus,
+    pub to_status: EndpointStatus,
+    /// `<scene>:<path>` of each endpoint in the scene that declares the node, following
+    /// instanced and inherited scenes, so a node has one id across every scene it appears in
+    pub from_id: String,
+    pub to_id: String,
+}
+
+impl SignalGraphEdge {
+    pub fn is_resolved(&self) -> bool {
+        self.from_status == EndpointStatus::Resolved && self.to_status == EndpointStatus::Resolved
+    }
 }
 
 /// Extract valid connections (with existing from/to nodes and present signal/method)
 /// from a single .tscn scene file. Returns edges with scene-relative path.
 pub fn extract_scene_connections(root: &Path, scene_rel: &Path) -> Vec<ConnectionEdge> {
-    let path = root.join(scene_rel);
-    let Ok(text) = fs::read_to_string(&path) else { return vec![] };
+    extract_scene_connections_with(root, scene_rel, &UidMap::load(root))
+}
 
-    let re_node_line = Regex::new(r#"^\s*\[node\b"#).unwrap();
-    let re_conn_line = Regex::new(r#"^\s*\[connection\b"#).unwrap();
+/// `extract_scene_connections` with an already loaded uid table.
+pub fn extract_scene_connections_with(root: &Path, scene_rel: &Path, uids: &UidMap) -> Vec<ConnectionEdge> {
+    scene_signal_graph_with(root, scene_rel, uids).into_iter().filter(SignalGraphEdge::is_resolved).map(|e| e.edge).collect()
+}
 
-    // Build known node paths
-    let mut node_paths: HashSet<String> = HashSet::new();
-    node_paths.insert(".".to_string());
-    for line in text.lines() {
-        let line_trim = line.trim_start();
-        if re_node_line.is_match(line_trim) {
-            if let Some(p) = extract_attr(line_trim, "path") {
-                node_paths.insert(p.to_string());
-                continue;
-            }
-            let name = extract_attr(line_trim, "name");
-            let parent = extract_attr(line_trim, "parent").unwrap_or(".");
-            if let Some(n) = name {
-                let full = if parent == "." { n.to_string() } else { format!("{}/{}", parent, n) };
-                node_paths.insert(full);
-            }
-        }
-    }
+/// Every well-formed connection (signal, from, to and method present) of a .tscn scene,
+/// including those whose endpoints aren't in the node tree, plus the `connect()` calls of
+/// the scripts attached to its nodes, sorted.
+pub fn scene_signal_graph_with(root: &Path, scene_rel: &Path, uids: &UidMap) -> Vec<SignalGraphEdge> {
+    let Some(doc) = SceneDocument::read(&root.join(scene_rel)) else { return vec![] };
+    let tree = doc.node_tree(root, uids);
+    let node_paths = scene_node_paths(&tree);
+    let status = |path: &str| if node_paths.contains(path) { EndpointStatus::Resolved } else { EndpointStatus::Missing };
 
-    // Collect well-formed connections
-    let mut edges: Vec<ConnectionEdge> = Vec::new();
-    for line in text.lines() {
-        let line_trim = line.trim_start();
-        if !re_conn_line.is_match(line_trim) { continue; }
-        let signal = extract_attr(line_trim, "signal");
-        let from = extract_attr(line_trim, "from");
-        let to = extract_attr(line_trim, "to");
-        let method = extract_attr(line_trim, "method");
+    let mut edges: Vec<SignalGraphEdge> = Vec::new();
+    for conn in doc.sections("connection") {
+        let signal = conn.attr_str("signal");
+        let from = conn.attr_str("from");
+        let to = conn.attr_str("to");
+        let method = conn.attr_str("method");
         let (Some(s), Some(f), Some(t), Some(m)) = (signal, from, to, method) else { continue };
-        if (f == "." || node_paths.contains(f)) && (t == "." || node_paths.contains(t)) {
-            edges.push(ConnectionEdge {
+        let flags = conn.attr("flags").and_then(|v| v.as_int()).unwrap_or(0);
+        edges.push(SignalGraphEdge {
+            edge: ConnectionEdge {
                 scene: scene_rel.to_path_buf(),
                 from: f.to_string(),
                 to: t.to_string(),
                 signal: s.to_string(),
                 method: m.to_string(),
-            });
-        }
+                line: conn.line,
+                flags,
+                script: None,
+            },
+            origin: ConnectionOrigin::Scene,
+            deferred: flags & 1 != 0,
+            one_shot: flags & 4 != 0,
+            from_status: status(f),
+            to_status: status(t),
+            from_id: String::new(),
+            to_id: String::new(),
+        });
+    }
+    edges.extend(code_edges(root, scene_rel, &tree).into_iter().map(|(_, e)| e));
+    let mut trees = HashMap::new();
+    for e in &mut edges {
+        let id = |path: &str, st: EndpointStatus, trees: &mut HashMap<String, Vec<SceneNode>>| match st {
+            EndpointStatus::Resolved => node_id(root, uids, scene_rel, &tree, path, trees, 0),
+            _ => format!("{}:{}", scene_rel.display(), path),
+        };
+        e.from_id = id(&e.edge.from, e.from_status, &mut trees);
+        e.to_id = id(&e.edge.to, e.to_status, &mut trees);
     }
     // Deterministic ordering
     edges.sort();
     edges
 }
 
+/// `connect()` calls of a script attached to no scene, as edges of the script itself
+/// (`scene` is the script's path); node paths stay as written, their status `unknown`.
+pub fn script_signal_graph(root: &Path, script_rel: &Path) -> Vec<SignalGraphEdge> {
+    let Some(script) = Script::read(&root.join(script_rel)) else { return vec![] };
+    let mut edges: Vec<SignalGraphEdge> = script_connections(&script).into_iter().map(|c| {
+        let shown = |r: &NodeRef| match r { NodeRef::Path(p) | NodeRef::Expr(p) => p.clone() };
+        let mut e = code_edge(script_rel, script_rel, &c, shown(&c.from), EndpointStatus::Unknown, shown(&c.to), EndpointStatus::Unknown);
+        e.from_id = format!("{}:{}", script_rel.display(), e.edge.from);
+        e.to_id = format!("{}:{}", script_rel.display(), e.edge.to);
+        e
+    }).collect();
+    edges.sort();
+    edges
+}
+
+/// Code connections of the scripts on the scene's own nodes, endpoints resolved against the
+/// node the script is attached to, with the line of that node.
+fn code_edges(root: &Path, scene_rel: &Path, tree: &[SceneNode]) -> Vec<(usize, SignalGraphEdge)> {
+    let node_paths = scene_node_paths(tree);
+    let mut out = Vec::new();
+    for node in tree.iter().filter(|n| n.source.is_none()) {
+        let Some(script_rel) = node.script.as_deref().filter(|s| s.ends_with(".gd")).and_then(|s| s.strip_prefix("res://")) else { continue };
+        let Some(script) = Script::read(&root.join(script_rel)) else { continue };
+        let endpoint = |r: &NodeRef| match r {
+            NodeRef::Path(p) => match resolve_node_path(&node.path, p) {
+                Some(path) => { let st = if node_paths.contains(&path) { EndpointStatus::Resolved } else { EndpointStatus::Missing }; (path, st) }
+                None => (p.clone(), EndpointStatus::Unknown),
+            },
+            NodeRef::Expr(e) => (e.clone(), EndpointStatus::Unknown),
+        };
+        for c in script_connections(&script) {
+            let ((from, from_status), (to, to_status)) = (endpoint(&c.from), endpoint(&c.to));
+            out.push((node.line, code_edge(scene_rel, Path::new(script_rel), &c, from, from_status, to, to_status)));
+        }
+    }
+    out
+}
+
+fn code_edge(scene: &Path, script: &Path, c: &CodeConnection, from: String, from_status: EndpointStatus, to: String, to_status: EndpointStatus) -> SignalGraphEdge {
+    SignalGraphEdge {
+        edge: ConnectionEdge {
+            scene: scene.to_path_buf(),
+            from,
+            to,
+            signal: c.signal.clone(),
+            method: c.method.clone(),
+            line: c.line,
+            flags: c.flags,
+            script: Some(script.to_path_buf()),
+        },
+        origin: ConnectionOrigin::Code,
+        deferred: c.flags & 1 != 0,
+        one_shot: c.flags & 4 != 0,
+        from_status,
+        to_status,
+        from_id: String::new(),
+        to_id: String::new(),
+    }
+}
+
+/// `<scene>:<path>` id of the node at `path` of `scene_rel` (whose expanded tree is `tree`):
+/// a node brought in by an instanced or inherited scene gets its id in that scene,
+/// recursively. `trees` caches the instanced scenes' trees by res:// path.
+fn node_id(root: &Path, uids: &UidMap, scene_rel: &Path, tree: &[SceneNode], path: &str, trees: &mut HashMap<String, Vec<SceneNode>>, depth: usize) -> String {
+    let parts: Vec<&str> = if path == "." { vec![] } else { path.split('/').collect() };
+    // The deepest instance the node lies in (or is the root of) that declares it
+    for k in (0..=parts.len()).rev() {
+        let prefix = if k == 0 { ".".to_string() } else { parts[..k].join("/") };
+        let Some(inst) = tree.iter().find(|n| n.path == prefix).and_then(|n| n.instance.clone()) else { continue };
+        let Some(inst_rel) = inst.strip_prefix("res://").map(PathBuf::from) else { continue };
+        if depth >= 16 { break; }
+        let inst_tree = trees.entry(inst.clone())
+            .or_insert_with(|| SceneDocument::read(&root.join(&inst_rel)).map(|d| d.node_tree(root, uids)).unwrap_or_default())
+            .clone();
+        let rel = if k == parts.len() { ".".to_string() } else { parts[k..].join("/") };
+        if inst_tree.iter().any(|n| n.path == rel) {
+            return node_id(root, uids, &inst_rel, &inst_tree, &rel, trees, depth + 1);
+        }
+    }
+    format!("{}:{}", scene_rel.display(), path)
+}
+
+/// A node a `connect()` call names: a path relative to the script's node (`$Path`,
+/// `get_node("Path")`, `self`, or a member var initialised with one), or another expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NodeRef { Path(String), Expr(String) }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CodeConnection { line: usize, signal: String, from: NodeRef, to: NodeRef, method: String, flags: i64 }
+
+/// `connect()` calls in the script's functions (inner classes aside): `sig.connect(callable)`
+/// and `node.sig.connect(callable, flags)`, `connect("sig", callable)`, and Godot 3's
+/// `connect("sig", target, "method", binds, flags)`. Callables are `method`, `target.method`,
+/// `Callable(target, "method")` (`.bind()`/`.unbind()` ignored) or a lambda (`<lambda>`).
+fn script_connections(script: &Script) -> Vec<CodeConnection> {
+    let recv = r#"\$"[^"]+"|\$[\w/]+|%\w+|get_node\(\s*\^?"[^"]+"\s*\)|[A-Za-z_]\w*"#;
+    let re_signal = Regex::new(&format!(r#"(?:^|[^\w.$"%])(?:({})\.)?([A-Za-z_]\w*)\.connect\("#, recv)).unwrap();
+    let re_string = Regex::new(&format!(r#"(?:^|[^\w.$"%])(?:({})\.)?connect\(\s*&?"(\w+)"\s*,"#, recv)).unwrap();
+    let mut out = Vec::new();
+    for st in script.functions.iter().flat_map(|f| &f.body) {
+        for caps in re_signal.captures_iter(&st.text) {
+            let args = call_args(&st.text[caps.get(0).unwrap().end()..]);
+            // `obj.connect("sig", ...)` is the string form, `obj` not a signal
+            if args.first().is_some_and(|a| unquote(a).is_some()) { continue; }
+            let Some((to, method)) = args.first().and_then(|a| callable(a, script)) else { continue };
+            let from = node_ref(caps.get(1).map_or("", |m| m.as_str()), script);
+            out.push(CodeConnection { line: st.line, signal: caps[2].to_string(), from, to, method, flags: connect_flags(args.get(1)) });
+        }
+        for caps in re_string.captures_iter(&st.text) {
+            let args = call_args(&st.text[caps.get(0).unwrap().end()..]);
+            let from = node_ref(caps.get(1).map_or("", |m| m.as_str()), script);
+            let godot3 = args.get(1).and_then(|a| unquote(a));
+            let (to, method, flags) = match godot3 {
+                Some(m) => (node_ref(&args[0], script), m, args.get(3)),
+                None => match args.first().and_then(|a| callable(a, script)) { Some((to, m)) => (to, m, args.get(1)), None => continue },
+            };
+            out.push(CodeConnection { line: st.line, signal: caps[2].to_string(), from, to, method, flags: connect_flags(flags) });
+        }
+    }
+    out
+}
+
+/// Top-level arguments of a call whose `(` was just consumed, up to the matching `)`.
+fn call_args(text: &str) -> Vec<String> {
+    let (mut args, mut cur, mut depth, mut quote) = (Vec::new(), String::new(), 0usize, None);
+    for ch in text.chars() {
+        match (quote, ch) {
+            (Some(q), c) => { if c == q { quote = None; } cur.push(c); continue; }
+            (None, '"' | '\'') => quote = Some(ch),
+            (None, '(' | '[' | '{') => depth += 1,
+            (None, ')' | ']' | '}') if depth == 0 => break,
+            (None, ')' | ']' | '}') => depth -= 1,
+            (None, ',') if depth == 0 => { args.push(cur.trim().to_string()); cur.clear(); continue; }
+            _ => {}
+        }
+        cur.push(ch);
+    }
+    if !cur.trim().is_empty() { args.push(cur.trim().to_string()); }
+    args
+}
+
+fn unquote(s: &str) -> Option<String> {
+    let s = s.trim().trim_start_matches(['&', '^']);
+    s.strip_prefix('"').and_then(|s| s.strip_suffix('"')).map(str::to_string)
+}
+
+/// Target node and method of a callable argument.
+fn callable(expr: &str, script: &Script) -> Option<(NodeRef, String)> {
+    let mut e = expr.trim();
+    for suffix in [".bind(", ".unbind("] {
+        if let Some(i) = e.rfind(suffix) { e = &e[..i]; }
+    }
+    if e.starts_with("func") { return Some((NodeRef::Path(".".into()), "<lambda>".into())); }
+    let (target, method) = match e.strip_prefix("Callable(").and_then(|r| r.strip_suffix(')')) {
+        Some(inner) => {
+            let args = call_args(inner);
+            (node_ref(args.first()?, script), unquote(args.get(1)?)?)
+        }
+        None => match e.rsplit_once('.') {
+            Some((t, m)) => (node_ref(t, script), m.to_string()),
+            None => (NodeRef::Path(".".into()), e.to_string()),
+        },
+    };
+    let ident = !method.is_empty() && method.chars().all(|c| c.is_alphanumeric() || c == '_') && !method.starts_with(|c: char| c.is_ascii_digit());
+    ident.then_some((target, method))
+}
+
+/// What a receiver or target expression names; member vars set to `$Path`/`get_node()` are
+/// followed.
+fn node_ref(expr: &str, script: &Script) -> NodeRef {
+    let e = expr.trim();
+    if e.is_empty() || e == "self" { return NodeRef::Path(".".into()); }
+    let literal = |e: &str| -> Option<String> {
+        let path = if let Some(r) = e.strip_prefix('$') {
+            unquote(r).unwrap_or_else(|| r.to_string())
+        } else {
+            unquote(e.strip_prefix("get_node(")?.strip_suffix(')')?)?
+        };
+        (!path.starts_with('/') && !path.is_empty()).then_some(path)
+    };
+    if let Some(p) = literal(e) { return NodeRef::Path(p); }
+    let member = script.vars.iter().find(|v| v.name == e && !v.is_const).and_then(|v| literal(v.value.as_deref()?.trim()));
+    member.map_or_else(|| NodeRef::Expr(e.to_string()), NodeRef::Path)
+}
+
+/// CONNECT_* bits of a flags argument: names (`CONNECT_ONE_SHOT | CONNECT_DEFERRED`, with or
+/// without `Object.`) or a number.
+fn connect_flags(arg: Option<&String>) -> i64 {
+    let Some(a) = arg else { return 0 };
+    if let Ok(n) = a.trim().parse() { return n; }
+    [("CONNECT_DEFERRED", 1), ("CONNECT_PERSIST", 2), ("CONNECT_ONE_SHOT", 4), ("CONNECT_REFERENCE_COUNTED", 8), ("CONNECT_APPEND_SOURCE_OBJECT", 16)]
+        .iter().filter(|(name, _)| a.contains(name)).map(|(_, bit)| bit).sum()
+}
+
 /// Render a set of connection edges to a DOT graph (directed). Uses composite node ids
 /// "<scene>:<node>" to avoid collisions. rankdir=LR for readability.
 pub fn connections_to_dot(edges: &[ConnectionEdge]) -> String {
+    dot(edges.iter().map(|e| (format!("{}:{}", e.scene.display(), e.from), format!("{}:{}", e.scene.display(), e.to), e)))
+}
+
+/// Render graph edges to DOT with their `from_id`/`to_id` as node ids, so a node shared by
+/// several scenes through instancing is drawn once and their graphs join up.
+pub fn signal_graph_to_dot(edges: &[SignalGraphEdge]) -> String {
+    dot(edges.iter().map(|e| (e.from_id.clone(), e.to_id.clone(), &e.edge)))
+}
+
+fn dot<'a>(edges: impl Iterator<Item = (String, String, &'a ConnectionEdge)>) -> String {
     fn esc<S: AsRef<str>>(s: S) -> String {
         s.as_ref().replace('\"', "\\\"")
     }
@@ -269,13 +639,13 @@ pub fn connections_to_dot(edges: &[ConnectionEdge]) -> String {
     out.push_str("digraph Signals {\n");
     out.push_str("  rankdir=LR;\n");
     // Emit edges
-    for e in edges {
-        let sid = format!("{}:{}", e.scene.display(), e.from);
-        let tid = format!("{}:{}", e.scene.display(), e.to);
+    for (sid, tid, e) in edges {
         let label = format!("{}:{}", e.signal, e.method);
+        // Connections made in code are dashed
+        let style = if e.script.is_some() { ", style=dashed" } else { "" };
         out.push_str(&format!(
-            "  \"{}\" -> \"{}\" [label=\"{}\"];\n",
-            esc(sid), esc(tid), esc(label)
+            "  \"{}\" -> \"{}\" [label=\"{}\"{}];\n",
+            esc(sid), esc(tid), esc(label), style
         ));
     }
     out.push_str("}\n");
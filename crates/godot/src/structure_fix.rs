@@ -1,10 +1,152 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use globset::{Glob, GlobBuilder, GlobMatcher, GlobSet, GlobSetBuilder};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+use crate::config::AnalyzerConfig;
+use crate::gdscript::Script;
+use crate::scene_validate::SceneDocument;
+use crate::script_lint::{is_snake_case, to_snake_case};
+
+/// Where apply keeps its backups and manifest, relative to the project root.
+pub const FIX_DIR: &str = ".structure_fix";
+
+/// Files Godot keeps next to an asset (`tex.png.import`, Godot 4.4's `player.gd.uid`), moved
+/// along with it.
+pub const SIDECAR_EXTS: &[&str] = &["import", "uid"];
+
+/// Layout rules used when `godot-analyzer.toml` has no `[structure] rules`.
+pub const DEFAULT_RULES: &[&str] = &[
+    "**/*.gd => res://scripts/<filename>",
+    "**/*.tscn => res://scenes/<filename>",
+    "**/*.{png,jpg,jpeg,webp,svg,tga,bmp,ogg,wav,mp3,ttf,otf,gdshader,tres} => res://assets/<relpath>",
+];
+
+/// A layout rule, `<glob> => <destination>`: files whose root-relative path matches the glob
+/// (case-insensitively) belong under the destination. The destination is a res:// template
+/// where `<filename>` is the file's name and `<relpath>` its root-relative path; one ending
+/// in `/` gets the file name appended. Files already inside the destination's fixed
+/// directory (the part before any `<...>`) stay where they are.
+#[derive(Debug, Clone)]
+pub struct MoveRule {
+    pub spec: String,
+    glob: GlobMatcher,
+    dest: String,
+}
+
+impl MoveRule {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (glob, dest) = spec.split_once("=>").with_context(|| format!("expected '<glob> => res://<destination>', got '{}'", spec))?;
+        let (glob, dest) = (glob.trim(), dest.trim());
+        if !dest.starts_with("res://") { bail!("destination of '{}' must start with res://", spec); }
+        let placeholders = Regex::new(r"<[^>]*>").unwrap();
+        if let Some(bad) = placeholders.find_iter(dest).map(|m| m.as_str()).find(|p| !matches!(*p, "<filename>" | "<relpath>")) {
+            bail!("unknown placeholder {} in '{}' (<filename>, <relpath>)", bad, spec);
+        }
+        let glob = GlobBuilder::new(glob).case_insensitive(true).build().with_context(|| format!("invalid glob in '{}'", spec))?.compile_matcher();
+        let dest = if dest.ends_with('/') { format!("{}<filename>", dest) } else { dest.to_string() };
+        Ok(Self { spec: spec.trim().to_string(), glob, dest })
+    }
+
+    /// Where the file at root-relative `rel` (`/` separators) belongs, if this rule covers it
+    /// and it isn't there already.
+    pub fn destination(&self, rel: &str) -> Option<String> {
+        if !self.glob.is_match(rel) { return None; }
+        let home = &self.dest["res://".len()..self.dest.find('<').unwrap_or(self.dest.len())];
+        let home = &home[..home.rfind('/').map_or(0, |i| i + 1)];
+        if rel.starts_with(home) { return None; }
+        let filename = rel.rsplit('/').next().unwrap_or(rel);
+        let to = self.dest.replace("<filename>", filename).replace("<relpath>", rel);
+        (to != format!("res://{}", rel)).then_some(to)
+    }
+}
+
+/// How the structure fix renames files (`[structure] rename`). Files keep their names unless
+/// a project opts in, since renames touch every reference to them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RenameStyle {
+    /// `PlayerController.gd` -> `player_controller.gd`, as the GDScript style guide asks
+    SnakeCase,
+    #[default]
+    None,
+}
+
+/// How the structure fix groups files (`[structure] strategy`, `--structure-strategy`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Strategy {
+    /// Scripts, scenes and assets in folders of their own, by the move rules
+    #[default]
+    ByType,
+    /// Each scene with its scripts and assets under `res://features/<scene>/`; files no
+    /// scene claims follow the move rules
+    ByFeature,
+}
+
+impl std::str::FromStr for Strategy {
+    type Err = String;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "by-type" => Ok(Self::ByType),
+            "by-feature" => Ok(Self::ByFeature),
+            other => Err(format!("unknown structure strategy '{}' (by-type|by-feature)", other)),
+        }
+    }
+}
+
+/// Folder the by-feature strategy groups scenes under.
+const FEATURES_DIR: &str = "features";
+
+/// Extensions of the files `RenameStyle::SnakeCase` renames: scripts, scenes, resources,
+/// shaders and assets. C# scripts keep their PascalCase names.
+const RENAMED_EXTS: &[&str] = &[
+    "gd", "tscn", "scn", "tres", "res", "gdshader",
+    "png", "jpg", "jpeg", "webp", "svg", "tga", "bmp", "ogg", "wav", "mp3", "ttf", "otf",
+];
+
+/// A project's layout: the move rules, the rename style and the grouping strategy.
+#[derive(Debug, Clone)]
+pub struct Layout {
+    pub rules: Vec<MoveRule>,
+    pub rename: RenameStyle,
+    pub strategy: Strategy,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self { rules: DEFAULT_RULES.iter().filter_map(|s| MoveRule::parse(s).ok()).collect(), rename: RenameStyle::default(), strategy: Strategy::default() }
+    }
+}
+
+impl Layout {
+    /// The `[structure]` section of the project's `godot-analyzer.toml`; `DEFAULT_RULES` when
+    /// it sets no rules.
+    pub fn configured(root: &Path) -> Result<Self> {
+        let structure = AnalyzerConfig::load(root)?.structure;
+        let rules = if structure.rules.is_empty() { Self::default().rules } else { structure.rules.iter().map(|s| MoveRule::parse(s)).collect::<Result<_>>()? };
+        Ok(Self { rules, rename: structure.rename, strategy: structure.strategy })
+    }
+
+    /// The name the file at `path` (root-relative `rel`) should have, if it differs from its
+    /// current one. A .gd named after its `class_name` keeps it, as `class-name-mismatch` asks.
+    fn renamed(&self, path: &Path, rel: &Path) -> Option<String> {
+        if self.rename == RenameStyle::None { return None; }
+        let ext = rel.extension().and_then(|s| s.to_str())?;
+        if !RENAMED_EXTS.contains(&ext.to_ascii_lowercase().as_str()) { return None; }
+        let stem = rel.file_stem().and_then(|s| s.to_str())?;
+        if is_snake_case(stem) { return None; }
+        if ext == "gd" && Script::read(path).and_then(|s| s.class_name).as_deref() == Some(stem) { return None; }
+        let name = format!("{}.{}", to_snake_case(stem), ext);
+        (name != format!("{}.{}", stem, ext)).then_some(name)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct FixPlan {
     pub rules: Vec<String>,
@@ -12,6 +154,8 @@ pub struct FixPlan {
     pub renames: Vec<FileRename>,
     pub edits: Vec<FileEdit>,
     pub skipped: Vec<String>,
+    /// Moves and renames left out because their destination was taken
+    pub collisions: Vec<Collision>,
     pub stats: PlanStats,
 }
 
@@ -24,200 +168,672 @@ pub struct FileMove { pub from: PathBuf, pub to: PathBuf }
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct FileRename { pub from: PathBuf, pub to: PathBuf }
 
+/// A move or rename to `to`, which `with` takes: the move kept there, or a file already
+/// there that stays. `proposed` is a free destination to use instead.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Collision { pub from: PathBuf, pub to: PathBuf, pub with: PathBuf, pub proposed: PathBuf }
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
-pub struct FileEdit { pub file: PathBuf, pub kind: String, pub count: usize }
-
-/// Build a dry-run structure fix plan. Only proposes moves for now; no apply.
-/// Rules v1:
-/// - .gd -> res://scripts/<filename>
-/// - .tscn -> res://scenes/<filename>
-/// - common assets (images/audio/fonts) -> res://assets/<relpath> (prefix with assets/ if not already)
-/// Skips: addons/, crates/, docs/, target/, .git/, .import files, uid://
+pub struct FileEdit {
+    pub file: PathBuf,
+    pub kind: String,
+    pub count: usize,
+    /// Unified diff of the edit, in dry-run plans
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+}
+
+/// Build a dry-run structure fix plan with the project's layout (see `Layout::configured`;
+/// the defaults when the config can't be read).
 pub fn plan_structure_fix(root: &Path) -> FixPlan {
-    let mut plan = FixPlan::default();
-    plan.rules = vec![
-        ".gd => res://scripts/<filename>".into(),
-        ".tscn => res://scenes/<filename>".into(),
-        "assets(ext) => res://assets/<relpath> (prefix)".into(),
-    ];
-
-    let asset_exts = [
-        // images
-        "png","jpg","jpeg","webp","svg","tga","bmp",
-        // audio
-        "ogg","wav","mp3",
-        // fonts
-        "ttf","otf",
-        // shader/material/text
-        "gdshader","tres",
-    ];
+    plan_structure_fix_with(root, &Layout::configured(root).unwrap_or_default())
+}
+
+/// Build a dry-run plan moving each file by the layout's strategy — the first rule that
+/// matches it, or its feature folder — and renaming files by the layout's rename style. A file
+/// that moves gets its new name at the destination; `renames` holds the files renamed where they are.
+///
+/// Skips: addons/, crates/, docs/, target/, .git/, fix backups, .import and .uid sidecars, uid://
+pub fn plan_structure_fix_with(root: &Path, layout: &Layout) -> FixPlan {
+    let mut rules: Vec<String> = layout.rules.iter().map(|r| r.spec.clone()).collect();
+    if layout.strategy == Strategy::ByFeature { rules.insert(0, "strategy => by-feature".into()); }
+    if layout.rename == RenameStyle::SnakeCase { rules.push("rename => snake_case".into()); }
+    let mut plan = FixPlan { rules, ..Default::default() };
 
+    let mut files = Vec::new();
     for entry in WalkDir::new(root).into_iter().flatten() {
-        let path = entry.path();
         if !entry.file_type().is_file() { continue; }
-        let rel = match path.strip_prefix(root) { Ok(p) => p, Err(_) => continue };
+        let rel = match entry.path().strip_prefix(root) { Ok(p) => p, Err(_) => continue };
 
-        // Skip known folders that are not part of the Godot asset tree
+        // Skip known folders that are not part of the Godot asset tree, and fix backups
         if rel.starts_with("addons") || rel.starts_with("crates") || rel.starts_with("docs") || rel.starts_with("target") || rel.starts_with(".git") { continue; }
-        // Skip import sidecars and lockfiles
-        if rel.extension().and_then(|s| s.to_str()) == Some("import") { continue; }
+        if rel.starts_with(FIX_DIR) || rel.starts_with(".lint_fix") { continue; }
+        // Skip sidecars, which move with their asset
+        if rel.extension().and_then(|s| s.to_str()).is_some_and(|e| SIDECAR_EXTS.contains(&e)) { continue; }
+        files.push(rel.to_string_lossy().replace('\\', "/"));
+    }
+    let features = match layout.strategy {
+        Strategy::ByFeature => feature_homes(root, &files),
+        Strategy::ByType => HashMap::new(),
+    };
 
+    for rel_s in &files {
         plan.stats.scanned += 1;
 
-        let ext = rel.extension().and_then(|s| s.to_str()).unwrap_or("").to_ascii_lowercase();
-        let rel_s = rel.to_string_lossy().replace('\\', "/");
-        let res_from = PathBuf::from(format!("res://{}", rel_s));
-
-        // .gd -> scripts/<filename>
-        if ext == "gd" {
-            if rel.components().next().map(|c| c.as_os_str()) == Some(std::ffi::OsStr::new("scripts")) {
-                continue; // already under scripts
+        let path = root.join(rel_s);
+        let from = PathBuf::from(format!("res://{}", rel_s));
+        let renamed = layout.renamed(&path, Path::new(rel_s));
+        let moved = match features.get(rel_s) {
+            Some(feature) => {
+                let home = format!("{}/{}/", FEATURES_DIR, feature);
+                let filename = rel_s.rsplit('/').next().unwrap_or(rel_s);
+                (!rel_s.starts_with(&home)).then(|| format!("res://{}{}", home, filename))
             }
-            let fname = rel.file_name().unwrap().to_string_lossy().to_string();
-            let to = PathBuf::from(format!("res://scripts/{}", fname));
-            if to != res_from {
-                plan.moves.push(FileMove { from: res_from, to });
+            // Files a team already keeps under features/ stay there
+            None if layout.strategy == Strategy::ByFeature && rel_s.starts_with(&format!("{}/", FEATURES_DIR)) => None,
+            None => layout.rules.iter().find(|r| r.glob.is_match(rel_s)).and_then(|r| r.destination(rel_s)),
+        };
+        match (moved, renamed) {
+            (Some(to), name) => {
+                let to = PathBuf::from(to);
+                let to = match name { Some(name) => to.with_file_name(name), None => to };
+                plan.moves.push(FileMove { from, to });
             }
-            continue;
+            (None, Some(name)) => plan.renames.push(FileRename { to: from.with_file_name(name), from }),
+            (None, None) => {}
         }
+    }
 
-        // .tscn -> scenes/<filename>
-        if ext == "tscn" {
-            if rel.components().next().map(|c| c.as_os_str()) == Some(std::ffi::OsStr::new("scenes")) {
-                continue; // already under scenes
-            }
-            let fname = rel.file_name().unwrap().to_string_lossy().to_string();
-            let to = PathBuf::from(format!("res://scenes/{}", fname));
-            if to != res_from {
-                plan.moves.push(FileMove { from: res_from, to });
+    plan.moves.sort();
+    plan.renames.sort();
+    resolve_collisions(root, &mut plan);
+    plan.edits = preview_edits(root, &plan);
+    plan.stats.proposed = plan.moves.len() + plan.renames.len();
+    plan
+}
+
+/// Take moves and renames whose destination an earlier one (by source) claims, or where a
+/// file that stays put lives, out of the plan: each is listed under `collisions` and
+/// `skipped` with a disambiguated destination — the source folder's name before the file
+/// name (`enemies_player.gd`), else a number after it (`player_2.gd`).
+fn resolve_collisions(root: &Path, plan: &mut FixPlan) {
+    let leaving: HashSet<PathBuf> = plan.moves.iter().map(|m| m.from.clone()).chain(plan.renames.iter().map(|r| r.from.clone())).collect();
+    let staying = |p: &Path| occupant(root, p).is_some_and(|o| !leaving.contains(&o));
+    let mut ops: Vec<(PathBuf, PathBuf, bool)> = plan.moves.drain(..).map(|m| (m.from, m.to, false))
+        .chain(plan.renames.drain(..).map(|r| (r.from, r.to, true)))
+        .collect();
+    ops.sort();
+    // Destination -> the source that claimed it
+    let mut claimed: HashMap<PathBuf, PathBuf> = HashMap::new();
+    let mut collided = Vec::new();
+    for (from, to, rename) in ops {
+        let with = if staying(&to) { Some(to.clone()) } else { claimed.get(&to).cloned() };
+        match with {
+            None => {
+                claimed.insert(to.clone(), from.clone());
+                if rename { plan.renames.push(FileRename { from, to }) } else { plan.moves.push(FileMove { from, to }) }
             }
-            continue;
+            Some(with) => collided.push(Collision { from, to, with, proposed: PathBuf::new() }),
         }
+    }
+    // Proposals avoid every kept destination and each other
+    for c in &mut collided {
+        let free = |p: &Path| !claimed.contains_key(p) && !staying(p);
+        c.proposed = disambiguated(&c.from, &c.to).into_iter().find(|p| free(p)).unwrap_or_default();
+        claimed.insert(c.proposed.clone(), c.from.clone());
+        plan.skipped.push(format!("{} -> {}: destination taken by {}; proposed {}", c.from.display(), c.to.display(), c.with.display(), c.proposed.display()));
+    }
+    plan.moves.sort();
+    plan.renames.sort();
+    plan.collisions = collided;
+}
 
-        // asset ext -> assets/<relpath> (prefix)
-        if asset_exts.contains(&ext.as_str()) {
-            if rel.components().next().map(|c| c.as_os_str()) == Some(std::ffi::OsStr::new("assets")) {
-                continue; // already under assets
-            }
-            let to = PathBuf::from(format!("res://assets/{}", rel_s));
-            if to != res_from {
-                plan.moves.push(FileMove { from: res_from, to });
+/// Alternative destinations for the file at `from` planned to go to `to`, best first.
+fn disambiguated(from: &Path, to: &Path) -> Vec<PathBuf> {
+    let name = to.file_name().and_then(|s| s.to_str()).unwrap_or_default();
+    let (stem, ext) = name.rsplit_once('.').map_or((name, String::new()), |(s, e)| (s, format!(".{}", e)));
+    let from_s = from.to_string_lossy();
+    let folders: Vec<&str> = from_s.trim_start_matches("res://").split('/').collect();
+    let parent = folders.len().checked_sub(2).map(|i| folders[i]).filter(|p| !p.is_empty());
+    let parent = parent.map(|p| if is_snake_case(stem) { to_snake_case(p) } else { p.to_string() });
+    parent.map(|p| format!("{}_{}{}", p, stem, ext)).into_iter()
+        .chain((2..1000).map(|n| format!("{}_{}{}", stem, n, ext)))
+        .map(|n| to.with_file_name(n))
+        .collect()
+}
+
+/// Root-relative file -> the feature it belongs to under the by-feature strategy. Each scene
+/// is a feature named after it in snake_case; another file joins the feature its name matches
+/// (`player.gd` with `player.tscn`), else the one feature whose scenes reference it. Files
+/// several features share, or none, are left out.
+fn feature_homes(root: &Path, files: &[String]) -> HashMap<String, String> {
+    let stem = |rel: &str| Path::new(rel).file_stem().and_then(|s| s.to_str()).map(to_snake_case);
+    let is_scene = |rel: &str| rel.ends_with(".tscn") || rel.ends_with(".scn");
+    let mut homes = HashMap::new();
+    let mut referrers: HashMap<String, HashSet<String>> = HashMap::new();
+    for rel in files.iter().filter(|r| is_scene(r)) {
+        let Some(feature) = stem(rel) else { continue };
+        if let Some(doc) = SceneDocument::read(&root.join(rel)) {
+            for (path, _) in doc.ext_resources().into_values() {
+                if let Some(target) = path.strip_prefix("res://") { referrers.entry(target.to_string()).or_default().insert(feature.clone()); }
             }
-            continue;
         }
+        homes.insert(rel.clone(), feature);
+    }
+    let features: HashSet<String> = homes.values().cloned().collect();
+    for rel in files.iter().filter(|r| !is_scene(r)) {
+        let by_name = stem(rel).filter(|s| features.contains(s));
+        let by_use = referrers.get(rel).filter(|f| f.len() == 1).and_then(|f| f.iter().next().cloned());
+        if let Some(feature) = by_name.or(by_use) { homes.insert(rel.clone(), feature); }
     }
+    homes
+}
 
-    plan.moves.sort();
-    plan.stats.proposed = plan.moves.len();
-    plan
+/// Which moves (and renames) of a plan to apply, for adopting a layout a piece at a time. Globs match a
+/// move's source or destination path without `res://` (`scripts/**` selects everything going
+/// to or living in scripts/); `moves` names moves by source (`res://player.gd`). A move is
+/// kept when it matches an include glob or is listed (everything, when neither is given) and
+/// matches no exclude glob.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MoveSelection {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub moves: Vec<PathBuf>,
+}
+
+impl MoveSelection {
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty() && self.moves.is_empty()
+    }
+
+    /// `plan` narrowed to the selected moves. Errors on an invalid glob or a listed move the
+    /// plan doesn't have. A narrowed plan has no `edits`; `preview_edits` gives its own.
+    pub fn select(&self, plan: &FixPlan) -> Result<FixPlan> {
+        let globs = |patterns: &[String]| -> Result<GlobSet> {
+            let mut b = GlobSetBuilder::new();
+            for p in patterns { b.add(Glob::new(p).with_context(|| format!("invalid glob '{}'", p))?); }
+            Ok(b.build()?)
+        };
+        let (include, exclude) = (globs(&self.include)?, globs(&self.exclude)?);
+        let planned = |m: &PathBuf| plan.moves.iter().any(|mv| &mv.from == m) || plan.renames.iter().any(|r| &r.from == m);
+        if let Some(missing) = self.moves.iter().find(|m| !planned(m)) {
+            bail!("{} isn't moved by the plan", missing.display());
+        }
+        let bare = |p: &Path| p.to_string_lossy().trim_start_matches("res://").to_string();
+        let matches = |set: &GlobSet, from: &Path, to: &Path| set.is_match(bare(from)) || set.is_match(bare(to));
+        let everything = self.include.is_empty() && self.moves.is_empty();
+        let keep = |from: &PathBuf, to: &PathBuf| (everything || matches(&include, from, to) || self.moves.contains(from)) && !matches(&exclude, from, to);
+        let mut out = plan.clone();
+        out.moves.retain(|mv| keep(&mv.from, &mv.to));
+        out.renames.retain(|r| keep(&r.from, &r.to));
+        out.stats.proposed = out.moves.len() + out.renames.len();
+        if !self.is_empty() { out.edits.clear(); }
+        Ok(out)
+    }
+}
+
+/// Apply only the moves of `plan` that `selection` keeps; references are rewritten for those
+/// alone, and the rest stay proposed for a later run.
+pub fn apply_structure_fix_selected(root: &Path, plan: &FixPlan, selection: &MoveSelection) -> Result<ApplySummary> {
+    apply_structure_fix(root, &selection.select(plan)?)
+}
+
+/// Stable fingerprint of a plan (sha256 of its JSON). Callers use it as a confirmation
+/// token: applying only proceeds if the freshly built plan still has the same token.
+pub fn plan_token(plan: &FixPlan) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(serde_json::to_vec(plan).unwrap_or_default());
+    format!("{:x}", hasher.finalize())
+}
+
+/// What the last apply changed, written to `.structure_fix/manifest.json` for
+/// `rollback_structure_fix`: every move in order (sidecars included) and the root-relative
+/// files edited in place, whose originals are under `.structure_fix/backup` like the moved
+/// files'.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct FixManifest {
+    pub moves: Vec<FileMove>,
+    pub edited: Vec<PathBuf>,
+    /// sha256 of each file the apply left behind (moved or edited), by root-relative path, so
+    /// rollback can tell when one was changed since
+    #[serde(default)]
+    pub written: BTreeMap<PathBuf, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct RollbackSummary {
+    /// Moves undone, as `{from: <moved to>, to: <restored at>}`
+    pub restored: Vec<FileMove>,
+    /// Files whose edits were reverted
+    pub reverted: Vec<PathBuf>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct ApplySummary {
     pub moved: Vec<FileMove>,
+    pub renamed: Vec<FileRename>,
+    /// `.import`/`.uid` files moved along with a planned move
+    pub sidecars: Vec<FileMove>,
     pub edited: Vec<FileEdit>,
     pub backed_up: usize,
 }
 
-/// Apply a previously generated plan: move files and update references in .tscn/.tres/.gd.
-/// Creates backups under .structure_fix/backup before moving.
+/// Apply a previously generated plan: move files and their sidecars, and update references in
+/// .tscn/.tres/.gd, project.godot (see `rewrite_project_settings`) and the moved `.import`
+/// files' `source_file`. Creates backups under .structure_fix/backup before moving or editing
+/// and records them in a manifest; only the latest apply can be rolled back.
 pub fn apply_structure_fix(root: &Path, plan: &FixPlan) -> Result<ApplySummary> {
+    // Renames are carried out like moves; (from, to, is a rename)
+    let ops: Vec<(&PathBuf, &PathBuf, bool)> = plan.moves.iter().map(|m| (&m.from, &m.to, false))
+        .chain(plan.renames.iter().map(|r| (&r.from, &r.to, true)))
+        .collect();
     // Build mapping of res://old -> res://new
     let mut mapping: Vec<(String, String)> = Vec::new();
-    for mv in &plan.moves {
-        let old = mv.from.to_string_lossy().to_string();
-        let newp = mv.to.to_string_lossy().to_string();
+    for (from, to, _) in &ops {
+        let old = from.to_string_lossy().to_string();
+        let newp = to.to_string_lossy().to_string();
         if old == newp { continue; }
         mapping.push((old, newp));
     }
     // Move files with backup
     let mut summary = ApplySummary::default();
-    let backup_root = root.join(".structure_fix/backup");
-    for mv in &plan.moves {
-        let from_res = mv.from.to_string_lossy().to_string();
-        let to_res = mv.to.to_string_lossy().to_string();
-        if from_res == to_res { continue; }
+    let fix_dir = root.join(FIX_DIR);
+    let backup_root = fix_dir.join("backup");
+    if mapping.is_empty() { return Ok(summary); }
+    // Never clobber: each destination is free, or a file this apply moves away (moves whose
+    // source is gone already are skipped below)
+    let sources: HashSet<&Path> = mapping.iter().map(|(from, _)| Path::new(from)).collect();
+    let mut targets = HashSet::new();
+    for (from, to) in &mapping {
+        if !res_to_fs(root, from)?.exists() { continue; }
+        if !targets.insert(to.as_str()) { bail!("two moves go to {}; plan again", to); }
+        if occupant(root, Path::new(to)).is_some_and(|o| !sources.contains(o.as_path())) { bail!("{} already exists; plan again", to); }
+    }
+    // A new apply replaces the previous one's backups
+    if fix_dir.exists() { fs::remove_dir_all(&fix_dir)?; }
+    let mut manifest = FixManifest::default();
+    for (step_from, step_to, op) in move_order(&ops) {
+        let step = FileMove { from: step_from.clone(), to: step_to.clone() };
+        // The plan's move or rename this step completes; staging steps report nothing
+        let planned = op.map(|k| (FileMove { from: ops[k].0.clone(), to: ops[k].1.clone() }, ops[k].2));
+        let from_res = planned.as_ref().map_or(&step.from, |(mv, _)| &mv.from).to_string_lossy().to_string();
+        let to_res = step.to.to_string_lossy().to_string();
 
-        let from_fs = res_to_fs(root, &from_res)?;
+        let from_fs = res_to_fs(root, &step.from.to_string_lossy())?;
         let to_fs = res_to_fs(root, &to_res)?;
         if !from_fs.exists() {
             // If already moved, skip
             continue;
         }
-        // Backup original
-        let backup_path = backup_root.join(from_fs.strip_prefix(root).unwrap_or(&from_fs));
-        if let Some(parent) = backup_path.parent() { fs::create_dir_all(parent)?; }
-        fs::copy(&from_fs, &backup_path)?;
+        move_with_backup(root, &backup_root, &from_fs, &to_fs)?;
         summary.backed_up += 1;
-        // Ensure target dir exists
-        if let Some(parent) = to_fs.parent() { fs::create_dir_all(parent)?; }
-        // Perform move (rename)
-        fs::rename(&from_fs, &to_fs)?;
-        summary.moved.push(FileMove { from: mv.from.clone(), to: mv.to.clone() });
+        match &planned {
+            Some((mv, true)) => summary.renamed.push(FileRename { from: mv.from.clone(), to: mv.to.clone() }),
+            Some((mv, false)) => summary.moved.push(mv.clone()),
+            None => {}
+        }
+        manifest.moves.push(step.clone());
+
+        for ext in SIDECAR_EXTS {
+            let sidecar = |p: &Path| PathBuf::from(format!("{}.{}", p.display(), ext));
+            let from_side = sidecar(&from_fs);
+            if !from_side.is_file() { continue; }
+            move_with_backup(root, &backup_root, &from_side, &sidecar(&to_fs))?;
+            summary.backed_up += 1;
+            if let Some((mv, _)) = &planned { summary.sidecars.push(FileMove { from: sidecar(&mv.from), to: sidecar(&mv.to) }); }
+            manifest.moves.push(FileMove { from: sidecar(&step.from), to: sidecar(&step.to) });
+            if *ext == "import" && planned.is_some() {
+                // [deps] source_file names the asset the import settings belong to
+                let to_side = sidecar(&to_fs);
+                let text = fs::read_to_string(&to_side)?;
+                let old = format!("source_file=\"{}\"", from_res);
+                if text.contains(&old) {
+                    fs::write(&to_side, text.replacen(&old, &format!("source_file=\"{}\"", to_res), 1))?;
+                    let rel = to_side.strip_prefix(root).unwrap_or(&to_side).to_path_buf();
+                    summary.edited.push(FileEdit { file: rel, kind: "import-source-file".into(), count: 1, diff: None });
+                }
+            }
+        }
     }
+    // Every staged file has moved on
+    let staging = fix_dir.join("staging");
+    if staging.exists() { fs::remove_dir_all(&staging)?; }
+
+    // Moved files' originals are backed up already; edits of other files back them up first
+    let moved: HashSet<PathBuf> = manifest.moves.iter().filter_map(|m| res_to_fs(root, &m.to.to_string_lossy()).ok()).collect();
 
     // Update references
-    let exts_requiring_extres_scan = ["tscn", "tres"];
-    let re_ext = Regex::new(r#"^\[ext_resource\s+[^\]]*path=\"([^\"]+)\""#).unwrap();
-    let re_gd = Regex::new(r#"(?m)\b(preload|load)\s*\(\s*\"(res://[^\"]+)\"\s*\)"#).unwrap();
+    for rel in reference_files(root) {
+        let path = root.join(&rel);
+        let Ok(contents) = fs::read_to_string(&path) else { continue };
+        let Some((newc, kind, edits)) = rewrite_references(&rel, &contents, &mapping) else { continue };
+        if !moved.contains(&path) {
+            backup_edit(root, &backup_root, &path, &mut manifest)?;
+            summary.backed_up += 1;
+        }
+        fs::write(&path, newc)?;
+        summary.edited.push(FileEdit { file: rel, kind: kind.into(), count: edits, diff: None });
+    }
 
-    for entry in WalkDir::new(root).into_iter().flatten() {
-        let path = entry.path();
-        if !entry.file_type().is_file() { continue; }
-        let rel = match path.strip_prefix(root) { Ok(p) => p, Err(_) => continue };
-        // Skip backups and non-project dirs
-        if rel.starts_with(".structure_fix") || rel.starts_with("target") || rel.starts_with(".git") || rel.starts_with("docs") || rel.starts_with("crates") { continue; }
+    // What the project holds now, to check against before a rollback overwrites it
+    let written: Vec<PathBuf> = moved.into_iter().chain(manifest.edited.iter().map(|rel| root.join(rel))).collect();
+    for path in written {
+        let Ok(bytes) = fs::read(&path) else { continue };
+        manifest.written.insert(path.strip_prefix(root).unwrap_or(&path).to_path_buf(), file_hash(&bytes));
+    }
+    fs::write(fix_dir.join("manifest.json"), serde_json::to_string_pretty(&manifest)?)?;
+    Ok(summary)
+}
+
+/// Hex sha256 of a file's contents, as `FixManifest::written` records it.
+fn file_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Undo the latest apply from its manifest: put moved files (and sidecars) back from their
+/// backups and restore the files it edited. Everything is checked and read before the first
+/// write, so a missing backup, a file in the way or a file changed since the apply leaves the
+/// project untouched. Removes `.structure_fix` afterwards.
+pub fn rollback_structure_fix(root: &Path) -> Result<RollbackSummary> {
+    let fix_dir = root.join(FIX_DIR);
+    let manifest_path = fix_dir.join("manifest.json");
+    let text = fs::read_to_string(&manifest_path).with_context(|| format!("no structure fix to roll back ({} not found)", manifest_path.display()))?;
+    let manifest: FixManifest = serde_json::from_str(&text).with_context(|| format!("parse {}", manifest_path.display()))?;
+    let backup_root = fix_dir.join("backup");
+    let backup = |rel: &Path| fs::read(backup_root.join(rel)).with_context(|| format!("read backup of {}", rel.display()));
+    // Rolling back replaces or removes what the apply wrote; edits made since would be lost
+    for (rel, hash) in &manifest.written {
+        let Ok(bytes) = fs::read(root.join(rel)) else { continue };
+        if file_hash(&bytes) != *hash { bail!("{} changed after the structure fix was applied; undo or save that change elsewhere first", rel.display()); }
+    }
+
+    // (restore at, original contents, moved copy to delete)
+    let mut restores: Vec<(PathBuf, Vec<u8>, Option<PathBuf>)> = Vec::new();
+    let mut summary = RollbackSummary::default();
+    // Undone last to first, so a path another move landed on is free again when it's restored
+    for (n, mv) in manifest.moves.iter().enumerate().rev() {
+        let from = res_to_fs(root, &mv.from.to_string_lossy())?;
+        let to = res_to_fs(root, &mv.to.to_string_lossy())?;
+        let restored_later = manifest.moves[n + 1..].iter().any(|later| later.from == mv.to);
+        if !to.is_file() && !restored_later { bail!("{} is gone; can't roll back its move", mv.to.display()); }
+        let landed_on = manifest.moves[n + 1..].iter().any(|later| later.to == mv.from);
+        if occupant(root, &mv.from).is_some_and(|o| o != mv.to) && !landed_on { bail!("{} exists again; move it out of the way first", mv.from.display()); }
+        restores.push((from.clone(), backup(from.strip_prefix(root).unwrap_or(&from))?, Some(to)));
+        summary.restored.push(FileMove { from: mv.to.clone(), to: mv.from.clone() });
+    }
+    for rel in &manifest.edited {
+        restores.push((root.join(rel), backup(rel)?, None));
+        summary.reverted.push(rel.clone());
+    }
+
+    for (path, contents, moved) in restores {
+        // The moved copy goes first: after a case-only rename it's the same file as `path` on
+        // a case-insensitive filesystem
+        if let Some(moved) = &moved { fs::remove_file(moved)?; }
+        if let Some(parent) = path.parent() { fs::create_dir_all(parent)?; }
+        fs::write(&path, contents)?;
+        if let Some(moved) = moved {
+            // Drop the folders the move created, if nothing else went in them
+            let mut dir = moved.parent();
+            while let Some(d) = dir.filter(|d| *d != root && d.starts_with(root)) {
+                if fs::remove_dir(d).is_err() { break; }
+                dir = d.parent();
+            }
+        }
+    }
+    fs::remove_dir_all(&fix_dir)?;
+    Ok(summary)
+}
 
+/// Copy the file at `path`, about to be edited in place, under `backup_root` and record it.
+fn backup_edit(root: &Path, backup_root: &Path, path: &Path, manifest: &mut FixManifest) -> Result<()> {
+    let rel = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+    let backup_path = backup_root.join(&rel);
+    if let Some(parent) = backup_path.parent() { fs::create_dir_all(parent)?; }
+    fs::copy(path, &backup_path)?;
+    manifest.edited.push(rel);
+    Ok(())
+}
+
+/// The reference edits applying `plan` would make, each with a unified diff from the file's
+/// current path to where it ends up (`file`): what `apply_structure_fix` reports as `edited`.
+pub fn preview_edits(root: &Path, plan: &FixPlan) -> Vec<FileEdit> {
+    let mapping: Vec<(String, String)> = plan.moves.iter().map(|m| (&m.from, &m.to))
+        .chain(plan.renames.iter().map(|r| (&r.from, &r.to)))
+        .map(|(from, to)| (from.to_string_lossy().to_string(), to.to_string_lossy().to_string()))
+        .filter(|(from, to)| from != to)
+        .collect();
+    let mut edits = Vec::new();
+    if mapping.is_empty() { return edits; }
+    let moved_to = |rel: &str| {
+        let res = format!("res://{}", rel);
+        mapping.iter().find(|(old, _)| *old == res).map_or(rel.to_string(), |(_, new)| new["res://".len()..].to_string())
+    };
+    // A moved asset's .import points source_file at the new path
+    for (from, to) in &mapping {
+        let Ok(text) = res_to_fs(root, &format!("{}.import", from)).and_then(|p| Ok(fs::read_to_string(p)?)) else { continue };
+        let old = format!("source_file=\"{}\"", from);
+        if !text.contains(&old) { continue; }
+        let newc = text.replacen(&old, &format!("source_file=\"{}\"", to), 1);
+        let (a, b) = (format!("{}.import", &from["res://".len()..]), format!("{}.import", &to["res://".len()..]));
+        edits.push(FileEdit { file: PathBuf::from(&b), kind: "import-source-file".into(), count: 1, diff: Some(unified_diff(&a, &b, &text, &newc)) });
+    }
+    let mut references = Vec::new();
+    for rel in reference_files(root) {
+        let Ok(contents) = fs::read_to_string(root.join(&rel)) else { continue };
+        let Some((newc, kind, count)) = rewrite_references(&rel, &contents, &mapping) else { continue };
+        let from = rel.to_string_lossy().replace('\\', "/");
+        let to = moved_to(&from);
+        references.push(FileEdit { file: PathBuf::from(&to), kind: kind.into(), count, diff: Some(unified_diff(&from, &to, &contents, &newc)) });
+    }
+    // In apply's order: sidecars as files move, then references by path, project.godot last
+    references.sort_by_key(|e| (e.file == Path::new("project.godot"), e.file.clone()));
+    edits.extend(references);
+    edits
+}
+
+/// Unified diff (3 lines of context) between the same-length line lists of `old` and `new`,
+/// as `a/<from>` and `b/<to>`.
+fn unified_diff(from: &str, to: &str, old: &str, new: &str) -> String {
+    const CONTEXT: usize = 3;
+    let (a, b): (Vec<&str>, Vec<&str>) = (old.lines().collect(), new.lines().collect());
+    let mut out = format!("--- a/{}\n+++ b/{}\n", from, to);
+    if a.len() != b.len() {
+        out.push_str(&format!("@@ -1,{} +1,{} @@\n", a.len(), b.len()));
+        for l in &a { out.push_str(&format!("-{}\n", l)); }
+        for l in &b { out.push_str(&format!("+{}\n", l)); }
+        return out;
+    }
+    let changed: Vec<usize> = (0..a.len()).filter(|&i| a[i] != b[i]).collect();
+    let mut i = 0;
+    while i < changed.len() {
+        // Changes whose context touches share a hunk
+        let mut j = i;
+        while j + 1 < changed.len() && changed[j + 1] <= changed[j] + 2 * CONTEXT + 1 { j += 1; }
+        let start = changed[i].saturating_sub(CONTEXT);
+        let end = (changed[j] + CONTEXT + 1).min(a.len());
+        out.push_str(&format!("@@ -{},{} +{},{} @@\n", start + 1, end - start, start + 1, end - start));
+        let mut k = start;
+        while k < end {
+            if a[k] == b[k] {
+                out.push_str(&format!(" {}\n", a[k]));
+                k += 1;
+                continue;
+            }
+            let run = (k..end).take_while(|&r| a[r] != b[r]).count();
+            for l in &a[k..k + run] { out.push_str(&format!("-{}\n", l)); }
+            for l in &b[k..k + run] { out.push_str(&format!("+{}\n", l)); }
+            k += run;
+        }
+        i = j + 1;
+    }
+    out
+}
+
+/// Root-relative files that may reference moved files: .tscn/.tres and .gd outside backups
+/// and non-project folders, by path, then project.godot.
+fn reference_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(root).sort_by_file_name().into_iter().flatten() {
+        if !entry.file_type().is_file() { continue; }
+        let Ok(rel) = entry.path().strip_prefix(root) else { continue };
+        if rel.starts_with(FIX_DIR) || rel.starts_with("target") || rel.starts_with(".git") || rel.starts_with("docs") || rel.starts_with("crates") { continue; }
         let ext = rel.extension().and_then(|s| s.to_str()).unwrap_or("").to_ascii_lowercase();
-        let is_tscn_or_tres = exts_requiring_extres_scan.contains(&ext.as_str());
-        let is_gd = ext == "gd";
-        if !is_tscn_or_tres && !is_gd { continue; }
+        if ["tscn", "tres", "gd"].contains(&ext.as_str()) { files.push(rel.to_path_buf()); }
+    }
+    if root.join("project.godot").is_file() { files.push(PathBuf::from("project.godot")); }
+    files
+}
 
-        let Ok(contents) = fs::read_to_string(path) else { continue };
+/// `contents` of the file at root-relative `rel` with references to moved files rewritten:
+/// ext_resource paths in .tscn/.tres, `preload`/`load` in .gd and project.godot's settings.
+/// Returns the new contents, the edit kind and the number of references; None when none changed.
+fn rewrite_references(rel: &Path, contents: &str, mapping: &[(String, String)]) -> Option<(String, &'static str, usize)> {
+    let ext = rel.extension().and_then(|s| s.to_str()).unwrap_or("").to_ascii_lowercase();
+    let (newc, kind, edits) = if rel == Path::new("project.godot") {
+        let (newc, edits) = rewrite_project_settings(contents, mapping);
+        (newc, "project-settings", edits)
+    } else if ext == "gd" {
+        // preload/load occurrences
+        let re_gd = Regex::new(r#"(?m)\b(preload|load)\s*\(\s*\"(res://[^\"]+)\"\s*\)"#).unwrap();
         let mut edits = 0usize;
         let mut newc = String::new();
-        if is_tscn_or_tres {
-            for line in contents.lines() {
-                if let Some(cap) = re_ext.captures(line) {
-                    let p = cap.get(1).map(|m| m.as_str()).unwrap_or("");
-                    if let Some((_, newp)) = mapping.iter().find(|(old, _)| old == p) {
-                        let replaced = line.replacen(p, newp, 1);
-                        newc.push_str(&replaced);
-                        newc.push('\n');
-                        edits += 1;
-                        continue;
-                    }
-                }
+        let mut last = 0usize;
+        for m in re_gd.captures_iter(contents) {
+            let m0 = m.get(0).unwrap();
+            newc.push_str(&contents[last..m0.start()]);
+            let whole = m0.as_str();
+            let p = m.get(2).map(|mm| mm.as_str()).unwrap_or("");
+            if let Some((_, newp)) = mapping.iter().find(|(old, _)| old == p) {
+                newc.push_str(&whole.replacen(p, newp, 1));
+                edits += 1;
+            } else {
+                newc.push_str(whole);
+            }
+            last = m0.end();
+        }
+        newc.push_str(&contents[last..]);
+        (newc, "gd-load-preload", edits)
+    } else if ext == "tscn" || ext == "tres" {
+        // ext_resource headers pointing at a moved file, by line
+        let doc = SceneDocument::parse(contents);
+        let moved: HashMap<usize, &(String, String)> = doc.sections("ext_resource")
+            .filter_map(|s| {
+                let p = s.attr_str("path")?;
+                Some((s.line, mapping.iter().find(|(old, _)| old == p)?))
+            })
+            .collect();
+        let mut edits = 0usize;
+        let mut newc = String::new();
+        for (i, line) in contents.lines().enumerate() {
+            if let Some((old, newp)) = moved.get(&(i + 1)) {
+                newc.push_str(&line.replacen(old.as_str(), newp, 1));
+                edits += 1;
+            } else {
                 newc.push_str(line);
-                newc.push('\n');
             }
-        } else {
-            // .gd: update preload/load occurrences
-            let mut last = 0usize;
-            for m in re_gd.captures_iter(&contents) {
-                let m0 = m.get(0).unwrap();
-                newc.push_str(&contents[last..m0.start()]);
-                let whole = m0.as_str();
-                let p = m.get(2).map(|mm| mm.as_str()).unwrap_or("");
-                if let Some((_, newp)) = mapping.iter().find(|(old, _)| old == p) {
-                    let replaced = whole.replacen(p, newp, 1);
-                    newc.push_str(&replaced);
-                    edits += 1;
-                } else {
-                    newc.push_str(whole);
+            newc.push('\n');
+        }
+        (newc, "ext_resource-path", edits)
+    } else {
+        return None;
+    };
+    (edits > 0).then_some((newc, kind, edits))
+}
+
+/// Point the project.godot settings that name project files at their new paths:
+/// `application/run/main_scene`, `application/config/icon`, `[autoload]` entries (`"*res://..."`
+/// included) and resources in `[input]` events. Other settings are left alone. Returns the new
+/// text and the number of paths replaced.
+pub fn rewrite_project_settings(contents: &str, mapping: &[(String, String)]) -> (String, usize) {
+    let doc = SceneDocument::parse(contents);
+    // Where each property's value starts and ends: up to the next property or section
+    let mut starts: Vec<usize> = doc.sections.iter().flat_map(|s| std::iter::once(s.line).chain(s.properties.iter().map(|p| p.line))).collect();
+    starts.sort_unstable();
+    let mut ranges = Vec::new();
+    for s in &doc.sections {
+        for p in &s.properties {
+            let wanted = match s.kind.as_str() {
+                "application" => matches!(p.key.as_str(), "run/main_scene" | "config/icon"),
+                "autoload" | "input" => true,
+                _ => false,
+            };
+            if !wanted { continue; }
+            let end = starts.iter().find(|&&l| l > p.line).copied().unwrap_or(usize::MAX);
+            ranges.push(p.line..end);
+        }
+    }
+    let mut edits = 0;
+    let mut out = String::new();
+    for (i, line) in contents.lines().enumerate() {
+        let mut line = line.to_string();
+        if ranges.iter().any(|r| r.contains(&(i + 1))) {
+            for (old, newp) in mapping {
+                for prefix in ["\"", "\"*"] {
+                    let quoted = format!("{}{}\"", prefix, old);
+                    let n = line.matches(&quoted).count();
+                    if n > 0 {
+                        line = line.replace(&quoted, &format!("{}{}\"", prefix, newp));
+                        edits += n;
+                    }
                 }
-                last = m0.end();
             }
-            newc.push_str(&contents[last..]);
         }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    (out, edits)
+}
 
-        if edits > 0 {
-            fs::write(path, newc)?;
-            summary.edited.push(FileEdit { file: rel.to_path_buf(), kind: if is_gd { "gd-load-preload".into() } else { "ext_resource-path".into() }, count: edits });
+/// Copy `from` to its place under `backup_root`, then move it to `to`.
+/// The moves that carry out `ops`, as (from, to, index of the op it completes): a file
+/// leaves its path before another lands there, and files moving in a cycle are parked under
+/// `.structure_fix/staging` first. Ops that keep their path are dropped.
+fn move_order(ops: &[(&PathBuf, &PathBuf, bool)]) -> Vec<(PathBuf, PathBuf, Option<usize>)> {
+    let mut pending: Vec<(PathBuf, usize)> = ops.iter().enumerate()
+        .filter(|(_, (from, to, _))| from != to).map(|(k, (from, _, _))| ((*from).clone(), k)).collect();
+    let mut steps = Vec::new();
+    while !pending.is_empty() {
+        let free = pending.iter().position(|(_, k)| !pending.iter().any(|(from, j)| j != k && from == ops[*k].1));
+        match free {
+            Some(n) => {
+                let (from, k) = pending.remove(n);
+                steps.push((from, ops[k].1.clone(), Some(k)));
+            }
+            None => {
+                let (from, _) = &mut pending[0];
+                let rel = from.to_string_lossy().trim_start_matches("res://").to_string();
+                let staged = PathBuf::from(format!("res://{}/staging/{}", FIX_DIR, rel));
+                steps.push((from.clone(), staged.clone(), None));
+                *from = staged;
+            }
         }
     }
+    steps
+}
 
-    Ok(summary)
+fn move_with_backup(root: &Path, backup_root: &Path, from: &Path, to: &Path) -> Result<()> {
+    let backup_path = backup_root.join(from.strip_prefix(root).unwrap_or(from));
+    if let Some(parent) = backup_path.parent() { fs::create_dir_all(parent)?; }
+    fs::copy(from, &backup_path)?;
+    if let Some(parent) = to.parent() { fs::create_dir_all(parent)?; }
+    fs::rename(from, to)?;
+    Ok(())
+}
+
+/// The res:// path of the file at `res`, named as on disk; `None` when there is none. On a
+/// case-insensitive filesystem `res://scripts/player.gd` finds `res://scripts/Player.gd`, so a
+/// case-only rename isn't mistaken for a move onto another file.
+fn occupant(root: &Path, res: &Path) -> Option<PathBuf> {
+    let path = res_to_fs(root, &res.to_string_lossy()).ok()?;
+    if !path.exists() { return None; }
+    let name = path.file_name()?.to_string_lossy().into_owned();
+    let names: Vec<String> = path.parent().and_then(|d| fs::read_dir(d).ok()).into_iter().flatten().flatten()
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .collect();
+    let found = names.iter().find(|n| **n == name).or_else(|| names.iter().find(|n| n.eq_ignore_ascii_case(&name)));
+    Some(found.map_or_else(|| res.to_path_buf(), |n| res.with_file_name(n)))
 }
 
 fn res_to_fs(root: &Path, res_uri: &str) -> Result<PathBuf> {
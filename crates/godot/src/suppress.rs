@@ -0,0 +1,65 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Suppression comments in a scene, resource or script, in the file's comment syntax
+/// (`;` for .tscn/.tres, `#` for .gd), with `godot-analyzer:` or `analyzer:` as the prefix:
+///
+/// - `; godot-analyzer: off` skips every finding in the file
+/// - `; godot-analyzer: disable=missing-ext-resource,unknown-signal` skips those codes in the file
+/// - `# analyzer: ignore-next-line` skips findings on the next non-comment line;
+///   `ignore-next-line=code,...` only the listed codes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Suppressions {
+    pub off: bool,
+    pub disabled: HashSet<String>,
+    /// Line -> codes ignored on it; an empty set ignores every code
+    pub lines: HashMap<usize, HashSet<String>>,
+}
+
+impl Suppressions {
+    pub fn parse(contents: &str, comment: char) -> Self {
+        let mut out = Self::default();
+        // ignore-next-line markers waiting for the next non-comment line
+        let mut pending: Option<HashSet<String>> = None;
+        for (i, line) in contents.lines().enumerate() {
+            let t = line.trim();
+            if t.is_empty() { continue; }
+            let Some(c) = t.strip_prefix(comment) else {
+                if let Some(codes) = pending.take() { out.lines.insert(i + 1, codes); }
+                continue;
+            };
+            let c = c.trim();
+            let Some(rest) = c.strip_prefix("godot-analyzer:").or_else(|| c.strip_prefix("analyzer:")) else { continue };
+            let rest = rest.trim();
+            let codes = |list: &str| list.split([',', ' ']).map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect::<HashSet<_>>();
+            if rest == "off" {
+                out.off = true;
+            } else if let Some(list) = rest.strip_prefix("disable=") {
+                out.disabled.extend(codes(list));
+            } else if let Some(rest) = rest.strip_prefix("ignore-next-line") {
+                let listed = rest.trim().strip_prefix('=').map(codes).unwrap_or_default();
+                // Stacked markers merge; a bare one ignores everything
+                match pending.as_mut() {
+                    None => pending = Some(listed),
+                    Some(p) if p.is_empty() => {}
+                    Some(p) if listed.is_empty() => p.clear(),
+                    Some(p) => p.extend(listed),
+                }
+            }
+        }
+        out
+    }
+
+    /// Suppressions of a file on disk, by its extension; none when unreadable.
+    pub fn read(path: &Path) -> Self {
+        let comment = if path.extension().and_then(|s| s.to_str()) == Some("gd") { '#' } else { ';' };
+        fs::read_to_string(path).map(|c| Self::parse(&c, comment)).unwrap_or_default()
+    }
+
+    /// Whether a finding with `code` on `line` is suppressed.
+    pub fn suppresses(&self, code: &str, line: Option<usize>) -> bool {
+        if self.off || self.disabled.contains(code) { return true; }
+        line.and_then(|l| self.lines.get(&l)).is_some_and(|codes| codes.is_empty() || codes.contains(code))
+    }
+}
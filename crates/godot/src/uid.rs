@@ -0,0 +1,97 @@
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::scene_validate::SceneDocument;
+
+/// Godot's resource UID table: `uid://...` -> `res://...`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UidMap {
+    map: HashMap<String, String>,
+}
+
+impl UidMap {
+    /// Collect the UIDs known for the project under root: `.godot/uid_cache.bin` if present,
+    /// then (overriding it, as files on disk are newer than the cache) `.uid` sidecars (4.4+),
+    /// the `uid` in .tscn/.tres headers and `.import` files' `[remap]` section.
+    pub fn load(root: &Path) -> Self {
+        let mut map: HashMap<String, String> = read_uid_cache(&root.join(".godot/uid_cache.bin")).unwrap_or_default().into_iter().collect();
+        for entry in WalkDir::new(root).into_iter().flatten() {
+            let path = entry.path();
+            if !entry.file_type().is_file() { continue; }
+            let rel = path.strip_prefix(root).unwrap_or(path);
+            if rel.starts_with(".godot") || rel.starts_with(".git") { continue; }
+            let ext = rel.extension().and_then(|s| s.to_str()).unwrap_or("");
+            if !matches!(ext, "uid" | "tscn" | "tres" | "import") { continue; }
+            let Ok(text) = fs::read_to_string(path) else { continue };
+            let rel_s = rel.to_string_lossy().replace('\\', "/");
+            let found = match ext {
+                // `player.gd.uid` holds the uid of `player.gd`
+                "uid" => text.lines().next().map(|l| (l.trim().to_string(), rel_s.trim_end_matches(".uid"))),
+                "import" => SceneDocument::parse(&text).sections("remap").next()
+                    .and_then(|s| s.property("uid")?.value.as_str().map(str::to_string))
+                    .map(|uid| (uid, rel_s.trim_end_matches(".import"))),
+                _ => SceneDocument::parse(&text).sections.first()
+                    .and_then(|s| s.attr_str("uid").map(str::to_string))
+                    .map(|uid| (uid, rel_s.as_str())),
+            };
+            if let Some((uid, target)) = found.filter(|(uid, _)| uid.starts_with("uid://")) {
+                map.insert(uid, format!("res://{}", target));
+            }
+        }
+        Self { map }
+    }
+
+    /// The `res://` path `uid` refers to.
+    pub fn resolve(&self, uid: &str) -> Option<&str> {
+        self.map.get(uid).map(String::as_str)
+    }
+
+    /// `path` as a `res://` path: unchanged unless it is a `uid://`, which is resolved; None
+    /// for a uid nothing declares (a dangling reference).
+    pub fn res_path<'a>(&'a self, path: &'a str) -> Option<&'a str> {
+        if path.starts_with("uid://") { self.resolve(path) } else { Some(path) }
+    }
+
+    pub fn len(&self) -> usize { self.map.len() }
+
+    pub fn is_empty(&self) -> bool { self.map.is_empty() }
+}
+
+/// Entries of a `uid_cache.bin`: a little-endian u32 count, then per entry an i64 id, a u32
+/// byte length and that many bytes of UTF-8 `res://` path.
+pub fn read_uid_cache(path: &Path) -> Result<Vec<(String, String)>> {
+    let bytes = fs::read(path)?;
+    let mut at = 0usize;
+    let mut take = |n: usize| -> Result<&[u8]> {
+        let Some(chunk) = bytes.get(at..at + n) else { bail!("truncated uid cache: {}", path.display()) };
+        at += n;
+        Ok(chunk)
+    };
+    let count = u32::from_le_bytes(take(4)?.try_into()?);
+    let mut out = Vec::new();
+    for _ in 0..count {
+        let id = i64::from_le_bytes(take(8)?.try_into()?);
+        let len = u32::from_le_bytes(take(4)?.try_into()?) as usize;
+        let res = String::from_utf8_lossy(take(len)?).trim_end_matches('\0').to_string();
+        out.push((id_to_text(id), res));
+    }
+    Ok(out)
+}
+
+/// Text form of a numeric resource UID, as `ResourceUID::id_to_text` writes it: base 36,
+/// most significant digit first, digits `a`-`z` then `0`-`9`.
+pub fn id_to_text(id: i64) -> String {
+    if id < 0 { return "uid://<invalid>".to_string(); }
+    let mut id = id as u64;
+    let mut digits = Vec::new();
+    loop {
+        let c = (id % 36) as u8;
+        digits.push(if c < 26 { b'a' + c } else { b'0' + c - 26 } as char);
+        id /= 36;
+        if id == 0 { break; }
+    }
+    format!("uid://{}", digits.iter().rev().collect::<String>())
+}
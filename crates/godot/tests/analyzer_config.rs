@@ -0,0 +1,59 @@
+use std::fs;
+use godot_analyzer::config::AnalyzerConfig;
+use godot_analyzer::{analyze_project, lint_gd, Severity};
+
+#[test]
+fn config_file_disables_rules_excludes_paths_and_regrades() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("project.godot"), "config_version=5\n").unwrap();
+    fs::create_dir_all(root.join("legacy")).unwrap();
+    let script = "class_name Wrong\nextends Node\nfunc _ready():\n    print(\"x\")\n    var t = load(\"res://gone.png\")\n";
+    fs::write(root.join("player.gd"), script).unwrap();
+    fs::write(root.join("legacy/old.gd"), script).unwrap();
+    fs::write(root.join("legacy/old.tscn"), "[gd_scene format=3]\n\n[ext_resource type=\"Texture2D\" path=\"res://gone.png\" id=\"1\"]\n").unwrap();
+    fs::write(root.join("godot-analyzer.toml"), r#"
+[rules]
+disable = ["debug-print"]
+
+[severity]
+missing-resource-ref = "error"
+script-lint = "info"
+
+[paths]
+exclude = ["legacy/**"]
+
+[thresholds]
+fail_on = "warn"
+max_issues = 10
+"#).unwrap();
+
+    let lint = lint_gd(root);
+    assert!(lint.iter().all(|i| i.file.as_deref() == Some(std::path::Path::new("player.gd"))), "{:?}", lint);
+    assert!(!lint.iter().any(|i| i.code.as_deref() == Some("debug-print")));
+    let sev = |code: &str| lint.iter().find(|i| i.code.as_deref() == Some(code)).map(|i| i.severity);
+    assert_eq!(sev("missing-resource-ref"), Some(Severity::Error));
+    assert_eq!(sev("class-name-mismatch"), Some(Severity::Info));
+
+    // analyze_project honors the same file
+    let report = analyze_project(root).unwrap();
+    assert!(!report.issues.iter().any(|i| i.message.starts_with("Missing ext_resource path:")), "{:?}", report.issues);
+
+    let config = AnalyzerConfig::load(root).unwrap();
+    assert_eq!(config.thresholds.fail_on, Some(Severity::Warn));
+    assert_eq!(config.thresholds.max_issues, Some(10));
+}
+
+#[test]
+fn config_is_optional_and_rejects_unknown_keys() {
+    let tmp = tempfile::tempdir().unwrap();
+    assert_eq!(AnalyzerConfig::load(tmp.path()).unwrap(), AnalyzerConfig::default());
+    assert!(AnalyzerConfig::parse("[rules]\ndisabled = [\"debug-print\"]\n").is_err());
+    assert!(AnalyzerConfig::parse("[paths]\nexclude = [\"[\"]\n").is_err());
+    let c = AnalyzerConfig::parse("[rules]\nenable = [\"script-lint\"]\n[severity]\nunknown-signal = \"warning\"\n").unwrap();
+    assert!(c.enables("script-lint") && !c.enables("orphan-assets"));
+    assert_eq!(c.severity.get("unknown-signal"), Some(&Severity::Warn));
+    let legacy = AnalyzerConfig::parse("[rules]\nenable = [\"gd-linter\"]\n[severity]\ngd-linter = \"info\"\n").unwrap();
+    assert!(legacy.enables("script-lint"));
+    assert_eq!(legacy.severity.get("script-lint"), Some(&Severity::Info));
+}
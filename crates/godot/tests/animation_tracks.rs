@@ -0,0 +1,100 @@
+use std::fs;
+use std::path::Path;
+use godot_analyzer::scene_validate::validate_scene;
+
+const PLAYER: &str = r#"[gd_scene load_steps=4 format=3]
+
+[ext_resource type="Script" path="res://player.gd" id="1"]
+
+[sub_resource type="Animation" id="Animation_walk"]
+resource_name = "walk"
+tracks/0/type = "value"
+tracks/0/path = NodePath("Sprite2D:frame")
+tracks/1/type = "value"
+tracks/1/path = NodePath("Sprite:frame")
+tracks/2/type = "value"
+tracks/2/path = NodePath("Sprite2D:fram")
+tracks/3/type = "bezier"
+tracks/3/path = NodePath(".:speed")
+tracks/4/type = "value"
+tracks/4/path = NodePath(".:jump_height")
+tracks/5/type = "method"
+tracks/5/path = NodePath(".")
+tracks/6/type = "bezier"
+tracks/6/path = NodePath("Sprite2D:position:x")
+tracks/7/type = "value"
+tracks/7/path = NodePath("%Hud:visible")
+tracks/8/type = "value"
+tracks/8/path = NodePath("Label:text")
+tracks/9/type = "value"
+tracks/9/path = NodePath("Sprite2D:metadata/step")
+
+[sub_resource type="AnimationLibrary" id="AnimationLibrary_1"]
+_data = {
+"walk": SubResource("Animation_walk")
+}
+
+[node name="Player" type="Node2D"]
+script = ExtResource("1")
+
+[node name="Sprite2D" type="Sprite2D" parent="."]
+
+[node name="Body" type="Node2D" parent="."]
+
+[node name="Sprite" type="Sprite2D" parent="Body"]
+
+[node name="Label" type="Label" parent="."]
+
+[node name="AnimationPlayer" type="AnimationPlayer" parent="."]
+libraries = {
+"": SubResource("AnimationLibrary_1")
+}
+"#;
+
+#[test]
+fn flags_animation_tracks_to_missing_nodes_and_properties() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("player.gd"), "extends Node2D\n\nvar speed := 1.0\n").unwrap();
+    fs::write(root.join("player.tscn"), PLAYER).unwrap();
+
+    let issues = validate_scene(root, Path::new("player.tscn"));
+    let found: Vec<(usize, Option<&str>, &str)> = issues.iter().map(|i| (i.line, i.node_path.as_deref(), i.message.as_str())).collect();
+    // Script vars, sub-properties, metadata and unique names pass; Label's properties aren't bundled
+    assert_eq!(found, vec![
+        (10, Some("AnimationPlayer"), "Animation track node missing: 'Sprite:frame' in animation 'walk' (track 1) — 'Sprite' is at 'Body/Sprite'"),
+        (12, Some("AnimationPlayer"), "Animation track property missing: 'Sprite2D:fram' in animation 'walk' (track 2); Sprite2D has no property 'fram'"),
+        (16, Some("AnimationPlayer"), "Animation track property missing: '.:jump_height' in animation 'walk' (track 4); Node2D has no property 'jump_height'"),
+    ]);
+    assert_eq!(issues[0].code, "animation-missing-node");
+    assert_eq!(issues[1].code, "animation-missing-property");
+}
+
+#[test]
+fn resolves_godot3_animations_from_root_node() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("rig.tscn"), r#"[gd_scene load_steps=2 format=2]
+
+[sub_resource type="Animation" id=1]
+resource_name = "wave"
+tracks/0/type = "value"
+tracks/0/path = NodePath("Arm:rotation_degrees")
+tracks/1/type = "value"
+tracks/1/path = NodePath("Leg:rotation_degrees")
+
+[node name="Root" type="Spatial"]
+
+[node name="Rig" type="Spatial" parent="."]
+
+[node name="Arm" type="Spatial" parent="Rig"]
+
+[node name="AnimationPlayer" type="AnimationPlayer" parent="."]
+root_node = NodePath("../Rig")
+anims/wave = SubResource( 1 )
+"#).unwrap();
+
+    let issues = validate_scene(root, Path::new("rig.tscn"));
+    let messages: Vec<&str> = issues.iter().map(|i| i.message.as_str()).filter(|m| m.starts_with("Animation")).collect();
+    assert_eq!(messages, vec!["Animation track node missing: 'Leg:rotation_degrees' in animation 'wave' (track 1)"]);
+}
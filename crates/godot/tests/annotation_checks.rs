@@ -0,0 +1,90 @@
+use std::fs;
+use godot_analyzer::script_lint::lint_gd_scripts;
+
+const CODES: &[&str] = &["onready-missing-node", "export-type", "export-untyped", "tool-placement", "legacy-annotation"];
+
+fn findings(root: &std::path::Path) -> Vec<(String, String, String, Option<usize>)> {
+    let mut got: Vec<_> = lint_gd_scripts(root).into_iter()
+        .filter(|f| CODES.contains(&f.code.as_str()))
+        .map(|f| (f.file.to_string_lossy().replace('\\', "/"), f.code, f.message, f.line))
+        .collect();
+    got.sort_by(|a, b| (&a.0, a.3, &a.1).cmp(&(&b.0, b.3, &b.1)));
+    got
+}
+
+#[test]
+fn checks_onready_paths_export_types_and_tool_placement() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("project.godot"), "config_version=5\n").unwrap();
+    fs::write(root.join("stats.gd"), "class_name Stats\nextends Resource\n").unwrap();
+    fs::write(root.join("helper.gd"), "class_name Helper\nextends RefCounted\n").unwrap();
+    fs::write(root.join("player.gd"), r#"extends Node2D
+enum Mode { IDLE, RUN }
+@onready var sprite = $Sprite
+@onready var gun: Node = $Arm/Gun
+@onready var hud = get_node("../HUD")
+@onready var missing = $Shield
+@onready var deep = get_node("Arm/Missing")
+@export var speed: float = 1.0
+@export var stats: Stats
+@export var helper: Helper
+@export var on_hit: Callable
+@export var mode: Mode
+@export var drops: Array[Stats]
+@export var thing
+@export_group("Misc")
+@export var tags: Array[Callable]
+"#).unwrap();
+    fs::write(root.join("main.tscn"), r#"[gd_scene load_steps=2 format=3]
+
+[ext_resource type="Script" path="res://player.gd" id="1"]
+
+[node name="Main" type="Node"]
+
+[node name="HUD" type="Control" parent="."]
+
+[node name="Player" type="Node2D" parent="."]
+script = ExtResource("1")
+
+[node name="Sprite" type="Sprite2D" parent="Player"]
+
+[node name="Arm" type="Node2D" parent="Player"]
+
+[node name="Gun" type="Node2D" parent="Player/Arm"]
+"#).unwrap();
+    fs::write(root.join("late_tool.gd"), "extends Node\n@tool\n").unwrap();
+
+    let got = findings(root);
+    let expected = vec![
+        ("late_tool.gd", "tool-placement", "Misplaced @tool: it must come before extends and class_name — hint: move it to the top of the script", Some(2)),
+        ("player.gd", "onready-missing-node", "Missing @onready node: $Shield (var missing) not found from 'Player' in res://main.tscn", Some(6)),
+        ("player.gd", "onready-missing-node", "Missing @onready node: get_node(\"Arm/Missing\") (var deep) not found from 'Player' in res://main.tscn", Some(7)),
+        ("player.gd", "export-type", "Export type not exportable: 'helper' is Helper — hint: export built-in types, enums, Resources or Nodes", Some(10)),
+        ("player.gd", "export-type", "Export type not exportable: 'on_hit' is Callable — hint: export built-in types, enums, Resources or Nodes", Some(11)),
+        ("player.gd", "export-untyped", "Export without type: 'thing' — hint: give it a type or a default value", Some(14)),
+        ("player.gd", "export-type", "Export type not exportable: 'tags' is Array[Callable] — hint: export built-in types, enums, Resources or Nodes", Some(16)),
+    ];
+    let expected: Vec<_> = expected.into_iter().map(|(f, c, m, l)| (f.to_string(), c.to_string(), m.to_string(), l)).collect();
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn legacy_keywords_only_in_godot4_projects() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("old.gd"), "tool\nextends Node\nonready var a = $A\nexport(int) var b = 1\nexport var c = 2\nvar tool_name = \"x\"\n").unwrap();
+    fs::write(root.join("project.godot"), "config_version=4\n").unwrap();
+    assert_eq!(findings(root), vec![]);
+
+    fs::write(root.join("project.godot"), "config_version=5\n").unwrap();
+    let got: Vec<_> = findings(root).into_iter().map(|(_, c, m, l)| (c, m, l)).collect();
+    let expected = vec![
+        ("legacy-annotation", "Legacy keyword: 'tool' is Godot 3 syntax — hint: use @tool in Godot 4", Some(1)),
+        ("legacy-annotation", "Legacy keyword: 'onready' is Godot 3 syntax — hint: use @onready in Godot 4", Some(3)),
+        ("legacy-annotation", "Legacy keyword: 'export' is Godot 3 syntax — hint: use @export in Godot 4", Some(4)),
+        ("legacy-annotation", "Legacy keyword: 'export' is Godot 3 syntax — hint: use @export in Godot 4", Some(5)),
+    ];
+    let expected: Vec<_> = expected.into_iter().map(|(c, m, l)| (c.to_string(), m.to_string(), l)).collect();
+    assert_eq!(got, expected);
+}
@@ -0,0 +1,41 @@
+use std::fs;
+use std::path::PathBuf;
+use godot_analyzer::asset_sizes::{asset_sizes, to_markdown};
+
+#[test]
+fn sums_asset_sizes_by_kind_and_folder() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    for (f, bytes) in [
+        ("art/hero.png", 3000),
+        ("art/ui/button.tga", 2048),
+        ("audio/theme.wav", 5000),
+        ("audio/click.ogg", 100),
+        ("main.tscn", 200),
+        ("addons/tool/plugin.gd", 50),
+        (".godot/imported/hero.png-1.ctex", 9999),
+        ("README.md", 400),
+    ] {
+        fs::create_dir_all(root.join(f).parent().unwrap()).unwrap();
+        fs::write(root.join(f), vec![b'x'; bytes]).unwrap();
+    }
+
+    let report = asset_sizes(root);
+    assert_eq!((report.files, report.bytes), (6, 10398));
+    let buckets = |v: &[godot_analyzer::asset_sizes::SizeBucket]| v.iter().map(|b| format!("{} {} {}", b.name, b.files, b.bytes)).collect::<Vec<_>>();
+    // Largest first; .godot and non-assets left out, addons counted
+    assert_eq!(buckets(&report.by_kind), vec!["audio 2 5100", "texture 2 5048", "scene 1 200", "script 1 50"]);
+    assert_eq!(buckets(&report.by_dir), vec!["audio 2 5100", "art 2 5048", ". 1 200", "addons 1 50"]);
+    assert_eq!(report.largest[0].path, PathBuf::from("audio/theme.wav"));
+    assert_eq!(report.largest.len(), 6);
+    let uncompressed: Vec<(PathBuf, &str)> = report.uncompressed.iter().map(|u| (u.path.clone(), u.hint.as_str())).collect();
+    assert_eq!(uncompressed, vec![
+        (PathBuf::from("audio/theme.wav"), "use .ogg for music and long sounds"),
+        (PathBuf::from("art/ui/button.tga"), "use .png or .webp"),
+    ]);
+
+    let md = to_markdown(&report);
+    assert!(md.starts_with("# Asset sizes\n\n6 assets, 10.2 KiB\n"), "{}", md);
+    assert!(md.contains("| audio | 2 | 5.0 KiB | 49.0% |"), "{}", md);
+    assert!(md.contains("| res://art/ui/button.tga | 2.0 KiB | use .png or .webp |"), "{}", md);
+}
@@ -0,0 +1,40 @@
+use std::fs;
+use godot_analyzer::autoload::read_autoloads;
+use godot_analyzer::{analyze_project, to_sarif};
+
+#[test]
+fn validates_autoload_paths_scripts_and_names() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("project.godot"), r#"config_version=5
+
+[autoload]
+
+Game="*res://game.gd"
+Music="*res://music.tscn"
+Gone="*res://gone.gd"
+Broken="*res://broken.gd"
+Player="res://player_autoload.gd"
+"#).unwrap();
+    fs::write(root.join("game.gd"), "extends Node\nvar empty = \"\"\nvar doc = \"\"\"a ( [\n\"\"\"\nvar d = {\"k\": [1, 2]} # ) ]\n").unwrap();
+    fs::write(root.join("music.tscn"), "[gd_scene format=3]\n\n[node name=\"Music\" type=\"AudioStreamPlayer\"]\n").unwrap();
+    fs::write(root.join("broken.gd"), "extends Node\nvar items = [1, 2\nfunc f():\n\tpass\n").unwrap();
+    fs::write(root.join("player_autoload.gd"), "extends Node\n").unwrap();
+    fs::write(root.join("player.gd"), "class_name Player\nextends CharacterBody2D\n").unwrap();
+
+    let autoloads = read_autoloads(root);
+    let names: Vec<_> = autoloads.iter().map(|a| (a.name.as_str(), a.path.as_str(), a.singleton)).collect();
+    assert_eq!(names[0], ("Game", "res://game.gd", true));
+    assert_eq!(names[4], ("Player", "res://player_autoload.gd", false));
+
+    let report = analyze_project(root).unwrap();
+    let mut got: Vec<_> = report.issues.iter().filter(|i| i.message.starts_with("Autoload '")).map(|i| i.message.as_str()).collect();
+    got.sort();
+    assert_eq!(got, [
+        "Autoload 'Broken' script does not parse: unclosed '[' at res://broken.gd:2",
+        "Autoload 'Gone' missing file: res://gone.gd",
+        "Autoload 'Player' collides with class_name Player in player.gd",
+    ]);
+    let sarif = serde_json::to_string(&to_sarif(&report)).unwrap();
+    assert!(sarif.contains("\"ruleId\":\"autoload-validator\""));
+}
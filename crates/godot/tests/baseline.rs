@@ -0,0 +1,36 @@
+use std::fs;
+use godot_analyzer::baseline::Baseline;
+use godot_analyzer::Issue;
+
+#[test]
+fn baseline_suppresses_recorded_issues_and_reports_new_ones() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("baseline.json");
+    let old = vec![
+        Issue::warn("Shadowed variable: 'hp' in f() at line 12 shadows the member declared at line 3", Some("player.gd".into())).at(12, Some(9)),
+        Issue::error("Missing script: res://gone.gd", Some("main.tscn".into())).at(4, None),
+    ];
+    Baseline::from_issues(&old).save(&path).unwrap();
+    let baseline = Baseline::load(&path).unwrap();
+    assert_eq!(baseline.entries.len(), 2);
+
+    // Same findings after lines moved, plus a new one and a second copy of a recorded one
+    let now = vec![
+        Issue::warn("Shadowed variable: 'hp' in f() at line 15 shadows the member declared at line 3", Some("player.gd".into())).at(15, Some(9)),
+        Issue::error("Missing script: res://gone.gd", Some("main.tscn".into())).at(6, None),
+        Issue::error("Missing script: res://gone.gd", Some("main.tscn".into())).at(9, None),
+        Issue::error("Missing script: res://gone.gd", Some("level.tscn".into())).at(4, None),
+    ];
+    let (kept, suppressed) = baseline.filter(now);
+    assert_eq!(suppressed, 2);
+    let left: Vec<_> = kept.iter().map(|i| (i.file.clone().unwrap(), i.line)).collect();
+    assert_eq!(left, vec![("main.tscn".into(), Some(9)), ("level.tscn".into(), Some(4))]);
+}
+
+#[test]
+fn baseline_rejects_unknown_versions() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("baseline.json");
+    fs::write(&path, r#"{"version":7,"entries":[]}"#).unwrap();
+    assert!(Baseline::load(&path).is_err());
+}
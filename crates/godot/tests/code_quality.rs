@@ -0,0 +1,33 @@
+use godot_analyzer::{to_code_quality, GodotProjectReport, Issue};
+
+#[test]
+fn code_quality_entries_carry_location_severity_and_unique_fingerprints() {
+    let report = GodotProjectReport {
+        issues: vec![
+            Issue::error("Missing script: res://gone.gd", Some("main.tscn".into())).at(4, None).with_code("missing-script"),
+            Issue::error("Missing script: res://gone.gd", Some("main.tscn".into())).at(9, None).with_code("missing-script"),
+            Issue::warn("Debug print found", Some("player.gd".into())).at(7, Some(5)).with_code("debug-print"),
+            Issue::info("Missing [application] section", None),
+        ],
+        ..Default::default()
+    };
+    let v = to_code_quality(&report);
+    let entries = v.as_array().unwrap();
+    assert_eq!(entries.len(), 4);
+
+    let first = &entries[0];
+    assert_eq!(first["type"], "issue");
+    assert_eq!(first["check_name"], "scene-validator");
+    assert_eq!(first["description"], "Missing script: res://gone.gd");
+    assert_eq!(first["severity"], "major");
+    assert_eq!(first["location"], serde_json::json!({ "path": "main.tscn", "lines": { "begin": 4 } }));
+    assert_eq!(entries[2]["severity"], "minor");
+    assert_eq!(entries[2]["categories"], serde_json::json!(["Style"]));
+    assert_eq!(entries[3]["location"], serde_json::json!({ "path": "project.godot", "lines": { "begin": 1 } }));
+
+    // Repeats stay distinct; fingerprints don't depend on the line
+    let fp = |e: &serde_json::Value| e["fingerprint"].as_str().unwrap().to_string();
+    assert_eq!(fp(&entries[1]), format!("{}-2", fp(&entries[0])));
+    let moved = GodotProjectReport { issues: vec![Issue::error("Missing script: res://gone.gd", Some("main.tscn".into())).at(12, None).with_code("missing-script")], ..Default::default() };
+    assert_eq!(fp(&to_code_quality(&moved)[0]), fp(&entries[0]));
+}
@@ -0,0 +1,41 @@
+use std::fs;
+use godot_analyzer::{signal_issues_as_report, Severity};
+
+#[test]
+fn flags_undefined_bits_one_shot_ui_signals_and_deferred_frees() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("hud.gd"), "extends Control\n\nfunc _on_start():\n    pass\n\nfunc _on_close():\n    queue_free()\n\nfunc _on_drop():\n    self.free()\n\nfunc _on_hide():\n    $Panel.queue_free()\n").unwrap();
+    let scene = r#"[gd_scene format=3]
+
+[ext_resource type="Script" path="res://hud.gd" id="1"]
+
+[node name="Hud" type="Control"]
+script = ExtResource("1")
+
+[node name="Start" type="Button" parent="."]
+
+[node name="Timer" type="Timer" parent="."]
+
+[connection signal="pressed" from="Start" to="." method="_on_start" flags=4]
+[connection signal="timeout" from="Timer" to="." method="_on_start" flags=4]
+[connection signal="pressed" from="Start" to="." method="_on_close" flags=3]
+[connection signal="timeout" from="Timer" to="." method="_on_drop" flags=1]
+[connection signal="timeout" from="Timer" to="." method="_on_hide" flags=1]
+[connection signal="timeout" from="Timer" to="." method="_on_close" flags=64]
+"#;
+    fs::write(root.join("hud.tscn"), scene).unwrap();
+
+    let issues = signal_issues_as_report(root);
+    let found: Vec<(usize, &str, Severity)> = issues.iter().map(|i| (i.line.unwrap(), i.code.as_deref().unwrap(), i.severity)).collect();
+    assert_eq!(found, vec![
+        (12, "one-shot-ui-signal", Severity::Warn),
+        (14, "deferred-free", Severity::Warn),
+        (15, "deferred-free", Severity::Warn),
+        (17, "invalid-connection-flags", Severity::Error),
+    ]);
+    assert!(issues[0].message.starts_with("One-shot UI connection: 'pressed' on from='Start' (Button)"));
+    assert!(issues[1].message.starts_with("Deferred connection to freeing method: method='_on_close' to='.'"));
+    assert!(issues[3].message.starts_with("Invalid connection flags: flags=64"));
+    assert!(issues[3].message.ends_with("CONNECT_REFERENCE_COUNTED (8), CONNECT_APPEND_SOURCE_OBJECT (16)"), "{}", issues[3].message);
+}
@@ -0,0 +1,46 @@
+use std::fs;
+use std::path::Path;
+use godot_analyzer::config::AnalyzerConfig;
+use godot_analyzer::{analyze_project, Severity};
+
+#[test]
+fn custom_rules_report_each_match_with_location() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("project.godot"), "config_version=5\n").unwrap();
+    fs::write(root.join("player.gd"), "extends Node\nfunc _ready():\n    var hud = get_node(\"/root/Hud\")\n    # analyzer: ignore-next-line=absolute-get-node\n    var ok = get_node(\"/root/Legacy\")\n    var gm = get_node(\"/root/Game\")\n").unwrap();
+    fs::write(root.join("main.tscn"), "[gd_scene format=3]\n\n[node name=\"Main\" type=\"Node\"]\neditor_description = \"TODO: remove\"\n").unwrap();
+    fs::write(root.join("godot-analyzer.toml"), r#"
+[[custom]]
+id = "absolute-get-node"
+pattern = 'get_node\("/root/'
+files = ["**/*.gd"]
+message = "Absolute /root path — reach autoloads by name instead"
+
+[[custom]]
+id = "todo"
+pattern = "TODO"
+message = "Leftover TODO"
+severity = "info"
+"#).unwrap();
+
+    let report = analyze_project(root).unwrap();
+    let found: Vec<_> = report.issues.iter()
+        .filter(|i| matches!(i.code.as_deref(), Some("absolute-get-node" | "todo")))
+        .map(|i| (i.code.clone().unwrap(), i.file.clone().unwrap(), i.line, i.column, i.severity))
+        .collect();
+    assert_eq!(found, vec![
+        ("todo".to_string(), Path::new("main.tscn").to_path_buf(), Some(4), Some(23), Severity::Info),
+        ("absolute-get-node".to_string(), Path::new("player.gd").to_path_buf(), Some(3), Some(15), Severity::Warn),
+        ("absolute-get-node".to_string(), Path::new("player.gd").to_path_buf(), Some(6), Some(14), Severity::Warn),
+    ]);
+}
+
+#[test]
+fn invalid_custom_rules_are_config_errors() {
+    assert!(AnalyzerConfig::parse("[[custom]]\nid = \"x\"\npattern = \"(\"\nmessage = \"m\"\n").is_err());
+    assert!(AnalyzerConfig::parse("[[custom]]\nid = \"x\"\npattern = \"a\"\nfiles = [\"[\"]\nmessage = \"m\"\n").is_err());
+    assert!(AnalyzerConfig::parse("[[custom]]\nid = \"x\"\npattern = \"a\"\n").is_err());
+    let c = AnalyzerConfig::parse("[[custom]]\nid = \"x\"\npattern = \"a\"\nmessage = \"m\"\n").unwrap();
+    assert_eq!(c.custom[0].severity, Severity::Warn);
+}
@@ -0,0 +1,14 @@
+use godot_analyzer::{read_export_presets, ExportPreset};
+use std::fs;
+
+#[test]
+fn reads_presets_with_quoted_values() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    assert!(read_export_presets(root).unwrap().is_empty());
+
+    fs::write(root.join("export_presets.cfg"), "[preset.0]\n\nname=\"Linux\"\nplatform=\"Linux\"\nexport_path=\"build/game.x86_64\"\n\n[preset.0.options]\n\n[preset.1]\n\nname=\"Web\"\nplatform=\"Web\"\nexport_path=\"\"\n").unwrap();
+    let presets = read_export_presets(root).unwrap();
+    assert_eq!(presets[0], ExportPreset { name: "Linux".into(), platform: "Linux".into(), export_path: Some("build/game.x86_64".into()) });
+    assert_eq!(presets[1].name, "Web");
+}
@@ -0,0 +1,78 @@
+use std::fs;
+use godot_analyzer::script_lint::lint_gd_scripts;
+
+const SCRIPT: &str = r#"extends Node
+
+func deep(x):
+    if x:
+        for i in x:
+            while i:
+                if i > 1:
+                    if i > 2:
+                        pass
+
+func branchy(a, b):
+    if a and b:
+        return 1
+    elif a or b:
+        return 2
+    var c = 1 if a else 2
+    match c:
+        1:
+            pass
+        2:
+            pass
+    return c
+
+func short():
+    return 1
+"#;
+
+fn metrics(root: &std::path::Path) -> Vec<(String, String, Option<usize>, Option<usize>)> {
+    let mut got: Vec<_> = lint_gd_scripts(root).into_iter()
+        .filter(|f| matches!(f.code.as_str(), "function-length" | "nesting-depth" | "cyclomatic-complexity"))
+        .map(|f| (f.code, f.message, f.line, f.column))
+        .collect();
+    got.sort_by_key(|g| (g.2, g.0.clone()));
+    got
+}
+
+#[test]
+fn default_limits_flag_only_deep_nesting() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("hotspot.gd"), SCRIPT).unwrap();
+    let got = metrics(root);
+    assert_eq!(got, vec![
+        ("nesting-depth".to_string(), "Nesting too deep: deep() nests 5 blocks at line 9 (max 4) — hint: return early or extract the inner blocks".to_string(), Some(3), Some(6)),
+    ]);
+}
+
+#[test]
+fn thresholds_from_config_apply_to_every_metric() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("hotspot.gd"), SCRIPT).unwrap();
+    fs::write(root.join("godot-analyzer.toml"), "[thresholds]\nmax_function_lines = 10\nmax_nesting_depth = 5\nmax_complexity = 6\n").unwrap();
+    let got = metrics(root);
+    let expected = vec![
+        ("cyclomatic-complexity", "Complexity too high: branchy() has cyclomatic complexity 8 (max 6) — hint: split the branches into functions", Some(11), Some(6)),
+        ("function-length", "Function too long: branchy() spans 12 lines (max 10) — hint: split it into smaller functions", Some(11), Some(6)),
+    ];
+    let expected: Vec<_> = expected.into_iter().map(|(c, m, l, col)| (c.to_string(), m.to_string(), l, col)).collect();
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn metric_findings_report_under_script_lint() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("hotspot.gd"), SCRIPT).unwrap();
+    fs::write(root.join("godot-analyzer.toml"), "[thresholds]\nmax_function_lines = 1\n").unwrap();
+    let issues: Vec<_> = godot_analyzer::lint_gd(root).into_iter().filter(|i| i.code.as_deref() == Some("function-length")).collect();
+    assert_eq!(issues.len(), 3, "{:?}", issues);
+    let sarif = godot_analyzer::to_sarif(&godot_analyzer::GodotProjectReport { issues, ..Default::default() });
+    for r in sarif["runs"][0]["results"].as_array().unwrap() {
+        assert_eq!(r["ruleId"], "script-lint", "{}", r["message"]["text"]);
+    }
+}
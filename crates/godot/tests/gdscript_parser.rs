@@ -0,0 +1,119 @@
+use std::fs;
+use godot_analyzer::gdscript::{parse, tokens, StmtKind, TokenKind};
+use godot_analyzer::script_lint::lint_gd_scripts;
+
+const PLAYER: &str = r#"@tool
+class_name Player extends CharacterBody2D
+
+signal hit(damage: int, source)
+signal died
+
+@export var speed: float = 200.0
+const MAX_HP := 10
+var items = [
+	"a", # first
+	"b",
+]
+enum State { IDLE, RUN = 2 }
+
+func _ready() -> void:
+	var speed := 3
+	for i in range(3):
+		if i > 1:
+			print_debug("x # not a comment")
+	match speed:
+		3:
+			pass
+
+static func make(hp: int = MAX_HP) -> Player: return null
+
+class Inner:
+	var a = 1
+	func f(a):
+		return a
+"#;
+
+#[test]
+fn parses_declarations() {
+    let s = parse(PLAYER);
+    assert!(s.errors.is_empty(), "{:?}", s.errors);
+    assert_eq!(s.annotations.iter().map(|a| (a.name.as_str(), a.line)).collect::<Vec<_>>(), vec![("tool", 1)]);
+    assert_eq!(s.class_name.as_deref(), Some("Player"));
+    assert_eq!(s.extends.as_deref(), Some("CharacterBody2D"));
+
+    assert_eq!(s.signals.iter().map(|g| g.name.as_str()).collect::<Vec<_>>(), vec!["hit", "died"]);
+    let hit = &s.signals[0].params;
+    assert_eq!((hit[0].name.as_str(), hit[0].type_hint.as_deref()), ("damage", Some("int")));
+    assert_eq!((hit[1].name.as_str(), hit[1].type_hint.as_deref()), ("source", None));
+
+    let speed = &s.vars[0];
+    assert_eq!((speed.name.as_str(), speed.type_hint.as_deref(), speed.value.as_deref(), speed.line), ("speed", Some("float"), Some("200.0"), 7));
+    assert_eq!(speed.annotations[0].name, "export");
+    assert!(s.vars[1].is_const && s.vars[1].inferred && s.vars[1].name == "MAX_HP");
+    // Bracketed continuation lines join into one declaration
+    assert_eq!((s.vars[2].name.as_str(), s.vars[2].line), ("items", 9));
+    assert!(s.vars[2].value.as_deref().unwrap().contains("\"b\""));
+    assert_eq!(s.enums[0].name.as_deref(), Some("State"));
+    assert_eq!(s.enums[0].values, vec!["IDLE", "RUN"]);
+
+    let make = s.function("make").unwrap();
+    assert!(make.is_static);
+    assert_eq!(make.return_type.as_deref(), Some("Player"));
+    assert_eq!((make.params[0].type_hint.as_deref(), make.params[0].default.as_deref()), (Some("int"), Some("MAX_HP")));
+    assert_eq!(make.body[0].kind, StmtKind::Return);
+
+    let inner = &s.classes[0];
+    assert_eq!((inner.name.as_str(), inner.line), ("Inner", 26));
+    assert_eq!(inner.script.vars[0].name, "a");
+    assert_eq!(inner.script.function("f").unwrap().params[0].name, "a");
+}
+
+#[test]
+fn parses_function_bodies() {
+    let s = parse(PLAYER);
+    let ready = s.function("_ready").unwrap();
+    assert_eq!((ready.line, ready.end_line, ready.return_type.as_deref()), (15, 22, Some("void")));
+    let shape: Vec<(usize, usize, &str)> = ready.body.iter().map(|st| (st.line, st.depth, match &st.kind {
+        StmtKind::Var { .. } => "var",
+        StmtKind::For { .. } => "for",
+        StmtKind::If => "if",
+        StmtKind::Match => "match",
+        StmtKind::Branch => "branch",
+        StmtKind::Pass => "pass",
+        StmtKind::Expr => "expr",
+        _ => "other",
+    })).collect();
+    assert_eq!(shape, vec![(16, 0, "var"), (17, 0, "for"), (18, 1, "if"), (19, 2, "expr"), (20, 0, "match"), (21, 1, "branch"), (22, 2, "pass")]);
+    assert!(matches!(&ready.body[0].kind, StmtKind::Var { name, inferred: true, .. } if name == "speed"));
+    assert!(matches!(&ready.body[1].kind, StmtKind::For { var, iter } if var == "i" && iter == "range(3)"));
+    // '#' inside a string is not a comment
+    assert!(ready.body[3].text.ends_with("\"x # not a comment\")"));
+
+    let toks = tokens(&ready.body[3].text);
+    assert_eq!(toks[0].kind, TokenKind::Ident);
+    assert_eq!(toks[2].string_value(), Some("x # not a comment"));
+}
+
+#[test]
+fn reports_structural_errors() {
+    let s = parse("extends Node\nfunc f():\n\tvar a = (1 + 2\n\tvar b = \"open\n\tvar c = 1]\n");
+    let errors: Vec<(usize, &str)> = s.errors.iter().map(|(l, e)| (*l, e.as_str())).collect();
+    assert_eq!(errors, vec![(3, "unclosed '('"), (4, "unterminated string"), (5, "unmatched ']'")]);
+}
+
+#[test]
+fn lints_from_parsed_structure() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("Player.gd"), PLAYER).unwrap();
+    // Methods named print and prints inside strings aren't debug prints
+    fs::write(root.join("quiet.gd"), "extends Node\nfunc _ready():\n    logger.print(\"a\")\n    var s = \"print(1)\"\n").unwrap();
+
+    let findings = lint_gd_scripts(root);
+    assert!(findings.iter().all(|f| f.code != "debug-print"));
+    let msgs: Vec<&str> = findings.iter().filter(|f| f.code == "shadowed-variable").map(|f| f.message.as_str()).collect();
+    assert_eq!(msgs, vec![
+        "Shadowed variable: 'a' in f() at line 28 shadows the member declared at line 27",
+        "Shadowed variable: 'speed' in _ready() at line 16 shadows the member declared at line 7",
+    ]);
+}
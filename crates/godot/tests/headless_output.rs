@@ -0,0 +1,25 @@
+use godot_analyzer::{headless::parse_godot_output, Severity};
+use std::path::PathBuf;
+
+#[test]
+fn parses_script_errors_with_location() {
+    let out = "Godot Engine v4.5.stable.official - https://godotengine.org\n\
+SCRIPT ERROR: Parse Error: Identifier \"foo\" not declared in the current scope.\n\
+          at: GDScript::reload (res://scripts/player.gd:12)\n\
+ERROR: Failed to load script \"res://scripts/player.gd\" with error \"Parse error\".\n\
+   at: load (modules/gdscript/gdscript.cpp:2936)\n\
+WARNING: Texture import deprecated\n";
+    let issues = parse_godot_output(out);
+    assert_eq!(issues.len(), 3);
+    assert_eq!(issues[0].severity, Severity::Error);
+    assert_eq!(issues[0].message, "Parse Error: Identifier \"foo\" not declared in the current scope. (line 12)");
+    assert_eq!(issues[0].file, Some(PathBuf::from("scripts/player.gd")));
+    // Engine-internal locations are not project files
+    assert_eq!(issues[1].file, None);
+    assert_eq!(issues[2].severity, Severity::Warn);
+}
+
+#[test]
+fn clean_output_has_no_issues() {
+    assert!(parse_godot_output("Godot Engine v4.5.stable.official\n").is_empty());
+}
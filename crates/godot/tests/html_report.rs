@@ -0,0 +1,34 @@
+use godot_analyzer::{to_html, GodotProjectReport, Issue};
+
+#[test]
+fn html_report_groups_issues_per_file_with_counts_and_filters() {
+    let report = GodotProjectReport {
+        project_path: "/games/demo".into(),
+        issues: vec![
+            Issue::warn("Debug print found", Some("player.gd".into())).at(7, Some(5)).with_code("debug-print"),
+            Issue::error("Missing script: res://<gone>.gd", Some("main.tscn".into())).at(4, None).with_code("missing-script"),
+            Issue::error("Unknown signal: 'x' on from='.' (Node)", Some("main.tscn".into())).at(9, None).with_code("unknown-signal"),
+            Issue::info("Missing [application] section", None),
+        ],
+        ..Default::default()
+    };
+    let html = to_html(&report);
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("errors: <b>2</b>") && html.contains("warnings: <b>1</b>") && html.contains("info: <b>1</b>") && html.contains("files: <b>3</b>"), "{}", html);
+    for sev in ["error", "warn", "info"] {
+        assert!(html.contains(&format!("<input type=\"checkbox\" value=\"{}\" checked>", sev)));
+    }
+    // One group per file, sorted, with its issue count; messages are escaped
+    let main = html.find("<summary>main.tscn (<span class=\"shown\">2</span>)</summary>").unwrap();
+    let player = html.find("<summary>player.gd (<span class=\"shown\">1</span>)</summary>").unwrap();
+    assert!(html.find("<summary>(project)").unwrap() < main && main < player);
+    assert!(html.contains("Missing script: res://&lt;gone&gt;.gd"));
+    assert!(html.contains("<td class=\"loc\">7:5</td>"));
+    assert!(html.contains("data-severity=\"error\"") && html.contains("<span class=\"rule\">scene-validator</span>"));
+}
+
+#[test]
+fn html_report_without_issues_says_so() {
+    let html = to_html(&GodotProjectReport::default());
+    assert!(html.contains("No issues found."));
+}
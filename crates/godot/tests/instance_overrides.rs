@@ -0,0 +1,79 @@
+use std::fs;
+use std::path::Path;
+use godot_analyzer::scene_validate::validate_scene;
+
+fn write_enemy(root: &Path) {
+    fs::write(root.join("ui.gd"), "extends Control\n").unwrap();
+    fs::write(root.join("flash.gd"), "extends Sprite2D\n").unwrap();
+    fs::write(root.join("enemy.tscn"), r#"[gd_scene format=3]
+
+[node name="Enemy" type="CharacterBody2D"]
+position = Vector2(0, 0)
+
+[node name="Sprite" type="Sprite2D" parent="."]
+modulate = Color(1, 1, 1, 1)
+"#).unwrap();
+}
+
+fn override_messages(root: &Path, scene: &str) -> Vec<(usize, String)> {
+    validate_scene(root, Path::new(scene)).into_iter()
+        .filter(|i| ["Orphaned override:", "Override type mismatch:", "Script override on"].iter().any(|p| i.message.starts_with(p)))
+        .map(|i| (i.line, i.message))
+        .collect()
+}
+
+#[test]
+fn flags_overrides_that_no_longer_fit_the_instanced_scene() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    write_enemy(root);
+    fs::write(root.join("level.tscn"), r#"[gd_scene format=3]
+
+[ext_resource type="PackedScene" path="res://enemy.tscn" id="1"]
+[ext_resource type="Script" path="res://ui.gd" id="2"]
+
+[node name="Level" type="Node2D"]
+
+[node name="Enemy" parent="." instance=ExtResource("1")]
+position = Vector3(1, 2, 3)
+
+[node name="Sprite" parent="Enemy"]
+modulate = Color(1, 0, 0, 1)
+script = ExtResource("2")
+
+[node name="OldGun" parent="Enemy"]
+visible = false
+
+[node name="Stray" parent="."]
+"#).unwrap();
+
+    assert_eq!(override_messages(root, "level.tscn"), vec![
+        (9, "Override type mismatch: 'Enemy' sets position to Vector3 but res://enemy.tscn has Vector2".to_string()),
+        (11, "Script override on 'Enemy/Sprite' extends Control but the node is a Sprite2D — hint: res://ui.gd".to_string()),
+        (15, "Orphaned override: 'Enemy/OldGun' no longer exists in res://enemy.tscn".to_string()),
+        (18, "Orphaned override: 'Stray' has no type and no instanced scene provides it".to_string()),
+    ]);
+}
+
+#[test]
+fn accepts_matching_overrides_in_inherited_scenes() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    write_enemy(root);
+    fs::write(root.join("boss.tscn"), r#"[gd_scene format=3]
+
+[ext_resource type="PackedScene" path="res://enemy.tscn" id="1"]
+[ext_resource type="Script" path="res://flash.gd" id="2"]
+
+[node name="Enemy" instance=ExtResource("1")]
+position = Vector2(5, 5)
+
+[node name="Sprite" parent="."]
+modulate = null
+script = ExtResource("2")
+
+[node name="Crown" type="Sprite2D" parent="Sprite"]
+"#).unwrap();
+
+    assert!(override_messages(root, "boss.tscn").is_empty());
+}
@@ -0,0 +1,65 @@
+use std::fs;
+use godot_analyzer::script_lint::{lint_gd_scripts, lint_gd_scripts_fix, LintFix};
+
+#[test]
+fn fixes_debug_prints_tabs_and_missing_extends_with_backups() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("spaced.gd"), "extends Node\nfunc f():\n  pass\n").unwrap();
+    let tabbed = "extends Node\n\nfunc _ready():\n\tprint(\"ready\")\n\tif true:\n\t\tprints(\"a\",\n\t\t\t\"b\")\n\tvar x = 1\n\tx.print(1)\n\t# analyzer: ignore-next-line\n\tprint(x)\n";
+    fs::write(root.join("tabbed.gd"), tabbed).unwrap();
+    fs::write(root.join("bare.gd"), "@tool\nclass_name Bare\n# helpers\n\nfunc g():\n  return 1\n").unwrap();
+
+    let summary = lint_gd_scripts_fix(root, &[]).unwrap();
+    let fix = |file: &str, code: &str, count| LintFix { file: file.into(), code: code.into(), count };
+    assert_eq!(summary.fixes, vec![
+        fix("bare.gd", "missing-extends", 1),
+        fix("tabbed.gd", "debug-print", 2),
+        fix("tabbed.gd", "tab-indentation", 6),
+    ]);
+    assert_eq!(summary.backed_up, 2);
+
+    assert_eq!(fs::read_to_string(root.join("tabbed.gd")).unwrap(),
+        "extends Node\n\nfunc _ready():\n  if true:\n    pass\n  var x = 1\n  x.print(1)\n  # analyzer: ignore-next-line\n  print(x)\n");
+    assert_eq!(fs::read_to_string(root.join("bare.gd")).unwrap(), "@tool\nclass_name Bare\n# helpers\nextends RefCounted\n\nfunc g():\n  return 1\n");
+    assert_eq!(fs::read_to_string(root.join(".lint_fix/backup/tabbed.gd")).unwrap(), tabbed);
+
+    let codes: Vec<String> = lint_gd_scripts(root).into_iter().map(|f| f.code).collect();
+    for code in ["debug-print", "tab-indentation", "missing-extends"] {
+        assert!(!codes.iter().any(|c| c == code), "{} left: {:?}", code, codes);
+    }
+}
+
+#[test]
+fn fixes_only_selected_rules_and_respects_controls() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("a.gd"), "func f():\n\tprint(1)\n").unwrap();
+    fs::write(root.join("b.gd"), "# gd-lint: disable=tab-indentation\nextends Node\nfunc f():\n\tpass\n").unwrap();
+
+    let summary = lint_gd_scripts_fix(root, &["tab-indentation".to_string()]).unwrap();
+    assert_eq!(summary.fixes, vec![LintFix { file: "a.gd".into(), code: "tab-indentation".into(), count: 1 }]);
+    assert_eq!(fs::read_to_string(root.join("a.gd")).unwrap(), "func f():\n    print(1)\n");
+    assert_eq!(fs::read_to_string(root.join("b.gd")).unwrap(), "# gd-lint: disable=tab-indentation\nextends Node\nfunc f():\n\tpass\n");
+
+    assert!(lint_gd_scripts_fix(root, &["unused-variable".to_string()]).is_err());
+}
+
+#[test]
+fn blocks_of_only_prints_keep_a_pass_and_addons_are_left_alone() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("hud.gd"), "extends Node\n\nfunc _ready():\n    print(\"a\")\n    print(\"b\")\n\nfunc f(x):\n    if x:\n        printt(x)\n        prints(x, 1)\n    return x\n").unwrap();
+    let vendored = "extends Node\nfunc g():\n\tprint(1)\n";
+    fs::create_dir_all(root.join("addons/tool")).unwrap();
+    fs::write(root.join("addons/tool/plugin.gd"), vendored).unwrap();
+    fs::create_dir_all(root.join(".structure_fix/backup")).unwrap();
+    fs::write(root.join(".structure_fix/backup/old.gd"), vendored).unwrap();
+
+    let summary = lint_gd_scripts_fix(root, &[]).unwrap();
+    assert_eq!(summary.fixes, vec![LintFix { file: "hud.gd".into(), code: "debug-print".into(), count: 4 }]);
+    assert_eq!(fs::read_to_string(root.join("hud.gd")).unwrap(),
+        "extends Node\n\nfunc _ready():\n    pass\n\nfunc f(x):\n    if x:\n        pass\n    return x\n");
+    assert_eq!(fs::read_to_string(root.join("addons/tool/plugin.gd")).unwrap(), vendored);
+    assert_eq!(fs::read_to_string(root.join(".structure_fix/backup/old.gd")).unwrap(), vendored);
+}
@@ -0,0 +1,76 @@
+use std::fs;
+use godot_analyzer::script_lint::lint_gd_scripts;
+
+const NAMING: &[&str] = &["function-name", "variable-name", "constant-name", "class-name-case", "file-name"];
+
+fn naming(root: &std::path::Path) -> Vec<(String, String, Option<usize>)> {
+    lint_gd_scripts(root).into_iter()
+        .filter(|f| NAMING.contains(&f.code.as_str()))
+        .map(|f| (f.code, f.message, f.line))
+        .collect()
+}
+
+#[test]
+fn flags_names_off_the_style_guide() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("enemyAI.gd"), r#"extends Node
+const maxSpeed = 10
+const Bullet = preload("res://bullet.tscn")
+const GRAVITY = 9.8
+var moveDir = Vector2.ZERO
+var _private_ok = 1
+
+func _ready():
+    pass
+
+func doThing(TargetNode, amount):
+    var HTTPResult = 0
+    for Item in []:
+        pass
+
+class inner_state:
+    var okName = 1
+"#).unwrap();
+
+    let mut got = naming(root);
+    got.sort_by(|a, b| a.2.cmp(&b.2).then(a.0.cmp(&b.0)).then(a.1.cmp(&b.1)));
+    let expected: Vec<(&str, &str, Option<usize>)> = vec![
+        ("file-name", "File name not snake_case: enemyAI.gd — hint: enemy_ai.gd", None),
+        ("constant-name", "Constant name not SCREAMING_SNAKE_CASE: 'maxSpeed' — hint: MAX_SPEED", Some(2)),
+        ("variable-name", "Variable name not snake_case: 'moveDir' — hint: move_dir", Some(5)),
+        ("function-name", "Function name not snake_case: 'doThing' — hint: do_thing", Some(11)),
+        ("variable-name", "Variable name not snake_case: 'TargetNode' in doThing() — hint: target_node", Some(11)),
+        ("variable-name", "Variable name not snake_case: 'HTTPResult' in doThing() — hint: http_result", Some(12)),
+        ("variable-name", "Variable name not snake_case: 'Item' in doThing() — hint: item", Some(13)),
+        ("class-name-case", "Class name not PascalCase: 'inner_state' — hint: InnerState", Some(16)),
+        ("variable-name", "Variable name not snake_case: 'okName' — hint: ok_name", Some(17)),
+    ];
+    let expected: Vec<(String, String, Option<usize>)> = expected.into_iter().map(|(c, m, l)| (c.to_string(), m.to_string(), l)).collect();
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn class_name_files_and_gd_lint_controls() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    // PascalCase file named after its class satisfies class-name-mismatch and file-name alike
+    fs::write(root.join("Player.gd"), "extends Node\nclass_name Player\n").unwrap();
+    fs::write(root.join("weird_class.gd"), "extends Node\nclass_name weird_class\n# gd-lint: disable=class-name-mismatch\n").unwrap();
+    fs::write(root.join("Legacy.gd"), "# gd-lint: disable=file-name,function-name\nextends Node\nfunc DoIt():\n    pass\n").unwrap();
+    let got = naming(root);
+    assert_eq!(got, vec![("class-name-case".to_string(), "Class name not PascalCase: 'weird_class' — hint: WeirdClass".to_string(), Some(2))]);
+}
+
+#[test]
+fn naming_findings_report_under_script_lint() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("badName.gd"), "extends Node\nconst maxHp = 3\nfunc doIt(x):\n    return x + maxHp\n").unwrap();
+    let issues = godot_analyzer::lint_gd(root);
+    assert_eq!(issues.len(), 3, "{:?}", issues);
+    let sarif = godot_analyzer::to_sarif(&godot_analyzer::GodotProjectReport { issues, ..Default::default() });
+    for r in sarif["runs"][0]["results"].as_array().unwrap() {
+        assert_eq!(r["ruleId"], "script-lint", "{}", r["message"]["text"]);
+    }
+}
@@ -0,0 +1,47 @@
+use std::fs;
+use std::path::Path;
+use godot_analyzer::scene_validate::validate_scene;
+
+#[test]
+fn flags_node_paths_to_missing_nodes() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("enemies.tscn"), "[gd_scene format=3]\n\n[node name=\"Enemies\" type=\"Node2D\"]\n\n[node name=\"Goblin\" type=\"Node2D\" parent=\".\"]\n").unwrap();
+    fs::write(root.join("level.tscn"), r#"[gd_scene load_steps=2 format=3]
+
+[ext_resource type="PackedScene" path="res://enemies.tscn" id="1"]
+[ext_resource type="PackedScene" path="res://gone.tscn" id="2"]
+
+[node name="Level" type="Node2D"]
+
+[node name="Player" type="Node2D" parent="." node_paths=PackedStringArray("target", "camera")]
+target = NodePath("../Enemies/Goblin")
+camera = NodePath("../Camera")
+waypoints = [NodePath("../Marker1"), NodePath("../Marker9")]
+follow = NodePath("../Enemies/Goblin:position")
+hud = NodePath("%Hud")
+world = NodePath("../../World")
+none = NodePath("")
+weak_spot = NodePath("../Boss/Head")
+
+[node name="Rig" type="Node2D" parent="."]
+
+[node name="Camera" type="Camera2D" parent="Rig"]
+
+[node name="Marker1" type="Marker2D" parent="."]
+
+[node name="Enemies" parent="." instance=ExtResource("1")]
+
+[node name="Boss" parent="." instance=ExtResource("2")]
+"#).unwrap();
+
+    let issues = validate_scene(root, Path::new("level.tscn"));
+    let issues: Vec<_> = issues.into_iter().filter(|i| i.code == "dangling-node-path").collect();
+    let found: Vec<(usize, Option<&str>, &str)> = issues.iter().map(|i| (i.line, i.node_path.as_deref(), i.message.as_str())).collect();
+    // Instanced nodes count; unique names, empty paths, paths leaving the scene and paths into
+    // instances whose scene can't be read aren't checked
+    assert_eq!(found, vec![
+        (10, Some("Player"), "Dangling NodePath: camera = '../Camera' points at 'Camera', which doesn't exist — 'Camera' is at 'Rig/Camera'"),
+        (11, Some("Player"), "Dangling NodePath: waypoints = '../Marker9' points at 'Marker9', which doesn't exist"),
+    ]);
+}
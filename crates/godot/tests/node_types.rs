@@ -0,0 +1,38 @@
+use std::fs;
+use std::path::Path;
+use godot_analyzer::class_db::ClassDb;
+use godot_analyzer::scene_validate::validate_scene;
+
+#[test]
+fn suggests_the_closest_builtin_class() {
+    let db = ClassDb::builtin();
+    assert_eq!(db.suggest("Sprite2d"), Some("Sprite2D"));
+    assert_eq!(db.suggest("CharacterBody2"), Some("CharacterBody2D"));
+    assert_eq!(db.suggest("Lable"), Some("Label"));
+    assert_eq!(db.suggest("Zzzzzzzz"), None);
+}
+
+#[test]
+fn flags_node_types_that_are_not_known_classes() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("health_bar.gd"), "class_name HealthBar\nextends ProgressBar\n").unwrap();
+    let scene = r#"[gd_scene format=3]
+
+[node name="Main" type="Node2D"]
+
+[node name="Sprite" type="Sprite2d" parent="."]
+
+[node name="Bar" type="HealthBar" parent="."]
+
+[node name="Thing" type="Frobnicator" parent="."]
+"#;
+    fs::write(root.join("main.tscn"), scene).unwrap();
+
+    let issues = validate_scene(root, Path::new("main.tscn"));
+    let found: Vec<(usize, Option<&str>, &str)> = issues.iter().map(|i| (i.line, i.node_path.as_deref(), i.message.as_str())).collect();
+    assert_eq!(found, vec![
+        (5, Some("Sprite"), "Unknown node type: Sprite2d — did you mean 'Sprite2D'?"),
+        (9, Some("Thing"), "Unknown node type: Frobnicator"),
+    ]);
+}
@@ -0,0 +1,65 @@
+use std::fs;
+use std::path::Path;
+use godot_analyzer::orphan_assets::find_orphan_assets;
+use godot_analyzer::uid::UidMap;
+use godot_analyzer::{orphan_assets_as_report, to_sarif};
+
+fn write(root: &Path, rel: &str, text: &str) {
+    let p = root.join(rel);
+    fs::create_dir_all(p.parent().unwrap()).unwrap();
+    fs::write(p, text).unwrap();
+}
+
+fn project(root: &Path) {
+    write(root, "project.godot", "config_version=5\n\n[application]\nrun/main_scene=\"uid://bmain\"\nconfig/icon=\"res://icon.svg\"\n\n[autoload]\nGame=\"*res://game.gd\"\n");
+    write(root, "icon.svg", "<svg/>");
+    write(root, "game.gd", "extends Node\n");
+    write(root, "main.tscn", "[gd_scene format=3 uid=\"uid://bmain\"]\n\n[ext_resource type=\"PackedScene\" path=\"res://player.tscn\" id=\"1\"]\n[ext_resource type=\"Texture2D\" path=\"res://art/hero.png\" id=\"2\"]\n\n[node name=\"Main\" type=\"Node2D\"]\n");
+    write(root, "player.tscn", "[gd_scene format=3]\n\n[ext_resource type=\"Script\" path=\"res://player.gd\" id=\"1\"]\n\n[node name=\"Player\" type=\"Node2D\"]\nscript = ExtResource(\"1\")\n");
+    write(root, "player.gd", "extends Node2D\nconst JUMP = preload(\"res://sfx/jump.wav\")\nvar weapon: Weapon\n");
+    write(root, "weapon.gd", "class_name Weapon\nextends Resource\n");
+    write(root, "art/hero.png", "png");
+    write(root, "sfx/jump.wav", "wav");
+    write(root, "default_bus_layout.tres", "[gd_resource type=\"AudioBusLayout\" format=3]\n");
+    // Nothing references these
+    write(root, "art/old.png", "old");
+    write(root, "sfx/unused.ogg", "ogg");
+    write(root, "old_level.tscn", "[gd_scene format=3]\n\n[ext_resource type=\"Texture2D\" path=\"res://art/hero.png\" id=\"1\"]\n\n[node name=\"Old\" type=\"Node2D\"]\n");
+    write(root, "helper.gd", "class_name Helper\nextends RefCounted\nstatic func make() -> Helper: return Helper.new()\n");
+    write(root, "self_ref.gd", "extends Node\nconst ME = preload(\"res://self_ref.gd\")\n");
+    // Skipped: addon internals and the import cache
+    write(root, "addons/tool/icon.png", "png");
+    write(root, ".godot/imported/hero.png-123.ctex", "ctex");
+}
+
+#[test]
+fn lists_assets_nothing_references() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    project(root);
+
+    let orphans = find_orphan_assets(root, &UidMap::load(root));
+    let found: Vec<(String, &str)> = orphans.iter().map(|o| (o.path.to_string_lossy().replace('\\', "/"), o.kind.as_str())).collect();
+    assert_eq!(found, vec![
+        ("art/old.png".to_string(), "texture"),
+        ("helper.gd".to_string(), "script"),
+        ("old_level.tscn".to_string(), "scene"),
+        ("self_ref.gd".to_string(), "script"),
+        ("sfx/unused.ogg".to_string(), "audio"),
+    ]);
+    assert_eq!(orphans[0].bytes, 3);
+}
+
+#[test]
+fn orphans_report_as_info_under_their_own_rule() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    project(root);
+
+    let issues = orphan_assets_as_report(root);
+    assert_eq!(issues[0].message, "Unused asset: res://art/old.png (texture, 3 bytes)");
+    assert!(issues.iter().all(|i| i.severity == godot_analyzer::Severity::Info));
+    let report = godot_analyzer::GodotProjectReport { issues, ..Default::default() };
+    let sarif = to_sarif(&report);
+    assert_eq!(sarif["runs"][0]["results"][0]["ruleId"], "orphan-assets");
+}
@@ -0,0 +1,61 @@
+use std::fs;
+use godot_analyzer::script_lint::lint_gd_scripts;
+
+#[test]
+fn flags_per_frame_lookups_awaits_and_string_building_in_loops() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("hud.gd"), r#"extends Node
+@onready var label = $Label
+
+func _process(_delta):
+    var bar = get_node("Bar")
+    $Icon.visible = bar.visible
+    await get_tree().process_frame
+
+func _physics_process(_delta):
+    var enemy = owner.find_child("Enemy")
+    enemy.position.x += 1
+
+func _ready():
+    var player = get_node("Player")
+    var text = ""
+    for i in 3:
+        text += str(i)
+        var n = i + 1
+        label.text = label.text + ", "
+    while player:
+        player = null
+    print(text)
+"#).unwrap();
+
+    let mut got: Vec<(String, String, Option<usize>, Option<usize>)> = lint_gd_scripts(root).into_iter()
+        .filter(|f| f.code.starts_with("perf-"))
+        .map(|f| (f.code, f.message, f.line, f.column))
+        .collect();
+    got.sort_by_key(|g| (g.2, g.0.clone()));
+    let expected = vec![
+        ("perf-node-lookup", "Performance: get_node() in _process() looks the node up every frame — hint: cache it in an @onready var", Some(5), Some(15)),
+        ("perf-node-lookup", "Performance: $Icon in _process() looks the node up every frame — hint: cache it in an @onready var", Some(6), Some(5)),
+        ("perf-await", "Performance: await in _process() suspends a per-frame callback — hint: start the wait from a separate function or a Timer", Some(7), Some(5)),
+        ("perf-node-lookup", "Performance: find_child() in _physics_process() looks the node up every frame — hint: cache it in an @onready var", Some(10), Some(23)),
+        ("perf-string-concat", "Performance: string concatenation in a loop in _ready() copies the string every iteration — hint: append to a PackedStringArray and join() it", Some(17), Some(9)),
+        ("perf-string-concat", "Performance: string concatenation in a loop in _ready() copies the string every iteration — hint: append to a PackedStringArray and join() it", Some(19), Some(9)),
+    ];
+    let expected: Vec<_> = expected.into_iter().map(|(c, m, l, col)| (c.to_string(), m.to_string(), l, col)).collect();
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn perf_findings_report_under_script_lint_with_performance_category() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("mover.gd"), "extends Node\nfunc _process(_delta):\n    $Sprite.rotate(0.1)\n").unwrap();
+    let issues: Vec<_> = godot_analyzer::lint_gd(root).into_iter().filter(|i| i.code.as_deref() == Some("perf-node-lookup")).collect();
+    assert_eq!(issues.len(), 1, "{:?}", issues);
+    let report = godot_analyzer::GodotProjectReport { issues, ..Default::default() };
+    let sarif = godot_analyzer::to_sarif(&report);
+    assert_eq!(sarif["runs"][0]["results"][0]["ruleId"], "script-lint");
+    let cq = godot_analyzer::to_code_quality(&report);
+    assert_eq!(cq[0]["categories"][0], "Performance");
+}
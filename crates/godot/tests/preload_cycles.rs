@@ -0,0 +1,28 @@
+use std::fs;
+use godot_analyzer::script_lint::lint_gd_scripts;
+
+#[test]
+fn flags_cyclic_and_self_preloads() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("a.gd"), "extends Node\nconst B = preload(\"res://b.gd\")\n").unwrap();
+    fs::write(root.join("b.gd"), "extends Node\nconst C = preload(\"res://c.tscn\")\n").unwrap();
+    fs::write(root.join("c.tscn"), "[gd_scene format=3]\n\n[ext_resource type=\"Script\" path=\"res://a.gd\" id=\"1\"]\n\n[node name=\"C\" type=\"Node\"]\nscript = ExtResource(\"1\")\n").unwrap();
+    fs::write(root.join("d.gd"), "extends \"res://e.gd\"\n").unwrap();
+    fs::write(root.join("e.gd"), "extends Node\nvar d = preload(\"res://d.gd\")\n").unwrap();
+    fs::write(root.join("me.gd"), "extends Node\nconst ME = preload(\"res://me.gd\")\n").unwrap();
+    // Runtime load() isn't resolved at load time
+    fs::write(root.join("f.gd"), "extends Node\nfunc _ready():\n    var g = load(\"res://g.gd\")\n").unwrap();
+    fs::write(root.join("g.gd"), "extends Node\nconst F = preload(\"res://f.gd\")\n").unwrap();
+    fs::write(root.join("quiet.gd"), "# gd-lint: disable=self-preload\nextends Node\nconst Q = preload(\"res://quiet.gd\")\n").unwrap();
+
+    let findings: Vec<(String, String, String)> = lint_gd_scripts(root).into_iter()
+        .filter(|f| f.code.ends_with("preload"))
+        .map(|f| (f.code, f.message, f.file.to_string_lossy().to_string()))
+        .collect();
+    assert_eq!(findings, vec![
+        ("cyclic-preload".to_string(), "Cyclic preload: res://a.gd -> res://b.gd -> res://c.tscn -> res://a.gd".to_string(), "a.gd".to_string()),
+        ("cyclic-preload".to_string(), "Cyclic preload: res://d.gd -> res://e.gd -> res://d.gd".to_string(), "d.gd".to_string()),
+        ("self-preload".to_string(), "Script preloads itself: res://me.gd".to_string(), "me.gd".to_string()),
+    ]);
+}
@@ -0,0 +1,57 @@
+use std::fs;
+use godot_analyzer::project_settings::ProjectSettings;
+
+const PROJECT: &str = r#"; Engine configuration file.
+;   [section] ; section goes between []
+
+config_version=5
+
+[application]
+
+config/name="Lantern \"Night\""
+config/features=PackedStringArray("4.3", "Forward Plus")
+run/main_scene="res://main.tscn"
+boot_splash/show_image=false
+
+[display]
+
+window/size/viewport_width=1280
+
+[input]
+
+jump={
+"deadzone": 0.5,
+"events": [Object(InputEventKey,"resource_local_to_scene":false,"keycode":32)]
+}
+"#;
+
+#[test]
+fn reads_sections_typed_values_and_arrays() {
+    let settings = ProjectSettings::parse(PROJECT);
+    assert_eq!(settings.config_version(), Some(5));
+    assert_eq!(settings.name(), Some("Lantern \"Night\""));
+    assert_eq!(settings.features(), ["4.3", "Forward Plus"]);
+    assert_eq!(settings.engine_version().as_deref(), Some("4.3"));
+    assert_eq!(settings.main_scene(), Some("res://main.tscn"));
+    assert_eq!(settings.icon(), None);
+    assert_eq!(settings.get("application/boot_splash/show_image").and_then(|v| v.as_bool()), Some(false));
+    assert_eq!(settings.get("display/window/size/viewport_width").and_then(|v| v.as_int()), Some(1280));
+    assert_eq!(settings.property("input/jump").map(|p| p.line), Some(19));
+    assert_eq!(settings.section("input").len(), 1);
+}
+
+#[test]
+fn analyzer_reads_the_settings_model() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("project.godot"), PROJECT.replace("\"4.3\"", "\"3.5\"")).unwrap();
+    fs::write(root.join("main.tscn"), "[gd_scene format=3]\n\n[node name=\"Main\" type=\"Node\"]\n").unwrap();
+
+    let report = godot_analyzer::analyze_project(root).unwrap();
+    assert_eq!(report.project_format_version, Some(5));
+    assert_eq!(report.features, ["3.5", "Forward Plus"]);
+    let messages: Vec<_> = report.issues.iter().map(|i| i.message.as_str()).collect();
+    assert!(messages.contains(&"config/features engine version 3.5 doesn't match config_version 5"), "{messages:?}");
+    assert!(messages.contains(&"No application icon configured (config/icon)"));
+    assert!(!messages.iter().any(|m| m.contains("main scene")));
+}
@@ -0,0 +1,32 @@
+use std::fs;
+use godot_analyzer::{lint_gd, scene_issues_as_report, to_junit, to_sarif, GodotProjectReport};
+
+#[test]
+fn sarif_and_junit_point_at_lines_and_columns() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("main.tscn"), "[gd_scene format=3]\n\n[node name=\"Main\" type=\"Node\"]\nscript = \"res://missing.gd\"\n").unwrap();
+    fs::write(root.join("player.gd"), "extends Node\nfunc _ready():\n    var t = load(\"res://gone.png\")\n").unwrap();
+
+    let mut issues = scene_issues_as_report(root);
+    issues.extend(lint_gd(root));
+    let report = GodotProjectReport { issues, ..Default::default() };
+
+    let sarif = to_sarif(&report);
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+    let scene = results.iter().find(|r| r["message"]["text"].as_str().unwrap().starts_with("Missing script:")).unwrap();
+    let loc = &scene["locations"][0]["physicalLocation"];
+    assert_eq!(loc["artifactLocation"]["uri"], "main.tscn");
+    assert_eq!(loc["region"], serde_json::json!({ "startLine": 4 }));
+    let lint = results.iter().find(|r| r["message"]["text"].as_str().unwrap().starts_with("GDScript load missing file:")).unwrap();
+    assert_eq!(lint["locations"][0]["physicalLocation"]["region"], serde_json::json!({ "startLine": 3, "startColumn": 18 }));
+
+    let junit = to_junit(&report);
+    assert!(junit.contains(">main.tscn:4</failure>"), "{}", junit);
+    assert!(junit.contains(">player.gd:3:18</failure>"), "{}", junit);
+
+    // Issues without a location keep the bare artifact
+    let plain = godot_analyzer::Issue::warn("x", Some("a.gd".into()));
+    let v = to_sarif(&GodotProjectReport { issues: vec![plain], ..Default::default() });
+    assert!(v["runs"][0]["results"][0]["locations"][0]["physicalLocation"].get("region").is_none());
+}
@@ -0,0 +1,69 @@
+use std::fs;
+use godot_analyzer::scene_validate::{SceneDocument, Value};
+
+const SCENE: &str = r#"[gd_scene load_steps=3 format=3 uid="uid://b1"]
+
+[ext_resource type="Script" path="res://player.gd" id="1_p"]
+
+[sub_resource type="Animation" id="Animation_a"]
+resource_name = "say \"hi\" [loud]"
+tracks/0/keys = {
+"times": PackedFloat32Array(0, 1),
+"values": [Vector2(0, 0), SubResource("Missing_1")]
+}
+
+[node name="Player" type="CharacterBody2D"]
+script = ExtResource("1_p")
+; a comment
+metadata/notes = "line one
+var t = preload(\"res://gone.tscn\")"
+
+[node name="Sprite" type="Sprite2D" parent="."]
+texture = ExtResource("2_tex")
+
+[connection signal="ready" from="Sprite" to="." method="_on_sprite_ready" binds=[
+1, "two"
+]]
+"#;
+
+#[test]
+fn parses_sections_multiline_values_and_quotes() {
+    let doc = SceneDocument::parse(SCENE);
+    let kinds: Vec<_> = doc.sections.iter().map(|s| (s.kind.as_str(), s.line)).collect();
+    assert_eq!(kinds, [("gd_scene", 1), ("ext_resource", 3), ("sub_resource", 5), ("node", 12), ("node", 18), ("connection", 21)]);
+
+    let anim = &doc.sections[2];
+    assert_eq!(anim.property("resource_name").unwrap().value, Value::Str("say \"hi\" [loud]".into()));
+    let keys = anim.property("tracks/0/keys").unwrap();
+    assert_eq!(keys.line, 7);
+    assert!(keys.value.nested().iter().any(|v| v.resource_id("SubResource") == Some("Missing_1")));
+
+    let player = &doc.sections[3];
+    assert_eq!(player.node_path().as_deref(), Some("."));
+    assert_eq!(player.script_path(&doc.ext_resources()).as_deref(), Some("res://player.gd"));
+    assert_eq!(player.properties.len(), 2);
+    assert_eq!(doc.sections[4].node_path().as_deref(), Some("Sprite"));
+
+    let conn = &doc.sections[5];
+    assert_eq!(conn.attr_str("method"), Some("_on_sprite_ready"));
+    assert_eq!(conn.attr("binds"), Some(&Value::Array(vec![Value::Number("1".into()), Value::Str("two".into())])));
+}
+
+#[test]
+fn validation_reads_multiline_and_nested_values() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("player.gd"), "extends CharacterBody2D\n\nfunc _on_sprite_ready(a, b):\n\tpass\n").unwrap();
+    fs::write(root.join("main.tscn"), SCENE).unwrap();
+
+    let issues = godot_analyzer::scene_validate::validate_scene(root, std::path::Path::new("main.tscn"));
+    let got: Vec<_> = issues.iter().map(|i| (i.line, i.node_path.as_deref(), i.message.as_str())).collect();
+    assert_eq!(got, [
+        (7, None, "Unknown SubResource id: Missing_1"),
+        (16, None, "Preload missing file: res://gone.tscn"),
+        (19, Some("Sprite"), "Unknown ExtResource id: 2_tex"),
+    ]);
+
+    let signals = godot_analyzer::signal_validate::validate_scene_signals(root, std::path::Path::new("main.tscn"));
+    assert!(signals.is_empty(), "signals: {signals:?}");
+}
@@ -0,0 +1,96 @@
+use std::fs;
+use std::path::Path;
+use godot_analyzer::scene_validate::{validate_scene, SceneDocument};
+use godot_analyzer::signal_validate::validate_scene_signals;
+use godot_analyzer::uid::UidMap;
+
+fn write_project(root: &Path) {
+    fs::write(root.join("enemy.gd"), "extends CharacterBody2D\nsignal died\n").unwrap();
+    fs::write(root.join("enemy.tscn"), r#"[gd_scene format=3]
+
+[ext_resource type="Script" path="res://enemy.gd" id="1"]
+
+[node name="Enemy" type="CharacterBody2D"]
+script = ExtResource("1")
+
+[node name="Sprite" type="Sprite2D" parent="."]
+
+[node name="Hitbox" type="Area2D" parent="."]
+"#).unwrap();
+    fs::write(root.join("base_level.tscn"), r#"[gd_scene format=3]
+
+[node name="Level" type="Node2D"]
+
+[node name="Spawn" type="Marker2D" parent="."]
+"#).unwrap();
+    fs::write(root.join("level.tscn"), r#"[gd_scene format=3]
+
+[ext_resource type="PackedScene" path="res://base_level.tscn" id="1"]
+[ext_resource type="PackedScene" path="res://enemy.tscn" id="2"]
+[ext_resource type="PackedScene" path="res://ghost.tscn" id="3"]
+
+[node name="Level" instance=ExtResource("1")]
+
+[node name="Enemy" parent="." instance=ExtResource("2")]
+
+[node name="Sprite" parent="Enemy"]
+visible = false
+
+[node name="Ghost" parent="." instance=ExtResource("3")]
+
+[connection signal="body_entered" from="Enemy/Hitbox" to="Spawn" method="_on_hit"]
+[connection signal="died" from="Enemy" to="Spawn" method="_on_died"]
+[connection signal="died" from="Enemy/Sprite" to="Spawn" method="_on_died"]
+[connection signal="ready" from="Enemy/Nope" to="Spawn" method="_on_ready"]
+"#).unwrap();
+}
+
+#[test]
+fn node_tree_expands_instanced_and_inherited_scenes() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    write_project(root);
+
+    let doc = SceneDocument::read(&root.join("level.tscn")).unwrap();
+    let tree = doc.node_tree(root, &UidMap::default());
+    let nodes: Vec<(&str, Option<&str>, Option<&str>)> = tree.iter().map(|n| (n.path.as_str(), n.ty.as_deref(), n.source.as_deref())).collect();
+    assert_eq!(nodes, vec![
+        (".", Some("Node2D"), None),
+        ("Spawn", Some("Marker2D"), Some("res://base_level.tscn")),
+        ("Enemy", Some("CharacterBody2D"), None),
+        ("Enemy/Sprite", Some("Sprite2D"), None),
+        ("Enemy/Hitbox", Some("Area2D"), Some("res://enemy.tscn")),
+        ("Ghost", None, None),
+    ]);
+    let enemy = &tree[2];
+    assert_eq!((enemy.script.as_deref(), enemy.instance.as_deref(), enemy.line), (Some("res://enemy.gd"), Some("res://enemy.tscn"), 9));
+}
+
+#[test]
+fn connections_reach_into_instanced_scenes() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    write_project(root);
+
+    let issues = validate_scene_signals(root, Path::new("level.tscn"));
+    let msgs: Vec<(usize, &str)> = issues.iter().map(|i| (i.line, i.message.as_str())).collect();
+    assert_eq!(msgs, vec![
+        (18, "Unknown signal: 'died' on from='Enemy/Sprite' (Sprite2D) — hint: check the signal name or declare 'signal died' in the node's script"),
+        (19, "Unknown connection 'from' node: Enemy/Nope — hint: create node or correct the 'from' path"),
+    ]);
+}
+
+#[test]
+fn flags_missing_and_recursive_instances() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    write_project(root);
+    fs::write(root.join("loop_a.tscn"), "[gd_scene format=3]\n\n[ext_resource type=\"PackedScene\" path=\"res://loop_b.tscn\" id=\"1\"]\n\n[node name=\"A\" type=\"Node\"]\n\n[node name=\"B\" parent=\".\" instance=ExtResource(\"1\")]\n").unwrap();
+    fs::write(root.join("loop_b.tscn"), "[gd_scene format=3]\n\n[ext_resource type=\"PackedScene\" path=\"res://loop_a.tscn\" id=\"1\"]\n\n[node name=\"B\" type=\"Node\"]\n\n[node name=\"A\" parent=\".\" instance=ExtResource(\"1\")]\n").unwrap();
+
+    let level: Vec<String> = validate_scene(root, Path::new("level.tscn")).into_iter().map(|i| i.message).collect();
+    assert!(level.contains(&"Instanced scene missing: ExtResource(3) res://ghost.tscn".to_string()), "{:?}", level);
+    let looped = validate_scene(root, Path::new("loop_a.tscn"));
+    assert_eq!(looped.len(), 1);
+    assert_eq!((looped[0].line, looped[0].message.as_str()), (7, "Recursive scene instance: res://loop_a.tscn -> res://loop_b.tscn -> res://loop_a.tscn"));
+}
@@ -35,7 +35,7 @@ _some_prop = preload("res://not_found/thing.tscn")
     fs::write(root.join("scripts/exists.gd"), "extends Node\n").unwrap();
     fs::write(root.join("main.tscn"), scene).unwrap();
     let issues = godot_analyzer::scene_validate::validate_scene(root, std::path::Path::new("main.tscn"));
-    assert!(issues.iter().any(|i| i.message.contains("Preload missing file: res://not_found/thing.tscn")), "issues: {issues:?}");
+    assert!(issues.iter().any(|i| i.code == "missing-preload" && i.message.contains("Preload missing file: res://not_found/thing.tscn")), "issues: {issues:?}");
 }
 
 #[test]
@@ -51,5 +51,5 @@ _some_prop = load("res://not_found/thing2.tscn")
 "#;
     fs::write(root.join("main.tscn"), scene).unwrap();
     let issues = godot_analyzer::scene_validate::validate_scene(root, std::path::Path::new("main.tscn"));
-    assert!(issues.iter().any(|i| i.message.contains("Load missing file: res://not_found/thing2.tscn")), "issues: {issues:?}");
+    assert!(issues.iter().any(|i| i.code == "missing-load" && i.message.contains("Load missing file: res://not_found/thing2.tscn")), "issues: {issues:?}");
 }
@@ -0,0 +1,58 @@
+use std::fs;
+use godot_analyzer::config::AnalyzerConfig;
+use godot_analyzer::{scene_issues_as_report, signal_issues_as_report, to_sarif, GodotProjectReport, Severity};
+
+fn scene_project() -> tempfile::TempDir {
+    let tmp = tempfile::tempdir().unwrap();
+    fs::write(tmp.path().join("main.tscn"), r#"[gd_scene format=3]
+
+[sub_resource type="GDScript" id="s"]
+script/source = "var a = preload(\"res://gone.gd\")"
+
+[node name="Main" type="Node"]
+script = "res://missing.gd"
+
+[node name="B" type="Button" parent="."]
+
+[connection signal="pressed" from="B" to="." method="_on_b"]
+[connection signal="pressed" from="B" to="." method="_on_b"]
+"#).unwrap();
+    tmp
+}
+
+#[test]
+fn config_maps_scene_and_signal_rules_by_message_prefix_and_code() {
+    let tmp = scene_project();
+    let root = tmp.path();
+    fs::write(root.join("godot-analyzer.toml"), "[severity]\n\"Preload missing file\" = \"warn\"\nduplicate-connection = \"info\"\n").unwrap();
+
+    let scene = scene_issues_as_report(root);
+    let sev = |issues: &[godot_analyzer::Issue], prefix: &str| issues.iter().find(|i| i.message.starts_with(prefix)).map(|i| i.severity);
+    assert_eq!(sev(&scene, "Preload missing file"), Some(Severity::Warn), "{:?}", scene);
+    assert_eq!(sev(&scene, "Missing script"), Some(Severity::Error));
+    let signals = signal_issues_as_report(root);
+    assert_eq!(sev(&signals, "Duplicate connection"), Some(Severity::Info), "{:?}", signals);
+
+    // SARIF levels follow the new severity
+    let sarif = to_sarif(&GodotProjectReport { issues: scene, ..Default::default() });
+    let preload = sarif["runs"][0]["results"].as_array().unwrap().iter()
+        .find(|r| r["message"]["text"].as_str().unwrap().starts_with("Preload missing file")).unwrap();
+    assert_eq!(preload["level"], "warning");
+}
+
+#[test]
+fn command_line_overrides_win_over_the_config_file() {
+    let tmp = scene_project();
+    let root = tmp.path();
+    let mut config = AnalyzerConfig::parse("[severity]\nmissing-script = \"warn\"\nscene-validator = \"info\"\n").unwrap();
+    config.set_severity("missing-script=error").unwrap();
+    assert!(config.set_severity("missing-script").is_err());
+    assert!(config.set_severity("missing-script=fatal").is_err());
+
+    let mut issues = scene_issues_as_report(root);
+    config.regrade(&mut issues);
+    let sev = |prefix: &str| issues.iter().find(|i| i.message.starts_with(prefix)).map(|i| i.severity);
+    assert_eq!(sev("Missing script"), Some(Severity::Error));
+    // Rules without their own entry fall back to the rule id
+    assert_eq!(sev("Preload missing file"), Some(Severity::Info));
+}
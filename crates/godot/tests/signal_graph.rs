@@ -22,3 +22,140 @@ fn generates_signal_graph_dot() {
     assert!(dot.contains("\"test.tscn:A\" -> \"test.tscn:B\" [label=\"pressed:on_pressed\"];"));
     assert!(dot.contains("\"test.tscn:B\" -> \"test.tscn:A\" [label=\"released:on_released\"];"));
 }
+
+#[test]
+fn signal_graph_json_keeps_lines_flags_and_endpoint_status() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let scene = r#"[gd_scene format=3]
+
+[node name="Root" type="Node"]
+
+[node name="Button" type="Button" parent="."]
+
+[connection signal="pressed" from="Button" to="." method="_on_pressed" flags=5]
+[connection signal="pressed" from="Gone" to="." method="_on_gone"]
+"#;
+    fs::write(root.join("menu.tscn"), scene).unwrap();
+
+    let v = godot_analyzer::signal_graph_json(root);
+    let edges = v["edges"].as_array().unwrap();
+    assert_eq!(edges.len(), 2);
+    assert_eq!(edges[0], serde_json::json!({
+        "scene": "menu.tscn", "from": "Button", "to": ".", "signal": "pressed", "method": "_on_pressed",
+        "line": 7, "flags": 5, "origin": "scene", "deferred": true, "one_shot": true, "from_status": "resolved", "to_status": "resolved",
+        "from_id": "menu.tscn:Button", "to_id": "menu.tscn:."
+    }));
+    assert_eq!(edges[1]["from"], "Gone");
+    assert_eq!(edges[1]["line"], 8);
+    assert_eq!(edges[1]["flags"], 0);
+    assert_eq!(edges[1]["from_status"], "missing");
+    assert_eq!(edges[1]["to_status"], "resolved");
+
+    // The DOT graph keeps only resolved connections
+    let dot = signal_graph_dot(root);
+    assert!(dot.contains("\"menu.tscn:Button\" -> \"menu.tscn:.\""));
+    assert!(!dot.contains("Gone"));
+}
+
+#[test]
+fn script_connect_calls_join_the_graph_as_code_edges() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("menu.gd"), r#"extends Control
+@onready var quit_button = $Box/Quit
+var timer: Timer
+
+func _ready():
+    $Box/Start.pressed.connect(_on_start)
+    quit_button.pressed.connect(self._on_quit.bind(1), CONNECT_ONE_SHOT)
+    connect("visibility_changed", Callable(self, "_on_visible"))
+    timer.timeout.connect(func(): pass)
+    $Missing.pressed.connect(_on_start, CONNECT_DEFERRED | CONNECT_ONE_SHOT)
+    $Box/Start.connect("focus_entered", $Box, "grab_focus")
+"#).unwrap();
+    fs::write(root.join("menu.tscn"), r#"[gd_scene format=3]
+
+[ext_resource type="Script" path="res://menu.gd" id="1"]
+
+[node name="Menu" type="Control"]
+script = ExtResource("1")
+
+[node name="Box" type="VBoxContainer" parent="."]
+
+[node name="Start" type="Button" parent="Box"]
+
+[node name="Quit" type="Button" parent="Box"]
+"#).unwrap();
+    fs::write(root.join("autoload.gd"), "extends Node
+func _ready():
+    get_tree().node_added.connect(_on_added)
+    child_entered_tree.connect(_on_child)
+").unwrap();
+
+    let edges = godot_analyzer::signal_graph(root);
+    // scene from -> to signal:method @line flags from_status/to_status
+    let got: Vec<String> = edges.iter().map(|e| {
+        let s = |st| serde_json::to_value(st).unwrap().as_str().unwrap().to_string();
+        format!("{} {} -> {} {}:{} @{} {} {}/{}", e.edge.scene.display(), e.edge.from, e.edge.to, e.edge.signal, e.edge.method, e.edge.line, e.edge.flags, s(e.from_status), s(e.to_status))
+    }).collect();
+    assert_eq!(got, vec![
+        "autoload.gd . -> . child_entered_tree:_on_child @4 0 unknown/unknown",
+        "menu.tscn . -> . visibility_changed:_on_visible @8 0 resolved/resolved",
+        "menu.tscn Box/Quit -> . pressed:_on_quit @7 4 resolved/resolved",
+        "menu.tscn Box/Start -> . pressed:_on_start @6 0 resolved/resolved",
+        "menu.tscn Box/Start -> Box focus_entered:grab_focus @11 0 resolved/resolved",
+        "menu.tscn Missing -> . pressed:_on_start @10 5 missing/resolved",
+        "menu.tscn timer -> . timeout:<lambda> @9 0 unknown/resolved",
+    ]);
+    assert!(edges.iter().all(|e| e.origin == godot_analyzer::signal_validate::ConnectionOrigin::Code));
+    assert!(edges.iter().filter(|e| e.edge.scene.ends_with("menu.tscn")).all(|e| e.edge.script.as_deref() == Some(std::path::Path::new("menu.gd"))));
+
+    let dot = signal_graph_dot(root);
+    assert!(dot.contains("\"menu.tscn:Box/Start\" -> \"menu.tscn:.\" [label=\"pressed:_on_start\", style=dashed];"), "{}", dot);
+}
+
+#[test]
+fn signal_graph_joins_scenes_through_instances() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("player.tscn"), r#"[gd_scene format=3]
+
+[node name="Player" type="CharacterBody2D"]
+
+[node name="Hitbox" type="Area2D" parent="."]
+
+[node name="Health" type="Node" parent="."]
+
+[connection signal="area_entered" from="Hitbox" to="Health" method="_on_hit"]
+"#).unwrap();
+    fs::write(root.join("main.tscn"), r#"[gd_scene load_steps=2 format=3]
+
+[ext_resource type="PackedScene" path="res://player.tscn" id="1"]
+
+[node name="Main" type="Node2D"]
+
+[node name="Player" parent="." instance=ExtResource("1")]
+
+[node name="Hud" type="CanvasLayer" parent="."]
+
+[connection signal="area_entered" from="Player/Hitbox" to="Hud" method="_on_player_hit"]
+[connection signal="ready" from="Player" to="." method="_on_player_ready"]
+[connection signal="ready" from="Player/Gone" to="." method="_on_gone"]
+"#).unwrap();
+
+    let edges = godot_analyzer::signal_graph(root);
+    let ids: Vec<String> = edges.iter().map(|e| format!("{} {} -> {}", e.edge.scene.display(), e.from_id, e.to_id)).collect();
+    assert_eq!(ids, vec![
+        "main.tscn player.tscn:. -> main.tscn:.",
+        "main.tscn main.tscn:Player/Gone -> main.tscn:.",
+        "main.tscn player.tscn:Hitbox -> main.tscn:Hud",
+        "player.tscn player.tscn:Hitbox -> player.tscn:Health",
+    ]);
+
+    // Both scenes' edges meet at the player's Hitbox node
+    let dot = signal_graph_dot(root);
+    assert!(dot.contains("\"player.tscn:Hitbox\" -> \"main.tscn:Hud\" [label=\"area_entered:_on_player_hit\"];"));
+    assert!(dot.contains("\"player.tscn:Hitbox\" -> \"player.tscn:Health\" [label=\"area_entered:_on_hit\"];"));
+    assert!(!dot.contains("main.tscn:Player/Hitbox"));
+}
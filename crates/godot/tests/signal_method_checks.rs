@@ -82,3 +82,62 @@ fn skips_method_check_for_csharp_script() {
     // Should not emit method-not-found for C# scripts
     assert!(!issues.iter().any(|i| i.message.starts_with("Target method not found:")));
 }
+
+#[test]
+fn flags_target_methods_taking_the_wrong_number_of_arguments() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("player.gd"), "extends CharacterBody2D\nsignal hit(damage: int, source: Node)\n").unwrap();
+    fs::write(root.join("hud.gd"), r#"extends Control
+
+func _on_toggled():
+    pass
+
+func _on_value(value: float, extra := 0):
+    pass
+
+func _on_hit(damage):
+    pass
+
+func _on_hit_bound(damage, source, label):
+    pass
+
+func _on_timeout(id):
+    pass
+"#).unwrap();
+    let scene = r#"[gd_scene format=3]
+
+[ext_resource type="Script" path="res://hud.gd" id="1"]
+[ext_resource type="Script" path="res://player.gd" id="2"]
+
+[node name="Hud" type="Control"]
+script = ExtResource("1")
+
+[node name="Check" type="CheckBox" parent="."]
+
+[node name="Slider" type="HSlider" parent="."]
+
+[node name="Player" type="CharacterBody2D" parent="."]
+script = ExtResource("2")
+
+[node name="Timer" type="Timer" parent="."]
+
+[connection signal="toggled" from="Check" to="." method="_on_toggled"]
+[connection signal="value_changed" from="Slider" to="." method="_on_value"]
+[connection signal="hit" from="Player" to="." method="_on_hit"]
+[connection signal="hit" from="Player" to="." method="_on_timeout" unbinds=1]
+[connection signal="hit" from="Player" to="." method="_on_hit_bound" binds=["ouch"]]
+[connection signal="timeout" from="Timer" to="." method="_on_timeout" binds=[7]]
+[connection signal="timeout" from="Timer" to="." method="_on_toggled" binds=[7]]
+"#;
+    fs::write(root.join("hud.tscn"), scene).unwrap();
+
+    let issues = signal_issues_as_report(root);
+    let found: Vec<(usize, &str)> = issues.iter().map(|i| (i.line.unwrap(), i.message.as_str())).collect();
+    assert_eq!(found, vec![
+        (18, "Target method argument mismatch: method='_on_toggled' to='.' takes 0 argument(s) but 'toggled' passes 1 — hint: match func _on_toggled(...) to the signal's arguments, or adjust binds/unbinds"),
+        (20, "Target method argument mismatch: method='_on_hit' to='.' takes 1 argument(s) but 'hit' passes 2 — hint: match func _on_hit(...) to the signal's arguments, or adjust binds/unbinds"),
+        (24, "Target method argument mismatch: method='_on_toggled' to='.' takes 0 argument(s) but 'timeout' passes 1 (0 from the signal, 1 bound, 0 unbound) — hint: match func _on_toggled(...) to the signal's arguments, or adjust binds/unbinds"),
+    ]);
+    assert!(issues.iter().all(|i| i.code.as_deref() == Some("method-arity-mismatch")));
+}
@@ -0,0 +1,72 @@
+use std::fs;
+use std::path::Path;
+use godot_analyzer::class_db::ClassDb;
+use godot_analyzer::signal_validate::validate_scene_signals;
+
+#[test]
+fn builtin_class_db_resolves_inherited_signals() {
+    let db = ClassDb::builtin();
+    assert!(db.contains("CharacterBody2D"));
+    assert_eq!(db.ancestors("Button"), vec!["Button", "BaseButton", "Control", "CanvasItem", "Node", "Object"]);
+    assert!(db.has_signal("Button", "pressed"));
+    assert!(db.has_signal("Button", "ready"));
+    assert!(db.has_signal("Area2D", "input_event"));
+    assert!(!db.has_signal("Node2D", "pressed"));
+    assert!(db.inherits("RigidBody3D", "CollisionObject3D"));
+    assert!(db.signals("NoSuchClass").is_empty());
+    assert_eq!(db.signal_args("CheckBox", "toggled"), Some(1));
+    assert_eq!(db.signal_args("Area2D", "body_shape_entered"), Some(4));
+    assert_eq!(db.signal_args("Button", "pressed"), Some(0));
+    assert_eq!(db.signal_args("Button", "nope"), None);
+}
+
+#[test]
+fn flags_signals_missing_on_the_source_node() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("base_enemy.gd"), "class_name BaseEnemy\nextends CharacterBody2D\nsignal died\n").unwrap();
+    fs::write(root.join("enemy.gd"), "extends BaseEnemy\nsignal health_changed(value: int)\nfunc _on(): pass\n").unwrap();
+    fs::write(root.join("odd.gd"), "extends SomethingCustom\n").unwrap();
+    fs::write(root.join("main.gd"), "extends Node\nfunc _on(_value = null): pass\n").unwrap();
+    let scene = r#"[gd_scene format=3]
+
+[ext_resource type="Script" path="res://main.gd" id="1"]
+[ext_resource type="Script" path="res://enemy.gd" id="2"]
+[ext_resource type="Script" path="res://odd.gd" id="3"]
+
+[node name="Main" type="Node"]
+script = ExtResource("1")
+
+[node name="Button" type="Button" parent="."]
+
+[node name="Enemy" type="CharacterBody2D" parent="."]
+script = ExtResource("2")
+
+[node name="Custom" type="MyThing" parent="."]
+
+[node name="Odd" type="Node" parent="."]
+script = ExtResource("3")
+
+[connection signal="pressed" from="Button" to="." method="_on"]
+[connection signal="ready" from="Button" to="." method="_on"]
+[connection signal="presed" from="Button" to="." method="_on"]
+[connection signal="health_changed" from="Enemy" to="." method="_on"]
+[connection signal="died" from="Enemy" to="." method="_on"]
+[connection signal="tree_exited" from="Enemy" to="." method="_on"]
+[connection signal="exploded" from="Enemy" to="." method="_on"]
+[connection signal="whatever" from="Custom" to="." method="_on"]
+[connection signal="died" from="Odd" to="." method="_on"]
+[connection signal="timeout" from="." to="." method="_on"]
+"#;
+    fs::write(root.join("main.tscn"), scene).unwrap();
+
+    let issues = validate_scene_signals(root, Path::new("main.tscn"));
+    let found: Vec<(usize, &str)> = issues.iter().map(|i| (i.line, i.message.as_str())).collect();
+    assert_eq!(found, vec![
+        (22, "Unknown signal: 'presed' on from='Button' (Button) — hint: check the signal name or declare 'signal presed' in the node's script"),
+        (26, "Unknown signal: 'exploded' on from='Enemy' (CharacterBody2D) — hint: check the signal name or declare 'signal exploded' in the node's script"),
+        // Signals of nodes with an unknown type or base can't be listed, but no class declares 'whatever'
+        (27, "Undeclared signal: 'whatever' on from='Custom' — no script or built-in class declares it"),
+        (29, "Unknown signal: 'timeout' on from='.' (Node) — hint: check the signal name or declare 'signal timeout' in the node's script"),
+    ]);
+}
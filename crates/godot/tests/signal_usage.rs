@@ -0,0 +1,56 @@
+use std::fs;
+use godot_analyzer::script_lint::lint_gd_scripts;
+use godot_analyzer::{lint_gd, to_sarif, GodotProjectReport};
+
+#[test]
+fn cross_references_signal_declarations_and_uses() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("player.gd"), r#"extends CharacterBody2D
+signal died
+signal health_changed(value)
+signal jumped
+signal never_used
+signal awaited_one
+
+func hurt():
+    health_changed.emit(1)
+    emit_signal("died")
+    emit_signal("dide")
+    emit_signal("ready")
+    await awaited_one
+
+class Inner:
+    signal inner_done
+    func go():
+        emit_signal("inner_done")
+"#).unwrap();
+    fs::write(root.join("hud.gd"), "extends Node\nfunc bind(p):\n    p.jumped.connect(_on)\n    p.connect(\"anything\", _on)\nfunc _on():\n    pass\n").unwrap();
+    fs::write(root.join("main.tscn"), "[gd_scene format=3]\n\n[node name=\"Main\" type=\"Node\"]\n\n[connection signal=\"died\" from=\".\" to=\".\" method=\"_on\"]\n").unwrap();
+
+    let mut got: Vec<(String, String, Option<usize>)> = lint_gd_scripts(root).into_iter()
+        .filter(|f| f.code.ends_with("-signal"))
+        .map(|f| (f.code, f.message, f.line))
+        .collect();
+    got.sort_by_key(|g| g.2);
+    let expected = vec![
+        ("unused-signal", "Signal never emitted: 'jumped' is connected but nothing emits it", Some(4)),
+        ("unused-signal", "Unused signal: 'never_used' is never emitted or connected", Some(5)),
+        ("unused-signal", "Signal never emitted: 'awaited_one' is connected but nothing emits it", Some(6)),
+        ("undeclared-script-signal", "Undeclared signal in script: emit_signal('dide') — no signal 'dide' in this script or its base classes", Some(11)),
+    ];
+    let expected: Vec<_> = expected.into_iter().map(|(c, m, l)| (c.to_string(), m.to_string(), l)).collect();
+    assert_eq!(got, expected);
+
+    let issues: Vec<_> = lint_gd(root).into_iter().filter(|i| i.code.as_deref().is_some_and(|c| c.ends_with("-signal"))).collect();
+    let sarif = to_sarif(&GodotProjectReport { issues, ..Default::default() });
+    assert!(sarif["runs"][0]["results"].as_array().unwrap().iter().all(|r| r["ruleId"] == "script-lint"));
+}
+
+#[test]
+fn unresolvable_base_classes_skip_the_undeclared_check() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("odd.gd"), "extends SomethingCustom\nfunc f():\n    emit_signal(\"from_base\")\n").unwrap();
+    assert!(!lint_gd_scripts(root).iter().any(|f| f.code == "undeclared-script-signal"));
+}
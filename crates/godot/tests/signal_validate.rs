@@ -19,3 +19,34 @@ fn flags_unknown_from_to_and_duplicate_connection() {
     assert!(issues.iter().any(|i| i.message.starts_with("Unknown connection 'to' node:")));
     assert!(issues.iter().any(|i| i.message.starts_with("Duplicate connection:")));
 }
+
+#[test]
+fn flags_code_connections_repeating_scene_or_code_connections() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("menu.gd"), "extends Control\n\nfunc _ready():\n    $Start.pressed.connect(_on_start)\n    $Quit.pressed.connect(_on_quit)\n    $Quit.pressed.connect(self._on_quit)\n\nfunc _on_start():\n    pass\n\nfunc _on_quit():\n    pass\n").unwrap();
+    let scene = r#"[gd_scene format=3]
+
+[ext_resource type="Script" path="res://menu.gd" id="1"]
+
+[node name="Menu" type="Control"]
+script = ExtResource("1")
+
+[node name="Start" type="Button" parent="."]
+
+[node name="Quit" type="Button" parent="."]
+
+[connection signal="pressed" from="Start" to="." method="_on_start"]
+"#;
+    fs::write(root.join("menu.tscn"), scene).unwrap();
+
+    let mut dups: Vec<(String, Option<usize>)> = signal_issues_as_report(root).into_iter()
+        .filter(|i| i.message.starts_with("Duplicate connection:"))
+        .map(|i| (i.message, i.line))
+        .collect();
+    dups.sort();
+    assert_eq!(dups, vec![
+        ("Duplicate connection: signal=pressed from=Quit to=. method=_on_quit — hint: connected in code at both menu.gd:5 and menu.gd:6; keep one of them".to_string(), Some(5)),
+        ("Duplicate connection: signal=pressed from=Start to=. method=_on_start — hint: menu.gd:4 also connects it in code; keep one of them".to_string(), Some(12)),
+    ]);
+}
@@ -31,3 +31,315 @@ fn apply_moves_and_updates_references() {
     let gd = fs::read_to_string(root.join("scripts/player.gd")).unwrap();
     assert!(gd.contains("res://scripts/player.gd"));
 }
+
+#[test]
+fn apply_moves_import_and_uid_sidecars_with_their_assets() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::create_dir_all(root.join("sub")).unwrap();
+    fs::write(root.join("sub/tex.png"), "fake").unwrap();
+    fs::write(root.join("sub/tex.png.import"), "[remap]\n\nimporter=\"texture\"\n\n[deps]\n\nsource_file=\"res://sub/tex.png\"\ndest_files=[\"res://.godot/imported/tex.png-1.ctex\"]\n").unwrap();
+    fs::write(root.join("player.gd"), "extends Node\n").unwrap();
+    fs::write(root.join("player.gd.uid"), "uid://b1x2y3\n").unwrap();
+
+    let plan = plan_structure_fix(root);
+    // Sidecars aren't planned on their own
+    assert_eq!(plan.moves.len(), 2);
+    let sum = apply_structure_fix(root, &plan).expect("apply");
+
+    let sidecars: Vec<(String, String)> = sum.sidecars.iter()
+        .map(|m| (m.from.to_string_lossy().to_string(), m.to.to_string_lossy().to_string()))
+        .collect();
+    assert_eq!(sidecars, vec![
+        ("res://player.gd.uid".to_string(), "res://scripts/player.gd.uid".to_string()),
+        ("res://sub/tex.png.import".to_string(), "res://assets/sub/tex.png.import".to_string()),
+    ]);
+    assert!(!root.join("sub/tex.png.import").exists());
+    assert!(!root.join("player.gd.uid").exists());
+    assert_eq!(fs::read_to_string(root.join("scripts/player.gd.uid")).unwrap(), "uid://b1x2y3\n");
+    let import = fs::read_to_string(root.join("assets/sub/tex.png.import")).unwrap();
+    assert!(import.contains("source_file=\"res://assets/sub/tex.png\""));
+    assert!(sum.edited.iter().any(|e| e.kind == "import-source-file" && e.file.ends_with("tex.png.import")));
+
+    // Backed up alongside the assets
+    assert_eq!(sum.backed_up, 4);
+    assert!(root.join(".structure_fix/backup/sub/tex.png.import").exists());
+    assert!(root.join(".structure_fix/backup/player.gd.uid").exists());
+}
+
+#[test]
+fn apply_updates_project_settings_references() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("project.godot"), r#"config_version=5
+
+[application]
+
+config/name="Demo"
+run/main_scene="res://main.tscn"
+config/icon="res://icon.svg"
+
+[autoload]
+
+Game="*res://game.gd"
+Audio="res://audio.gd"
+
+[editor_plugins]
+
+enabled=PackedStringArray("res://main.tscn")
+
+[input]
+
+fire={
+"deadzone": 0.5,
+"events": [Object(InputEventAction,"resource_path":"res://main.tscn","action":"fire")]
+}
+"#).unwrap();
+    for f in ["main.tscn", "icon.svg", "game.gd", "audio.gd"] {
+        fs::write(root.join(f), "").unwrap();
+    }
+
+    let plan = plan_structure_fix(root);
+    let sum = apply_structure_fix(root, &plan).expect("apply");
+    let project = fs::read_to_string(root.join("project.godot")).unwrap();
+    assert!(project.contains("run/main_scene=\"res://scenes/main.tscn\""));
+    assert!(project.contains("config/icon=\"res://assets/icon.svg\""));
+    assert!(project.contains("Game=\"*res://scripts/game.gd\""));
+    assert!(project.contains("Audio=\"res://scripts/audio.gd\""));
+    assert!(project.contains("\"resource_path\":\"res://scenes/main.tscn\""));
+    // Settings outside the known ones stay as they were
+    assert!(project.contains("enabled=PackedStringArray(\"res://main.tscn\")"));
+    let edit = sum.edited.iter().find(|e| e.file.as_os_str() == "project.godot").unwrap();
+    assert_eq!((edit.kind.as_str(), edit.count), ("project-settings", 5));
+}
+
+#[test]
+fn rollback_restores_moves_and_reference_edits() {
+    use godot_analyzer::structure_fix::rollback_structure_fix;
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let project = "config_version=5\n\n[application]\n\nrun/main_scene=\"res://main.tscn\"\n";
+    let scene = "[gd_scene format=3]\n\n[ext_resource type=\"Script\" path=\"res://player.gd\" id=\"1\"]\n\n[node name=\"Root\" type=\"Node\"]\n";
+    let level = "[gd_scene format=3]\n\n[ext_resource type=\"PackedScene\" path=\"res://main.tscn\" id=\"1\"]\n\n[node name=\"Level\" type=\"Node\"]\n";
+    fs::write(root.join("project.godot"), project).unwrap();
+    fs::write(root.join("main.tscn"), scene).unwrap();
+    fs::write(root.join("player.gd"), "extends Node\n").unwrap();
+    fs::write(root.join("player.gd.uid"), "uid://b1x2y3\n").unwrap();
+    fs::create_dir_all(root.join("scenes")).unwrap();
+    fs::write(root.join("scenes/level.tscn"), level).unwrap();
+
+    let plan = plan_structure_fix(root);
+    apply_structure_fix(root, &plan).expect("apply");
+    assert!(root.join("scripts/player.gd").exists());
+    assert!(root.join(".structure_fix/manifest.json").exists());
+    assert!(fs::read_to_string(root.join("scenes/level.tscn")).unwrap().contains("res://scenes/main.tscn"));
+
+    let sum = rollback_structure_fix(root).expect("rollback");
+    assert_eq!(sum.restored.len(), 3);
+    assert_eq!(sum.reverted, vec![std::path::PathBuf::from("scenes/level.tscn"), std::path::PathBuf::from("project.godot")]);
+    assert_eq!(fs::read_to_string(root.join("project.godot")).unwrap(), project);
+    assert_eq!(fs::read_to_string(root.join("main.tscn")).unwrap(), scene);
+    assert_eq!(fs::read_to_string(root.join("scenes/level.tscn")).unwrap(), level);
+    assert_eq!(fs::read_to_string(root.join("player.gd.uid")).unwrap(), "uid://b1x2y3\n");
+    assert!(root.join("player.gd").exists());
+    assert!(!root.join("scenes/main.tscn").exists());
+    // Folders the fix created are gone, the backups too
+    assert!(!root.join("scripts").exists());
+    assert!(!root.join(".structure_fix").exists());
+    // Nothing left to undo
+    assert!(rollback_structure_fix(root).is_err());
+}
+
+#[test]
+fn rollback_changes_nothing_when_a_file_is_in_the_way() {
+    use godot_analyzer::structure_fix::rollback_structure_fix;
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("a.gd"), "extends Node\n").unwrap();
+    fs::write(root.join("b.gd"), "extends Node\n").unwrap();
+    apply_structure_fix(root, &plan_structure_fix(root)).expect("apply");
+    // A new file took b.gd's old place
+    fs::write(root.join("b.gd"), "# new\n").unwrap();
+
+    let err = rollback_structure_fix(root).unwrap_err();
+    assert!(err.to_string().contains("res://b.gd exists again"));
+    assert!(root.join("scripts/a.gd").exists());
+    assert!(!root.join("a.gd").exists());
+    assert!(root.join(".structure_fix/manifest.json").exists());
+}
+
+#[test]
+fn rollback_refuses_to_drop_changes_made_since_the_apply() {
+    use godot_analyzer::structure_fix::rollback_structure_fix;
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("a.gd"), "extends Node\n").unwrap();
+    fs::write(root.join("main.tscn"), "[gd_scene format=3]\n\n[ext_resource type=\"Script\" path=\"res://a.gd\" id=\"1\"]\n").unwrap();
+    fs::write(root.join("b.gd"), "const A = preload(\"res://a.gd\")\n").unwrap();
+    fs::write(root.join("godot-analyzer.toml"), "[structure]\nrules = [\"b.gd => res://\", \"**/*.gd => res://scripts/<filename>\"]\n").unwrap();
+    apply_structure_fix(root, &plan_structure_fix(root)).expect("apply");
+
+    // Edits to a moved file and to a file the apply edited in place are both kept
+    for (file, edit) in [("scripts/a.gd", "extends Node\nvar hp = 3\n"), ("b.gd", "const A = preload(\"res://scripts/a.gd\")\nvar x = 1\n")] {
+        let before = fs::read_to_string(root.join(file)).unwrap();
+        fs::write(root.join(file), edit).unwrap();
+        let err = rollback_structure_fix(root).unwrap_err();
+        assert!(err.to_string().contains(&format!("{} changed after the structure fix", file)), "{}", err);
+        assert_eq!(fs::read_to_string(root.join(file)).unwrap(), edit);
+        assert!(!root.join("a.gd").exists());
+        fs::write(root.join(file), before).unwrap();
+    }
+    rollback_structure_fix(root).expect("rollback");
+    assert_eq!(fs::read_to_string(root.join("a.gd")).unwrap(), "extends Node\n");
+    assert_eq!(fs::read_to_string(root.join("b.gd")).unwrap(), "const A = preload(\"res://a.gd\")\n");
+    assert!(fs::read_to_string(root.join("main.tscn")).unwrap().contains("path=\"res://a.gd\""));
+}
+
+#[test]
+fn applies_only_the_selected_moves() {
+    use godot_analyzer::structure_fix::{apply_structure_fix_selected, MoveSelection};
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("player.gd"), "extends Node\n").unwrap();
+    fs::write(root.join("enemy.gd"), "extends Node\n").unwrap();
+    fs::write(root.join("main.tscn"), "[gd_scene format=3]\n\n[ext_resource type=\"Script\" path=\"res://player.gd\" id=\"1\"]\n\n[ext_resource type=\"Script\" path=\"res://enemy.gd\" id=\"2\"]\n").unwrap();
+    fs::write(root.join("icon.png"), "fake").unwrap();
+    let plan = plan_structure_fix(root);
+    assert_eq!(plan.moves.len(), 4);
+
+    let scripts_but_enemy = MoveSelection { include: vec!["scripts/**".into()], exclude: vec!["enemy.gd".into()], ..Default::default() };
+    let selected = scripts_but_enemy.select(&plan).unwrap();
+    assert_eq!(selected.moves.iter().map(|m| m.from.to_string_lossy().to_string()).collect::<Vec<_>>(), vec!["res://player.gd"]);
+    assert_eq!(selected.stats.proposed, 1);
+
+    let sum = apply_structure_fix_selected(root, &plan, &scripts_but_enemy).unwrap();
+    assert_eq!(sum.moved.len(), 1);
+    assert!(root.join("scripts/player.gd").exists());
+    assert!(root.join("enemy.gd").exists());
+    assert!(root.join("main.tscn").exists());
+    // Only the moved script's reference changes
+    let scene = fs::read_to_string(root.join("main.tscn")).unwrap();
+    assert!(scene.contains("path=\"res://scripts/player.gd\""));
+    assert!(scene.contains("path=\"res://enemy.gd\""));
+
+    // An explicit list, and the rest is still proposed next time
+    let listed = MoveSelection { moves: vec!["res://icon.png".into()], ..Default::default() };
+    let sum = apply_structure_fix_selected(root, &plan_structure_fix(root), &listed).unwrap();
+    assert_eq!(sum.moved.len(), 1);
+    assert!(root.join("assets/icon.png").exists());
+    assert_eq!(plan_structure_fix(root).moves.len(), 2);
+
+    let bogus = MoveSelection { moves: vec!["res://nope.gd".into()], ..Default::default() };
+    assert!(bogus.select(&plan).unwrap_err().to_string().contains("res://nope.gd isn't moved by the plan"));
+}
+
+#[test]
+fn apply_renames_files_and_rewrites_references() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::create_dir_all(root.join("scripts")).unwrap();
+    fs::create_dir_all(root.join("scenes")).unwrap();
+    fs::write(root.join("scripts/PlayerController.gd"), "extends Node\nconst Self = preload(\"res://scripts/PlayerController.gd\")\n").unwrap();
+    fs::write(root.join("scripts/PlayerController.gd.uid"), "uid://c1\n").unwrap();
+    fs::write(root.join("scenes/main.tscn"), "[gd_scene format=3]\n\n[ext_resource type=\"Script\" path=\"res://scripts/PlayerController.gd\" id=\"1\"]\n").unwrap();
+    fs::write(root.join("godot-analyzer.toml"), "[structure]\nrename = \"snake_case\"\n").unwrap();
+
+    let sum = apply_structure_fix(root, &plan_structure_fix(root)).expect("apply");
+    assert_eq!(sum.renamed.len(), 1);
+    assert!(sum.moved.is_empty());
+    assert!(root.join("scripts/player_controller.gd").exists());
+    assert!(root.join("scripts/player_controller.gd.uid").exists());
+    assert!(!root.join("scripts/PlayerController.gd").exists());
+    assert!(fs::read_to_string(root.join("scenes/main.tscn")).unwrap().contains("path=\"res://scripts/player_controller.gd\""));
+    assert!(fs::read_to_string(root.join("scripts/player_controller.gd")).unwrap().contains("preload(\"res://scripts/player_controller.gd\")"));
+}
+
+#[test]
+fn case_only_renames_apply_and_roll_back() {
+    use godot_analyzer::structure_fix::rollback_structure_fix;
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::create_dir_all(root.join("scripts")).unwrap();
+    fs::write(root.join("scripts/Player.gd"), "extends Node\n").unwrap();
+    fs::write(root.join("main.gd"), "const P = preload(\"res://scripts/Player.gd\")\n").unwrap();
+    fs::write(root.join("godot-analyzer.toml"), "[structure]\nrules = [\"main.gd => res://\", \"**/*.gd => res://scripts/<filename>\"]\nrename = \"snake_case\"\n").unwrap();
+
+    // Not a collision with itself, where the filesystem ignores case
+    let plan = plan_structure_fix(root);
+    assert!(plan.collisions.is_empty(), "{:?}", plan.collisions);
+    assert_eq!(plan.renames.len(), 1);
+    assert_eq!(plan.renames[0].to.to_string_lossy(), "res://scripts/player.gd");
+
+    apply_structure_fix(root, &plan).expect("apply");
+    assert_eq!(fs::read_to_string(root.join("scripts/player.gd")).unwrap(), "extends Node\n");
+    assert!(fs::read_to_string(root.join("main.gd")).unwrap().contains("res://scripts/player.gd"));
+
+    rollback_structure_fix(root).expect("rollback");
+    assert_eq!(fs::read_to_string(root.join("scripts/Player.gd")).unwrap(), "extends Node\n");
+    assert!(fs::read_to_string(root.join("main.gd")).unwrap().contains("res://scripts/Player.gd"));
+}
+
+#[test]
+fn apply_refuses_to_overwrite_files() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("a.gd"), "extends Node\n").unwrap();
+    fs::write(root.join("b.gd"), "extends Node\n").unwrap();
+    let plan = plan_structure_fix(root);
+    // A file appeared at a destination after planning
+    fs::create_dir_all(root.join("scripts")).unwrap();
+    fs::write(root.join("scripts/b.gd"), "# mine\n").unwrap();
+
+    let err = apply_structure_fix(root, &plan).unwrap_err();
+    assert!(err.to_string().contains("res://scripts/b.gd already exists"));
+    assert!(root.join("a.gd").exists());
+    assert_eq!(fs::read_to_string(root.join("scripts/b.gd")).unwrap(), "# mine\n");
+}
+
+#[test]
+fn apply_moves_a_file_out_before_another_lands_on_its_path() {
+    use godot_analyzer::structure_fix::{rollback_structure_fix, FileMove, FileRename, FixPlan};
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::create_dir_all(root.join("ui")).unwrap();
+    fs::create_dir_all(root.join("scripts")).unwrap();
+    fs::write(root.join("ui/Player.gd"), "# ui\n").unwrap();
+    fs::write(root.join("scripts/Player.gd"), "# scripts\n").unwrap();
+    fs::write(root.join("scripts/Player.gd.uid"), "uid://p1\n").unwrap();
+    let mv = |from: &str, to: &str| FileMove { from: from.into(), to: to.into() };
+    let plan = FixPlan {
+        moves: vec![mv("res://ui/Player.gd", "res://scripts/Player.gd")],
+        renames: vec![FileRename { from: "res://scripts/Player.gd".into(), to: "res://scripts/player.gd".into() }],
+        ..Default::default()
+    };
+
+    let sum = apply_structure_fix(root, &plan).expect("apply");
+    assert_eq!(sum.moved, plan.moves);
+    assert_eq!(sum.renamed, plan.renames);
+    assert_eq!(fs::read_to_string(root.join("scripts/Player.gd")).unwrap(), "# ui\n");
+    assert_eq!(fs::read_to_string(root.join("scripts/player.gd")).unwrap(), "# scripts\n");
+    assert_eq!(fs::read_to_string(root.join("scripts/player.gd.uid")).unwrap(), "uid://p1\n");
+    assert!(!root.join("ui/Player.gd").exists());
+
+    rollback_structure_fix(root).expect("rollback");
+    assert_eq!(fs::read_to_string(root.join("ui/Player.gd")).unwrap(), "# ui\n");
+    assert_eq!(fs::read_to_string(root.join("scripts/Player.gd")).unwrap(), "# scripts\n");
+    assert_eq!(fs::read_to_string(root.join("scripts/Player.gd.uid")).unwrap(), "uid://p1\n");
+    assert!(!root.join("scripts/player.gd").exists());
+
+    // Two files trading places go through a staging name
+    let swap = FixPlan { moves: vec![mv("res://ui/Player.gd", "res://scripts/Player.gd"), mv("res://scripts/Player.gd", "res://ui/Player.gd")], ..Default::default() };
+    let mut sum = apply_structure_fix(root, &swap).expect("apply swap");
+    sum.moved.sort();
+    assert_eq!(sum.moved, vec![swap.moves[1].clone(), swap.moves[0].clone()]);
+    assert_eq!(fs::read_to_string(root.join("scripts/Player.gd")).unwrap(), "# ui\n");
+    assert_eq!(fs::read_to_string(root.join("ui/Player.gd")).unwrap(), "# scripts\n");
+    assert_eq!(fs::read_to_string(root.join("ui/Player.gd.uid")).unwrap(), "uid://p1\n");
+    assert!(!root.join(".structure_fix/staging").exists());
+
+    rollback_structure_fix(root).expect("rollback swap");
+    assert_eq!(fs::read_to_string(root.join("ui/Player.gd")).unwrap(), "# ui\n");
+    assert_eq!(fs::read_to_string(root.join("scripts/Player.gd")).unwrap(), "# scripts\n");
+    assert_eq!(fs::read_to_string(root.join("scripts/Player.gd.uid")).unwrap(), "uid://p1\n");
+    assert!(!root.join(".structure_fix").exists());
+}
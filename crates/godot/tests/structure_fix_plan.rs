@@ -20,3 +20,162 @@ fn plans_moves_for_scripts_and_scenes() {
     assert!(moves.contains(&("res://main.tscn".into(), "res://scenes/main.tscn".into())));
     assert!(moves.contains(&("res://subdir/tex.png".into(), "res://assets/subdir/tex.png".into())));
 }
+
+#[test]
+fn plans_moves_by_configured_layout_rules() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("godot-analyzer.toml"), r#"
+[structure]
+rules = [
+    "ui/**.gd => res://ui/scripts/",
+    "**/*.gd => res://code/<relpath>",
+    "**/*.PNG => res://art/<filename>",
+]
+"#).unwrap();
+    for f in ["ui/menus/menu.gd", "ui/scripts/hud.gd", "player/player.gd", "code/util.gd", "levels/icon.png", "levels/level.tscn"] {
+        fs::create_dir_all(root.join(f).parent().unwrap()).unwrap();
+        fs::write(root.join(f), "").unwrap();
+    }
+
+    let plan = plan_structure_fix(root);
+    assert_eq!(plan.rules, vec!["ui/**.gd => res://ui/scripts/", "**/*.gd => res://code/<relpath>", "**/*.PNG => res://art/<filename>"]);
+    let moves: Vec<(String, String)> = plan.moves.iter()
+        .map(|m| (m.from.to_string_lossy().to_string(), m.to.to_string_lossy().to_string()))
+        .collect();
+    assert_eq!(moves, vec![
+        ("res://levels/icon.png".to_string(), "res://art/icon.png".to_string()),
+        ("res://player/player.gd".to_string(), "res://code/player/player.gd".to_string()),
+        ("res://ui/menus/menu.gd".to_string(), "res://ui/scripts/menu.gd".to_string()),
+    ]);
+}
+
+#[test]
+fn rejects_malformed_layout_rules() {
+    use godot_analyzer::config::AnalyzerConfig;
+    for (rules, expected) in [
+        (r#"["scripts/*.gd"]"#, "expected '<glob> => res://<destination>'"),
+        (r#"["*.gd => scripts/"]"#, "must start with res://"),
+        (r#"["*.gd => res://<dir>/"]"#, "unknown placeholder <dir>"),
+        (r#"["[.gd => res://scripts/"]"#, "invalid glob"),
+    ] {
+        let err = AnalyzerConfig::parse(&format!("[structure]\nrules = {}\n", rules)).unwrap_err();
+        assert!(format!("{:#}", err).contains(expected), "{}: {:#}", rules, err);
+    }
+}
+
+#[test]
+fn plans_snake_case_renames() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::create_dir_all(root.join("scripts")).unwrap();
+    fs::write(root.join("scripts/PlayerController.gd"), "extends Node\n").unwrap();
+    fs::write(root.join("scripts/EnemyAI.gd"), "class_name EnemyAI\nextends Node\n").unwrap();
+    fs::write(root.join("scripts/Player.cs"), "").unwrap();
+    fs::write(root.join("MainMenu.tscn"), "").unwrap();
+    fs::write(root.join("README.md"), "").unwrap();
+
+    // Files keep their names unless the project opts in
+    let plan = plan_structure_fix(root);
+    assert!(plan.renames.is_empty());
+    assert_eq!(plan.moves[0].to.to_string_lossy(), "res://scenes/MainMenu.tscn");
+
+    fs::write(root.join("godot-analyzer.toml"), "[structure]\nrename = \"snake_case\"\n").unwrap();
+    let plan = plan_structure_fix(root);
+    let pairs = |v: Vec<(&std::path::PathBuf, &std::path::PathBuf)>| v.into_iter().map(|(a, b)| format!("{} -> {}", a.display(), b.display())).collect::<Vec<_>>();
+    // Renamed in place, or at the destination when the file moves too
+    assert_eq!(pairs(plan.renames.iter().map(|r| (&r.from, &r.to)).collect()), vec!["res://scripts/PlayerController.gd -> res://scripts/player_controller.gd"]);
+    assert_eq!(pairs(plan.moves.iter().map(|m| (&m.from, &m.to)).collect()), vec!["res://MainMenu.tscn -> res://scenes/main_menu.tscn"]);
+    assert_eq!(plan.stats.proposed, 2);
+}
+
+#[test]
+fn plans_feature_folders() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("godot-analyzer.toml"), "[structure]\nstrategy = \"by-feature\"\n").unwrap();
+    let files = [
+        ("Player.tscn", "[gd_scene format=3]\n[ext_resource type=\"Script\" path=\"res://player/controller.gd\" id=\"1\"]\n[ext_resource type=\"Texture2D\" path=\"res://art/hero.png\" id=\"2\"]\n[ext_resource type=\"Texture2D\" path=\"res://art/shared.png\" id=\"3\"]\n"),
+        ("features/enemy/enemy.tscn", "[gd_scene format=3]\n[ext_resource type=\"Texture2D\" path=\"res://art/shared.png\" id=\"1\"]\n"),
+        ("player.gd", "extends Node\n"),
+        ("player/controller.gd", "extends Node\n"),
+        ("art/hero.png", ""),
+        ("art/shared.png", ""),
+        ("util.gd", "extends Node\n"),
+        ("features/common/helpers.gd", "extends Node\n"),
+    ];
+    for (f, contents) in files {
+        fs::create_dir_all(root.join(f).parent().unwrap()).unwrap();
+        fs::write(root.join(f), contents).unwrap();
+    }
+
+    let plan = plan_structure_fix(root);
+    assert_eq!(plan.rules.first().map(String::as_str), Some("strategy => by-feature"));
+    let moves: Vec<String> = plan.moves.iter().map(|m| format!("{} -> {}", m.from.display(), m.to.display())).collect();
+    // Named like the scene or used by it alone: with the scene; shared or unclaimed: by the rules
+    assert_eq!(moves, vec![
+        "res://Player.tscn -> res://features/player/Player.tscn",
+        "res://art/hero.png -> res://features/player/hero.png",
+        "res://art/shared.png -> res://assets/art/shared.png",
+        "res://player/controller.gd -> res://features/player/controller.gd",
+        "res://player.gd -> res://features/player/player.gd",
+        "res://util.gd -> res://scripts/util.gd",
+    ]);
+
+    // The CLI's --structure-strategy parses the same names
+    use godot_analyzer::structure_fix::Strategy;
+    assert_eq!("by-type".parse::<Strategy>(), Ok(Strategy::ByType));
+    assert!("by-folder".parse::<Strategy>().unwrap_err().contains("by-type|by-feature"));
+}
+
+#[test]
+fn plans_around_destination_collisions() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    for f in ["a/player.gd", "b/player.gd", "scripts/util.gd", "util.gd"] {
+        fs::create_dir_all(root.join(f).parent().unwrap()).unwrap();
+        fs::write(root.join(f), "extends Node\n").unwrap();
+    }
+
+    let plan = plan_structure_fix(root);
+    let moves: Vec<String> = plan.moves.iter().map(|m| format!("{} -> {}", m.from.display(), m.to.display())).collect();
+    assert_eq!(moves, vec!["res://a/player.gd -> res://scripts/player.gd"]);
+    assert_eq!(plan.stats.proposed, 1);
+    // Another move got there first, or a file already lives there
+    let collisions: Vec<String> = plan.collisions.iter().map(|c| format!("{} -> {} ({}) => {}", c.from.display(), c.to.display(), c.with.display(), c.proposed.display())).collect();
+    assert_eq!(collisions, vec![
+        "res://b/player.gd -> res://scripts/player.gd (res://a/player.gd) => res://scripts/b_player.gd",
+        "res://util.gd -> res://scripts/util.gd (res://scripts/util.gd) => res://scripts/util_2.gd",
+    ]);
+    assert_eq!(plan.skipped, vec![
+        "res://b/player.gd -> res://scripts/player.gd: destination taken by res://a/player.gd; proposed res://scripts/b_player.gd",
+        "res://util.gd -> res://scripts/util.gd: destination taken by res://scripts/util.gd; proposed res://scripts/util_2.gd",
+    ]);
+}
+
+#[test]
+fn plans_show_reference_edits_as_diffs() {
+    use godot_analyzer::structure_fix::apply_structure_fix;
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::create_dir_all(root.join("scenes")).unwrap();
+    fs::write(root.join("scenes/main.tscn"), "[gd_scene load_steps=2 format=3]\n\n[ext_resource type=\"Script\" path=\"res://player.gd\" id=\"1\"]\n\n[node name=\"Main\" type=\"Node\"]\nscript = ExtResource(\"1\")\n").unwrap();
+    fs::write(root.join("player.gd"), "extends Node\n").unwrap();
+    fs::write(root.join("hud.gd"), "extends Node\n\nconst Player = preload(\"res://player.gd\")\n").unwrap();
+
+    let plan = plan_structure_fix(root);
+    let edits: Vec<(String, &str, usize)> = plan.edits.iter().map(|e| (e.file.to_string_lossy().replace('\\', "/"), e.kind.as_str(), e.count)).collect();
+    assert_eq!(edits, vec![
+        ("scenes/main.tscn".to_string(), "ext_resource-path", 1),
+        ("scripts/hud.gd".to_string(), "gd-load-preload", 1),
+    ]);
+    // A file that moves too is diffed from where it is to where it goes
+    assert_eq!(plan.edits[1].diff.as_deref(), Some("--- a/hud.gd\n+++ b/scripts/hud.gd\n@@ -1,3 +1,3 @@\n extends Node\n \n-const Player = preload(\"res://player.gd\")\n+const Player = preload(\"res://scripts/player.gd\")\n"));
+    assert_eq!(plan.edits[0].diff.as_deref(), Some("--- a/scenes/main.tscn\n+++ b/scenes/main.tscn\n@@ -1,6 +1,6 @@\n [gd_scene load_steps=2 format=3]\n \n-[ext_resource type=\"Script\" path=\"res://player.gd\" id=\"1\"]\n+[ext_resource type=\"Script\" path=\"res://scripts/player.gd\" id=\"1\"]\n \n [node name=\"Main\" type=\"Node\"]\n script = ExtResource(\"1\")\n"));
+
+    // The preview is what apply does
+    let sum = apply_structure_fix(root, &plan).unwrap();
+    let applied: Vec<(String, &str, usize)> = sum.edited.iter().map(|e| (e.file.to_string_lossy().replace('\\', "/"), e.kind.as_str(), e.count)).collect();
+    assert_eq!(applied, edits);
+    assert!(sum.edited.iter().all(|e| e.diff.is_none()));
+}
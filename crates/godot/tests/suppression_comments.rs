@@ -0,0 +1,57 @@
+use std::fs;
+use std::path::Path;
+use godot_analyzer::analyze_project;
+use godot_analyzer::scene_validate::validate_scene;
+use godot_analyzer::script_lint::lint_gd_scripts;
+use godot_analyzer::signal_validate::validate_scene_signals;
+
+#[test]
+fn scene_comments_disable_codes_and_single_lines() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("main.tscn"), r#"[gd_scene load_steps=2 format=3]
+; godot-analyzer: disable=missing-ext-resource
+
+[ext_resource type="Texture2D" path="res://gone.png" id="1"]
+
+[node name="Main" type="Node2D"]
+; godot-analyzer: ignore-next-line=missing-script
+script = "res://missing.gd"
+
+[node name="Other" type="Sprite2D" parent="."]
+script = "res://missing_too.gd"
+
+; godot-analyzer: ignore-next-line
+[connection signal="ready" from="." to="Ghost" method="_on"]
+[connection signal="ready" from="." to="Ghost" method="_off"]
+"#).unwrap();
+
+    let msgs: Vec<String> = validate_scene(root, Path::new("main.tscn")).into_iter().map(|i| i.message).collect();
+    assert!(!msgs.iter().any(|m| m.starts_with("Missing ext_resource path:")), "{:?}", msgs);
+    assert!(!msgs.iter().any(|m| m == "Missing script: res://missing.gd"), "{:?}", msgs);
+    assert!(msgs.iter().any(|m| m == "Missing script: res://missing_too.gd"), "{:?}", msgs);
+
+    let sig: Vec<usize> = validate_scene_signals(root, Path::new("main.tscn")).into_iter().map(|i| i.line).collect();
+    assert_eq!(sig, vec![15]);
+
+    // The project-wide ext_resource scan honors the file's disable list too
+    let report = analyze_project(root).unwrap();
+    assert!(!report.issues.iter().any(|i| i.message.starts_with("Missing ext_resource path:")), "{:?}", report.issues);
+}
+
+#[test]
+fn script_ignore_next_line_skips_one_line() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("player.gd"), "extends Node\nfunc _ready():\n    # analyzer: ignore-next-line\n    var a = load(\"res://a.png\")\n    var b = load(\"res://b.png\")\n").unwrap();
+    let msgs: Vec<String> = lint_gd_scripts(root).into_iter().filter(|f| f.code == "missing-resource-ref").map(|f| f.message).collect();
+    assert_eq!(msgs, vec!["GDScript load missing file: res://b.png".to_string()]);
+}
+
+#[test]
+fn scene_off_skips_the_file() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("legacy.tscn"), "; godot-analyzer: off\n[gd_scene format=3]\n\n[node name=\"Main\" type=\"Nod\"]\nscript = \"res://missing.gd\"\n").unwrap();
+    assert!(validate_scene(root, Path::new("legacy.tscn")).is_empty());
+}
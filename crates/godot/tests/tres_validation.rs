@@ -0,0 +1,39 @@
+use std::fs;
+use std::path::Path;
+use godot_analyzer::{scene_issues_as_report, scene_validate::validate_scene};
+
+#[test]
+fn standalone_tres_files_are_validated() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("project.godot"), "config_version=5\n").unwrap();
+    fs::write(root.join("theme.tres"), r#"[gd_resource type="Theme" load_steps=3 format=3]
+
+[ext_resource type="FontFile" path="res://fonts/missing.ttf" id="1_f"]
+
+[sub_resource type="StyleBoxFlat" id="StyleBoxFlat_a"]
+bg_color = Color(0, 0, 0, 1)
+
+[resource]
+default_font = ExtResource("1_f")
+Button/styles/normal = SubResource("StyleBoxFlat_a")
+Button/styles/hover = SubResource("StyleBoxFlat_gone")
+"#).unwrap();
+    fs::write(root.join("broken.tres"), "[resource\nvalue = 1\n").unwrap();
+
+    let issues = validate_scene(root, Path::new("theme.tres"));
+    let got: Vec<_> = issues.iter().map(|i| (i.line, i.message.as_str())).collect();
+    assert_eq!(got, [
+        (3, "Missing ext_resource path: res://fonts/missing.ttf"),
+        (9, "Property 'default_font' ExtResource(1_f) missing file res://fonts/missing.ttf"),
+        (11, "Unknown SubResource id: StyleBoxFlat_gone"),
+    ]);
+
+    let broken: Vec<_> = validate_scene(root, Path::new("broken.tres")).into_iter().map(|i| (i.line, i.message)).collect();
+    assert_eq!(broken, [(1, "Malformed section header: [resource".to_string()), (1, "Missing gd_resource header".to_string())]);
+
+    // Reported alongside scenes, with the file each finding came from
+    let report = scene_issues_as_report(root);
+    assert!(report.iter().any(|i| i.message == "Missing gd_resource header" && i.file.as_deref() == Some(Path::new("broken.tres"))));
+    assert!(report.iter().any(|i| i.message.starts_with("Unknown SubResource id") && i.file.as_deref() == Some(Path::new("theme.tres"))));
+}
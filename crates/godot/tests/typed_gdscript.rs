@@ -0,0 +1,72 @@
+use std::fs;
+use godot_analyzer::script_lint::lint_gd_scripts;
+
+const SCRIPT: &str = r#"extends Node
+var speed = 10
+var hp: int = 3
+var label := $Label
+var loot := roll()
+var total := sum(1, 2)
+
+func roll():
+    return randi()
+
+func sum(a: int, b: int) -> int:
+    var twice := self.roll()
+    var plain = a
+    return a + b + plain + twice
+
+func _ready() -> void:
+    pass
+
+func hit(amount, source := "") -> void:
+    pass
+"#;
+
+fn typed(root: &std::path::Path) -> Vec<(String, String, Option<usize>, Option<usize>)> {
+    let mut got: Vec<_> = lint_gd_scripts(root).into_iter()
+        .filter(|f| godot_analyzer::script_lint::TYPED_RULES.contains(&f.code.as_str()))
+        .map(|f| (f.code, f.message, f.line, f.column))
+        .collect();
+    got.sort_by_key(|g| (g.2, g.0.clone()));
+    got
+}
+
+#[test]
+fn typed_rules_are_off_by_default() {
+    let tmp = tempfile::tempdir().unwrap();
+    fs::write(tmp.path().join("unit.gd"), SCRIPT).unwrap();
+    assert_eq!(typed(tmp.path()), vec![]);
+}
+
+#[test]
+fn typed_gdscript_group_enables_every_rule() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("unit.gd"), SCRIPT).unwrap();
+    fs::write(root.join("godot-analyzer.toml"), "[rules]\nenable = [\"typed-gdscript\"]\n").unwrap();
+    let expected = vec![
+        ("untyped-variable", "Untyped variable: 'speed' — hint: declare its type (`var speed: int`) or infer it with `:=`", Some(2), Some(5)),
+        ("inferred-untyped-call", "Inferred from untyped call: 'loot' := roll() is Variant — hint: give roll() a return type or declare 'loot' with a type", Some(5), Some(5)),
+        ("missing-return-type", "Missing return type: roll() — hint: declare it, e.g. `func roll() -> void`", Some(8), Some(6)),
+        ("inferred-untyped-call", "Inferred from untyped call: 'twice' in sum() := roll() is Variant — hint: give roll() a return type or declare 'twice' with a type", Some(12), Some(9)),
+        ("untyped-variable", "Untyped variable: 'plain' in sum() — hint: declare its type (`var plain: int`) or infer it with `:=`", Some(13), Some(9)),
+        ("untyped-parameter", "Untyped parameter: 'amount' in hit() — hint: declare its type (`amount: int`)", Some(19), Some(10)),
+    ];
+    let expected: Vec<_> = expected.into_iter().map(|(c, m, l, col)| (c.to_string(), m.to_string(), l, col)).collect();
+    assert_eq!(typed(root), expected);
+}
+
+#[test]
+fn single_typed_rule_can_be_enabled() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("unit.gd"), SCRIPT).unwrap();
+    fs::write(root.join("godot-analyzer.toml"), "[rules]\nenable = [\"missing-return-type\"]\n").unwrap();
+    let got = typed(root);
+    assert_eq!(got.len(), 1, "{:?}", got);
+    assert_eq!(got[0].0, "missing-return-type");
+    let issues = godot_analyzer::lint_gd(root);
+    let sarif = godot_analyzer::to_sarif(&godot_analyzer::GodotProjectReport { issues: issues.into_iter().filter(|i| i.code.as_deref() == Some("missing-return-type")).collect(), ..Default::default() });
+    assert_eq!(sarif["runs"][0]["results"][0]["ruleId"], "script-lint");
+}
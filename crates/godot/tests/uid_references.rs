@@ -0,0 +1,56 @@
+use std::fs;
+use godot_analyzer::uid::{id_to_text, UidMap};
+use godot_analyzer::Severity;
+
+fn uid_cache(entries: &[(i64, &str)]) -> Vec<u8> {
+    let mut out = (entries.len() as u32).to_le_bytes().to_vec();
+    for (id, path) in entries {
+        out.extend(id.to_le_bytes());
+        out.extend((path.len() as u32).to_le_bytes());
+        out.extend(path.as_bytes());
+    }
+    out
+}
+
+#[test]
+fn resolves_uids_and_reports_dangling_ones() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("project.godot"), "[application]\nconfig_version=5\nrun/main_scene=\"uid://c\"\n").unwrap();
+    fs::create_dir_all(root.join(".godot")).unwrap();
+    fs::write(root.join(".godot/uid_cache.bin"), uid_cache(&[(2, "res://main.tscn")])).unwrap();
+    fs::write(root.join("player.gd"), "extends Node\n").unwrap();
+    fs::write(root.join("player.gd.uid"), "uid://b\n").unwrap();
+    fs::write(root.join("old.gd.uid"), "uid://e\n").unwrap();
+    fs::write(root.join("icon.png"), "png").unwrap();
+    fs::write(root.join("icon.png.import"), "[remap]\n\nimporter=\"texture\"\nuid=\"uid://d\"\n").unwrap();
+    let scene = r#"[gd_scene format=3 uid="uid://m"]
+
+[ext_resource type="Script" path="uid://b" id="1"]
+[ext_resource type="Texture2D" path="uid://gone" id="2"]
+[ext_resource type="Texture2D" uid="uid://d" path="res://icon.png" id="3"]
+[ext_resource type="Texture2D" uid="uid://zz" path="res://icon.png" id="4"]
+[ext_resource type="Script" path="uid://e" id="5"]
+
+[node name="Main" type="Node"]
+script = ExtResource("1")
+texture = ExtResource("2")
+"#;
+    fs::write(root.join("main.tscn"), scene).unwrap();
+
+    assert_eq!(id_to_text(36), "uid://ba");
+    let uids = UidMap::load(root);
+    assert_eq!(uids.resolve("uid://b"), Some("res://player.gd"));
+    assert_eq!(uids.resolve("uid://c"), Some("res://main.tscn"));
+    assert_eq!(uids.resolve("uid://d"), Some("res://icon.png"));
+    assert_eq!(uids.resolve("uid://m"), Some("res://main.tscn"));
+
+    let issues = godot_analyzer::scene_validate::validate_scene(root, std::path::Path::new("main.tscn"));
+    let got: Vec<_> = issues.iter().map(|i| (i.line, i.message.as_str())).collect();
+    assert_eq!(got, [(4, "Dangling uid: uid://gone"), (7, "Missing ext_resource path: res://old.gd (uid://e)")]);
+
+    let report = godot_analyzer::analyze_project(root).unwrap();
+    assert!(report.issues.iter().any(|i| i.severity == Severity::Error && i.message == "Dangling uid: uid://gone"));
+    assert!(report.issues.iter().any(|i| i.severity == Severity::Warn && i.message == "Dangling uid: uid://zz (falls back to path res://icon.png)"));
+    assert!(!report.issues.iter().any(|i| i.message.starts_with("Missing main scene")), "issues: {:?}", report.issues);
+}
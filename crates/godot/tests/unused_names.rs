@@ -0,0 +1,67 @@
+use std::fs;
+use godot_analyzer::script_lint::lint_gd_scripts;
+
+#[test]
+fn flags_locals_and_parameters_never_read() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("player.gd"), r#"extends Node
+var speed = 1
+
+func _process(delta):
+    pass
+
+func _physics_process(_delta):
+    pass
+
+func move(dir, amount):
+    var unused = 3
+    var written = 0
+    written = 5
+    var counter = 0
+    counter += 1
+    var total = amount * 2
+    if total > 1:
+        var inner = 1
+    self.speed = dir.x
+    return counter
+
+func scoped():
+    if true:
+        var a = 1
+    else:
+        var a = 2
+        print(a)
+
+func _ignored():
+    var _tmp = 1
+"#).unwrap();
+
+    let mut got: Vec<(String, String, Option<usize>, Option<usize>)> = lint_gd_scripts(root).into_iter()
+        .filter(|f| f.code.starts_with("unused-"))
+        .map(|f| (f.code, f.message, f.line, f.column))
+        .collect();
+    got.sort_by_key(|g| (g.2, g.1.clone()));
+    let expected = vec![
+        ("unused-parameter", "Unused parameter: 'delta' in _process() — hint: prefix it with '_' if the signature needs it", Some(4), Some(15)),
+        ("unused-variable", "Unused variable: 'unused' in move() — hint: remove it or prefix it with '_'", Some(11), Some(9)),
+        ("unused-variable", "Unused variable: 'written' in move() — hint: remove it or prefix it with '_'", Some(12), Some(9)),
+        ("unused-variable", "Unused variable: 'inner' in move() — hint: remove it or prefix it with '_'", Some(18), Some(13)),
+        ("unused-variable", "Unused variable: 'a' in scoped() — hint: remove it or prefix it with '_'", Some(24), Some(13)),
+    ];
+    let expected: Vec<_> = expected.into_iter().map(|(c, m, l, col)| (c.to_string(), m.to_string(), l, col)).collect();
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn unused_name_findings_report_under_script_lint() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    fs::write(root.join("enemy.gd"), "extends Node\nfunc hit(amount):\n    var left = 1\n").unwrap();
+    let issues = godot_analyzer::lint_gd(root);
+    assert_eq!(issues.len(), 2, "{:?}", issues);
+    let sarif = godot_analyzer::to_sarif(&godot_analyzer::GodotProjectReport { issues, ..Default::default() });
+    for r in sarif["runs"][0]["results"].as_array().unwrap() {
+        assert_eq!(r["ruleId"], "script-lint", "{}", r["message"]["text"]);
+    }
+}
@@ -37,7 +37,7 @@ fn main() -> Result<()> {
             for (score, path) in hits { println!("{score:.3}\t{path}"); }
         }
         "watch" => {
-            let dir = args.get(0).cloned().unwrap_or_else(|| String::from("."));
+            let dir = args.first().cloned().unwrap_or_else(|| String::from("."));
             idx.scan_and_index(Path::new(&dir))?;
             println!("Initial scan complete. Watching for changes...");
             idx.watch(Path::new(&dir))?;
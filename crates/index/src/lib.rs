@@ -18,7 +18,7 @@ use std::sync::mpsc::channel;
 use xxhash_rust::xxh3::xxh3_64;
 use std::time::Duration;
 use std::collections::HashSet;
-use std::sync::{Arc};
+use std::sync::{Arc, RwLock};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::RecvTimeoutError;
 
@@ -33,6 +33,40 @@ pub struct SearchIndex {
     writer: IndexWriter,
     fields: Fields,
     root: PathBuf,
+    data_dir: PathBuf,
+    reader: SearchReader,
+    /// Configured path fragments skipped on top of the built-in rules
+    extra_skips: Vec<String>,
+}
+
+/// Read side of a `SearchIndex`: cheap to clone and queryable from any thread while the
+/// owner keeps writing. Each query opens a fresh searcher, so commits are visible at once.
+#[derive(Clone)]
+pub struct SearchReader {
+    /// Swapped by `SearchIndex::reset` so existing readers follow the recreated index
+    index: Arc<RwLock<Index>>,
+    fields: Fields,
+    root: PathBuf,
+}
+
+/// Paths touched within one watcher debounce window.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangeBatch {
+    pub to_delete: Vec<PathBuf>,
+    /// Never contains a path that is also in `to_delete`
+    pub to_index: Vec<PathBuf>,
+}
+
+/// Advanced query hit: (score, path, kind, optional snippet).
+pub type FilteredHit = (f32, String, String, Option<String>);
+
+/// A path touched by a watcher commit, normalized as stored in the index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedPath {
+    pub path: String,
+    pub kind: &'static str,
+    /// File was removed (and dropped from the index) rather than (re)indexed
+    pub removed: bool,
 }
 
 #[derive(Clone, Copy)]
@@ -48,7 +82,8 @@ pub fn build_schema() -> Schema {
     builder.build()
 }
 
-fn detect_kind(path: &Path) -> &'static str {
+/// Index `kind` for a file, from its extension.
+pub fn detect_kind(path: &Path) -> &'static str {
     match path.extension().and_then(|e| e.to_str()) {
         Some("rs") => "rust",
         Some("gd") => "gdscript",
@@ -85,7 +120,46 @@ impl SearchIndex {
     let _ = index.set_default_multithread_executor();
         // Canonicalize root for consistent normalization
         let root = paths.root.canonicalize().unwrap_or(paths.root.clone());
-    Ok(Self { index, writer, fields, root })
+        let reader = SearchReader { index: Arc::new(RwLock::new(index.clone())), fields, root: root.clone() };
+    Ok(Self { index, writer, fields, root, data_dir: paths.data_dir.clone(), reader, extra_skips: Vec::new() })
+    }
+
+    /// Replace the configured skip fragments; takes effect for subsequent scans and changes.
+    pub fn set_extra_skips(&mut self, fragments: Vec<String>) {
+        self.extra_skips = fragments;
+    }
+
+    /// Whether `path` is left out of this index: the built-in rules or a configured fragment.
+    pub fn skips(&self, path: &Path) -> bool {
+        is_skipped_with(path, &self.extra_skips)
+    }
+
+    /// Handle for querying this index without access to the writer.
+    pub fn reader(&self) -> SearchReader {
+        self.reader.clone()
+    }
+
+    /// Drop all indexed data: release the writer, wipe the data directory and recreate an
+    /// empty index with the current schema. Recovers from a corrupted data directory.
+    pub fn reset(&mut self) -> Result<()> {
+        let paths = IndexPaths { root: self.root.clone(), data_dir: self.data_dir.clone() };
+        // The writer holds a lock file in data_dir; swap in an in-RAM index so it is released first
+        let ram = Index::create_in_ram(build_schema());
+        let ram_writer = ram.writer_with_num_threads(1, 15_000_000)?;
+        drop(std::mem::replace(&mut self.writer, ram_writer));
+        self.reader.swap(ram.clone());
+        self.index = ram;
+        if paths.data_dir.exists() {
+            fs::remove_dir_all(&paths.data_dir)?;
+        }
+        let reader = self.reader.clone();
+        let extra_skips = std::mem::take(&mut self.extra_skips);
+        *self = Self::open(&paths)?;
+        reader.swap(self.index.clone());
+        self.reader = reader;
+        self.extra_skips = extra_skips;
+        info!("Index reset at {}", paths.data_dir.display());
+        Ok(())
     }
 
     fn normalize_path(&self, path: &Path) -> String {
@@ -100,31 +174,34 @@ impl SearchIndex {
 
     /// Convert a normalized index path (e.g., "./rel/path") back to an absolute PathBuf using the index root.
     pub fn absolutize_path(&self, normalized: &str) -> PathBuf {
-        let p = std::path::Path::new(normalized);
-    if let Some(stripped) = normalized.strip_prefix("./") {
-            return self.root.join(stripped);
-        }
-        if p.is_relative() {
-            return self.root.join(p);
-        }
-        p.to_path_buf()
+        self.reader.absolutize_path(normalized)
     }
 
     pub fn scan_and_index(&mut self, root: &Path) -> Result<usize> {
+        let files = collect_files(root);
+        let (count, _) = self.index_paths(&files)?;
+        Ok(count)
+    }
+
+    /// Index a batch of files in one commit. Files matching skip rules have any previously
+    /// indexed doc removed instead. Returns (indexed, per-file errors).
+    pub fn index_paths(&mut self, paths: &[PathBuf]) -> Result<(usize, Vec<String>)> {
         let mut count = 0usize;
-        for entry in WalkDir::new(root).into_iter().filter_map(|e| e.ok()) {
-            if !entry.file_type().is_file() { continue; }
-            let path = entry.path();
+        let mut errors = Vec::new();
+        for path in paths {
             // If file matches skip rules, ensure any previously indexed doc is removed
-            if should_skip(path) {
+            if self.skips(path) {
                 let path_str = self.normalize_path(path);
                 let _ = self.writer.delete_term(Term::from_field_text(self.fields.path, &path_str));
                 continue;
             }
-            count += self.index_file(path).unwrap_or(0);
+            match self.index_file(path) {
+                Ok(n) => count += n,
+                Err(e) => errors.push(format!("{}: {}", path.display(), e)),
+            }
         }
-    let _ = self.writer.commit()?;
-        Ok(count)
+        self.writer.commit()?;
+        Ok((count, errors))
     }
 
     pub fn index_file(&mut self, path: &Path) -> Result<usize> {
@@ -146,33 +223,7 @@ impl SearchIndex {
     }
 
     pub fn query(&self, q: &str, limit: usize) -> Result<Vec<(f32, String)>> {
-        let q = q.trim();
-        if q.is_empty() { return Ok(vec![]); }
-        let reader = self.index.reader()?;
-        let searcher = reader.searcher();
-
-        // Build AND-of-terms query over the content field
-        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
-        for term in q.split_whitespace().filter(|s| !s.is_empty()) {
-            let tq = TermQuery::new(Term::from_field_text(self.fields.content, term), tantivy::schema::IndexRecordOption::Basic);
-            clauses.push((Occur::Must, Box::new(tq)));
-        }
-        if clauses.is_empty() { return Ok(vec![]); }
-        let query: Box<dyn Query> = if clauses.len() == 1 {
-            clauses.pop().unwrap().1
-        } else {
-            Box::new(BooleanQuery::new(clauses))
-        };
-
-        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
-        let mut hits = Vec::new();
-        for (score, addr) in top_docs {
-            let doc_map = searcher.doc::<std::collections::HashMap<Field, tantivy::schema::document::OwnedValue>>(addr)?;
-            if let Some(tantivy::schema::document::OwnedValue::Str(path_str)) = doc_map.get(&self.fields.path) {
-                hits.push((score, path_str.clone()));
-            }
-        }
-        Ok(hits)
+        self.reader.query(q, limit)
     }
 
     /// Apply a batch of deletions and (re)indexing in a single commit.
@@ -180,7 +231,7 @@ impl SearchIndex {
     pub fn apply_batch(&mut self, to_delete: &[PathBuf], to_index: &[PathBuf]) -> Result<()> {
     // Apply deletions first
         for p in to_delete.iter() {
-            if should_skip(p) { continue; }
+            if self.skips(p) { continue; }
             let path_str = self.normalize_path(p);
             let _ = self.writer.delete_term(Term::from_field_text(self.fields.path, &path_str));
         }
@@ -191,7 +242,7 @@ impl SearchIndex {
         let del_set: HashSet<&PathBuf> = to_delete.iter().collect();
         for p in to_index.iter() {
             if del_set.contains(p) { continue; }
-            if should_skip(p) { continue; }
+            if self.skips(p) { continue; }
             let _ = self.index_file(p);
         }
     self.writer.commit()?;
@@ -205,70 +256,34 @@ impl SearchIndex {
         kind: Option<&str>,
         limit: usize,
         with_snippet: bool,
-    ) -> Result<Vec<(f32, String, String, Option<String>)>> {
-    // Use a fresh reader to ensure we always see the latest committed data
-    let reader = self.index.reader()?;
-    let searcher = reader.searcher();
-
-        // Build content query
-        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
-        if !q.trim().is_empty() {
-            let mut inner: Vec<(Occur, Box<dyn Query>)> = Vec::new();
-            for term in q.split_whitespace().filter(|s| !s.is_empty()) {
-                let tq = TermQuery::new(Term::from_field_text(self.fields.content, term), tantivy::schema::IndexRecordOption::Basic);
-                inner.push((Occur::Must, Box::new(tq)));
-            }
-            if inner.len() == 1 {
-                clauses.push(inner.pop().unwrap());
-            } else if !inner.is_empty() {
-                clauses.push((Occur::Must, Box::new(BooleanQuery::new(inner))));
-            }
-        }
-        // Optional kind filter as exact term query
-        if let Some(k) = kind {
-            let term = Term::from_field_text(self.fields.kind, k);
-            clauses.push((Occur::Must, Box::new(TermQuery::new(term, tantivy::schema::IndexRecordOption::Basic))));
-        }
-
-        let query: Box<dyn Query> = if clauses.is_empty() {
-            // Match nothing if no query provided
-            Box::new(BooleanQuery::new(vec![]))
-        } else if clauses.len() == 1 {
-            clauses.pop().unwrap().1
-        } else {
-            Box::new(BooleanQuery::new(clauses))
-        };
-
-        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
-        let mut hits = Vec::new();
-        for (score, addr) in top_docs {
-            let doc_map = searcher.doc::<std::collections::HashMap<Field, tantivy::schema::document::OwnedValue>>(addr)?;
-            let path = match doc_map.get(&self.fields.path) {
-                Some(tantivy::schema::document::OwnedValue::Str(s)) => s.clone(),
-                _ => continue,
-            };
-            let kind_val = match doc_map.get(&self.fields.kind) {
-                Some(tantivy::schema::document::OwnedValue::Str(s)) => s.clone(),
-                _ => "".to_string(),
-            };
-            let snippet = if with_snippet {
-                match doc_map.get(&self.fields.content) {
-                    Some(tantivy::schema::document::OwnedValue::Str(c)) => Some(make_snippet(c, q)),
-                    _ => None,
-                }
-            } else { None };
-            hits.push((score, path, kind_val, snippet));
-        }
-        Ok(hits)
+    ) -> Result<Vec<FilteredHit>> {
+        self.reader.query_filtered(q, kind, limit, with_snippet)
     }
 
     /// Lightweight health info: (doc_count, segment_count)
     pub fn health(&self) -> Result<(u64, usize)> {
-        let reader = self.index.reader()?;
-        let searcher = reader.searcher();
-        let doc_count = searcher.num_docs() as u64;
-        let segments = searcher.segment_readers().len();
-        Ok((doc_count, segments))
+        self.reader.health()
+    }
+
+    /// Apply one watcher batch (deletions, then re-indexing) in a single commit and return
+    /// the paths it touched, sorted.
+    pub fn apply_changes(&mut self, batch: &ChangeBatch) -> Result<Vec<ChangedPath>> {
+        for p in batch.to_delete.iter() {
+            let path_str = self.normalize_path(p);
+            let _ = self.writer.delete_term(Term::from_field_text(self.fields.path, &path_str));
+        }
+        let mut changes: Vec<ChangedPath> = batch.to_delete.iter()
+            .map(|p| ChangedPath { path: self.normalize_path(p), kind: detect_kind(p), removed: true })
+            .collect();
+        // The watcher only knows the built-in rules; configured fragments are applied here
+        for p in batch.to_index.iter() {
+            if !self.skips(p) && self.index_file(p).is_ok() {
+                changes.push(ChangedPath { path: self.normalize_path(p), kind: detect_kind(p), removed: false });
+            }
+        }
+        self.writer.commit()?;
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(changes)
     }
 
     /// Watch the filesystem under root and incrementally index changes.
@@ -294,12 +309,12 @@ impl SearchIndex {
                 match event_kind {
                     EventKind::Create(_) | EventKind::Modify(_) => {
                         for p in paths {
-                            if p.is_file() && !should_skip(p) { to_index.insert(p.clone()); }
+                            if p.is_file() && !self.skips(p) { to_index.insert(p.clone()); }
                         }
                     }
                     EventKind::Remove(_) => {
                         for p in paths {
-                            if !should_skip(p) { to_delete.insert(p.clone()); }
+                            if !self.skips(p) { to_delete.insert(p.clone()); }
                         }
                     }
                     _ => {}
@@ -334,80 +349,236 @@ impl SearchIndex {
     /// Same as `watch` but allows cooperative shutdown via an AtomicBool.
     /// When `shutdown` is set to true, the watcher will stop shortly after.
     pub fn watch_with_shutdown(&mut self, root: &Path, shutdown: Arc<AtomicBool>) -> Result<()> {
-        let (tx, rx) = channel();
-        let mut watcher: RecommendedWatcher = RecommendedWatcher::new(tx, notify::Config::default())?;
-        watcher.watch(root, RecursiveMode::Recursive)?;
-        info!("Starting index watcher on {} (with shutdown)", root.display());
+        self.watch_with_notify(root, shutdown, |_| {})
+    }
 
-        'outer: loop {
-            if shutdown.load(Ordering::Relaxed) { break; }
-            // Block for the first event with a timeout so we can observe shutdown
-            let evt = match rx.recv_timeout(Duration::from_millis(500)) {
-                Ok(Ok(e)) => e,
-                Ok(Err(e)) => { warn!(error=%e, "watch error"); continue; },
-                Err(RecvTimeoutError::Timeout) => { continue; },
-                Err(e) => { warn!(error=%e, "recv error"); continue; },
-            };
+    /// `watch_with_shutdown` that reports the paths of each successful commit to `on_commit`.
+    pub fn watch_with_notify(
+        &mut self,
+        root: &Path,
+        shutdown: Arc<AtomicBool>,
+        mut on_commit: impl FnMut(Vec<ChangedPath>),
+    ) -> Result<()> {
+        watch_changes(root, shutdown, |batch| match self.apply_changes(&batch) {
+            Ok(changes) if !changes.is_empty() => on_commit(changes),
+            Ok(_) => {}
+            Err(e) => warn!(error=%e, "watch commit failed"),
+        })
+    }
 
-            let mut to_index: HashSet<PathBuf> = HashSet::new();
-            let mut to_delete: HashSet<PathBuf> = HashSet::new();
+    #[allow(dead_code)]
+    fn rescan(&mut self, root: &Path) -> Result<()> {
+        // Create a new writer (simple approach) and rescan
+        self.writer.rollback()?;
+        let _ = self.scan_and_index(root)?;
+        Ok(())
+    }
+}
 
-            let mut push_event = |event_kind: &EventKind, paths: &Vec<PathBuf>| {
-                match event_kind {
-                    EventKind::Create(_) | EventKind::Modify(_) => {
-                        for p in paths {
-                            if p.is_file() && !should_skip(p) { to_index.insert(p.clone()); }
-                        }
-                    }
-                    EventKind::Remove(_) => {
-                        for p in paths {
-                            if !should_skip(p) { to_delete.insert(p.clone()); }
-                        }
-                    }
-                    _ => {}
-                }
-            };
+impl SearchReader {
+    fn swap(&self, index: Index) {
+        *self.index.write().unwrap() = index;
+    }
 
-            push_event(&evt.kind, &evt.paths);
+    /// Convert a normalized index path (e.g., "./rel/path") back to an absolute PathBuf using the index root.
+    pub fn absolutize_path(&self, normalized: &str) -> PathBuf {
+        let p = std::path::Path::new(normalized);
+        if let Some(stripped) = normalized.strip_prefix("./") {
+            return self.root.join(stripped);
+        }
+        if p.is_relative() {
+            return self.root.join(p);
+        }
+        p.to_path_buf()
+    }
 
-            // Debounce window: accumulate events for a short period
-            while !shutdown.load(Ordering::Relaxed) {
-                match rx.recv_timeout(Duration::from_millis(200)) {
-                    Ok(Ok(e)) => push_event(&e.kind, &e.paths),
-                    Ok(Err(e)) => { warn!(error=%e, "watch error"); break; },
-                    Err(RecvTimeoutError::Timeout) => { break; },
-                    Err(e) => { warn!(error=%e, "recv error"); break; },
-                }
+    pub fn query(&self, q: &str, limit: usize) -> Result<Vec<(f32, String)>> {
+        let q = q.trim();
+        if q.is_empty() { return Ok(vec![]); }
+        let reader = self.index.read().unwrap().reader()?;
+        let searcher = reader.searcher();
+
+        // Build AND-of-terms query over the content field
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        for term in q.split_whitespace().filter(|s| !s.is_empty()) {
+            let tq = TermQuery::new(Term::from_field_text(self.fields.content, term), tantivy::schema::IndexRecordOption::Basic);
+            clauses.push((Occur::Must, Box::new(tq)));
+        }
+        if clauses.is_empty() { return Ok(vec![]); }
+        let query: Box<dyn Query> = if clauses.len() == 1 {
+            clauses.pop().unwrap().1
+        } else {
+            Box::new(BooleanQuery::new(clauses))
+        };
+
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
+        let mut hits = Vec::new();
+        for (score, addr) in top_docs {
+            let doc_map = searcher.doc::<std::collections::HashMap<Field, tantivy::schema::document::OwnedValue>>(addr)?;
+            if let Some(tantivy::schema::document::OwnedValue::Str(path_str)) = doc_map.get(&self.fields.path) {
+                hits.push((score, path_str.clone()));
             }
+        }
+        Ok(hits)
+    }
 
-            if shutdown.load(Ordering::Relaxed) { break 'outer; }
+    /// Advanced query with optional kind filtering and optional snippet extraction.
+    pub fn query_filtered(
+        &self,
+        q: &str,
+        kind: Option<&str>,
+        limit: usize,
+        with_snippet: bool,
+    ) -> Result<Vec<FilteredHit>> {
+    // Use a fresh reader to ensure we always see the latest committed data
+    let reader = self.index.read().unwrap().reader()?;
+    let searcher = reader.searcher();
 
-            // Apply deletions first
-            for p in to_delete.iter() {
-                let path_str = self.normalize_path(p);
-                let _ = self.writer.delete_term(Term::from_field_text(self.fields.path, &path_str));
+        // Build content query
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+        if !q.trim().is_empty() {
+            let mut inner: Vec<(Occur, Box<dyn Query>)> = Vec::new();
+            for term in q.split_whitespace().filter(|s| !s.is_empty()) {
+                let tq = TermQuery::new(Term::from_field_text(self.fields.content, term), tantivy::schema::IndexRecordOption::Basic);
+                inner.push((Occur::Must, Box::new(tq)));
             }
-            // Then apply (re)indexing; skip any files that were also deleted in this batch
-            for p in to_index.into_iter() {
-                if to_delete.contains(&p) { continue; }
-                let _ = self.index_file(&p);
+            if inner.len() == 1 {
+                clauses.push(inner.pop().unwrap());
+            } else if !inner.is_empty() {
+                clauses.push((Occur::Must, Box::new(BooleanQuery::new(inner))));
             }
+        }
+        // Optional kind filter as exact term query
+        if let Some(k) = kind {
+            let term = Term::from_field_text(self.fields.kind, k);
+            clauses.push((Occur::Must, Box::new(TermQuery::new(term, tantivy::schema::IndexRecordOption::Basic))));
+        }
 
-            let _ = self.writer.commit();
+        let query: Box<dyn Query> = if clauses.is_empty() {
+            // Match nothing if no query provided
+            Box::new(BooleanQuery::new(vec![]))
+        } else if clauses.len() == 1 {
+            clauses.pop().unwrap().1
+        } else {
+            Box::new(BooleanQuery::new(clauses))
+        };
+
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
+        let mut hits = Vec::new();
+        for (score, addr) in top_docs {
+            let doc_map = searcher.doc::<std::collections::HashMap<Field, tantivy::schema::document::OwnedValue>>(addr)?;
+            let path = match doc_map.get(&self.fields.path) {
+                Some(tantivy::schema::document::OwnedValue::Str(s)) => s.clone(),
+                _ => continue,
+            };
+            let kind_val = match doc_map.get(&self.fields.kind) {
+                Some(tantivy::schema::document::OwnedValue::Str(s)) => s.clone(),
+                _ => "".to_string(),
+            };
+            let snippet = if with_snippet {
+                match doc_map.get(&self.fields.content) {
+                    Some(tantivy::schema::document::OwnedValue::Str(c)) => Some(make_snippet(c, q)),
+                    _ => None,
+                }
+            } else { None };
+            hits.push((score, path, kind_val, snippet));
         }
-        info!("Index watcher shutdown complete");
-        Ok(())
+        Ok(hits)
     }
 
-    #[allow(dead_code)]
-    fn rescan(&mut self, root: &Path) -> Result<()> {
-        // Create a new writer (simple approach) and rescan
-        self.writer.rollback()?;
-        let _ = self.scan_and_index(root)?;
-        Ok(())
+    /// Full stored content for a normalized path, or `None` if the path isn't indexed.
+    pub fn stored_content(&self, path: &str) -> Result<Option<String>> {
+        let reader = self.index.read().unwrap().reader()?;
+        let searcher = reader.searcher();
+        let query = TermQuery::new(Term::from_field_text(self.fields.path, path), tantivy::schema::IndexRecordOption::Basic);
+        let Some((_, addr)) = searcher.search(&query, &TopDocs::with_limit(1))?.into_iter().next() else {
+            return Ok(None);
+        };
+        let doc_map = searcher.doc::<std::collections::HashMap<Field, tantivy::schema::document::OwnedValue>>(addr)?;
+        Ok(match doc_map.get(&self.fields.content) {
+            Some(tantivy::schema::document::OwnedValue::Str(c)) => Some(c.clone()),
+            _ => None,
+        })
+    }
+
+    /// Opstamp of the last commit: grows with every commit, so equal generations mean the
+    /// same indexed content. Starts over after `SearchIndex::reset`.
+    pub fn generation(&self) -> Result<u64> {
+        Ok(self.index.read().unwrap().load_metas()?.opstamp)
+    }
+
+    /// Lightweight health info: (doc_count, segment_count)
+    pub fn health(&self) -> Result<(u64, usize)> {
+        let reader = self.index.read().unwrap().reader()?;
+        let searcher = reader.searcher();
+        let doc_count = searcher.num_docs();
+        let segments = searcher.segment_readers().len();
+        Ok((doc_count, segments))
     }
 }
 
+/// Watch `root` until `shutdown` is set, handing each debounced batch of changes to `on_batch`.
+/// Needs no index access; pair with `SearchIndex::apply_changes` to commit the batches.
+pub fn watch_changes(root: &Path, shutdown: Arc<AtomicBool>, mut on_batch: impl FnMut(ChangeBatch)) -> Result<()> {
+    let (tx, rx) = channel();
+    let mut watcher: RecommendedWatcher = RecommendedWatcher::new(tx, notify::Config::default())?;
+    watcher.watch(root, RecursiveMode::Recursive)?;
+    info!("Starting index watcher on {} (with shutdown)", root.display());
+
+    'outer: loop {
+        if shutdown.load(Ordering::Relaxed) { break; }
+        // Block for the first event with a timeout so we can observe shutdown
+        let evt = match rx.recv_timeout(Duration::from_millis(500)) {
+            Ok(Ok(e)) => e,
+            Ok(Err(e)) => { warn!(error=%e, "watch error"); continue; },
+            Err(RecvTimeoutError::Timeout) => { continue; },
+            Err(e) => { warn!(error=%e, "recv error"); continue; },
+        };
+
+        let mut to_index: HashSet<PathBuf> = HashSet::new();
+        let mut to_delete: HashSet<PathBuf> = HashSet::new();
+
+        let mut push_event = |event_kind: &EventKind, paths: &Vec<PathBuf>| {
+            match event_kind {
+                EventKind::Create(_) | EventKind::Modify(_) => {
+                    for p in paths {
+                        if p.is_file() && !should_skip(p) { to_index.insert(p.clone()); }
+                    }
+                }
+                EventKind::Remove(_) => {
+                    for p in paths {
+                        if !should_skip(p) { to_delete.insert(p.clone()); }
+                    }
+                }
+                _ => {}
+            }
+        };
+
+        push_event(&evt.kind, &evt.paths);
+
+        // Debounce window: accumulate events for a short period
+        while !shutdown.load(Ordering::Relaxed) {
+            match rx.recv_timeout(Duration::from_millis(200)) {
+                Ok(Ok(e)) => push_event(&e.kind, &e.paths),
+                Ok(Err(e)) => { warn!(error=%e, "watch error"); break; },
+                Err(RecvTimeoutError::Timeout) => { break; },
+                Err(e) => { warn!(error=%e, "recv error"); break; },
+            }
+        }
+
+        if shutdown.load(Ordering::Relaxed) { break 'outer; }
+
+        // Skip any files that were also deleted in this batch
+        let batch = ChangeBatch {
+            to_index: to_index.into_iter().filter(|p| !to_delete.contains(p)).collect(),
+            to_delete: to_delete.into_iter().collect(),
+        };
+        on_batch(batch);
+    }
+    info!("Index watcher shutdown complete");
+    Ok(())
+}
+
 fn make_snippet(content: &str, q: &str) -> String {
     // Very lightweight snippet: find first occurrence of any term in q, else start of file
     let terms: Vec<String> = q.split_whitespace().map(|s| s.to_lowercase()).collect();
@@ -421,11 +592,22 @@ fn make_snippet(content: &str, q: &str) -> String {
     let window_start = start.saturating_sub(60);
     let window_end = ((start + 200).min(content.len())).max(window_start);
     let mut snippet = content[window_start..window_end].to_string();
-    snippet = snippet.replace('\n', " ").replace('\r', " ");
+    snippet = snippet.replace(['\n', '\r'], " ");
     if window_end < content.len() { snippet.push_str("..."); }
     snippet
 }
 
+/// All regular files under `root` (skip rules are applied later by `index_paths`).
+/// Needs no index access, so callers can walk the tree without holding the index lock.
+pub fn collect_files(root: &Path) -> Vec<PathBuf> {
+    WalkDir::new(root)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .collect()
+}
+
 fn should_skip(path: &Path) -> bool {
     let p = path.to_string_lossy();
     p.contains("/.git/")
@@ -442,7 +624,14 @@ fn should_skip(path: &Path) -> bool {
     || p.contains("/rust-book/")
 }
 
-/// Public helper to check whether a path should be skipped by the index.
+/// Public helper to check whether a path should be skipped by the index (built-in rules).
 pub fn is_skipped(path: &Path) -> bool { should_skip(path) }
 
+/// `is_skipped`, or `path` contains one of the configured `extra` fragments.
+pub fn is_skipped_with(path: &Path, extra: &[String]) -> bool {
+    if should_skip(path) { return true; }
+    let p = path.to_string_lossy();
+    extra.iter().any(|frag| p.contains(frag.as_str()))
+}
+
 //EOF
\ No newline at end of file
@@ -0,0 +1,25 @@
+use index::{IndexPaths, SearchIndex};
+
+#[test]
+fn configured_fragments_are_skipped() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(root.join("exports")).unwrap();
+    std::fs::write(root.join("main.gd"), "func kiwi(): pass").unwrap();
+    std::fs::write(root.join("exports/build.gd"), "func kiwi(): pass").unwrap();
+
+    let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") }).unwrap();
+    idx.set_extra_skips(vec!["/exports/".into()]);
+    assert!(idx.skips(&root.join("exports/build.gd")));
+    idx.scan_and_index(&root).unwrap();
+    let paths: Vec<String> = idx.query("kiwi", 10).unwrap().into_iter().map(|(_, p)| p).collect();
+    assert_eq!(paths, vec!["./main.gd"]);
+
+    // Fragments belong to the index that was given them
+    let other = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("other") }).unwrap();
+    assert!(!other.skips(&root.join("exports/build.gd")));
+    assert!(!index::is_skipped(&root.join("exports/build.gd")));
+
+    idx.set_extra_skips(vec![]);
+    assert!(!idx.skips(&root.join("exports/build.gd")));
+}
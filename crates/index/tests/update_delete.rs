@@ -1,6 +1,6 @@
 use std::{fs, time::Instant};
 use tempfile::tempdir;
-use index::{IndexPaths, SearchIndex};
+use index::{ChangeBatch, IndexPaths, SearchIndex};
 
 #[test]
 fn add_update_delete_updates_index() {
@@ -22,7 +22,7 @@ fn add_update_delete_updates_index() {
     // Update file content
     fs::write(&file_path, "hello rust").unwrap();
     let t0 = Instant::now();
-    idx.apply_batch(&[], &[file_path.clone()]).unwrap();
+    idx.apply_batch(&[], std::slice::from_ref(&file_path)).unwrap();
     let dt = t0.elapsed();
     // Basic perf sanity check (avoid flakiness on slow CI): <250ms per file
     assert!(dt.as_millis() < 250, "apply_batch took {:?}", dt);
@@ -37,7 +37,52 @@ fn add_update_delete_updates_index() {
 
     // Delete file and propagate
     fs::remove_file(&file_path).unwrap();
-    idx.apply_batch(&[file_path.clone()], &[]).unwrap();
+    idx.apply_batch(std::slice::from_ref(&file_path), &[]).unwrap();
     let hits_after_delete = idx.query("rust", 5).unwrap();
     assert!(!hits_after_delete.iter().any(|(_, p)| p.ends_with("file.txt")));
 }
+
+#[test]
+fn reset_wipes_data_dir_and_reopens_empty() {
+    let dir = tempdir().unwrap();
+    let root = dir.path().to_path_buf();
+    let data_dir = root.join(".index_data");
+    let paths = IndexPaths { root: root.clone(), data_dir: data_dir.clone() };
+    fs::write(root.join("file.txt"), "hello world").unwrap();
+
+    let mut idx = SearchIndex::open(&paths).unwrap();
+    let _ = idx.scan_and_index(&root).unwrap();
+    assert_eq!(idx.health().unwrap().0, 1);
+    // Stray file in the data dir should not survive the wipe
+    fs::write(data_dir.join("junk.bin"), b"junk").unwrap();
+
+    idx.reset().unwrap();
+    assert_eq!(idx.health().unwrap().0, 0);
+    assert!(!data_dir.join("junk.bin").exists());
+    assert!(idx.query("world", 5).unwrap().is_empty());
+
+    // Index is usable again after reset
+    let _ = idx.scan_and_index(&root).unwrap();
+    assert!(idx.query("world", 5).unwrap().iter().any(|(_, p)| p.ends_with("file.txt")));
+}
+
+#[test]
+fn reader_sees_commits_and_follows_reset() {
+    let dir = tempdir().unwrap();
+    let root = dir.path().to_path_buf();
+    let paths = IndexPaths { root: root.clone(), data_dir: root.join(".index_data") };
+    let file_path = root.join("a.gd");
+    fs::write(&file_path, "func a(): pass # kiwi").unwrap();
+
+    let mut idx = SearchIndex::open(&paths).unwrap();
+    let reader = idx.reader();
+    let changes = idx.apply_changes(&ChangeBatch { to_delete: vec![], to_index: vec![file_path.clone()] }).unwrap();
+    assert_eq!(changes.len(), 1);
+    assert!(!changes[0].removed);
+    assert_eq!(reader.query("kiwi", 5).unwrap().len(), 1);
+
+    idx.reset().unwrap();
+    assert_eq!(reader.health().unwrap().0, 0);
+    let _ = idx.scan_and_index(&root).unwrap();
+    assert_eq!(reader.query("kiwi", 5).unwrap().len(), 1);
+}
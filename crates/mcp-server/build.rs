@@ -0,0 +1,7 @@
+// Generate the gRPC service code from proto/; protox compiles the .proto so no protoc install is needed.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let fds = protox::compile(["proto/godot_mcp.proto"], ["proto"])?;
+    tonic_build::configure().compile_fds(fds)?;
+    println!("cargo:rerun-if-changed=proto");
+    Ok(())
+}
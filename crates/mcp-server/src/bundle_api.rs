@@ -0,0 +1,499 @@
+// Context bundles: one-shot JSON bundles and streaming NDJSON bundles.
+use std::{sync::Arc, time::Instant};
+
+use axum::{routing::{get, post}, extract::{Query, State}, http::{header, StatusCode}, response::{IntoResponse, Response}, Json, Router};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use context::{
+    Bundle, BundleBuilder, BundleItem, BundleManifest, BundleOptions, ContentMode, Delta, ExcludeGlobs, KindQuotas, KnownFiles, ManifestIndex,
+    Synonyms, WeightedQuery,
+};
+
+use crate::{
+    api_error, graphql, bounded, check_query, ndjson_response, project_relative_path, resolve_project, sandboxed_path, ApiError, AppRouter, ProjectState,
+    ServerState,
+    MAX_LIMIT,
+    sessions::{ItemStatus, SessionFilter, SessionMode},
+};
+
+/// Shared by `/context/bundle` and `/context/bundle/stream`.
+#[derive(Deserialize)]
+pub(crate) struct BundleRequest {
+    /// Required unless `queries` is given
+    pub q: Option<String>,
+    /// Extra queries merged into the same bundle, as strings or `{ q, weight }`
+    #[serde(default)]
+    pub queries: Vec<WeightedQuery>,
+    pub limit: Option<usize>, pub cap_bytes: Option<usize>, pub kind: Option<String>, pub project: Option<String>,
+    pub session: Option<u64>,
+    #[serde(default)]
+    pub session_mode: SessionMode,
+    #[serde(default)]
+    pub content_mode: ContentMode,
+    /// MMR weight in 0..=1; omitted or 0 keeps relevance order
+    pub diversity: Option<f32>,
+    /// Recency weight in 0..=1; omitted uses `bundle.recency_weight`
+    pub recency: Option<f32>,
+    /// Percent of the cap per kind, as a map or `kind:pct,...`; empty means one shared budget
+    #[serde(default)]
+    pub quotas: KindQuotas,
+    /// Globs over project-relative paths to leave out, as a list or comma-separated
+    #[serde(default)]
+    pub exclude: ExcludeGlobs,
+    /// Also search Godot-domain synonyms of the terms (built-in plus `bundle.synonyms`)
+    #[serde(default)]
+    pub expand: bool,
+    /// A previously delivered bundle; items it holds unchanged are skipped
+    pub manifest: Option<BundleManifest>,
+    /// `{ path, hash }` of whole files the client holds; files still matching are skipped
+    #[serde(default)]
+    pub known_files: KnownFiles,
+    /// `/context/bundle` only: `json` (default), `markdown` or `xml`
+    pub format: Option<String>,
+}
+#[derive(Serialize)]
+pub(crate) struct BundleItemDto {
+    pub path: String, pub kind: String, pub score: i32, pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens_estimate: Option<usize>,
+    /// Only set for session, manifest or `known_files` bundles
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<ItemStatus>,
+}
+#[derive(Serialize)]
+pub(crate) struct BundleResponse {
+    pub query: String, pub items: Vec<BundleItemDto>, pub size_bytes: usize,
+    /// Index generation the bundle was built from
+    pub generation: u64,
+    /// Content bytes and estimated tokens over all items
+    pub content_bytes: usize, pub tokens_estimate: usize,
+    /// Paths left out because the session, manifest or `known_files` already has them
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<String>,
+}
+
+/// `/context/bundle/scene`: a scene with its scripts and resources.
+#[derive(Deserialize)]
+pub(crate) struct SceneBundleRequest {
+    /// `res://` or project-relative .tscn path
+    pub scene: String, pub project: Option<String>, pub cap_bytes: Option<usize>,
+    /// `json` (default), `markdown` or `xml`
+    pub format: Option<String>,
+}
+
+/// `/context/bundle/error`: the files a Godot error log points at.
+#[derive(Deserialize)]
+pub(crate) struct ErrorBundleRequest {
+    /// Error message and/or stack trace as printed by Godot
+    pub log: String, pub project: Option<String>, pub cap_bytes: Option<usize>,
+    /// `json` (default), `markdown` or `xml`
+    pub format: Option<String>,
+}
+
+/// `/context/bundle/symbol`: a definition with its call sites.
+#[derive(Deserialize)]
+pub(crate) struct SymbolBundleRequest {
+    pub name: String, pub project: Option<String>, pub limit: Option<usize>, pub cap_bytes: Option<usize>, pub kind: Option<String>,
+    #[serde(default)]
+    pub exclude: ExcludeGlobs,
+    /// `json` (default), `markdown` or `xml`
+    pub format: Option<String>,
+}
+
+/// `/context/render`: a bundle request plus the template to render it into.
+#[derive(Deserialize)]
+pub(crate) struct RenderRequest {
+    /// Template name, read from `<config dir>/templates/<name>.md`
+    pub template: String,
+    /// `format` picks how `{{bundle}}` is rendered: `markdown` (default) or `xml`
+    #[serde(flatten)]
+    pub bundle: BundleRequest,
+}
+
+/// One NDJSON line: zero or more `item`s followed by a single `end` (or `error`).
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub(crate) enum StreamLine {
+    Item {
+        #[serde(flatten)]
+        item: BundleItem,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        status: Option<ItemStatus>,
+    },
+    End {
+        query: String, count: usize, size_bytes: usize, generation: u64, content_bytes: usize, tokens_estimate: usize,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        skipped: Vec<String>,
+    },
+    Error { error: String },
+}
+
+pub fn routes() -> AppRouter {
+    Router::new()
+        .route("/context/bundle", get(
+            |State(state): State<Arc<ServerState>>, Query(req): Query<BundleRequest>| async move {
+                render_bundle(&state, req).await
+            }
+        ).post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<BundleRequest>| async move {
+                render_bundle(&state, req).await
+            }
+        ))
+        .route("/context/bundle/scene", get(
+            |State(state): State<Arc<ServerState>>, Query(req): Query<SceneBundleRequest>| async move {
+                scene_bundle(&state, req).await
+            }
+        ).post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<SceneBundleRequest>| async move {
+                scene_bundle(&state, req).await
+            }
+        ))
+        .route("/context/bundle/error", get(
+            |State(state): State<Arc<ServerState>>, Query(req): Query<ErrorBundleRequest>| async move {
+                error_bundle(&state, req).await
+            }
+        ).post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<ErrorBundleRequest>| async move {
+                error_bundle(&state, req).await
+            }
+        ))
+        .route("/context/bundle/symbol", get(
+            |State(state): State<Arc<ServerState>>, Query(req): Query<SymbolBundleRequest>| async move {
+                symbol_bundle(&state, req).await
+            }
+        ).post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<SymbolBundleRequest>| async move {
+                symbol_bundle(&state, req).await
+            }
+        ))
+        .route("/context/render", post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<RenderRequest>| async move {
+                render_prompt(&state, req).await
+            }
+        ))
+        .route("/context/bundle/stream", get(
+            |State(state): State<Arc<ServerState>>, Query(req): Query<BundleRequest>| async move {
+                stream_bundle(&state, req)
+            }
+        ).post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<BundleRequest>| async move {
+                stream_bundle(&state, req)
+            }
+        ))
+}
+
+/// Queries merged into one bundle, beyond `q`.
+const MAX_QUERIES: usize = 8;
+
+/// `q` followed by `queries` (`q` may be omitted or empty when `queries` is given), each checked.
+fn check_queries(req: &BundleRequest) -> Result<Vec<WeightedQuery>, ApiError> {
+    let mut queries = req.queries.clone();
+    if queries.len() > MAX_QUERIES {
+        return Err(api_error(StatusCode::BAD_REQUEST, format!("at most {} queries", MAX_QUERIES)));
+    }
+    match &req.q {
+        Some(q) if !q.is_empty() || queries.is_empty() => queries.insert(0, WeightedQuery::new(q.clone())),
+        Some(_) => {}
+        None if queries.is_empty() => return Err(api_error(StatusCode::BAD_REQUEST, "missing q")),
+        None => {}
+    }
+    for wq in &queries {
+        check_query(&wq.q)?;
+        if !(wq.weight.is_finite() && wq.weight > 0.0) {
+            return Err(api_error(StatusCode::BAD_REQUEST, format!("weight for '{}' must be positive (got {})", wq.q, wq.weight)));
+        }
+    }
+    Ok(queries)
+}
+
+/// Session and manifest checks for a loaded item: `None` skips it, otherwise its status
+/// (set when any check applies: the manifest's, then `files`, then the session's).
+/// Unchanged `files` are skipped before loading, so here they only decide new vs changed.
+fn item_status(
+    filter: Option<&SessionFilter>,
+    known: Option<&ManifestIndex>,
+    files: &KnownFiles,
+    it: &BundleItem,
+) -> Option<Option<ItemStatus>> {
+    let session = match filter {
+        Some(f) => Some(f.check(it)?),
+        None => None,
+    };
+    let delta = match known {
+        Some(k) => Some(match k.delta(it)? {
+            Delta::New => ItemStatus::New,
+            Delta::Changed => ItemStatus::Changed,
+        }),
+        None => None,
+    };
+    let file = (!files.is_empty()).then(|| if files.contains(&it.path) { ItemStatus::Changed } else { ItemStatus::New });
+    Some(delta.or(file).or(session))
+}
+
+/// A ranking weight (`diversity`, `recency`), `default` when omitted; must lie in 0..=1.
+fn check_weight(name: &str, value: Option<f32>, default: f32) -> Result<f32, ApiError> {
+    match value {
+        None => Ok(default),
+        Some(v) if (0.0..=1.0).contains(&v) => Ok(v),
+        Some(v) => Err(api_error(StatusCode::BAD_REQUEST, format!("{} must be between 0 and 1 (got {})", name, v))),
+    }
+}
+
+/// `json` when omitted; otherwise one of the bundle renderings.
+fn check_format(format: Option<String>) -> Result<String, ApiError> {
+    let format = format.unwrap_or_else(|| "json".to_string());
+    if !matches!(format.as_str(), "json" | "markdown" | "xml") {
+        return Err(api_error(StatusCode::BAD_REQUEST, format!("unknown format: {}", format)));
+    }
+    Ok(format)
+}
+
+/// `bundle` as markdown or XML; callers serialize `json` themselves. The XML layout carries
+/// content unescaped, so it isn't well-formed XML and goes out as plain text.
+fn rendered(format: &str, bundle: Bundle) -> Response {
+    match format {
+        "markdown" => ([(header::CONTENT_TYPE, "text/markdown; charset=utf-8")], bundle.to_markdown()).into_response(),
+        _ => ([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], bundle.to_xml()).into_response(),
+    }
+}
+
+/// `run_bundle` in the requested `format`.
+async fn render_bundle(state: &ServerState, mut req: BundleRequest) -> Result<Response, ApiError> {
+    let format = check_format(req.format.take())?;
+    let Json(resp) = run_bundle(state, req).await?;
+    Ok(if format == "json" { Json(resp).into_response() } else { rendered(&format, to_bundle(resp)) })
+}
+
+/// `context::bundle_for_scene` over the project's files, in the requested `format`.
+async fn scene_bundle(state: &ServerState, req: SceneBundleRequest) -> Result<Response, ApiError> {
+    let format = check_format(req.format)?;
+    let project = resolve_project(state, req.project.as_deref())?;
+    let rel = project_relative_path(&req.scene)?;
+    let sizing = state.bundle_config();
+    let cap = bounded("cap_bytes", req.cap_bytes, sizing.default_cap_bytes, sizing.max_cap_bytes)?;
+    if rel.extension().and_then(|e| e.to_str()) != Some("tscn") {
+        return Err(api_error(StatusCode::BAD_REQUEST, format!("not a .tscn scene: {}", req.scene)));
+    }
+    // Symlinks out of the project are refused like `..`
+    if !sandboxed_path(&project.root, &req.scene)?.is_file() {
+        return Err(api_error(StatusCode::NOT_FOUND, format!("scene not found: {}", rel.display())));
+    }
+    let root = project.root.clone();
+    let bundle = tokio::task::spawn_blocking(move || context::bundle_for_scene(&root, &rel.to_string_lossy(), Some(cap)))
+        .await
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(if format == "json" { Json(bundle).into_response() } else { rendered(&format, bundle) })
+}
+
+/// `context::bundle_for_error` over the project's files, in the requested `format`.
+async fn error_bundle(state: &ServerState, req: ErrorBundleRequest) -> Result<Response, ApiError> {
+    let format = check_format(req.format)?;
+    let project = resolve_project(state, req.project.as_deref())?;
+    if req.log.trim().is_empty() {
+        return Err(api_error(StatusCode::BAD_REQUEST, "missing log"));
+    }
+    let sizing = state.bundle_config();
+    let cap = bounded("cap_bytes", req.cap_bytes, sizing.default_cap_bytes, sizing.max_cap_bytes)?;
+    let root = project.root.clone();
+    let bundle = tokio::task::spawn_blocking(move || context::bundle_for_error(&root, &req.log, Some(cap)))
+        .await
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(if format == "json" { Json(bundle).into_response() } else { rendered(&format, bundle) })
+}
+
+/// Drop session annotations for the plain-text renderers.
+fn to_bundle(resp: BundleResponse) -> Bundle {
+    let items = resp.items.into_iter()
+        .map(|it| BundleItem {
+            path: it.path, kind: it.kind, score: it.score, content: it.content, start_line: it.start_line, end_line: it.end_line,
+            hash: it.hash, bytes: it.bytes, tokens_estimate: it.tokens_estimate,
+        })
+        .collect();
+    Bundle::new(resp.query, items, resp.size_bytes, resp.generation)
+}
+
+/// `context::bundle_for_symbol` over the project's index, in the requested `format`.
+async fn symbol_bundle(state: &ServerState, req: SymbolBundleRequest) -> Result<Response, ApiError> {
+    let format = check_format(req.format)?;
+    let project = resolve_project(state, req.project.as_deref())?;
+    if !context::is_symbol_name(&req.name) {
+        return Err(api_error(StatusCode::BAD_REQUEST, format!("invalid symbol name: {}", req.name)));
+    }
+    let limit = bounded("limit", req.limit, 10, MAX_LIMIT)?;
+    let sizing = state.bundle_config();
+    let cap = bounded("cap_bytes", req.cap_bytes, sizing.default_cap_bytes, sizing.max_cap_bytes)?;
+    let kind = req.kind.clone();
+    let opts = BundleOptions { limit, cap_bytes: Some(cap), kind: req.kind, exclude: req.exclude, ..Default::default() };
+    let started = Instant::now();
+    let reader = project.index.reader().clone();
+    let bundle = tokio::task::spawn_blocking(move || context::bundle_for_symbol(&reader, &req.name, &opts))
+        .await
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    state.history().record("bundle_symbol", &project.id, &bundle.query, kind.as_deref(), bundle.items.len(), started.elapsed());
+    Ok(if format == "json" { Json(bundle).into_response() } else { rendered(&format, bundle) })
+}
+
+/// The named template with the bundle for `req.bundle`, its query and, when the template asks
+/// for them, the report issues of the bundled files filled in, as plain text.
+async fn render_prompt(state: &ServerState, mut req: RenderRequest) -> Result<Response, ApiError> {
+    if !context::is_template_name(&req.template) {
+        return Err(api_error(StatusCode::BAD_REQUEST, format!("invalid template name: {}", req.template)));
+    }
+    let format = match req.bundle.format.take().as_deref() {
+        None | Some("markdown") => "markdown",
+        Some("xml") => "xml",
+        Some(other) => return Err(api_error(StatusCode::BAD_REQUEST, format!("unknown format: {}", other))),
+    };
+    let template = context::load_template(state.config_dir(), &req.template)
+        .map_err(|_| api_error(StatusCode::NOT_FOUND, format!("template not found: {}", req.template)))?;
+    let project = resolve_project(state, req.bundle.project.as_deref())?;
+    let Json(resp) = run_bundle(state, req.bundle).await?;
+    let issues = if context::uses_placeholder(&template, "issues") {
+        let paths: Vec<&str> = resp.items.iter().map(|it| it.path.trim_start_matches("./")).collect();
+        let lines: Vec<String> = graphql::project_issues(project.root.clone()).await?
+            .into_iter()
+            .filter(|i| i.file.as_deref().is_some_and(|f| paths.contains(&f)))
+            .map(|i| match i.code {
+                Some(code) => format!("- {} {}: {} ({})", i.severity.as_str(), i.file.unwrap_or_default(), i.message, code),
+                None => format!("- {} {}: {}", i.severity.as_str(), i.file.unwrap_or_default(), i.message),
+            })
+            .collect();
+        if lines.is_empty() { "(none)".to_string() } else { lines.join("\n") }
+    } else {
+        String::new()
+    };
+    let bundle = to_bundle(resp);
+    let rendered = if format == "xml" { bundle.to_xml() } else { bundle.to_markdown() };
+    let prompt = context::render_template(&template, &context::PromptVars { bundle: &rendered, query: &bundle.query, issues: &issues });
+    Ok(([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], prompt).into_response())
+}
+
+/// A validated `BundleRequest`, ready for `context::BundleBuilder`.
+struct BundlePlan {
+    project: Arc<ProjectState>,
+    queries: Vec<WeightedQuery>,
+    opts: BundleOptions,
+    session: Option<u64>,
+    filter: Option<SessionFilter>,
+    known: Option<ManifestIndex>,
+}
+
+fn plan_bundle(state: &ServerState, req: BundleRequest) -> Result<BundlePlan, ApiError> {
+    let project = resolve_project(state, req.project.as_deref())?;
+    let queries = check_queries(&req)?;
+    let limit = bounded("limit", req.limit, 10, MAX_LIMIT)?;
+    let sizing = state.bundle_config();
+    let cap = bounded("cap_bytes", req.cap_bytes, sizing.default_cap_bytes, sizing.max_cap_bytes)?;
+    let diversity = check_weight("diversity", req.diversity, 0.0)?;
+    let recency = check_weight("recency", req.recency, sizing.recency_weight)?;
+    let filter = match req.session {
+        Some(id) => Some(state.sessions().filter(id, &project.id, req.session_mode)?),
+        None => None,
+    };
+    let known = req.manifest.as_ref().map(BundleManifest::index);
+    let opts = BundleOptions {
+        limit, cap_bytes: Some(cap), kind: req.kind, content_mode: req.content_mode, diversity, recency, quotas: req.quotas,
+        exclude: req.exclude,
+        synonyms: req.expand.then(|| Synonyms::builtin().extended(&sizing.synonyms)),
+        known_files: req.known_files,
+    };
+    Ok(BundlePlan { project, queries, opts, session: req.session, filter, known })
+}
+
+impl BundlePlan {
+    /// Builder over the project's index; session and manifest filtering happen before the cap
+    /// so the budget goes to unseen content.
+    fn builder(&self) -> anyhow::Result<BundleBuilder<'_>> {
+        let (filter, known, files) = (self.filter.as_ref(), self.known.as_ref(), &self.opts.known_files);
+        Ok(BundleBuilder::new(self.project.index.reader(), &self.queries, &self.opts)?
+            .with_filter(move |it| item_status(filter, known, files, it).is_some()))
+    }
+
+    /// Status of an item the builder yielded.
+    fn status(&self, it: &BundleItem) -> Option<ItemStatus> {
+        item_status(self.filter.as_ref(), self.known.as_ref(), &self.opts.known_files, it).flatten()
+    }
+}
+
+pub(crate) async fn run_bundle(state: &ServerState, req: BundleRequest) -> Result<Json<BundleResponse>, ApiError> {
+    let plan = plan_bundle(state, req)?;
+    let started = Instant::now();
+    let mut builder = plan.builder().map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    state.history().record("bundle", &plan.project.id, builder.query(), plan.opts.kind.as_deref(), builder.hits(), started.elapsed());
+    let admitted: Vec<BundleItem> = builder.by_ref().collect();
+    if let Some(id) = plan.session {
+        state.sessions().record(id, &admitted);
+    }
+    let items = admitted.into_iter()
+        .map(|it| BundleItemDto {
+            status: plan.status(&it),
+            path: it.path, kind: it.kind, score: it.score, content: it.content, start_line: it.start_line, end_line: it.end_line,
+            hash: it.hash, bytes: it.bytes, tokens_estimate: it.tokens_estimate,
+        })
+        .collect();
+    Ok(Json(BundleResponse {
+        query: builder.query().to_string(),
+        items,
+        size_bytes: builder.size_bytes(),
+        generation: builder.generation(),
+        content_bytes: builder.content_bytes(),
+        tokens_estimate: builder.tokens_estimate(),
+        skipped: builder.skipped().to_vec(),
+    }))
+}
+
+fn stream_bundle(state: &Arc<ServerState>, req: BundleRequest) -> Result<Response, ApiError> {
+    bundle_lines(state, req).map(ndjson_response)
+}
+
+/// Validate `req` and assemble its bundle on the blocking pool, sending lines as items are admitted.
+pub(crate) fn bundle_lines(state: &Arc<ServerState>, req: BundleRequest) -> Result<mpsc::Receiver<StreamLine>, ApiError> {
+    let plan = plan_bundle(state, req)?;
+    let state = state.clone();
+
+    // Small buffer: the producer stays at most a few items ahead of the client
+    let (tx, rx) = mpsc::channel::<StreamLine>(4);
+    tokio::task::spawn_blocking(move || {
+        let started = Instant::now();
+        let mut builder = match plan.builder() {
+            Ok(builder) => builder,
+            Err(e) => { let _ = tx.blocking_send(StreamLine::Error { error: e.to_string() }); return; }
+        };
+        state.history().record("bundle_stream", &plan.project.id, builder.query(), plan.opts.kind.as_deref(), builder.hits(), started.elapsed());
+        let mut count = 0;
+        for it in builder.by_ref() {
+            let status = plan.status(&it);
+            let served = plan.session.map(|id| (id, it.clone()));
+            // Client went away: stop assembling
+            if tx.blocking_send(StreamLine::Item { item: it, status }).is_err() { return; }
+            // Only count an item as served once it has been handed to the client
+            if let Some((id, it)) = served {
+                state.sessions().record(id, [&it]);
+            }
+            count += 1;
+        }
+        let _ = tx.blocking_send(StreamLine::End {
+            query: builder.query().to_string(),
+            count,
+            size_bytes: builder.size_bytes(),
+            generation: builder.generation(),
+            content_bytes: builder.content_bytes(),
+            tokens_estimate: builder.tokens_estimate(),
+            skipped: builder.skipped().to_vec(),
+        });
+    });
+
+    Ok(rx)
+}
@@ -0,0 +1,17 @@
+// Config reload: re-read config files and apply reloadable settings without a restart.
+use std::sync::Arc;
+
+use axum::{routing::post, extract::State, http::StatusCode, Json, Router};
+
+use crate::{api_error, require, ApiError, AppRouter, Permission, ServerState};
+
+pub fn routes() -> AppRouter {
+    Router::new().route("/config/reload", post(
+        |State(state): State<Arc<ServerState>>| async move {
+            require(&state, Permission::ConfigWrite)?;
+            let report = state.reload().await
+                .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, format!("config reload failed: {:#}", e)))?;
+            Ok::<_, ApiError>(Json(report))
+        }
+    ))
+}
@@ -0,0 +1,107 @@
+// Editor bridge: TCP link from the in-editor GDScript plugin (addons/godot_mcp_bridge).
+// The plugin sends newline-delimited JSON messages; the latest state is served at /editor/state.
+use std::{collections::VecDeque, sync::{Arc, RwLock}, time::{SystemTime, UNIX_EPOCH}};
+
+use serde::{Deserialize, Serialize};
+use tokio::{io::{AsyncBufReadExt, BufReader}, net::TcpListener};
+use tracing::{info, warn};
+
+/// Most recent runtime errors kept for clients; older ones are dropped.
+pub const MAX_EDITOR_ERRORS: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RuntimeError {
+    pub message: String,
+    #[serde(default)]
+    pub file: Option<String>,
+    #[serde(default)]
+    pub line: Option<usize>,
+}
+
+/// Live editor state as last reported by the plugin.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EditorState {
+    pub connected: bool,
+    pub project: Option<String>,
+    pub godot_version: Option<String>,
+    pub current_scene: Option<String>,
+    pub selected_nodes: Vec<String>,
+    pub errors: VecDeque<RuntimeError>,
+    /// Unix seconds of the last message
+    pub updated_at: Option<u64>,
+}
+
+/// Messages sent by the editor plugin, one JSON object per line.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EditorMessage {
+    Hello { project: Option<String>, godot_version: Option<String> },
+    SceneChanged { scene: Option<String> },
+    Selection { nodes: Vec<String> },
+    RuntimeError(RuntimeError),
+    ClearErrors,
+}
+
+#[derive(Default)]
+pub struct EditorBridge {
+    state: RwLock<EditorState>,
+}
+
+impl EditorBridge {
+    pub fn snapshot(&self) -> EditorState {
+        self.state.read().unwrap().clone()
+    }
+
+    pub fn apply(&self, msg: EditorMessage) {
+        let mut st = self.state.write().unwrap();
+        match msg {
+            EditorMessage::Hello { project, godot_version } => {
+                st.project = project;
+                st.godot_version = godot_version;
+            }
+            EditorMessage::SceneChanged { scene } => st.current_scene = scene,
+            EditorMessage::Selection { nodes } => st.selected_nodes = nodes,
+            EditorMessage::RuntimeError(e) => {
+                if st.errors.len() == MAX_EDITOR_ERRORS { st.errors.pop_front(); }
+                st.errors.push_back(e);
+            }
+            EditorMessage::ClearErrors => st.errors.clear(),
+        }
+        st.updated_at = Some(SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0));
+    }
+
+    fn set_connected(&self, connected: bool) {
+        let mut st = self.state.write().unwrap();
+        st.connected = connected;
+        if !connected {
+            // Scene/selection are only meaningful while the editor is attached
+            st.current_scene = None;
+            st.selected_nodes.clear();
+        }
+    }
+}
+
+/// Accept plugin connections on `listener` and feed their messages into `bridge`.
+/// Connections are handled one after another; the editor keeps a single long-lived link.
+pub async fn serve_editor_bridge(listener: TcpListener, bridge: Arc<EditorBridge>) -> anyhow::Result<()> {
+    info!(addr=%listener.local_addr()?, "Editor bridge listening");
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        info!(%peer, "Editor plugin connected");
+        bridge.set_connected(true);
+        let mut lines = BufReader::new(stream).lines();
+        loop {
+            match lines.next_line().await {
+                Ok(Some(line)) if line.trim().is_empty() => continue,
+                Ok(Some(line)) => match serde_json::from_str::<EditorMessage>(&line) {
+                    Ok(msg) => bridge.apply(msg),
+                    Err(e) => warn!(error=%e, "ignoring malformed editor message"),
+                },
+                Ok(None) => break,
+                Err(e) => { warn!(error=%e, "editor bridge read error"); break; }
+            }
+        }
+        info!(%peer, "Editor plugin disconnected");
+        bridge.set_connected(false);
+    }
+}
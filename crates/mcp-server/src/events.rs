@@ -0,0 +1,74 @@
+// Change feed: watcher commits as a Server-Sent Events stream on /events.
+use std::{convert::Infallible, sync::Arc, time::Duration};
+
+use axum::{
+    routing::get, extract::{Query, State},
+    response::sse::{Event, KeepAlive, Sse},
+    Router,
+};
+use futures_util::{stream, Stream};
+use serde::Deserialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::{resolve_project, webhooks::CommitEvent, ApiError, AppRouter, ServerState};
+
+/// Commits buffered per subscriber; slower clients get a `lagged` event and skip ahead.
+const FEED_CAPACITY: usize = 256;
+/// Comment line sent on idle streams so proxies keep the connection open.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Broadcast of every watcher commit across projects.
+pub struct EventFeed {
+    tx: broadcast::Sender<Arc<CommitEvent>>,
+}
+
+impl Default for EventFeed {
+    fn default() -> Self {
+        Self { tx: broadcast::channel(FEED_CAPACITY).0 }
+    }
+}
+
+impl EventFeed {
+    /// Send to current subscribers; dropped when nobody is listening.
+    pub fn publish(&self, event: CommitEvent) {
+        let _ = self.tx.send(Arc::new(event));
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<CommitEvent>> {
+        self.tx.subscribe()
+    }
+}
+
+#[derive(Deserialize)]
+struct EventsQuery {
+    /// Only stream commits for this project; omitted streams every project
+    project: Option<String>,
+}
+
+/// `index.commit` events (data: the webhook payload) for `project`, or all projects.
+fn commit_stream(rx: broadcast::Receiver<Arc<CommitEvent>>, project: Option<String>) -> impl Stream<Item = Result<Event, Infallible>> {
+    stream::unfold((rx, project), |(mut rx, project)| async move {
+        loop {
+            let event = match rx.recv().await {
+                Ok(ev) if project.as_deref().is_some_and(|p| p != ev.project) => continue,
+                Ok(ev) => Event::default().event(ev.event).json_data(&*ev).unwrap_or_default(),
+                Err(RecvError::Lagged(skipped)) => Event::default().event("lagged").data(format!("{{\"skipped\":{}}}", skipped)),
+                Err(RecvError::Closed) => return None,
+            };
+            return Some((Ok(event), (rx, project)));
+        }
+    })
+}
+
+pub fn routes() -> AppRouter {
+    Router::new().route("/events", get(
+        |State(state): State<Arc<ServerState>>, Query(q): Query<EventsQuery>| async move {
+            let project = match q.project.as_deref() {
+                Some(id) => Some(resolve_project(&state, Some(id))?.id.clone()),
+                None => None,
+            };
+            let events = commit_stream(state.events().subscribe(), project);
+            Ok::<_, ApiError>(Sse::new(events).keep_alive(KeepAlive::new().interval(KEEP_ALIVE)))
+        }
+    ))
+}
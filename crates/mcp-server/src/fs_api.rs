@@ -0,0 +1,209 @@
+// Filesystem routes: sandboxed read access to project files.
+use std::{fs::File, io::{self, BufRead, BufReader, Read, Seek, SeekFrom}, path::Path, sync::Arc, time::UNIX_EPOCH};
+
+use axum::{routing::get, extract::{Query, State}, http::StatusCode, Json, Router};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::{api_error, bounded, resolve_project, sandboxed_path, ApiError, AppRouter, ServerState};
+
+/// Upper bound on content returned by a single /fs/read call.
+pub const MAX_READ_BYTES: usize = 1024 * 1024;
+/// Upper bound on entries returned by a single /fs/list call.
+pub const MAX_LIST_ENTRIES: usize = 5000;
+
+#[derive(Deserialize, Default)]
+pub(crate) struct ReadRequest {
+    pub project: Option<String>,
+    /// res://, ./relative or root-relative path (as returned by search hits)
+    pub path: String,
+    /// Byte range [start_byte, end_byte)
+    pub start_byte: Option<usize>,
+    pub end_byte: Option<usize>,
+    /// 1-based inclusive line range
+    pub start_line: Option<usize>,
+    pub end_line: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ReadResponse {
+    pub path: String,
+    /// Total file size in bytes
+    pub size: usize,
+    pub content: String,
+    /// True when the selection was cut at MAX_READ_BYTES
+    pub truncated: bool,
+}
+
+#[derive(Deserialize)]
+struct ListRequest {
+    project: Option<String>,
+    /// Directory to list; defaults to the project root
+    path: Option<String>,
+    /// How many levels to descend (default 1, max 16)
+    depth: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct ListEntry {
+    /// Root-relative path with `/` separators
+    path: String,
+    /// file, dir or symlink
+    kind: &'static str,
+    size: u64,
+    /// Seconds since the Unix epoch
+    mtime: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct ListResponse { entries: Vec<ListEntry>, truncated: bool }
+
+pub fn routes() -> AppRouter {
+    Router::new()
+        .route("/fs/read", get(
+            |State(state): State<Arc<ServerState>>, Query(req): Query<ReadRequest>| async move {
+                read_file(&state, req).await
+            }
+        ).post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<ReadRequest>| async move {
+                read_file(&state, req).await
+            }
+        ))
+        .route("/fs/list", get(
+            |State(state): State<Arc<ServerState>>, Query(req): Query<ListRequest>| async move {
+                list_dir(&state, req).await
+            }
+        ).post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<ListRequest>| async move {
+                list_dir(&state, req).await
+            }
+        ))
+}
+
+async fn list_dir(state: &ServerState, req: ListRequest) -> Result<Json<ListResponse>, ApiError> {
+    let project = resolve_project(state, req.project.as_deref())?;
+    let root = sandboxed_path(&project.root, ".")?;
+    let dir = sandboxed_path(&project.root, req.path.as_deref().unwrap_or("."))?;
+    if !dir.is_dir() {
+        return Err(api_error(StatusCode::BAD_REQUEST, format!("not a directory: {}", req.path.unwrap_or_default())));
+    }
+    let depth = bounded("depth", req.depth, 1, 16)?;
+    let skip = state.index_config().skip;
+    let (entries, truncated) = tokio::task::spawn_blocking(move || walk_listing(&root, &dir, depth, &skip))
+        .await
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(ListResponse { entries, truncated }))
+}
+
+/// Walk `dir` up to `depth` levels, applying the index skip rules (.git, target, .godot, ...)
+/// and the configured `skip` fragments.
+fn walk_listing(root: &Path, dir: &Path, depth: usize, skip: &[String]) -> (Vec<ListEntry>, bool) {
+    let mut out = Vec::new();
+    let walker = WalkDir::new(dir)
+        .min_depth(1)
+        .max_depth(depth)
+        .sort_by_file_name()
+        .into_iter()
+        // Skip rules match on `/<dir>/`, so test directories with a trailing separator
+        .filter_entry(|e| !index::is_skipped_with(&if e.file_type().is_dir() { e.path().join("") } else { e.path().to_path_buf() }, skip));
+    for entry in walker.flatten() {
+        if out.len() >= MAX_LIST_ENTRIES { return (out, true); }
+        let ft = entry.file_type();
+        let kind = if ft.is_symlink() { "symlink" } else if ft.is_dir() { "dir" } else { "file" };
+        let meta = entry.metadata().ok();
+        let rel = entry.path().strip_prefix(root).unwrap_or(entry.path());
+        out.push(ListEntry {
+            path: rel.to_string_lossy().replace('\\', "/"),
+            kind,
+            size: meta.as_ref().filter(|m| m.is_file()).map(|m| m.len()).unwrap_or(0),
+            mtime: meta.and_then(|m| m.modified().ok()).and_then(|t| t.duration_since(UNIX_EPOCH).ok()).map(|d| d.as_secs()),
+        });
+    }
+    (out, false)
+}
+
+pub(crate) async fn read_file(state: &ServerState, req: ReadRequest) -> Result<Json<ReadResponse>, ApiError> {
+    let project = resolve_project(state, req.project.as_deref())?;
+    let by_bytes = req.start_byte.is_some() || req.end_byte.is_some();
+    let by_lines = req.start_line.is_some() || req.end_line.is_some();
+    if by_bytes && by_lines {
+        return Err(api_error(StatusCode::BAD_REQUEST, "use either a byte range or a line range, not both"));
+    }
+    let full = sandboxed_path(&project.root, &req.path)?;
+    if !full.is_file() {
+        return Err(api_error(StatusCode::BAD_REQUEST, format!("not a file: {}", req.path)));
+    }
+    let size = full.metadata().map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?.len() as usize;
+
+    let span = if by_lines {
+        let start = req.start_line.unwrap_or(1).max(1);
+        let end = req.end_line.unwrap_or(usize::MAX);
+        if end < start {
+            return Err(api_error(StatusCode::BAD_REQUEST, "end_line must be >= start_line"));
+        }
+        Span::Lines(start, end)
+    } else {
+        let start = req.start_byte.unwrap_or(0).min(size);
+        let end = req.end_byte.unwrap_or(size).min(size);
+        if end < start {
+            return Err(api_error(StatusCode::BAD_REQUEST, "end_byte must be >= start_byte"));
+        }
+        Span::Bytes(start, end)
+    };
+    let (selected, truncated) = tokio::task::spawn_blocking(move || read_span(&full, span, MAX_READ_BYTES))
+        .await
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(ReadResponse {
+        path: req.path,
+        size,
+        content: String::from_utf8_lossy(&selected).into_owned(),
+        truncated,
+    }))
+}
+
+/// Part of a file to read: a byte range [start, end) or 1-based inclusive lines.
+#[derive(Clone, Copy)]
+enum Span {
+    Bytes(usize, usize),
+    Lines(usize, usize),
+}
+
+/// Read `span` of the file at `path`, keeping at most `limit` bytes; true when more was selected.
+/// Only the selection is read: byte ranges seek to their start and lines are streamed.
+fn read_span(path: &Path, span: Span, limit: usize) -> io::Result<(Vec<u8>, bool)> {
+    let mut file = File::open(path)?;
+    let mut out = Vec::new();
+    match span {
+        Span::Bytes(start, end) => {
+            file.seek(SeekFrom::Start(start as u64))?;
+            file.take((end - start).min(limit) as u64).read_to_end(&mut out)?;
+            Ok((out, end - start > limit))
+        }
+        Span::Lines(start, end) => {
+            let mut reader = BufReader::new(file);
+            let mut line = 1;
+            'read: loop {
+                let buf = reader.fill_buf()?;
+                let len = buf.len();
+                if len == 0 { break; }
+                let mut pos = 0;
+                while pos < len {
+                    if line > end { break 'read; }
+                    let newline = buf[pos..].iter().position(|&b| b == b'\n');
+                    let stop = newline.map_or(len, |n| pos + n + 1);
+                    if line >= start {
+                        out.extend_from_slice(&buf[pos..stop]);
+                        if out.len() > limit { break 'read; }
+                    }
+                    if newline.is_some() { line += 1; }
+                    pos = stop;
+                }
+                reader.consume(len);
+            }
+            let truncated = out.len() > limit;
+            out.truncate(limit);
+            Ok((out, truncated))
+        }
+    }
+}
@@ -0,0 +1,402 @@
+// Godot analyzer routes: expose godot_analyzer checks over HTTP.
+use std::{path::Path, process::Stdio, sync::{Arc, Mutex}, time::Duration};
+
+use axum::{routing::{get, post}, extract::{Query, State}, http::StatusCode, response::Response, Json, Router};
+use serde::{Deserialize, Serialize};
+use tokio::{io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader}, sync::mpsc};
+
+use godot_analyzer::{
+    analyze_project, headless::{export_args, headless_args, parse_godot_output}, lint_finding_as_issue, read_export_presets, scene_validate::SceneIssue, script_lint::{lint_gd_scripts, LintFinding},
+    signal_graph, signal_validate::SignalGraphEdge, structure_fix::{apply_structure_fix, Layout, MoveSelection, plan_structure_fix_with, plan_token, ApplySummary, FixPlan},
+    to_sarif, validate_scenes_with, GodotProjectReport, Issue, SceneCheckOptions, Severity,
+};
+
+use crate::{
+    api_error, ndjson_response, project_relative_path, require, resolve_project, ApiError, AppRouter, Permission, ServerState,
+};
+
+#[derive(Deserialize)]
+pub(crate) struct AnalyzeRequest { pub project: Option<String>, pub sarif: Option<bool> }
+
+#[derive(Deserialize)]
+struct SceneValidateRequest {
+    project: Option<String>,
+    /// Single scene to validate (res:// or root-relative); omitted validates all scenes
+    scene: Option<String>,
+    /// Checks to run: script, properties, subresource, preload, load (default: all)
+    #[serde(default)]
+    checks: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct SceneValidateResponse { issues: Vec<SceneIssue> }
+
+#[derive(Deserialize)]
+pub(crate) struct LintRequest {
+    pub project: Option<String>,
+    /// Drop findings below this severity (info, warn, error)
+    pub min_severity: Option<Severity>,
+    pub sarif: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct LintResponse {
+    pub findings: Vec<LintFinding>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sarif: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct SignalGraphRequest { project: Option<String> }
+
+#[derive(Serialize)]
+struct SignalGraphResponse { edges: Vec<SignalGraphEdge> }
+
+#[derive(Deserialize)]
+struct StructurePlanRequest { project: Option<String> }
+
+#[derive(Serialize)]
+struct StructurePlanResponse {
+    plan: FixPlan,
+    /// Pass back to /godot/structure-fix/apply to confirm this exact plan
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct StructureApplyRequest {
+    project: Option<String>,
+    token: String,
+    /// Apply only some of the confirmed plan's moves (`include`/`exclude` globs, `moves` by source)
+    #[serde(default, flatten)]
+    selection: MoveSelection,
+}
+
+/// Keep only the tail of engine output in responses.
+const MAX_ENGINE_OUTPUT: usize = 64 * 1024;
+
+#[derive(Deserialize)]
+struct CheckRequest {
+    project: Option<String>,
+    /// Parse-check a single script (res:// or root-relative); omitted runs a full `--import`
+    script: Option<String>,
+}
+
+#[derive(Serialize)]
+struct CheckResponse {
+    command: Vec<String>,
+    exit_code: Option<i32>,
+    timed_out: bool,
+    issues: Vec<Issue>,
+    /// Combined stdout/stderr (tail, capped at 64 KiB)
+    output: String,
+}
+
+#[derive(Deserialize)]
+struct ExportRequest {
+    project: Option<String>,
+    /// Preset name from export_presets.cfg
+    preset: String,
+    /// Use --export-debug instead of --export-release
+    #[serde(default)]
+    debug: bool,
+    /// Artifact path relative to the project root; defaults to the preset's export_path
+    output: Option<String>,
+}
+
+/// One NDJSON line of an export run: `log` lines as the engine prints them, then one `end`.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum ExportLine {
+    Log { stream: &'static str, line: String },
+    End {
+        exit_code: Option<i32>,
+        timed_out: bool,
+        /// Root-relative artifact path
+        artifact: String,
+        artifact_exists: bool,
+        artifact_bytes: Option<u64>,
+    },
+}
+
+#[derive(Serialize)]
+pub(crate) struct AnalyzeResponse {
+    pub report: GodotProjectReport,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sarif: Option<serde_json::Value>,
+}
+
+pub fn routes() -> AppRouter {
+    Router::new()
+        .route("/godot/analyze", get(
+            |State(state): State<Arc<ServerState>>, Query(req): Query<AnalyzeRequest>| async move {
+                analyze(&state, req).await
+            }
+        ).post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<AnalyzeRequest>| async move {
+                analyze(&state, req).await
+            }
+        ))
+        .route("/godot/lint", get(
+            |State(state): State<Arc<ServerState>>, Query(req): Query<LintRequest>| async move {
+                lint(&state, req).await
+            }
+        ).post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<LintRequest>| async move {
+                lint(&state, req).await
+            }
+        ))
+        .route("/godot/signals/graph", get(
+            |State(state): State<Arc<ServerState>>, Query(req): Query<SignalGraphRequest>| async move {
+                let project = resolve_project(&state, req.project.as_deref())?;
+                let root = project.root.clone();
+                let edges = tokio::task::spawn_blocking(move || signal_graph(&root))
+                    .await
+                    .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+                Ok::<_, ApiError>(Json(SignalGraphResponse { edges }))
+            }
+        ))
+        .route("/godot/structure-fix/plan", post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<StructurePlanRequest>| async move {
+                let project = resolve_project(&state, req.project.as_deref())?;
+                let plan = build_plan(project.root.clone()).await?;
+                let token = plan_token(&plan);
+                Ok::<_, ApiError>(Json(StructurePlanResponse { plan, token }))
+            }
+        ))
+        .route("/godot/structure-fix/apply", post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<StructureApplyRequest>| async move {
+                apply_structure(&state, req).await
+            }
+        ))
+        .route("/godot/check", post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<CheckRequest>| async move {
+                check(&state, req).await
+            }
+        ))
+        .route("/godot/export", post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<ExportRequest>| async move {
+                export(&state, req).await
+            }
+        ))
+        .route("/godot/scenes/validate", post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<SceneValidateRequest>| async move {
+                validate_scenes(&state, req).await
+            }
+        ))
+}
+
+async fn validate_scenes(state: &ServerState, req: SceneValidateRequest) -> Result<Json<SceneValidateResponse>, ApiError> {
+    let project = resolve_project(state, req.project.as_deref())?;
+    let opts = SceneCheckOptions::from_names(&req.checks)
+        .map_err(|bad| api_error(StatusCode::BAD_REQUEST, format!("unknown scene check: {}", bad)))?;
+    let scene = req.scene.as_deref().map(project_relative_path).transpose()?;
+    if let Some(rel) = scene.as_ref() {
+        if !project.root.join(rel).is_file() {
+            return Err(api_error(StatusCode::NOT_FOUND, format!("scene not found: {}", rel.display())));
+        }
+    }
+    let root = project.root.clone();
+    let mut issues = tokio::task::spawn_blocking(move || validate_scenes_with(&root, scene.as_deref(), &opts))
+        .await
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    // Deterministic ordering for clients diffing results
+    issues.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)).then(a.message.cmp(&b.message)));
+    Ok(Json(SceneValidateResponse { issues }))
+}
+
+pub(crate) async fn lint(state: &ServerState, req: LintRequest) -> Result<Json<LintResponse>, ApiError> {
+    let project = resolve_project(state, req.project.as_deref())?;
+    let root = project.root.clone();
+    let mut findings = tokio::task::spawn_blocking(move || lint_gd_scripts(&root))
+        .await
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if let Some(min) = req.min_severity {
+        findings.retain(|f| f.severity >= min);
+    }
+    let sarif = req.sarif.unwrap_or(false).then(|| {
+        let report = GodotProjectReport {
+            project_path: project.root.clone(),
+            issues: findings.iter().cloned().map(lint_finding_as_issue).collect(),
+            ..Default::default()
+        };
+        to_sarif(&report)
+    });
+    Ok(Json(LintResponse { findings, sarif }))
+}
+
+async fn build_plan(root: std::path::PathBuf) -> Result<FixPlan, ApiError> {
+    // A broken [structure] section is the caller's to fix, not a reason to fall back to the defaults
+    tokio::task::spawn_blocking(move || Layout::configured(&root).map(|layout| plan_structure_fix_with(&root, &layout)))
+        .await
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| api_error(StatusCode::BAD_REQUEST, format!("{:#}", e)))
+}
+
+async fn apply_structure(state: &ServerState, req: StructureApplyRequest) -> Result<Json<ApplySummary>, ApiError> {
+    require(state, Permission::FsWrite)?;
+    let project = resolve_project(state, req.project.as_deref())?;
+    // Re-plan and compare: the token confirms the caller reviewed exactly what will be moved
+    let plan = build_plan(project.root.clone()).await?;
+    if plan_token(&plan) != req.token {
+        return Err(api_error(StatusCode::CONFLICT, "plan token mismatch; request a fresh plan and confirm it"));
+    }
+    let plan = req.selection.select(&plan).map_err(|e| api_error(StatusCode::BAD_REQUEST, format!("{:#}", e)))?;
+    let root = project.root.clone();
+    let summary = tokio::task::spawn_blocking(move || apply_structure_fix(&root, &plan))
+        .await
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(summary))
+}
+
+async fn check(state: &ServerState, req: CheckRequest) -> Result<Json<CheckResponse>, ApiError> {
+    let project = resolve_project(state, req.project.as_deref())?;
+    let cfg = state.godot_config();
+    let bin = cfg.binary.clone()
+        .ok_or_else(|| api_error(StatusCode::SERVICE_UNAVAILABLE, "no Godot binary configured (godot.binary or --godot-bin)"))?;
+    let script = match req.script.as_deref() {
+        Some(s) => {
+            let rel = project_relative_path(s)?;
+            if !project.root.join(&rel).is_file() {
+                return Err(api_error(StatusCode::NOT_FOUND, format!("script not found: {}", s)));
+            }
+            Some(format!("res://{}", rel.to_string_lossy().replace('\\', "/")))
+        }
+        None => None,
+    };
+    let args = headless_args(&project.root, script.as_deref());
+    let (exit_code, timed_out, output) = run_engine(&bin, &args, Duration::from_secs(cfg.timeout_secs)).await?;
+    let issues = parse_godot_output(&output);
+    let mut command = vec![bin.to_string_lossy().to_string()];
+    command.extend(args);
+    Ok(Json(CheckResponse { command, exit_code, timed_out, issues, output: tail(output, MAX_ENGINE_OUTPUT) }))
+}
+
+async fn export(state: &ServerState, req: ExportRequest) -> Result<Response, ApiError> {
+    require(state, Permission::FsWrite)?;
+    let project = resolve_project(state, req.project.as_deref())?;
+    let cfg = state.godot_config();
+    let bin = cfg.binary.clone()
+        .ok_or_else(|| api_error(StatusCode::SERVICE_UNAVAILABLE, "no Godot binary configured (godot.binary or --godot-bin)"))?;
+    let presets = read_export_presets(&project.root)
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, format!("export_presets.cfg: {}", e)))?;
+    let preset = presets.into_iter().find(|p| p.name == req.preset)
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, format!("unknown export preset: {}", req.preset)))?;
+    let output = req.output.or(preset.export_path)
+        .ok_or_else(|| api_error(StatusCode::BAD_REQUEST, format!("preset '{}' has no export_path; pass output", preset.name)))?;
+    let rel = project_relative_path(&output)?;
+    let target = project.root.join(&rel);
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+
+    let args = export_args(&project.root, &preset.name, req.debug, &target);
+    let mut child = tokio::process::Command::new(&bin)
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, format!("failed to start {}: {}", bin.display(), e)))?;
+
+    let (tx, rx) = mpsc::channel::<ExportLine>(64);
+    let forwarders = [
+        child.stdout.take().map(|out| tokio::spawn(forward_lines(out, "stdout", tx.clone()))),
+        child.stderr.take().map(|err| tokio::spawn(forward_lines(err, "stderr", tx.clone()))),
+    ];
+    let timeout = Duration::from_secs(cfg.timeout_secs);
+    tokio::spawn(async move {
+        let waited = tokio::select! {
+            r = tokio::time::timeout(timeout, child.wait()) => r,
+            // Client disconnected: dropping the child kills the export
+            _ = tx.closed() => return,
+        };
+        let (exit_code, timed_out) = match waited {
+            Ok(Ok(status)) => (status.code(), false),
+            Ok(Err(_)) => (None, false),
+            Err(_) => { let _ = child.kill().await; (None, true) }
+        };
+        for f in forwarders.into_iter().flatten() { let _ = f.await; }
+        let artifact_bytes = std::fs::metadata(&target).ok().map(|m| m.len());
+        let _ = tx.send(ExportLine::End {
+            exit_code,
+            timed_out,
+            artifact: rel.to_string_lossy().replace('\\', "/"),
+            artifact_exists: artifact_bytes.is_some(),
+            artifact_bytes,
+        }).await;
+    });
+    Ok(ndjson_response(rx))
+}
+
+async fn forward_lines(pipe: impl AsyncRead + Unpin, stream: &'static str, tx: mpsc::Sender<ExportLine>) {
+    let mut lines = BufReader::new(pipe).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if tx.send(ExportLine::Log { stream, line }).await.is_err() { break; }
+    }
+}
+
+/// Run the engine to completion (or until `timeout`), returning exit code, whether it
+/// timed out, and stdout followed by stderr. Output is collected as it arrives, so a run
+/// that times out still returns what it printed.
+async fn run_engine(bin: &Path, args: &[String], timeout: Duration) -> Result<(Option<i32>, bool, String), ApiError> {
+    let mut child = tokio::process::Command::new(bin)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, format!("failed to start {}: {}", bin.display(), e)))?;
+    let (stdout, stderr) = (Arc::new(Mutex::new(Vec::new())), Arc::new(Mutex::new(Vec::new())));
+    let readers = [
+        child.stdout.take().map(|out| tokio::spawn(collect_output(out, stdout.clone()))),
+        child.stderr.take().map(|err| tokio::spawn(collect_output(err, stderr.clone()))),
+    ];
+    let (exit_code, timed_out) = match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(Ok(status)) => {
+            for r in readers.into_iter().flatten() { let _ = r.await; }
+            (status.code(), false)
+        }
+        Ok(Err(e)) => return Err(api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        Err(_) => {
+            let _ = child.kill().await;
+            // Children of the engine may still hold the pipes open; keep what has arrived
+            for r in readers.into_iter().flatten() { r.abort(); }
+            (None, true)
+        }
+    };
+    let mut text = String::from_utf8_lossy(&stdout.lock().unwrap()).into_owned();
+    text.push_str(&String::from_utf8_lossy(&stderr.lock().unwrap()));
+    Ok((exit_code, timed_out, text))
+}
+
+async fn collect_output(mut pipe: impl AsyncRead + Unpin, buf: Arc<Mutex<Vec<u8>>>) {
+    let mut chunk = [0u8; 8192];
+    while let Ok(n) = pipe.read(&mut chunk).await {
+        if n == 0 { break; }
+        buf.lock().unwrap().extend_from_slice(&chunk[..n]);
+    }
+}
+
+fn tail(mut s: String, max: usize) -> String {
+    if s.len() > max {
+        let mut cut = s.len() - max;
+        while !s.is_char_boundary(cut) { cut += 1; }
+        s.drain(..cut);
+    }
+    s
+}
+
+pub(crate) async fn analyze(state: &ServerState, req: AnalyzeRequest) -> Result<Json<AnalyzeResponse>, ApiError> {
+    let project = resolve_project(state, req.project.as_deref())?;
+    let root = project.root.clone();
+    // Walks the whole project tree; keep it off the async workers
+    let report = tokio::task::spawn_blocking(move || analyze_project(&root))
+        .await
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let sarif = req.sarif.unwrap_or(false).then(|| to_sarif(&report));
+    Ok(Json(AnalyzeResponse { report, sarif }))
+}
@@ -0,0 +1,202 @@
+// GraphQL: one request can search, read the top hits and pull report issues for them.
+use std::{path::Path, sync::{Arc, OnceLock}};
+
+use async_graphql::{ComplexObject, Context, EmptyMutation, EmptySubscription, Enum, ErrorExtensions, Object, Schema, SimpleObject};
+use axum::{routing::post, extract::State, http::StatusCode, Json, Router};
+use tokio::sync::OnceCell;
+
+use godot_analyzer::{analyze_project, script_lint::lint_gd_scripts};
+
+use crate::{api_error, fs_api, index_api, resolve_project, ApiError, AppRouter, ProjectState, ServerState};
+
+/// Deepest selection set accepted; the schema itself is at most four levels deep.
+const MAX_DEPTH: usize = 8;
+
+pub type ApiSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// The schema is stateless; server state travels with each request.
+fn schema() -> &'static ApiSchema {
+    static SCHEMA: OnceLock<ApiSchema> = OnceLock::new();
+    SCHEMA.get_or_init(|| Schema::build(QueryRoot, EmptyMutation, EmptySubscription).limit_depth(MAX_DEPTH).finish())
+}
+
+/// Schema definition (SDL), e.g. for client code generation.
+pub fn sdl() -> String {
+    schema().sdl()
+}
+
+/// Surface an HTTP-style error as a GraphQL error carrying the status under `extensions.status`.
+fn gql_error(e: ApiError) -> async_graphql::Error {
+    let (code, Json(body)) = e;
+    async_graphql::Error::new(body.error).extend_with(|_, ext| ext.set("status", code.as_u16()))
+}
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Severity { Info, Warn, Error }
+
+impl Severity {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self { Severity::Info => "info", Severity::Warn => "warn", Severity::Error => "error" }
+    }
+}
+
+impl From<godot_analyzer::Severity> for Severity {
+    fn from(s: godot_analyzer::Severity) -> Self {
+        match s {
+            godot_analyzer::Severity::Info => Severity::Info,
+            godot_analyzer::Severity::Warn => Severity::Warn,
+            godot_analyzer::Severity::Error => Severity::Error,
+        }
+    }
+}
+
+/// Analyzer or lint issue; `file` is root-relative with `/` separators.
+#[derive(SimpleObject, Clone)]
+pub(crate) struct Issue {
+    pub(crate) severity: Severity,
+    pub(crate) message: String,
+    pub(crate) file: Option<String>,
+    /// Lint rule code; null for analyzer issues
+    pub(crate) code: Option<String>,
+    /// 1-based line in `file`, when known
+    pub(crate) line: Option<i32>,
+}
+
+#[derive(SimpleObject)]
+struct Project { id: String, root: String, is_default: bool, watching: bool }
+
+#[derive(SimpleObject)]
+struct FileContent {
+    /// Total file size in bytes
+    size: u64,
+    content: String,
+    truncated: bool,
+}
+
+/// Report issues for one project, computed at most once per search.
+type IssueCache = Arc<OnceCell<Vec<Issue>>>;
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+struct Hit {
+    score: f32,
+    path: String,
+    kind: String,
+    snippet: Option<String>,
+    #[graphql(skip)]
+    project: Arc<ProjectState>,
+    #[graphql(skip)]
+    issues: IssueCache,
+}
+
+#[ComplexObject]
+impl Hit {
+    /// File contents (1-based inclusive line range; whole file when omitted), capped like /fs/read.
+    async fn content(&self, ctx: &Context<'_>, start_line: Option<usize>, end_line: Option<usize>) -> async_graphql::Result<FileContent> {
+        let state = ctx.data::<Arc<ServerState>>()?;
+        let req = fs_api::ReadRequest {
+            project: Some(self.project.id.clone()),
+            path: self.path.clone(),
+            start_line,
+            end_line,
+            ..Default::default()
+        };
+        let Json(resp) = fs_api::read_file(state, req).await.map_err(gql_error)?;
+        Ok(FileContent { size: resp.size as u64, content: resp.content, truncated: resp.truncated })
+    }
+
+    /// Project report (analyzer and lint) issues for this file.
+    async fn issues(&self, min_severity: Option<Severity>) -> async_graphql::Result<Vec<Issue>> {
+        let root = self.project.root.clone();
+        let all = self.issues.get_or_try_init(|| project_issues(root)).await.map_err(gql_error)?;
+        let path = normalize(&self.path);
+        Ok(all.iter()
+            .filter(|i| i.file.as_deref() == Some(path.as_str()))
+            .filter(|i| min_severity.is_none_or(|min| rank(i.severity) >= rank(min)))
+            .cloned()
+            .collect())
+    }
+}
+
+fn rank(s: Severity) -> u8 {
+    match s { Severity::Info => 0, Severity::Warn => 1, Severity::Error => 2 }
+}
+
+/// `./a/b.gd`, `res://a/b.gd` and `a\b.gd` all become `a/b.gd`.
+fn normalize(p: &str) -> String {
+    let p = p.strip_prefix("res://").or_else(|| p.strip_prefix("./")).unwrap_or(p);
+    p.replace('\\', "/")
+}
+
+fn rel(p: &Path) -> String {
+    normalize(&p.to_string_lossy())
+}
+
+/// Analyzer issues plus lint findings, walked on the blocking pool.
+pub(crate) async fn project_issues(root: std::path::PathBuf) -> Result<Vec<Issue>, ApiError> {
+    tokio::task::spawn_blocking(move || {
+        let report = analyze_project(&root).map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        let mut out: Vec<Issue> = report.issues.into_iter()
+            .map(|i| Issue { severity: i.severity.into(), message: i.message, file: i.file.as_deref().map(rel), code: i.code, line: i.line.map(|l| l as i32) })
+            .collect();
+        out.extend(lint_gd_scripts(&root).into_iter().map(|f| Issue {
+            severity: f.severity.into(),
+            message: f.message,
+            file: Some(rel(&f.file)),
+            code: Some(f.code),
+            line: f.line.map(|l| l as i32),
+        }));
+        Ok(out)
+    })
+    .await
+    .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn projects(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Project>> {
+        let state = ctx.data::<Arc<ServerState>>()?;
+        Ok(index_api::list_projects(state).await
+            .into_iter()
+            .map(|p| Project { id: p.id, root: p.root, is_default: p.default, watching: p.watching })
+            .collect())
+    }
+
+    /// Ranked search (same rules as /index/query/advanced); select `content` and `issues` on hits as needed.
+    async fn search(
+        &self,
+        ctx: &Context<'_>,
+        q: String,
+        limit: Option<usize>,
+        kind: Option<String>,
+        #[graphql(default)] snippet: bool,
+        project: Option<String>,
+    ) -> async_graphql::Result<Vec<Hit>> {
+        let state = ctx.data::<Arc<ServerState>>()?;
+        let resolved = resolve_project(state, project.as_deref()).map_err(gql_error)?;
+        let req = index_api::QueryAdvancedRequest { q, kind, limit, snippet: Some(snippet), project };
+        let hits = index_api::run_query_advanced(state, req).await.map_err(gql_error)?;
+        let issues = IssueCache::default();
+        Ok(hits.into_iter()
+            .map(|h| Hit {
+                score: h.score,
+                path: h.path,
+                kind: h.kind,
+                snippet: h.snippet,
+                project: resolved.clone(),
+                issues: issues.clone(),
+            })
+            .collect())
+    }
+}
+
+pub fn routes() -> AppRouter {
+    Router::new()
+        .route("/graphql", post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<async_graphql::Request>| async move {
+                Json(schema().execute(req.data(state)).await)
+            }
+        ).get(|| async { sdl() }))
+}
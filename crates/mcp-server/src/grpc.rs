@@ -0,0 +1,321 @@
+// gRPC listener: typed protobuf mirror of the index, context and godot routes (proto/godot_mcp.proto).
+use std::{collections::BTreeMap, pin::Pin, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use axum::{http::StatusCode, Json};
+use tokio::{net::TcpListener, sync::mpsc};
+use tokio_stream::{wrappers::{ReceiverStream, TcpListenerStream}, Stream};
+use context::{BundleManifest, ContentMode, KnownFile, ManifestEntry, WeightedQuery};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+use crate::{
+    bundle_api::{self, BundleItemDto, StreamLine},
+    godot_api, index_api,
+    jobs::{JobState, JobStatus},
+    sessions::{ItemStatus, SessionMode},
+    ApiError, ServerState,
+};
+
+/// Generated messages, clients and servers for package `godot_mcp.v1`.
+pub mod proto {
+    #![allow(clippy::all)]
+    tonic::include_proto!("godot_mcp.v1");
+}
+
+use proto::{
+    bundle_event, context_service_server::{ContextService, ContextServiceServer},
+    godot_service_server::{GodotService, GodotServiceServer}, index_service_server::{IndexService, IndexServiceServer},
+};
+
+/// How often `IndexService/Scan` samples job progress.
+const JOB_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+type GrpcStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+
+/// Serve the gRPC services on `listener` until it fails.
+pub async fn serve_grpc(listener: TcpListener, state: Arc<ServerState>) -> Result<()> {
+    info!(addr=%listener.local_addr()?, "Listening for gRPC");
+    let service = GrpcService { state };
+    tonic::transport::Server::builder()
+        .add_service(IndexServiceServer::new(service.clone()))
+        .add_service(ContextServiceServer::new(service.clone()))
+        .add_service(GodotServiceServer::new(service))
+        .serve_with_incoming(TcpListenerStream::new(listener))
+        .await?;
+    Ok(())
+}
+
+/// Implements every service over the shared server state.
+#[derive(Clone)]
+struct GrpcService {
+    state: Arc<ServerState>,
+}
+
+/// Map an HTTP error onto the closest gRPC code, keeping the message.
+fn status(e: ApiError) -> Status {
+    let (code, Json(body)) = e;
+    match code {
+        StatusCode::BAD_REQUEST => Status::invalid_argument(body.error),
+        StatusCode::FORBIDDEN => Status::permission_denied(body.error),
+        StatusCode::NOT_FOUND => Status::not_found(body.error),
+        StatusCode::CONFLICT => Status::failed_precondition(body.error),
+        StatusCode::SERVICE_UNAVAILABLE => Status::unavailable(body.error),
+        _ => Status::internal(body.error),
+    }
+}
+
+fn job(j: JobStatus) -> proto::Job {
+    let state = match j.state {
+        JobState::Running => proto::JobState::Running,
+        JobState::Completed => proto::JobState::Completed,
+        JobState::Failed => proto::JobState::Failed,
+    };
+    proto::Job {
+        id: j.id,
+        kind: j.kind.to_string(),
+        project: j.project,
+        state: state.into(),
+        total: j.total.map(|t| t as u64),
+        processed: j.processed as u64,
+        indexed: j.indexed as u64,
+        errors: j.errors,
+        started_at: j.started_at,
+        finished_at: j.finished_at,
+    }
+}
+
+fn item_status(s: Option<ItemStatus>) -> i32 {
+    match s {
+        None => proto::ItemStatus::Unspecified,
+        Some(ItemStatus::New) => proto::ItemStatus::New,
+        Some(ItemStatus::Changed) => proto::ItemStatus::Changed,
+    }
+    .into()
+}
+
+fn bundle_item(it: BundleItemDto) -> proto::BundleItem {
+    proto::BundleItem {
+        path: it.path,
+        kind: it.kind,
+        score: it.score,
+        content: it.content,
+        status: item_status(it.status),
+        start_line: it.start_line.map(|l| l as u64),
+        end_line: it.end_line.map(|l| l as u64),
+        hash: it.hash,
+        bytes: it.bytes.map(|b| b as u64),
+        tokens_estimate: it.tokens_estimate.map(|t| t as u64),
+    }
+}
+
+fn severity(s: godot_analyzer::Severity) -> i32 {
+    match s {
+        godot_analyzer::Severity::Info => proto::Severity::Info,
+        godot_analyzer::Severity::Warn => proto::Severity::Warn,
+        godot_analyzer::Severity::Error => proto::Severity::Error,
+    }
+    .into()
+}
+
+#[tonic::async_trait]
+impl IndexService for GrpcService {
+    type ScanStream = GrpcStream<proto::Job>;
+
+    async fn list_projects(&self, _: Request<proto::ListProjectsRequest>) -> Result<Response<proto::ListProjectsResponse>, Status> {
+        let projects = index_api::list_projects(&self.state).await
+            .into_iter()
+            .map(|p| proto::Project { id: p.id, root: p.root, is_default: p.default, watching: p.watching })
+            .collect();
+        Ok(Response::new(proto::ListProjectsResponse { projects }))
+    }
+
+    async fn query(&self, req: Request<proto::QueryRequest>) -> Result<Response<proto::QueryResponse>, Status> {
+        let req = req.into_inner();
+        let req = index_api::QueryRequest { q: req.q, limit: req.limit.map(|l| l as usize), project: req.project };
+        let Json(resp) = index_api::run_query(&self.state, req).await.map_err(status)?;
+        let hits = resp.hits.into_iter().map(|h| proto::Hit { score: h.score, path: h.path }).collect();
+        Ok(Response::new(proto::QueryResponse { hits }))
+    }
+
+    async fn query_advanced(&self, req: Request<proto::AdvancedQueryRequest>) -> Result<Response<proto::AdvancedQueryResponse>, Status> {
+        let req = req.into_inner();
+        let req = index_api::QueryAdvancedRequest {
+            q: req.q,
+            kind: req.kind,
+            limit: req.limit.map(|l| l as usize),
+            snippet: Some(req.snippet),
+            project: req.project,
+        };
+        let hits = index_api::run_query_advanced(&self.state, req).await.map_err(status)?
+            .into_iter()
+            .map(|h| proto::AdvancedHit { score: h.score, path: h.path, kind: h.kind, snippet: h.snippet })
+            .collect();
+        Ok(Response::new(proto::AdvancedQueryResponse { hits }))
+    }
+
+    async fn scan(&self, req: Request<proto::ScanRequest>) -> Result<Response<Self::ScanStream>, Status> {
+        let req = req.into_inner();
+        let job_id = index_api::start_scan(&self.state, index_api::ScanRequest { path: req.path, project: req.project })
+            .map_err(status)?;
+        let state = self.state.clone();
+        let (tx, rx) = mpsc::channel(4);
+        tokio::spawn(async move {
+            // Send a snapshot whenever progress moves, then the final state
+            let mut last = None;
+            while let Some(j) = state.jobs().get(job_id) {
+                let done = j.state != JobState::Running;
+                let progress = (j.total, j.processed);
+                if last != Some(progress) || done {
+                    last = Some(progress);
+                    if tx.send(Ok(job(j))).await.is_err() || done {
+                        return;
+                    }
+                }
+                tokio::time::sleep(JOB_POLL_INTERVAL).await;
+            }
+        });
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    async fn get_job(&self, req: Request<proto::GetJobRequest>) -> Result<Response<proto::Job>, Status> {
+        let id = req.into_inner().id;
+        self.state.jobs().get(id)
+            .map(|j| Response::new(job(j)))
+            .ok_or_else(|| Status::not_found(format!("unknown job: {}", id)))
+    }
+}
+
+/// Fails (with the message for `invalid_argument`) on an unknown `content_mode`, bad `quotas` or `exclude` globs.
+fn bundle_request(req: proto::BundleRequest) -> Result<bundle_api::BundleRequest, String> {
+    let session_mode = match req.session_mode() {
+        proto::SessionMode::Exclude => SessionMode::Exclude,
+        proto::SessionMode::Diff => SessionMode::Diff,
+    };
+    let content_mode = match req.content_mode.as_deref() {
+        Some(m) => m.parse()?,
+        None => ContentMode::default(),
+    };
+    let quotas = req.quotas.into_iter().collect::<BTreeMap<_, _>>().try_into()?;
+    let exclude = req.exclude.try_into()?;
+    Ok(bundle_api::BundleRequest {
+        q: Some(req.q),
+        queries: req.queries.into_iter().map(|wq| WeightedQuery { q: wq.q, weight: wq.weight.unwrap_or(1.0) }).collect(),
+        limit: req.limit.map(|l| l as usize),
+        cap_bytes: req.cap_bytes.map(|c| c as usize),
+        kind: req.kind,
+        project: req.project,
+        session: req.session,
+        session_mode,
+        content_mode,
+        diversity: req.diversity,
+        recency: req.recency,
+        quotas,
+        exclude,
+        expand: req.expand.unwrap_or_default(),
+        known_files: req.known_files.into_iter().map(|f| KnownFile { path: f.path, hash: f.hash }).collect::<Vec<_>>().into(),
+        manifest: req.manifest.map(|m| BundleManifest {
+            generation: m.generation,
+            items: m.items.into_iter()
+                .map(|e| ManifestEntry {
+                    path: e.path, start_line: e.start_line.map(|l| l as usize), end_line: e.end_line.map(|l| l as usize), hash: e.hash,
+                })
+                .collect(),
+        }),
+        format: None,
+    })
+}
+
+#[tonic::async_trait]
+impl ContextService for GrpcService {
+    type BundleStreamStream = GrpcStream<proto::BundleEvent>;
+
+    async fn bundle(&self, req: Request<proto::BundleRequest>) -> Result<Response<proto::BundleResponse>, Status> {
+        let Json(resp) = bundle_api::run_bundle(&self.state, bundle_request(req.into_inner()).map_err(Status::invalid_argument)?).await.map_err(status)?;
+        Ok(Response::new(proto::BundleResponse {
+            query: resp.query,
+            items: resp.items.into_iter().map(bundle_item).collect(),
+            size_bytes: resp.size_bytes as u64,
+            skipped: resp.skipped,
+            generation: resp.generation,
+            content_bytes: resp.content_bytes as u64,
+            tokens_estimate: resp.tokens_estimate as u64,
+        }))
+    }
+
+    async fn bundle_stream(&self, req: Request<proto::BundleRequest>) -> Result<Response<Self::BundleStreamStream>, Status> {
+        let mut lines = bundle_api::bundle_lines(&self.state, bundle_request(req.into_inner()).map_err(Status::invalid_argument)?).map_err(status)?;
+        let (tx, rx) = mpsc::channel(4);
+        tokio::spawn(async move {
+            while let Some(line) = lines.recv().await {
+                let event = match line {
+                    StreamLine::Item { item, status } => Ok(bundle_event::Event::Item(proto::BundleItem {
+                        path: item.path, kind: item.kind, score: item.score, content: item.content, status: item_status(status),
+                        start_line: item.start_line.map(|l| l as u64), end_line: item.end_line.map(|l| l as u64), hash: item.hash,
+                        bytes: item.bytes.map(|b| b as u64), tokens_estimate: item.tokens_estimate.map(|t| t as u64),
+                    })),
+                    StreamLine::End { query, count, size_bytes, generation, content_bytes, tokens_estimate, skipped } => {
+                        Ok(bundle_event::Event::End(proto::BundleEnd {
+                            query, count: count as u64, size_bytes: size_bytes as u64, skipped, generation,
+                            content_bytes: content_bytes as u64, tokens_estimate: tokens_estimate as u64,
+                        }))
+                    }
+                    StreamLine::Error { error } => Err(Status::internal(error)),
+                };
+                let msg = event.map(|e| proto::BundleEvent { event: Some(e) });
+                if tx.send(msg).await.is_err() {
+                    return;
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+}
+
+#[tonic::async_trait]
+impl GodotService for GrpcService {
+    async fn analyze(&self, req: Request<proto::AnalyzeRequest>) -> Result<Response<proto::AnalyzeResponse>, Status> {
+        let req = godot_api::AnalyzeRequest { project: req.into_inner().project, sarif: None };
+        let Json(resp) = godot_api::analyze(&self.state, req).await.map_err(status)?;
+        let report = resp.report;
+        Ok(Response::new(proto::AnalyzeResponse {
+            project_path: report.project_path.display().to_string(),
+            project_format_version: report.project_format_version,
+            addons: report.addons,
+            features: report.features,
+            export_presets: report.export_presets.into_iter().map(|p| p.name).collect(),
+            issues: report.issues.into_iter()
+                .map(|i| proto::Issue {
+                    severity: severity(i.severity),
+                    message: i.message,
+                    file: i.file.map(|f| f.display().to_string()),
+                    code: i.code,
+                    line: i.line.map(|l| l as u32),
+                    column: i.column.map(|c| c as u32),
+                })
+                .collect(),
+        }))
+    }
+
+    async fn lint(&self, req: Request<proto::LintRequest>) -> Result<Response<proto::LintResponse>, Status> {
+        let req = req.into_inner();
+        let min_severity = req.min_severity.map(|_| match req.min_severity() {
+            proto::Severity::Info => godot_analyzer::Severity::Info,
+            proto::Severity::Warn => godot_analyzer::Severity::Warn,
+            proto::Severity::Error => godot_analyzer::Severity::Error,
+        });
+        let req = godot_api::LintRequest { project: req.project, min_severity, sarif: None };
+        let Json(resp) = godot_api::lint(&self.state, req).await.map_err(status)?;
+        let findings = resp.findings.into_iter()
+            .map(|f| proto::Issue {
+                severity: severity(f.severity),
+                message: f.message,
+                file: Some(f.file.display().to_string()),
+                code: Some(f.code),
+                line: f.line.map(|l| l as u32),
+                column: f.column.map(|c| c as u32),
+            })
+            .collect();
+        Ok(Response::new(proto::LintResponse { findings }))
+    }
+}
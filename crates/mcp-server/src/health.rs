@@ -0,0 +1,101 @@
+// Server health: version, uptime, per-project watcher/index state and a config summary.
+use std::{path::PathBuf, sync::Arc};
+
+use axum::{routing::get, extract::State, Json, Router};
+use serde::Serialize;
+
+use crate::{AppRouter, Permission, ProjectState, ServerState};
+
+#[derive(Serialize)]
+struct HealthResponse {
+    status: &'static str,
+    version: &'static str,
+    uptime_secs: u64,
+    /// Root of the default project
+    workspace_root: PathBuf,
+    projects: Vec<ProjectHealth>,
+    config: ConfigSummary,
+}
+
+#[derive(Serialize)]
+struct ProjectHealth {
+    id: String,
+    root: PathBuf,
+    watching: bool,
+    /// `None` when the index can't be read
+    docs: Option<u64>,
+    segments: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct ConfigSummary {
+    host: Option<String>,
+    port: Option<u16>,
+    socket_path: Option<PathBuf>,
+    editor_port: Option<u16>,
+    grpc_port: Option<u16>,
+    auto_start_watchers: Option<bool>,
+    godot_binary: Option<PathBuf>,
+    godot_timeout_secs: u64,
+    webhooks: usize,
+    permissions: Permissions,
+}
+
+/// Effective `server.allow_*` flags.
+#[derive(Serialize)]
+struct Permissions {
+    scan: bool,
+    watch_control: bool,
+    fs_write: bool,
+    config_write: bool,
+}
+
+async fn project_health(p: &ProjectState) -> ProjectHealth {
+    let counts = p.index.reader().health().ok();
+    ProjectHealth {
+        id: p.id.clone(),
+        root: p.root.clone(),
+        watching: p.watcher_running().await,
+        docs: counts.map(|(d, _)| d),
+        segments: counts.map(|(_, s)| s),
+    }
+}
+
+pub fn routes() -> AppRouter {
+    Router::new().route("/health", get(
+        |State(state): State<Arc<ServerState>>| async move {
+            let mut projects = Vec::new();
+            for p in state.registry().projects() {
+                projects.push(project_health(p).await);
+            }
+            let workspace_root = state.registry().get(None).map(|p| p.root.clone()).unwrap_or_default();
+            let server = state.server_config();
+            let server = server.as_ref();
+            let godot = state.godot_config();
+            Json(HealthResponse {
+                status: "ok",
+                version: env!("CARGO_PKG_VERSION"),
+                uptime_secs: state.uptime().as_secs(),
+                workspace_root,
+                projects,
+                config: ConfigSummary {
+                    host: server.map(|s| s.host.clone()),
+                    port: server.map(|s| s.port),
+                    socket_path: server.and_then(|s| s.socket_path.clone()),
+                    editor_port: server.and_then(|s| s.editor_port),
+                    grpc_port: server.and_then(|s| s.grpc_port),
+                    auto_start_watchers: server.map(|s| s.auto_start_watchers),
+                    godot_binary: godot.binary.clone(),
+                    godot_timeout_secs: godot.timeout_secs,
+                    webhooks: state.webhooks().list().len(),
+                    permissions: Permissions {
+                        scan: state.allows(Permission::Scan),
+                        watch_control: state.allows(Permission::WatchControl),
+                        fs_write: state.allows(Permission::FsWrite),
+                        config_write: state.allows(Permission::ConfigWrite),
+                    },
+                },
+            })
+        }
+    ))
+}
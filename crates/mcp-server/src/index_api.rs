@@ -0,0 +1,219 @@
+// Index routes: projects, query/scan/reset/watch/health/history, jobs and editor state.
+use std::{path::Path, sync::Arc, time::Instant};
+
+use axum::{routing::{get, post}, extract::{Path as UrlPath, Query, State}, http::StatusCode, Json, Router};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api_error, bounded, check_query, jobs, require, resolve_project, sandboxed_path, ApiError, AppRouter, Permission,
+    ProjectState, ServerState, MAX_LIMIT, query_history::{QueryRecord, QuerySummary, MAX_QUERY_HISTORY},
+};
+
+#[derive(Deserialize)]
+struct ProjectQuery { project: Option<String> }
+#[derive(Deserialize)]
+pub(crate) struct QueryRequest { pub q: String, pub limit: Option<usize>, pub project: Option<String> }
+#[derive(Serialize)]
+pub(crate) struct Hit { pub score: f32, pub path: String }
+#[derive(Serialize)]
+pub(crate) struct QueryResponse { pub hits: Vec<Hit> }
+#[derive(Deserialize)]
+pub(crate) struct ScanRequest { pub path: Option<String>, pub project: Option<String> }
+#[derive(Deserialize)]
+pub(crate) struct QueryAdvancedRequest {
+    pub q: String, pub kind: Option<String>, pub limit: Option<usize>, pub snippet: Option<bool>, pub project: Option<String>,
+}
+#[derive(Serialize)]
+pub(crate) struct HitAdv { pub score: f32, pub path: String, pub kind: String, pub snippet: Option<String> }
+#[derive(Serialize)]
+struct HealthResponse { docs: u64, segments: usize }
+#[derive(Serialize)]
+struct ScanResponse { job_id: u64, status_url: String }
+#[derive(Serialize)]
+struct WatchResponse { status: &'static str }
+#[derive(Deserialize)]
+struct ResetRequest { project: Option<String>, #[serde(default)] scan: bool }
+#[derive(Serialize)]
+struct ResetResponse {
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scan_job: Option<u64>,
+}
+#[derive(Deserialize)]
+struct HistoryRequest { project: Option<String>, limit: Option<usize> }
+#[derive(Serialize)]
+struct HistoryResponse { entries: Vec<QueryRecord>, summary: QuerySummary }
+#[derive(Serialize)]
+pub(crate) struct ProjectInfo { pub id: String, pub root: String, pub default: bool, pub watching: bool }
+
+/// Scan job body (runs on the blocking pool). Files are sent to the index actor in batches,
+/// so watcher commits interleave and queries never wait on the scan.
+fn run_scan_job(state: &ServerState, project: &ProjectState, root: &Path, job_id: u64) {
+    let jobs = state.jobs();
+    let files = index::collect_files(root);
+    jobs.update(job_id, |job| job.total = Some(files.len()));
+    for batch in files.chunks(jobs::SCAN_BATCH_SIZE) {
+        let result = project.index.blocking_index_paths(batch.to_vec());
+        match result {
+            Ok((indexed, errors)) => jobs.update(job_id, |job| {
+                job.processed += batch.len();
+                job.indexed += indexed;
+                job.errors.extend(errors);
+            }),
+            Err(e) => return jobs.finish(job_id, Some(e.to_string())),
+        }
+    }
+    jobs.finish(job_id, None);
+}
+
+pub(crate) async fn list_projects(state: &ServerState) -> Vec<ProjectInfo> {
+    let mut out = Vec::new();
+    for p in state.registry().projects() {
+        out.push(ProjectInfo {
+            id: p.id.clone(),
+            root: p.root.display().to_string(),
+            default: p.id == state.registry().default_id(),
+            watching: p.watcher_running().await,
+        });
+    }
+    out
+}
+
+pub(crate) async fn run_query(state: &ServerState, req: QueryRequest) -> Result<Json<QueryResponse>, ApiError> {
+    let project = resolve_project(state, req.project.as_deref())?;
+    check_query(&req.q)?;
+    let limit = bounded("limit", req.limit, 10, MAX_LIMIT)?;
+    let started = Instant::now();
+    let hits: Vec<Hit> = project.index.reader().query(&req.q, limit).unwrap_or_default()
+        .into_iter()
+        .map(|(score, path)| Hit { score, path })
+        .collect();
+    state.history().record("query", &project.id, &req.q, None, hits.len(), started.elapsed());
+    Ok(Json(QueryResponse { hits }))
+}
+
+pub(crate) async fn run_query_advanced(state: &ServerState, req: QueryAdvancedRequest) -> Result<Vec<HitAdv>, ApiError> {
+    let project = resolve_project(state, req.project.as_deref())?;
+    check_query(&req.q)?;
+    let limit = bounded("limit", req.limit, 10, MAX_LIMIT)?;
+    let started = Instant::now();
+    let with_snippet = req.snippet.unwrap_or(false);
+    let hits = project.index.reader()
+        .query_filtered(&req.q, req.kind.as_deref(), limit, with_snippet)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(score, path, kind, snippet)| HitAdv { score, path, kind, snippet })
+        .collect::<Vec<_>>();
+    state.history().record("advanced", &project.id, &req.q, req.kind.as_deref(), hits.len(), started.elapsed());
+    Ok(hits)
+}
+
+/// Validate a scan request and start it as a background job; returns the job id.
+pub(crate) fn start_scan(state: &Arc<ServerState>, req: ScanRequest) -> Result<u64, ApiError> {
+    require(state, Permission::Scan)?;
+    let project = resolve_project(state, req.project.as_deref())?;
+    // Scans are confined to the project tree
+    let root_override = match req.path.as_deref() {
+        Some(p) => sandboxed_path(&project.root, p)?,
+        None => project.root.clone(),
+    };
+    if !root_override.is_dir() {
+        return Err(api_error(StatusCode::BAD_REQUEST, format!("not a directory: {}", req.path.unwrap_or_default())));
+    }
+    let job_id = state.jobs().create("scan", &project.id);
+    let state = state.clone();
+    tokio::task::spawn_blocking(move || run_scan_job(&state, &project, &root_override, job_id));
+    Ok(job_id)
+}
+
+pub fn routes() -> AppRouter {
+    Router::new()
+        .route("/projects", get(
+            |State(state): State<Arc<ServerState>>| async move { Json(list_projects(&state).await) }
+        ))
+        .route("/index/query", post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<QueryRequest>| async move {
+                run_query(&state, req).await
+            }
+        ))
+        .route("/index/query", get(
+            |State(state): State<Arc<ServerState>>, Query(req): Query<QueryRequest>| async move {
+                run_query(&state, req).await
+            }
+        ))
+        .route("/index/scan", post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<ScanRequest>| async move {
+                let job_id = start_scan(&state, req)?;
+                Ok::<_, ApiError>((StatusCode::ACCEPTED, Json(ScanResponse { job_id, status_url: format!("/jobs/{}", job_id) })))
+            }
+        ))
+        .route("/index/reset", post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<ResetRequest>| async move {
+                require(&state, Permission::Scan)?;
+                let project = resolve_project(&state, req.project.as_deref())?;
+                // Queued behind any pending watcher or scan writes; the watcher keeps running
+                project.index.reset()
+                    .await
+                    .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, format!("index reset failed: {}", e)))?;
+                let mut scan_job = None;
+                if req.scan {
+                    let job_id = state.jobs().create("scan", &project.id);
+                    let (st, p) = (state.clone(), project.clone());
+                    tokio::task::spawn_blocking(move || run_scan_job(&st, &p, &p.root, job_id));
+                    scan_job = Some(job_id);
+                }
+                Ok::<_, ApiError>(Json(ResetResponse { status: "reset", scan_job }))
+            }
+        ))
+        .route("/editor/state", get(
+            |State(state): State<Arc<ServerState>>| async move { Json(state.editor().snapshot()) }
+        ))
+        .route("/jobs/:id", get(
+            |State(state): State<Arc<ServerState>>, UrlPath(id): UrlPath<u64>| async move {
+                state.jobs().get(id)
+                    .map(Json)
+                    .ok_or_else(|| api_error(StatusCode::NOT_FOUND, format!("unknown job: {}", id)))
+            }
+        ))
+        .route("/index/query/advanced", post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<QueryAdvancedRequest>| async move {
+                run_query_advanced(&state, req).await.map(Json)
+            }
+        ))
+        .route("/index/query/history", get(
+            |State(state): State<Arc<ServerState>>, Query(req): Query<HistoryRequest>| async move {
+                // Omitted project means all projects; an explicit one must exist
+                let project = match req.project.as_deref() {
+                    Some(id) => Some(resolve_project(&state, Some(id))?.id.clone()),
+                    None => None,
+                };
+                let limit = bounded("limit", req.limit, 50, MAX_QUERY_HISTORY)?;
+                let history = state.history();
+                Ok::<_, ApiError>(Json(HistoryResponse {
+                    entries: history.recent(project.as_deref(), limit),
+                    summary: history.summary(project.as_deref()),
+                }))
+            }
+        ))
+        .route("/index/watch/start", post(
+            |State(state): State<Arc<ServerState>>, Query(pq): Query<ProjectQuery>| async move {
+                require(&state, Permission::WatchControl)?;
+                let project = resolve_project(&state, pq.project.as_deref())?;
+                Ok::<_, ApiError>(Json(WatchResponse { status: project.start_watcher().await }))
+            }
+        ))
+        .route("/index/watch/stop", post(
+            |State(state): State<Arc<ServerState>>, Query(pq): Query<ProjectQuery>| async move {
+                require(&state, Permission::WatchControl)?;
+                let project = resolve_project(&state, pq.project.as_deref())?;
+                Ok::<_, ApiError>(Json(WatchResponse { status: project.stop_watcher().await }))
+            }
+        ))
+        .route("/index/health", get(
+            |State(state): State<Arc<ServerState>>, Query(pq): Query<ProjectQuery>| async move {
+                let project = resolve_project(&state, pq.project.as_deref())?;
+                let (docs, segments) = project.index.reader().health().unwrap_or((0,0));
+                Ok::<_, ApiError>(Json(HealthResponse { docs, segments }))
+            }
+        ))
+}
@@ -0,0 +1,104 @@
+// Index actor: one thread owns each project's writer and applies commands in order;
+// queries go straight to a cloned `SearchReader` and never wait on writes.
+use std::{path::PathBuf, thread};
+
+use anyhow::{anyhow, Result};
+use index::{ChangeBatch, ChangedPath, SearchIndex, SearchReader};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::jobs::SCAN_BATCH_SIZE;
+
+/// Writes queued per index before senders wait for the actor to catch up.
+const COMMAND_CAPACITY: usize = 64;
+
+type Reply<T> = oneshot::Sender<Result<T>>;
+
+enum Command {
+    IndexPaths(Vec<PathBuf>, Reply<(usize, Vec<String>)>),
+    ApplyChanges(ChangeBatch, Reply<Vec<ChangedPath>>),
+    Reset(Reply<()>),
+    SetExtraSkips(Vec<String>, Reply<()>),
+}
+
+/// Handle to a project's index actor. Cheap to clone; the actor stops when every handle is dropped.
+#[derive(Clone)]
+pub struct IndexHandle {
+    reader: SearchReader,
+    tx: mpsc::Sender<Command>,
+}
+
+impl IndexHandle {
+    /// Move `index` onto a dedicated writer thread. Panics if the thread can't be spawned.
+    pub fn spawn(mut index: SearchIndex) -> Self {
+        let reader = index.reader();
+        let (tx, mut rx) = mpsc::channel(COMMAND_CAPACITY);
+        thread::Builder::new().name("index-writer".into()).spawn(move || {
+            while let Some(cmd) = rx.blocking_recv() {
+                // A dropped reply just means the caller stopped waiting
+                match cmd {
+                    Command::IndexPaths(paths, reply) => { let _ = reply.send(index.index_paths(&paths)); }
+                    Command::ApplyChanges(batch, reply) => { let _ = reply.send(index.apply_changes(&batch)); }
+                    Command::Reset(reply) => { let _ = reply.send(index.reset()); }
+                    Command::SetExtraSkips(fragments, reply) => { index.set_extra_skips(fragments); let _ = reply.send(Ok(())); }
+                }
+            }
+        }).expect("failed to spawn index writer thread");
+        Self { reader, tx }
+    }
+
+    /// Read side; sees each write as soon as the actor commits it.
+    pub fn reader(&self) -> &SearchReader {
+        &self.reader
+    }
+
+    async fn call<T>(&self, cmd: impl FnOnce(Reply<T>) -> Command) -> Result<T> {
+        let (reply, rx) = oneshot::channel();
+        self.tx.send(cmd(reply)).await.map_err(|_| stopped())?;
+        rx.await.map_err(|_| stopped())?
+    }
+
+    /// `call` for the blocking pool (scan jobs, the watcher); panics inside an async context.
+    fn blocking_call<T>(&self, cmd: impl FnOnce(Reply<T>) -> Command) -> Result<T> {
+        let (reply, rx) = oneshot::channel();
+        self.tx.blocking_send(cmd(reply)).map_err(|_| stopped())?;
+        rx.blocking_recv().map_err(|_| stopped())?
+    }
+
+    /// Index `paths` in one commit; returns (indexed, per-file errors).
+    pub async fn index_paths(&self, paths: Vec<PathBuf>) -> Result<(usize, Vec<String>)> {
+        self.call(|r| Command::IndexPaths(paths, r)).await
+    }
+
+    pub fn blocking_index_paths(&self, paths: Vec<PathBuf>) -> Result<(usize, Vec<String>)> {
+        self.blocking_call(|r| Command::IndexPaths(paths, r))
+    }
+
+    /// Walk `root` and index it in `SCAN_BATCH_SIZE` commits so other writes can interleave.
+    pub async fn scan(&self, root: PathBuf) -> Result<usize> {
+        let files = tokio::task::spawn_blocking(move || index::collect_files(&root)).await?;
+        let mut indexed = 0;
+        for batch in files.chunks(SCAN_BATCH_SIZE) {
+            indexed += self.index_paths(batch.to_vec()).await?.0;
+        }
+        Ok(indexed)
+    }
+
+    /// Commit one watcher batch; returns the touched paths.
+    pub fn blocking_apply_changes(&self, batch: ChangeBatch) -> Result<Vec<ChangedPath>> {
+        self.blocking_call(|r| Command::ApplyChanges(batch, r))
+    }
+
+    /// Replace the configured skip fragments (`index.skip`) for later scans and watcher batches.
+    pub async fn set_extra_skips(&self, fragments: Vec<String>) -> Result<()> {
+        self.call(|r| Command::SetExtraSkips(fragments, r)).await
+    }
+
+    /// Wipe and recreate the index; readers follow the new index.
+    pub async fn reset(&self) -> Result<()> {
+        self.call(Command::Reset).await
+    }
+}
+
+fn stopped() -> anyhow::Error {
+    anyhow!("index writer stopped")
+}
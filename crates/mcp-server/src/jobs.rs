@@ -0,0 +1,93 @@
+// Background job table: long-running work (scans) reports progress here for /jobs/{id} polling.
+use std::{collections::BTreeMap, sync::{atomic::{AtomicU64, Ordering}, Mutex}, time::{SystemTime, UNIX_EPOCH}};
+
+use serde::Serialize;
+
+/// Files indexed per commit during a scan; watcher writes can interleave between batches.
+pub const SCAN_BATCH_SIZE: usize = 256;
+/// Finished jobs kept for polling; the oldest are dropped past this.
+pub const MAX_FINISHED_JOBS: usize = 100;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState { Running, Completed, Failed }
+
+/// Snapshot of a job as returned by `/jobs/{id}`.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStatus {
+    pub id: u64,
+    pub kind: &'static str,
+    pub project: String,
+    pub state: JobState,
+    /// Files discovered by the walk; `None` until the walk completes
+    pub total: Option<usize>,
+    pub processed: usize,
+    pub indexed: usize,
+    pub errors: Vec<String>,
+    /// Unix seconds
+    pub started_at: u64,
+    pub finished_at: Option<u64>,
+}
+
+/// In-memory job table. Running jobs are kept until they finish; after that only the
+/// `MAX_FINISHED_JOBS` most recent finished jobs stay available.
+#[derive(Default)]
+pub struct JobTable {
+    next_id: AtomicU64,
+    jobs: Mutex<BTreeMap<u64, JobStatus>>,
+}
+
+impl JobTable {
+    /// Register a new running job and return its id.
+    pub fn create(&self, kind: &'static str, project: &str) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let status = JobStatus {
+            id,
+            kind,
+            project: project.to_string(),
+            state: JobState::Running,
+            total: None,
+            processed: 0,
+            indexed: 0,
+            errors: Vec::new(),
+            started_at: unix_now(),
+            finished_at: None,
+        };
+        self.jobs.lock().unwrap().insert(id, status);
+        id
+    }
+
+    pub fn get(&self, id: u64) -> Option<JobStatus> {
+        self.jobs.lock().unwrap().get(&id).cloned()
+    }
+
+    /// Mutate a job in place; unknown ids are ignored.
+    pub fn update(&self, id: u64, f: impl FnOnce(&mut JobStatus)) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(&id) {
+            f(job);
+        }
+    }
+
+    /// Mark a job finished, failed if `error` is set, evicting the oldest finished jobs
+    /// past `MAX_FINISHED_JOBS`.
+    pub fn finish(&self, id: u64, error: Option<String>) {
+        let mut jobs = self.jobs.lock().unwrap();
+        let Some(job) = jobs.get_mut(&id) else { return };
+        job.state = if error.is_some() { JobState::Failed } else { JobState::Completed };
+        job.errors.extend(error);
+        job.finished_at = Some(unix_now());
+        let mut finished: Vec<(u64, u64)> = jobs.values()
+            .filter_map(|j| j.finished_at.map(|at| (at, j.id)))
+            .collect();
+        if finished.len() > MAX_FINISHED_JOBS {
+            finished.sort_unstable();
+            for (_, old) in &finished[..finished.len() - MAX_FINISHED_JOBS] {
+                jobs.remove(old);
+            }
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
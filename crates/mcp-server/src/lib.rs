@@ -1,180 +1,195 @@
 // Public factory for building the MCP server Router, reusable in tests.
-use std::{path::PathBuf, sync::{Arc, atomic::AtomicBool}};
+use std::{convert::Infallible, path::{Component, Path, PathBuf}, sync::Arc};
 
-use axum::{routing::{get, post}, extract::{Query, State}, Json, Router};
-use serde::{Deserialize, Serialize};
-use tokio::task::JoinHandle;
+use axum::{
+    body::{Body, Bytes}, extract::{DefaultBodyLimit, MatchedPath},
+    http::{header, Request, StatusCode}, response::{IntoResponse, Response}, Json, Router,
+};
+use futures_util::stream;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tower_http::{
+    compression::CompressionLayer,
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    trace::{DefaultOnResponse, TraceLayer},
+    LatencyUnit,
+};
+use tracing::{Level, Span};
 
 use index::SearchIndex;
 
-pub fn build_router(
-    shared_index: Arc<tokio::sync::Mutex<SearchIndex>>,
-    watcher_handle: Arc<tokio::sync::Mutex<Option<JoinHandle<()>>>>,
-    watcher_shutdown: Arc<AtomicBool>,
-    workspace_root: PathBuf,
-) -> Router {
-    // HTTP models
-    #[derive(Deserialize)]
-    struct QueryRequest { q: String, limit: Option<usize> }
-    #[derive(Serialize)]
-    struct Hit { score: f32, path: String }
-    #[derive(Serialize)]
-    struct QueryResponse { hits: Vec<Hit> }
-    #[derive(Deserialize)]
-    struct ScanRequest { path: Option<String> }
-    #[derive(Deserialize)]
-    struct QueryAdvancedRequest { q: String, kind: Option<String>, limit: Option<usize>, snippet: Option<bool> }
-    #[derive(Serialize)]
-    struct HitAdv { score: f32, path: String, kind: String, snippet: Option<String> }
-    #[derive(Serialize)]
-    struct HealthResponse { docs: u64, segments: usize }
-    #[derive(Serialize)]
-    struct ScanResponse { indexed: usize }
-    #[derive(Serialize)]
-    struct WatchResponse { status: &'static str }
-    #[derive(Deserialize)]
-    struct BundleRequest { q: String, limit: Option<usize>, cap_bytes: Option<usize>, kind: Option<String> }
-    #[derive(Serialize)]
-    struct BundleItemDto { path: String, kind: String, score: i32, content: String }
-    #[derive(Serialize)]
-    struct BundleResponse { query: String, items: Vec<BundleItemDto>, size_bytes: usize }
-
-    Router::new()
-        .route("/index/query", post({
-            let shared_index = shared_index.clone();
-            move |State(_): State<Arc<tokio::sync::Mutex<SearchIndex>>>, Json(req): Json<QueryRequest>| {
-                let shared_index = shared_index.clone();
-                async move {
-                    let guard = shared_index.lock().await;
-                    let limit = req.limit.unwrap_or(10).min(100).max(1);
-                    let hits = guard.query(&req.q, limit).unwrap_or_default()
-                        .into_iter()
-                        .map(|(score, path)| Hit { score, path })
-                        .collect();
-                    Json(QueryResponse { hits })
-                }
-            }
-        }))
-        .route("/index/query", get({
-            let shared_index = shared_index.clone();
-            move |State(_): State<Arc<tokio::sync::Mutex<SearchIndex>>>, Query(req): Query<QueryRequest>| {
-                let shared_index = shared_index.clone();
-                async move {
-                    let guard = shared_index.lock().await;
-                    let limit = req.limit.unwrap_or(10).min(100).max(1);
-                    let hits = guard.query(&req.q, limit).unwrap_or_default()
-                        .into_iter()
-                        .map(|(score, path)| Hit { score, path })
-                        .collect();
-                    Json(QueryResponse { hits })
-                }
-            }
-        }))
-        .route("/index/scan", post({
-            let shared_index = shared_index.clone();
-            let workspace_root = workspace_root.clone();
-            move |State(_): State<Arc<tokio::sync::Mutex<SearchIndex>>>, Json(req): Json<ScanRequest>| {
-                let shared_index = shared_index.clone();
-                let root_override = req.path.map(PathBuf::from).unwrap_or(workspace_root.clone());
-                async move {
-                    let mut guard = shared_index.lock().await;
-                    let n = guard.scan_and_index(&root_override).unwrap_or(0);
-                    Json(ScanResponse { indexed: n })
-                }
-            }
-        }))
-        .route("/index/query/advanced", post({
-            let shared_index = shared_index.clone();
-            move |State(_): State<Arc<tokio::sync::Mutex<SearchIndex>>>, Json(req): Json<QueryAdvancedRequest>| {
-                let shared_index = shared_index.clone();
-                async move {
-                    let guard = shared_index.lock().await;
-                    let limit = req.limit.unwrap_or(10).min(100).max(1);
-                    let with_snippet = req.snippet.unwrap_or(false);
-                    let hits = guard
-                        .query_filtered(&req.q, req.kind.as_deref(), limit, with_snippet)
-                        .unwrap_or_default()
-                        .into_iter()
-                        .map(|(score, path, kind, snippet)| HitAdv { score, path, kind, snippet })
-                        .collect::<Vec<_>>();
-                    Json(hits)
-                }
-            }
-        }))
-        .route("/index/watch/start", post({
-            let shared_index = shared_index.clone();
-            let watcher_handle = watcher_handle.clone();
-            let watcher_shutdown = watcher_shutdown.clone();
-            let workspace_root = workspace_root.clone();
-            move |State(_): State<Arc<tokio::sync::Mutex<SearchIndex>>>| {
-                let shared_index = shared_index.clone();
-                let watcher_handle = watcher_handle.clone();
-                let watcher_shutdown = watcher_shutdown.clone();
-                let workspace_root = workspace_root.clone();
-                async move {
-                    let mut handle_guard = watcher_handle.lock().await;
-                    if handle_guard.is_some() {
-                        return Json(WatchResponse { status: "already_running" });
-                    }
-                    watcher_shutdown.store(false, std::sync::atomic::Ordering::Relaxed);
-                    // Spawn a background task that runs the blocking watch loop
-                    let shared_for_thread = shared_index.clone();
-                    let root = workspace_root.clone();
-                    let shutdown = watcher_shutdown.clone();
-                    let handle = tokio::task::spawn_blocking(move || {
-                        let rt = tokio::runtime::Handle::current();
-                        rt.block_on(async move {
-                            let mut idx = shared_for_thread.lock().await;
-                            let _ = idx.watch_with_shutdown(&root, shutdown);
-                        });
-                    });
-                    *handle_guard = Some(handle);
-                    Json(WatchResponse { status: "started" })
-                }
-            }
-        }))
-        .route("/index/watch/stop", post({
-            let watcher_handle = watcher_handle.clone();
-            let watcher_shutdown = watcher_shutdown.clone();
-            move |State(_): State<Arc<tokio::sync::Mutex<SearchIndex>>>| {
-                let watcher_handle = watcher_handle.clone();
-                let watcher_shutdown = watcher_shutdown.clone();
-                async move {
-                    let mut handle_guard = watcher_handle.lock().await;
-                    if let Some(handle) = handle_guard.take() {
-                        watcher_shutdown.store(true, std::sync::atomic::Ordering::Relaxed);
-                        let _ = handle.await;
-                        return Json(WatchResponse { status: "stopped" });
-                    }
-                    Json(WatchResponse { status: "not_running" })
-                }
-            }
-        }))
-        .route("/index/health", get({
-            let shared_index = shared_index.clone();
-            move |State(_): State<Arc<tokio::sync::Mutex<SearchIndex>>>| {
-                let shared_index = shared_index.clone();
-                async move {
-                    let guard = shared_index.lock().await;
-                    let (docs, segments) = guard.health().unwrap_or((0,0));
-                    Json(HealthResponse { docs, segments })
-                }
-            }
-        }))
-        .route("/context/bundle", post({
-            let shared_index = shared_index.clone();
-            move |State(_): State<Arc<tokio::sync::Mutex<SearchIndex>>>, Json(req): Json<BundleRequest>| {
-                let shared_index = shared_index.clone();
-                async move {
-                    let guard = shared_index.lock().await;
-                    let limit = req.limit.unwrap_or(10).min(100).max(1);
-                    let cap = req.cap_bytes.or(Some(context::DEFAULT_BUNDLE_CAP));
-                    let b = context::bundle_query(&*guard, &req.q, limit, cap, req.kind.as_deref())
-                        .unwrap_or_else(|_| context::Bundle { query: req.q, items: vec![], size_bytes: 0 });
-                    let items = b.items.into_iter().map(|it| BundleItemDto { path: it.path, kind: it.kind, score: it.score, content: it.content }).collect();
-                    Json(BundleResponse { query: b.query, items, size_bytes: b.size_bytes })
-                }
-            }
-        }))
-        .with_state(shared_index.clone())
+pub mod editor_bridge;
+pub mod events;
+pub mod graphql;
+pub mod grpc;
+pub mod index_service;
+pub mod jobs;
+pub mod query_history;
+pub mod registry;
+pub mod sessions;
+pub mod state;
+#[cfg(unix)]
+pub mod unix_socket;
+pub mod webhooks;
+mod bundle_api;
+mod config_api;
+mod fs_api;
+mod godot_api;
+mod health;
+mod index_api;
+mod tools_api;
+mod ui;
+
+pub use registry::{ProjectRegistry, ProjectState, DEFAULT_PROJECT_ID};
+pub use state::{Permission, ServerState};
+
+/// Router type every route group builds against.
+pub type AppRouter = Router<Arc<ServerState>>;
+
+/// JSON error body returned by all routes.
+#[derive(Debug, Serialize)]
+pub struct ErrorBody { pub error: String }
+
+/// Error half of handler results: status plus JSON body.
+pub type ApiError = (StatusCode, Json<ErrorBody>);
+
+pub fn api_error(status: StatusCode, msg: impl Into<String>) -> ApiError {
+    (status, Json(ErrorBody { error: msg.into() }))
+}
+
+/// Look up the requested project (or the default one), mapping unknown ids to 404.
+pub fn resolve_project(state: &ServerState, id: Option<&str>) -> Result<Arc<ProjectState>, ApiError> {
+    state.registry()
+        .get(id)
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, format!("unknown project: {}", id.unwrap_or_default())))
+}
+
+/// Reject the request with 403 when `permission` is disabled in the server config.
+pub fn require(state: &ServerState, permission: Permission) -> Result<(), ApiError> {
+    if state.allows(permission) {
+        return Ok(());
+    }
+    Err(api_error(StatusCode::FORBIDDEN, format!("disabled by {}", permission.config_key())))
+}
+
+/// Stream each message from `rx` as one JSON line (`application/x-ndjson`) until the sender is dropped.
+pub fn ndjson_response<T: Serialize + Send + 'static>(rx: mpsc::Receiver<T>) -> Response {
+    let lines = stream::unfold(rx, |mut rx| async move {
+        let line = rx.recv().await?;
+        let mut buf = serde_json::to_vec(&line).unwrap_or_default();
+        buf.push(b'\n');
+        Some((Ok::<_, Infallible>(Bytes::from(buf)), rx))
+    });
+    ([(header::CONTENT_TYPE, "application/x-ndjson")], Body::from_stream(lines)).into_response()
+}
+
+/// Largest accepted request body; larger bodies are rejected with 413.
+pub const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// Upper bound for `limit` on query and bundle routes.
+pub const MAX_LIMIT: usize = 100;
+/// Upper bound for the length of a search query.
+pub const MAX_QUERY_LEN: usize = 1024;
+
+/// Validate an optional numeric parameter against `1..=max`, using `default` when omitted.
+pub fn bounded(name: &str, value: Option<usize>, default: usize, max: usize) -> Result<usize, ApiError> {
+    match value {
+        None => Ok(default),
+        Some(v) if (1..=max).contains(&v) => Ok(v),
+        Some(v) => Err(api_error(StatusCode::BAD_REQUEST, format!("{} must be between 1 and {} (got {})", name, max, v))),
+    }
+}
+
+/// Reject overlong search queries.
+pub fn check_query(q: &str) -> Result<(), ApiError> {
+    if q.len() > MAX_QUERY_LEN {
+        return Err(api_error(StatusCode::BAD_REQUEST, format!("q exceeds {} bytes", MAX_QUERY_LEN)));
+    }
+    Ok(())
+}
+
+/// Parse a client-supplied project path (`res://` or relative) into a root-relative path.
+/// Rejects absolute paths and `..` components so requests can't escape the project.
+pub fn project_relative_path(p: &str) -> Result<PathBuf, ApiError> {
+    let rel = Path::new(p.strip_prefix("res://").unwrap_or(p));
+    if rel.is_absolute() || rel.components().any(|c| !matches!(c, Component::Normal(_) | Component::CurDir)) {
+        return Err(api_error(StatusCode::BAD_REQUEST, format!("path must be relative to the project root: {}", p)));
+    }
+    Ok(rel.to_path_buf())
+}
+
+/// Resolve a client path to an existing file or directory inside `root`. Canonicalizes both
+/// sides so symlinks can't point outside the project; missing paths map to 404.
+pub fn sandboxed_path(root: &Path, p: &str) -> Result<PathBuf, ApiError> {
+    let rel = project_relative_path(p)?;
+    let root = root.canonicalize().map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let full = root.join(&rel).canonicalize().map_err(|_| api_error(StatusCode::NOT_FOUND, format!("not found: {}", p)))?;
+    if !full.starts_with(&root) {
+        return Err(api_error(StatusCode::FORBIDDEN, format!("path escapes the project root: {}", p)));
+    }
+    Ok(full)
+}
+
+/// Build a router serving a single project (registered as `default`).
+pub fn build_router(index: SearchIndex, workspace_root: PathBuf) -> Router {
+    let project = ProjectState::from_index(DEFAULT_PROJECT_ID, workspace_root, index);
+    build_router_with_registry(ProjectRegistry::new(project))
+}
+
+/// Build a router serving every project in the registry. All index/context routes
+/// accept an optional `project` id (body field or query param); omitted means default.
+pub fn build_router_with_registry(registry: ProjectRegistry) -> Router {
+    build_router_with_state(ServerState::new(registry))
+}
+
+/// Build the full server router over `state`.
+pub fn build_router_with_state(state: impl Into<Arc<ServerState>>) -> Router {
+    build_router_with_groups(state, [])
+}
+
+/// Build the server router with additional route groups merged after the built-in ones.
+/// Groups are plain routers over the shared state; see `route_groups` for the built-ins.
+pub fn build_router_with_groups(state: impl Into<Arc<ServerState>>, extra: impl IntoIterator<Item = AppRouter>) -> Router {
+    route_groups()
+        .into_iter()
+        .chain(extra)
+        .fold(Router::new(), Router::merge)
+        .layer(DefaultBodyLimit::max(MAX_BODY_BYTES))
+        // gzip/br negotiated from Accept-Encoding; bundles and snippets compress well
+        .layer(CompressionLayer::new())
+        // Outermost last: assign x-request-id (keeping a client-supplied one), log within a span
+        // carrying it, then echo it on the response
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(request_span)
+                .on_request(())
+                .on_response(DefaultOnResponse::new().level(Level::INFO).latency_unit(LatencyUnit::Millis)),
+        )
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+        .with_state(state.into())
+}
+
+/// Span for one request: method, matched route (raw path for unmatched requests) and request id.
+fn request_span(req: &Request<Body>) -> Span {
+    let route = req.extensions().get::<MatchedPath>().map(|p| p.as_str()).unwrap_or(req.uri().path());
+    let request_id = req.headers().get("x-request-id").and_then(|v| v.to_str().ok()).unwrap_or("-");
+    tracing::info_span!("request", method = %req.method(), route, request_id)
+}
+
+/// Built-in route groups. A new group is a module exposing `routes() -> AppRouter`, listed here.
+fn route_groups() -> Vec<AppRouter> {
+    vec![
+        health::routes(),
+        index_api::routes(),
+        config_api::routes(),
+        bundle_api::routes(),
+        sessions::routes(),
+        webhooks::routes(),
+        events::routes(),
+        fs_api::routes(),
+        godot_api::routes(),
+        tools_api::routes(),
+        graphql::routes(),
+        ui::routes(),
+    ]
 }
@@ -5,65 +5,131 @@
 // ┃ Last Updated: 2025-09-02                                           ┃
 // ┗━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━┛
 
-use axum::{Json, Router};
+use clap::Parser;
 use common::{init_logging, load_config};
-use serde::Serialize;
+use mcp_server::{ProjectRegistry, ProjectState, ServerState, DEFAULT_PROJECT_ID};
 use std::{net::SocketAddr, path::PathBuf, sync::Arc};
 use tracing::{info, warn};
-use index::{IndexPaths, SearchIndex};
-use tokio::sync::Mutex;
-use tokio::task::JoinHandle;
-use std::sync::atomic::{AtomicBool, Ordering};
 
-#[derive(Serialize)]
-struct Health { status: &'static str }
+#[derive(Parser, Debug)]
+#[command(name = "mcp-server", version, about = "Serve the Master Index and context bundles for a Godot project", long_about = None)]
+struct Args {
+    /// Workspace root to index and serve (defaults to the current directory)
+    #[arg(short, long, value_name = "PATH")]
+    root: Option<PathBuf>,
+    /// Index data directory (defaults to <root>/.index_data)
+    #[arg(long, value_name = "PATH")]
+    data_dir: Option<PathBuf>,
+    /// Bind host; overrides server.host from config
+    #[arg(long)]
+    host: Option<String>,
+    /// Bind port; overrides server.port from config
+    #[arg(long)]
+    port: Option<u16>,
+    /// Listen on a Unix domain socket instead of TCP; overrides server.socket_path
+    #[arg(long, value_name = "PATH")]
+    socket: Option<PathBuf>,
+    /// Accept the Godot editor plugin on this TCP port; overrides server.editor_port
+    #[arg(long, value_name = "PORT")]
+    editor_port: Option<u16>,
+    /// Serve the gRPC services on this TCP port; overrides server.grpc_port
+    #[arg(long, value_name = "PORT")]
+    grpc_port: Option<u16>,
+    /// Godot executable for /godot/check and /godot/export; overrides godot.binary
+    #[arg(long, value_name = "PATH")]
+    godot_bin: Option<PathBuf>,
+    /// Serve an additional project (repeatable), as ID=PATH
+    #[arg(long = "project", value_name = "ID=PATH")]
+    projects: Vec<String>,
+}
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
     init_logging();
-    let cfg = load_config().unwrap_or_else(|e| {
+    let mut cfg = load_config().unwrap_or_else(|e| {
         warn!(error=?e, "Config not found; using defaults");
         // default fallback
-        common::AppConfig { server: common::ServerConfig { host: "127.0.0.1".into(), port: 8080, auto_start_watchers: true } }
+        common::AppConfig::default()
     });
+    // CLI flags take precedence over config files and env
+    if let Some(host) = args.host { cfg.server.host = host; }
+    if let Some(port) = args.port { cfg.server.port = port; }
+    if let Some(socket) = args.socket { cfg.server.socket_path = Some(socket); }
+    if let Some(port) = args.editor_port { cfg.server.editor_port = Some(port); }
+    if let Some(port) = args.grpc_port { cfg.server.grpc_port = Some(port); }
+    if let Some(bin) = args.godot_bin { cfg.godot.binary = Some(bin); }
+    for spec in &args.projects {
+        let Some((id, root)) = spec.split_once('=') else {
+            anyhow::bail!("invalid --project '{}', expected ID=PATH", spec);
+        };
+        cfg.projects.push(common::ProjectConfig { id: id.to_string(), root: PathBuf::from(root), data_dir: None });
+    }
 
-    // Initialize shared index state
-    let workspace_root = PathBuf::from(".");
-    let data_dir = PathBuf::from(".index_data");
-    let index_paths = IndexPaths { root: workspace_root.clone(), data_dir };
-    let mut idx = SearchIndex::open(&index_paths)?;
-    // Perform an initial scan if index is empty; cheap no-op otherwise
-    let _ = idx.scan_and_index(&workspace_root);
-    let shared_index: Arc<Mutex<SearchIndex>> = Arc::new(Mutex::new(idx));
-    // Watcher task handle managed in state
-    let watcher_handle: Arc<Mutex<Option<JoinHandle<()>>>> = Arc::new(Mutex::new(None));
-    let watcher_shutdown: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+    // Initialize the default project from the workspace root
+    let workspace_root = args.root.unwrap_or_else(|| PathBuf::from("."));
+    let data_dir = args.data_dir.unwrap_or_else(|| workspace_root.join(".index_data"));
+    info!(root=%workspace_root.display(), data_dir=%data_dir.display(), "Workspace configured");
+    let mut registry = ProjectRegistry::new(ProjectState::open(DEFAULT_PROJECT_ID, workspace_root, data_dir)?);
+    for p in &cfg.projects {
+        let data_dir = p.data_dir.clone().unwrap_or_else(|| p.root.join(".index_data"));
+        info!(project=%p.id, root=%p.root.display(), "Registering project");
+        registry.register(ProjectState::open(p.id.clone(), p.root.clone(), data_dir)?)?;
+    }
+
+    for url in &cfg.server.webhooks {
+        registry.webhooks().add(url, None)?;
+    }
 
-    // Auto-start the index watcher on server startup (toggle via config)
-    if cfg.server.auto_start_watchers {
-        let mut handle_guard = watcher_handle.lock().await;
-        if handle_guard.is_none() {
-            watcher_shutdown.store(false, Ordering::Relaxed);
-            let shared_for_thread = Arc::clone(&shared_index);
-            let root = workspace_root.clone();
-            let shutdown = Arc::clone(&watcher_shutdown);
-            let handle = tokio::task::spawn_blocking(move || {
-                let rt = tokio::runtime::Handle::current();
-                rt.block_on(async move {
-                    let mut idx = shared_for_thread.lock().await;
-                    let _ = idx.watch_with_shutdown(&root, shutdown);
-                });
-            });
-            *handle_guard = Some(handle);
-            info!("Index watcher auto-started");
+    let mut state = ServerState::new(registry);
+    state.set_godot_config(cfg.godot.clone());
+    state.set_server_config(cfg.server.clone());
+    state.set_bundle_config(cfg.bundle.clone());
+    // Before the initial scan so configured skips apply to it
+    state.set_index_config(cfg.index.clone()).await?;
+
+    for project in state.registry().projects() {
+        // Perform an initial scan if index is empty; cheap no-op otherwise
+        let _ = project.index.scan(project.root.clone()).await;
+        // Auto-start the index watcher on server startup (toggle via config)
+        if cfg.server.auto_start_watchers {
+            project.start_watcher().await;
         }
     }
 
+    if let Some(port) = cfg.server.editor_port {
+        let listener = tokio::net::TcpListener::bind((cfg.server.host.as_str(), port)).await?;
+        let bridge = state.editor().clone();
+        tokio::spawn(async move {
+            if let Err(e) = mcp_server::editor_bridge::serve_editor_bridge(listener, bridge).await {
+                warn!(error=?e, "Editor bridge stopped");
+            }
+        });
+    }
+
+    let state = Arc::new(state);
+    #[cfg(unix)]
+    spawn_sighup_reload(state.clone())?;
+
+    if let Some(port) = cfg.server.grpc_port {
+        let listener = tokio::net::TcpListener::bind((cfg.server.host.as_str(), port)).await?;
+        let state = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = mcp_server::grpc::serve_grpc(listener, state).await {
+                warn!(error=?e, "gRPC server stopped");
+            }
+        });
+    }
+
     // Build routes via lib factory
-    let app_routes = mcp_server::build_router(shared_index.clone(), watcher_handle.clone(), watcher_shutdown.clone(), workspace_root.clone());
-    let app = Router::new()
-        .route("/health", axum::routing::get(|| async { Json(Health { status: "ok" }) }))
-        .merge(app_routes);
+    let app = mcp_server::build_router_with_state(state);
+
+    if let Some(socket) = &cfg.server.socket_path {
+        #[cfg(unix)]
+        return mcp_server::unix_socket::serve_unix(socket, app).await;
+        #[cfg(not(unix))]
+        anyhow::bail!("server.socket_path ({}) requires a Unix platform", socket.display());
+    }
 
     let addr: SocketAddr = format!("{}:{}", cfg.server.host, cfg.server.port).parse()?;
     info!(%addr, "Starting MCP server");
@@ -71,4 +137,19 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-//EOF
\ No newline at end of file
+/// Re-read config files on SIGHUP, same as POST /config/reload.
+#[cfg(unix)]
+fn spawn_sighup_reload(state: Arc<ServerState>) -> anyhow::Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+    let mut hangup = signal(SignalKind::hangup())?;
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            if let Err(e) = state.reload().await {
+                warn!(error=?e, "Config reload failed");
+            }
+        }
+    });
+    Ok(())
+}
+
+//EOF
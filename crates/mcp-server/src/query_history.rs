@@ -0,0 +1,104 @@
+// Query history: ring buffer of recent searches with hit counts and latencies for /index/query/history.
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+/// Most recent queries kept; older ones are dropped.
+pub const MAX_QUERY_HISTORY: usize = 500;
+/// Entries in the `top` and `zero_hit` summaries.
+const SUMMARY_LEN: usize = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryRecord {
+    pub q: String,
+    /// Route that served the query ("query", "advanced", "bundle", ...)
+    pub route: &'static str,
+    pub project: String,
+    pub kind: Option<String>,
+    pub hits: usize,
+    pub latency_ms: f64,
+    /// Unix seconds
+    pub at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct QueryCount {
+    pub q: String,
+    pub count: usize,
+}
+
+/// Aggregates over the retained history.
+#[derive(Debug, Clone, Serialize)]
+pub struct QuerySummary {
+    pub queries: usize,
+    pub avg_latency_ms: f64,
+    pub max_latency_ms: f64,
+    /// Most frequent queries
+    pub top: Vec<QueryCount>,
+    /// Most frequent queries that returned nothing; candidates for synonyms or doc fixes
+    pub zero_hit: Vec<QueryCount>,
+}
+
+#[derive(Default)]
+pub struct QueryHistory {
+    entries: Mutex<VecDeque<QueryRecord>>,
+}
+
+impl QueryHistory {
+    pub fn record(&self, route: &'static str, project: &str, q: &str, kind: Option<&str>, hits: usize, latency: Duration) {
+        let rec = QueryRecord {
+            q: q.to_string(),
+            route,
+            project: project.to_string(),
+            kind: kind.map(str::to_string),
+            hits,
+            latency_ms: latency.as_secs_f64() * 1000.0,
+            at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+        };
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() == MAX_QUERY_HISTORY {
+            entries.pop_front();
+        }
+        entries.push_back(rec);
+    }
+
+    /// Newest first, optionally restricted to one project.
+    pub fn recent(&self, project: Option<&str>, limit: usize) -> Vec<QueryRecord> {
+        self.entries.lock().unwrap()
+            .iter()
+            .rev()
+            .filter(|r| project.is_none_or(|p| r.project == p))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    pub fn summary(&self, project: Option<&str>) -> QuerySummary {
+        let entries = self.entries.lock().unwrap();
+        let matching: Vec<&QueryRecord> = entries.iter().filter(|r| project.is_none_or(|p| r.project == p)).collect();
+        let total_ms: f64 = matching.iter().map(|r| r.latency_ms).sum();
+        QuerySummary {
+            queries: matching.len(),
+            avg_latency_ms: if matching.is_empty() { 0.0 } else { total_ms / matching.len() as f64 },
+            max_latency_ms: matching.iter().map(|r| r.latency_ms).fold(0.0, f64::max),
+            top: most_frequent(matching.iter().copied()),
+            zero_hit: most_frequent(matching.iter().copied().filter(|r| r.hits == 0)),
+        }
+    }
+}
+
+/// Count by query text; ties broken alphabetically for stable output.
+fn most_frequent<'a>(records: impl Iterator<Item = &'a QueryRecord>) -> Vec<QueryCount> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for r in records {
+        *counts.entry(r.q.as_str()).or_default() += 1;
+    }
+    let mut out: Vec<QueryCount> = counts.into_iter().map(|(q, count)| QueryCount { q: q.to_string(), count }).collect();
+    out.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.q.cmp(&b.q)));
+    out.truncate(SUMMARY_LEN);
+    out
+}
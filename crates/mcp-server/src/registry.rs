@@ -0,0 +1,141 @@
+// Project registry: per-project index + watcher state keyed by id.
+use std::{collections::BTreeMap, path::PathBuf, sync::{Arc, atomic::{AtomicBool, Ordering}}};
+
+use anyhow::{bail, Result};
+use index::{IndexPaths, SearchIndex};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::{events::EventFeed, index_service::IndexHandle, webhooks::{CommitEvent, WebhookTable}};
+
+/// Id used for the project served when no explicit `project` is requested.
+pub const DEFAULT_PROJECT_ID: &str = "default";
+
+/// Shared state for one served Godot project.
+pub struct ProjectState {
+    pub id: String,
+    pub root: PathBuf,
+    pub index: IndexHandle,
+    watcher_handle: Mutex<Option<JoinHandle<()>>>,
+    watcher_shutdown: Arc<AtomicBool>,
+    /// Notified after each watcher commit; set by the registry on registration
+    webhooks: Arc<WebhookTable>,
+    events: Arc<EventFeed>,
+}
+
+impl ProjectState {
+    /// Open (or create) the index for a project. Does not scan.
+    pub fn open(id: impl Into<String>, root: PathBuf, data_dir: PathBuf) -> Result<Self> {
+        let paths = IndexPaths { root: root.clone(), data_dir };
+        Ok(Self::from_index(id, root, SearchIndex::open(&paths)?))
+    }
+
+    /// Serve an already-opened index (used by `build_router`).
+    pub fn from_index(id: impl Into<String>, root: PathBuf, index: SearchIndex) -> Self {
+        Self {
+            id: id.into(),
+            root,
+            index: IndexHandle::spawn(index),
+            watcher_handle: Mutex::new(None),
+            watcher_shutdown: Arc::new(AtomicBool::new(false)),
+            webhooks: Arc::default(),
+            events: Arc::default(),
+        }
+    }
+
+    /// Spawn the blocking watch loop for this project if not already running.
+    /// Returns "started" or "already_running".
+    pub async fn start_watcher(&self) -> &'static str {
+        let mut handle_guard = self.watcher_handle.lock().await;
+        if handle_guard.is_some() {
+            return "already_running";
+        }
+        self.watcher_shutdown.store(false, Ordering::Relaxed);
+        let handle = self.index.clone();
+        let root = self.root.clone();
+        let shutdown = self.watcher_shutdown.clone();
+        let (id, webhooks, events) = (self.id.clone(), self.webhooks.clone(), self.events.clone());
+        // Batches go through the index actor, so scans and queries proceed while watching
+        let task = tokio::task::spawn_blocking(move || {
+            let _ = index::watch_changes(&root, shutdown, |batch| match handle.blocking_apply_changes(batch) {
+                Ok(changes) if !changes.is_empty() => {
+                    let event = CommitEvent::new(&id, changes);
+                    webhooks.notify(&event);
+                    events.publish(event);
+                }
+                Ok(_) => {}
+                Err(e) => warn!(project=%id, error=%e, "watch commit failed"),
+            });
+        });
+        *handle_guard = Some(task);
+        info!(project=%self.id, "Index watcher started");
+        "started"
+    }
+
+    /// Signal the watcher to stop and wait for it. Returns "stopped" or "not_running".
+    pub async fn stop_watcher(&self) -> &'static str {
+        let mut handle_guard = self.watcher_handle.lock().await;
+        if let Some(handle) = handle_guard.take() {
+            self.watcher_shutdown.store(true, Ordering::Relaxed);
+            let _ = handle.await;
+            return "stopped";
+        }
+        "not_running"
+    }
+
+    pub async fn watcher_running(&self) -> bool {
+        self.watcher_handle.lock().await.is_some()
+    }
+}
+
+/// Registry of served projects. Built at startup; lookups fall back to the default project.
+pub struct ProjectRegistry {
+    default_id: String,
+    projects: BTreeMap<String, Arc<ProjectState>>,
+    /// Shared with every registered project's watcher
+    webhooks: Arc<WebhookTable>,
+    events: Arc<EventFeed>,
+}
+
+impl ProjectRegistry {
+    pub fn new(mut default: ProjectState) -> Self {
+        let default_id = default.id.clone();
+        let webhooks = Arc::<WebhookTable>::default();
+        let events = Arc::<EventFeed>::default();
+        default.webhooks = webhooks.clone();
+        default.events = events.clone();
+        let mut projects = BTreeMap::new();
+        projects.insert(default_id.clone(), Arc::new(default));
+        Self { default_id, projects, webhooks, events }
+    }
+
+    /// Add a project; ids must be unique.
+    pub fn register(&mut self, mut project: ProjectState) -> Result<()> {
+        if self.projects.contains_key(&project.id) {
+            bail!("duplicate project id: {}", project.id);
+        }
+        project.webhooks = self.webhooks.clone();
+        project.events = self.events.clone();
+        self.projects.insert(project.id.clone(), Arc::new(project));
+        Ok(())
+    }
+
+    /// Resolve a project by id; `None` selects the default project.
+    pub fn get(&self, id: Option<&str>) -> Option<Arc<ProjectState>> {
+        self.projects.get(id.unwrap_or(&self.default_id)).cloned()
+    }
+
+    pub fn default_id(&self) -> &str { &self.default_id }
+
+    /// URLs notified after watcher commits.
+    pub fn webhooks(&self) -> &WebhookTable { &self.webhooks }
+
+    /// Live feed of watcher commits (SSE subscribers).
+    pub fn events(&self) -> &EventFeed { &self.events }
+
+    /// All projects in id order.
+    pub fn projects(&self) -> impl Iterator<Item = &Arc<ProjectState>> {
+        self.projects.values()
+    }
+}
@@ -0,0 +1,165 @@
+// Agent sessions: remember which bundle items were already served so later bundles can skip them.
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap},
+    hash::{Hash, Hasher},
+    sync::{atomic::{AtomicU64, Ordering}, Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use axum::{routing::{get, post}, extract::{Path as UrlPath, State}, http::StatusCode, Json, Router};
+use serde::{Deserialize, Serialize};
+
+use context::BundleItem;
+
+use crate::{api_error, resolve_project, ApiError, AppRouter, ServerState};
+
+/// How a bundle request treats items already served in its session.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionMode {
+    /// Skip every path served before
+    #[default]
+    Exclude,
+    /// Skip a path only if the served content is unchanged; changed content is sent again
+    Diff,
+}
+
+/// Session listing returned by `/sessions/{id}`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionInfo {
+    pub id: u64,
+    pub project: String,
+    /// Unix seconds
+    pub created_at: u64,
+    /// Paths served so far, in path order
+    pub served: Vec<String>,
+}
+
+struct Session {
+    project: String,
+    created_at: u64,
+    /// path -> hash of the content last served
+    served: BTreeMap<String, u64>,
+}
+
+/// In-memory session table. Sessions live until deleted or the process exits.
+#[derive(Default)]
+pub struct SessionTable {
+    next_id: AtomicU64,
+    sessions: Mutex<BTreeMap<u64, Session>>,
+}
+
+impl SessionTable {
+    /// Open a session bound to `project` and return its id.
+    pub fn create(&self, project: &str) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let session = Session { project: project.to_string(), created_at: unix_now(), served: BTreeMap::new() };
+        self.sessions.lock().unwrap().insert(id, session);
+        id
+    }
+
+    pub fn get(&self, id: u64) -> Option<SessionInfo> {
+        self.sessions.lock().unwrap().get(&id).map(|s| SessionInfo {
+            id,
+            project: s.project.clone(),
+            created_at: s.created_at,
+            served: s.served.keys().cloned().collect(),
+        })
+    }
+
+    /// Drop a session; returns false for unknown ids.
+    pub fn remove(&self, id: u64) -> bool {
+        self.sessions.lock().unwrap().remove(&id).is_some()
+    }
+
+    /// Record items as served; unknown ids are ignored.
+    pub fn record<'a>(&self, id: u64, items: impl IntoIterator<Item = &'a BundleItem>) {
+        if let Some(s) = self.sessions.lock().unwrap().get_mut(&id) {
+            for it in items {
+                s.served.insert(it.path.clone(), content_hash(&it.content));
+            }
+        }
+    }
+
+    /// Snapshot what a session has seen so a bundle can be filtered without holding the lock.
+    /// Unknown ids map to 404; sessions opened for another project to 400.
+    pub fn filter(&self, id: u64, project: &str, mode: SessionMode) -> Result<SessionFilter, ApiError> {
+        let sessions = self.sessions.lock().unwrap();
+        let s = sessions.get(&id).ok_or_else(|| api_error(StatusCode::NOT_FOUND, format!("unknown session: {}", id)))?;
+        if s.project != project {
+            return Err(api_error(StatusCode::BAD_REQUEST, format!("session {} belongs to project {}", id, s.project)));
+        }
+        Ok(SessionFilter { served: s.served.clone(), mode })
+    }
+}
+
+/// Whether an item was served before in the session.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemStatus {
+    New,
+    Changed,
+}
+
+/// Served-content snapshot for one bundle request.
+pub struct SessionFilter {
+    served: BTreeMap<String, u64>,
+    mode: SessionMode,
+}
+
+impl SessionFilter {
+    /// `None` means the item should be skipped.
+    pub fn check(&self, item: &BundleItem) -> Option<ItemStatus> {
+        match (self.served.get(&item.path), self.mode) {
+            (None, _) => Some(ItemStatus::New),
+            (Some(_), SessionMode::Exclude) => None,
+            (Some(&h), SessionMode::Diff) if h == content_hash(&item.content) => None,
+            (Some(_), SessionMode::Diff) => Some(ItemStatus::Changed),
+        }
+    }
+}
+
+fn content_hash(content: &str) -> u64 {
+    let mut h = DefaultHasher::new();
+    content.hash(&mut h);
+    h.finish()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+#[derive(Deserialize, Default)]
+struct CreateRequest { project: Option<String> }
+
+#[derive(Serialize)]
+struct CreateResponse { id: u64, project: String }
+
+#[derive(Serialize)]
+struct DeleteResponse { status: &'static str }
+
+pub fn routes() -> AppRouter {
+    Router::new()
+        .route("/sessions", post(
+            |State(state): State<Arc<ServerState>>, req: Option<Json<CreateRequest>>| async move {
+                let req = req.map(|Json(r)| r).unwrap_or_default();
+                let project = resolve_project(&state, req.project.as_deref())?;
+                let id = state.sessions().create(&project.id);
+                Ok::<_, ApiError>((StatusCode::CREATED, Json(CreateResponse { id, project: project.id.clone() })))
+            }
+        ))
+        .route("/sessions/:id", get(
+            |State(state): State<Arc<ServerState>>, UrlPath(id): UrlPath<u64>| async move {
+                state.sessions().get(id)
+                    .map(Json)
+                    .ok_or_else(|| api_error(StatusCode::NOT_FOUND, format!("unknown session: {}", id)))
+            }
+        ).delete(
+            |State(state): State<Arc<ServerState>>, UrlPath(id): UrlPath<u64>| async move {
+                if !state.sessions().remove(id) {
+                    return Err(api_error(StatusCode::NOT_FOUND, format!("unknown session: {}", id)));
+                }
+                Ok(Json(DeleteResponse { status: "deleted" }))
+            }
+        ))
+}
@@ -0,0 +1,208 @@
+// Server state: the project registry plus the server-wide tables every route group shares.
+use std::{path::{Path, PathBuf}, sync::{Arc, RwLock}, time::{Duration, Instant}};
+
+use anyhow::{bail, Result};
+use common::{AppConfig, BundleConfig, GodotConfig, IndexConfig, ServerConfig};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::{
+    editor_bridge::EditorBridge, events::EventFeed, jobs::JobTable, query_history::QueryHistory, registry::ProjectRegistry,
+    sessions::SessionTable, webhooks::WebhookTable,
+};
+
+/// Everything handlers need, held once in axum state as `Arc<ServerState>`.
+pub struct ServerState {
+    registry: ProjectRegistry,
+    jobs: JobTable,
+    sessions: SessionTable,
+    history: QueryHistory,
+    editor: Arc<EditorBridge>,
+    godot: GodotConfig,
+    /// Listener settings, when started from config (reported by /health)
+    server: RwLock<Option<ServerConfig>>,
+    bundle: RwLock<BundleConfig>,
+    index: RwLock<IndexConfig>,
+    /// Directory re-read by `reload` (`default.yaml`, `local.yaml`)
+    config_dir: PathBuf,
+    started: Instant,
+}
+
+/// Capabilities that deployments can switch off (`server.allow_*`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    Scan,
+    WatchControl,
+    FsWrite,
+    ConfigWrite,
+}
+
+impl Permission {
+    pub fn config_key(self) -> &'static str {
+        match self {
+            Permission::Scan => "server.allow_scan",
+            Permission::WatchControl => "server.allow_watch_control",
+            Permission::FsWrite => "server.allow_fs_write",
+            Permission::ConfigWrite => "server.allow_config_write",
+        }
+    }
+
+    fn allowed_by(self, server: &ServerConfig) -> bool {
+        match self {
+            Permission::Scan => server.allow_scan,
+            Permission::WatchControl => server.allow_watch_control,
+            Permission::FsWrite => server.allow_fs_write,
+            Permission::ConfigWrite => server.allow_config_write,
+        }
+    }
+}
+
+/// Outcome of a config reload.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReloadReport {
+    /// Reloadable settings whose value changed
+    pub changed: Vec<&'static str>,
+    /// Projects whose watcher was started or stopped because `auto_start_watchers` changed
+    pub watchers_started: Vec<String>,
+    pub watchers_stopped: Vec<String>,
+}
+
+impl ServerState {
+    pub fn new(registry: ProjectRegistry) -> Self {
+        Self {
+            registry,
+            jobs: JobTable::default(),
+            sessions: SessionTable::default(),
+            history: QueryHistory::default(),
+            editor: Arc::default(),
+            godot: GodotConfig::default(),
+            server: RwLock::new(None),
+            bundle: RwLock::default(),
+            index: RwLock::default(),
+            config_dir: PathBuf::from("config"),
+            started: Instant::now(),
+        }
+    }
+
+    /// Served projects.
+    pub fn registry(&self) -> &ProjectRegistry { &self.registry }
+
+    /// Background jobs across all projects.
+    pub fn jobs(&self) -> &JobTable { &self.jobs }
+
+    /// Agent sessions tracking already-served bundle items.
+    pub fn sessions(&self) -> &SessionTable { &self.sessions }
+
+    /// Recent queries across all projects.
+    pub fn history(&self) -> &QueryHistory { &self.history }
+
+    /// URLs notified after watcher commits.
+    pub fn webhooks(&self) -> &WebhookTable { self.registry.webhooks() }
+
+    /// Watcher commit feed behind `/events`.
+    pub fn events(&self) -> &EventFeed { self.registry.events() }
+
+    /// State reported by the connected editor plugin, if any.
+    pub fn editor(&self) -> &Arc<EditorBridge> { &self.editor }
+
+    /// Engine binary and limits for engine-backed routes.
+    pub fn godot_config(&self) -> &GodotConfig { &self.godot }
+
+    pub fn set_godot_config(&mut self, godot: GodotConfig) { self.godot = godot; }
+
+    pub fn server_config(&self) -> Option<ServerConfig> { self.server.read().unwrap().clone() }
+
+    pub fn set_server_config(&mut self, server: ServerConfig) { *self.server.get_mut().unwrap() = Some(server); }
+
+    /// Whether `permission` is enabled; everything is allowed when no server config was set.
+    pub fn allows(&self, permission: Permission) -> bool {
+        self.server.read().unwrap().as_ref().is_none_or(|s| permission.allowed_by(s))
+    }
+
+    /// Current bundle sizing (may change on reload).
+    pub fn bundle_config(&self) -> BundleConfig { self.bundle.read().unwrap().clone() }
+
+    pub fn set_bundle_config(&mut self, bundle: BundleConfig) { *self.bundle.get_mut().unwrap() = bundle; }
+
+    /// Current indexing rules (may change on reload).
+    pub fn index_config(&self) -> IndexConfig { self.index.read().unwrap().clone() }
+
+    /// Install indexing rules and hand the skip fragments to every project's index.
+    pub async fn set_index_config(&mut self, index: IndexConfig) -> Result<()> {
+        for p in self.registry.projects() {
+            p.index.set_extra_skips(index.skip.clone()).await?;
+        }
+        *self.index.get_mut().unwrap() = index;
+        Ok(())
+    }
+
+    /// Directory `reload` reads config files from (defaults to `config`).
+    pub fn set_config_dir(&mut self, dir: PathBuf) { self.config_dir = dir; }
+
+    /// Directory holding the config files and `templates/`.
+    pub fn config_dir(&self) -> &Path { &self.config_dir }
+
+    /// Re-read the config files and apply the reloadable settings.
+    pub async fn reload(&self) -> Result<ReloadReport> {
+        let cfg = common::load_config_from(&self.config_dir)?;
+        self.apply_config(cfg).await
+    }
+
+    /// Apply reloadable settings from `cfg`: bundle sizing and ranking, index skip fragments,
+    /// `server.allow_*` and `server.auto_start_watchers`. Listener settings (host, port, sockets) need a restart.
+    /// Nothing is applied when the bundle caps contradict each other.
+    pub async fn apply_config(&self, cfg: AppConfig) -> Result<ReloadReport> {
+        if cfg.bundle.max_cap_bytes < cfg.bundle.default_cap_bytes {
+            bail!("bundle.max_cap_bytes ({}) is below bundle.default_cap_bytes ({})", cfg.bundle.max_cap_bytes, cfg.bundle.default_cap_bytes);
+        }
+        let mut report = ReloadReport { changed: Vec::new(), watchers_started: Vec::new(), watchers_stopped: Vec::new() };
+        {
+            let mut bundle = self.bundle.write().unwrap();
+            if bundle.default_cap_bytes != cfg.bundle.default_cap_bytes { report.changed.push("bundle.default_cap_bytes"); }
+            if bundle.max_cap_bytes != cfg.bundle.max_cap_bytes { report.changed.push("bundle.max_cap_bytes"); }
+            if bundle.recency_weight != cfg.bundle.recency_weight { report.changed.push("bundle.recency_weight"); }
+            if bundle.synonyms != cfg.bundle.synonyms { report.changed.push("bundle.synonyms"); }
+            *bundle = cfg.bundle;
+        }
+        let skip_changed = *self.index.read().unwrap() != cfg.index;
+        if skip_changed {
+            report.changed.push("index.skip");
+            for p in self.registry.projects() {
+                if let Err(e) = p.index.set_extra_skips(cfg.index.skip.clone()).await {
+                    warn!(project=%p.id, error=%e, "index skip update failed");
+                }
+            }
+            *self.index.write().unwrap() = cfg.index;
+        }
+        let auto_watch = cfg.server.auto_start_watchers;
+        let previous = {
+            let mut server = self.server.write().unwrap();
+            let current = server.get_or_insert_with(|| cfg.server.clone());
+            for permission in [Permission::Scan, Permission::WatchControl, Permission::FsWrite, Permission::ConfigWrite] {
+                if permission.allowed_by(current) != permission.allowed_by(&cfg.server) {
+                    report.changed.push(permission.config_key());
+                }
+            }
+            current.allow_scan = cfg.server.allow_scan;
+            current.allow_watch_control = cfg.server.allow_watch_control;
+            current.allow_fs_write = cfg.server.allow_fs_write;
+            current.allow_config_write = cfg.server.allow_config_write;
+            std::mem::replace(&mut current.auto_start_watchers, auto_watch)
+        };
+        if previous != auto_watch {
+            report.changed.push("server.auto_start_watchers");
+            for p in self.registry.projects() {
+                if auto_watch && p.start_watcher().await == "started" {
+                    report.watchers_started.push(p.id.clone());
+                } else if !auto_watch && p.stop_watcher().await == "stopped" {
+                    report.watchers_stopped.push(p.id.clone());
+                }
+            }
+        }
+        info!(changed=?report.changed, "Configuration reloaded");
+        Ok(report)
+    }
+
+    /// Time since the state was built (server start).
+    pub fn uptime(&self) -> Duration { self.started.elapsed() }
+}
@@ -0,0 +1,81 @@
+// Tools routes: expose the repo tooling (metatagger, autodoc) over HTTP.
+use std::sync::Arc;
+
+use axum::{routing::{get, post}, extract::{Query, State}, http::{header, StatusCode}, response::{IntoResponse, Response}, Json, Router};
+use serde::Deserialize;
+
+use tools::{autodoc::{self, AutoDocReport, EnsureOpts}, metatagger::{self, Severity}};
+
+use crate::{api_error, require, resolve_project, ApiError, AppRouter, Permission, ServerState};
+
+#[derive(Deserialize)]
+struct MetataggerRequest {
+    project: Option<String>,
+    /// Drop findings below this severity (info, warn, error)
+    min_severity: Option<Severity>,
+    /// json (default), sarif or junit
+    format: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AutodocRequest {
+    project: Option<String>,
+    /// Write created/updated docs; default is a dry run
+    #[serde(default)]
+    apply: bool,
+}
+
+pub fn routes() -> AppRouter {
+    Router::new()
+        .route("/tools/metatagger", get(
+            |State(state): State<Arc<ServerState>>, Query(req): Query<MetataggerRequest>| async move {
+                run_metatagger(&state, req).await
+            }
+        ).post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<MetataggerRequest>| async move {
+                run_metatagger(&state, req).await
+            }
+        ))
+        .route("/tools/autodoc", post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<AutodocRequest>| async move {
+                run_autodoc(&state, req).await
+            }
+        ))
+}
+
+async fn run_autodoc(state: &ServerState, req: AutodocRequest) -> Result<Json<AutoDocReport>, ApiError> {
+    // Dry runs only report; applying writes doc headers into scripts
+    if req.apply {
+        require(state, Permission::FsWrite)?;
+    }
+    let project = resolve_project(state, req.project.as_deref())?;
+    let root = project.root.clone();
+    let opts = EnsureOpts { dry_run: !req.apply, check_only: false };
+    let report = tokio::task::spawn_blocking(move || autodoc::ensure_autodocs_opts(&root, opts))
+        .await
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(report))
+}
+
+async fn run_metatagger(state: &ServerState, req: MetataggerRequest) -> Result<Response, ApiError> {
+    let project = resolve_project(state, req.project.as_deref())?;
+    let format = req.format.as_deref().unwrap_or("json");
+    if !matches!(format, "json" | "sarif" | "junit") {
+        return Err(api_error(StatusCode::BAD_REQUEST, format!("unknown format: {}", format)));
+    }
+    let root = project.root.clone();
+    // Read-only scan: the HTTP route never rewrites PROJECT_INDEX.md
+    let mut report = tokio::task::spawn_blocking(move || metatagger::scan(&root))
+        .await
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if let Some(min) = req.min_severity {
+        report.findings.retain(|f| f.severity >= min);
+    }
+    Ok(match format {
+        "sarif" => Json(metatagger::to_sarif(&report)).into_response(),
+        "junit" => ([(header::CONTENT_TYPE, "application/xml")], metatagger::to_junit(&report)).into_response(),
+        _ => Json(report).into_response(),
+    })
+}
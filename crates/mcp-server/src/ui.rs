@@ -0,0 +1,13 @@
+// Built-in dashboard: a single static page at /ui that drives the JSON routes from the browser.
+use axum::{routing::get, response::{Html, Redirect}, Router};
+
+use crate::AppRouter;
+
+/// Embedded at build time so the binary stays self-contained.
+const INDEX_HTML: &str = include_str!("../ui/index.html");
+
+pub fn routes() -> AppRouter {
+    Router::new()
+        .route("/ui", get(|| async { Html(INDEX_HTML) }))
+        .route("/ui/", get(|| async { Redirect::permanent("/ui") }))
+}
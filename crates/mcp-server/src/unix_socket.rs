@@ -0,0 +1,31 @@
+// Unix domain socket listener: serve the router on a local socket instead of TCP.
+use std::path::Path;
+
+use anyhow::Result;
+use axum::Router;
+use hyper::server::conn::http1;
+use hyper_util::{rt::TokioIo, service::TowerToHyperService};
+use tokio::net::UnixListener;
+use tracing::{info, warn};
+
+/// Bind `path` and serve `app` over HTTP/1.1 until the listener fails.
+/// A stale socket file left by a previous run is removed first.
+pub async fn serve_unix(path: &Path, app: Router) -> Result<()> {
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    info!(socket=%path.display(), "Listening on unix socket");
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let service = TowerToHyperService::new(app.clone());
+        tokio::spawn(async move {
+            if let Err(e) = http1::Builder::new().serve_connection(TokioIo::new(stream), service).await {
+                warn!(error=%e, "unix socket connection error");
+            }
+        });
+    }
+}
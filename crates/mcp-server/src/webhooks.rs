@@ -0,0 +1,171 @@
+// Webhooks: POST a JSON summary of each watcher commit to registered URLs.
+use std::{
+    collections::BTreeMap,
+    sync::{atomic::{AtomicU64, Ordering}, Arc, RwLock},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, bail, Result};
+use axum::{routing::{delete, get}, body::Body, extract::{Path as UrlPath, State}, http::{header, StatusCode, Uri}, Json, Router};
+use hyper_util::rt::TokioIo;
+use index::ChangedPath;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::{api_error, require, resolve_project, ApiError, AppRouter, Permission, ServerState};
+
+/// Give up on a delivery after this long; slow receivers must not pile up tasks.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Webhook {
+    pub id: u64,
+    pub url: String,
+    /// Only notify for this project; `None` means every project
+    pub project: Option<String>,
+}
+
+/// Body POSTed to each webhook after a watcher commit.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitEvent {
+    pub event: &'static str,
+    pub project: String,
+    /// Unix seconds
+    pub at: u64,
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Change {
+    pub path: String,
+    pub kind: &'static str,
+    /// "indexed" or "removed"
+    pub change: &'static str,
+}
+
+impl CommitEvent {
+    pub fn new(project: &str, changes: Vec<ChangedPath>) -> Self {
+        CommitEvent {
+            event: "index.commit",
+            project: project.to_string(),
+            at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+            changes: changes.into_iter()
+                .map(|c| Change { path: c.path, kind: c.kind, change: if c.removed { "removed" } else { "indexed" } })
+                .collect(),
+        }
+    }
+}
+
+/// Registered webhooks, shared by every project's watcher.
+#[derive(Default)]
+pub struct WebhookTable {
+    next_id: AtomicU64,
+    hooks: RwLock<BTreeMap<u64, Webhook>>,
+}
+
+impl WebhookTable {
+    /// Register a URL; only plain `http://` endpoints are supported.
+    pub fn add(&self, url: &str, project: Option<String>) -> Result<Webhook> {
+        parse_http_url(url)?;
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let hook = Webhook { id, url: url.to_string(), project };
+        self.hooks.write().unwrap().insert(id, hook.clone());
+        Ok(hook)
+    }
+
+    pub fn remove(&self, id: u64) -> bool {
+        self.hooks.write().unwrap().remove(&id).is_some()
+    }
+
+    pub fn list(&self) -> Vec<Webhook> {
+        self.hooks.read().unwrap().values().cloned().collect()
+    }
+
+    /// Fan a commit out to matching webhooks. Must be called from within a Tokio runtime;
+    /// deliveries run as detached tasks so the watcher never waits on receivers.
+    pub fn notify(&self, event: &CommitEvent) {
+        let targets: Vec<String> = self.hooks.read().unwrap()
+            .values()
+            .filter(|h| h.project.as_deref().is_none_or(|p| p == event.project))
+            .map(|h| h.url.clone())
+            .collect();
+        if targets.is_empty() {
+            return;
+        }
+        let body = match serde_json::to_vec(event) {
+            Ok(b) => b,
+            Err(e) => return warn!(error=%e, "Failed to encode webhook event"),
+        };
+        for url in targets {
+            let body = body.clone();
+            tokio::spawn(async move {
+                match tokio::time::timeout(DELIVERY_TIMEOUT, deliver(&url, body)).await {
+                    Ok(Ok(status)) => debug!(%url, %status, "Webhook delivered"),
+                    Ok(Err(e)) => warn!(%url, error=%e, "Webhook delivery failed"),
+                    Err(_) => warn!(%url, "Webhook delivery timed out"),
+                }
+            });
+        }
+    }
+}
+
+fn parse_http_url(url: &str) -> Result<Uri> {
+    let uri: Uri = url.parse().map_err(|e| anyhow!("invalid webhook url {}: {}", url, e))?;
+    if uri.scheme_str() != Some("http") || uri.host().is_none() {
+        bail!("webhook url must be http://host[:port]/path: {}", url);
+    }
+    Ok(uri)
+}
+
+/// POST `body` to `url` over a fresh HTTP/1.1 connection.
+async fn deliver(url: &str, body: Vec<u8>) -> Result<StatusCode> {
+    let uri = parse_http_url(url)?;
+    let host = uri.host().unwrap_or_default().to_string();
+    let port = uri.port_u16().unwrap_or(80);
+    let stream = tokio::net::TcpStream::connect((host.as_str(), port)).await?;
+    let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream)).await?;
+    tokio::spawn(conn);
+    let path = uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    let authority = uri.authority().map(|a| a.as_str()).unwrap_or(host.as_str());
+    let req = hyper::Request::post(path)
+        .header(header::HOST, authority)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body))?;
+    let resp = sender.send_request(req).await?;
+    if !resp.status().is_success() {
+        bail!("receiver returned {}", resp.status());
+    }
+    Ok(resp.status())
+}
+
+#[derive(Deserialize)]
+struct AddRequest { url: String, project: Option<String> }
+
+#[derive(Serialize)]
+struct DeleteResponse { status: &'static str }
+
+pub fn routes() -> AppRouter {
+    Router::new()
+        .route("/webhooks", get(
+            |State(state): State<Arc<ServerState>>| async move { Json(state.webhooks().list()) }
+        ).post(
+            |State(state): State<Arc<ServerState>>, Json(req): Json<AddRequest>| async move {
+                require(&state, Permission::ConfigWrite)?;
+                if let Some(id) = req.project.as_deref() {
+                    resolve_project(&state, Some(id))?;
+                }
+                let hook = state.webhooks().add(&req.url, req.project)
+                    .map_err(|e| api_error(StatusCode::BAD_REQUEST, e.to_string()))?;
+                Ok::<_, ApiError>((StatusCode::CREATED, Json(hook)))
+            }
+        ))
+        .route("/webhooks/:id", delete(
+            |State(state): State<Arc<ServerState>>, UrlPath(id): UrlPath<u64>| async move {
+                require(&state, Permission::ConfigWrite)?;
+                if !state.webhooks().remove(id) {
+                    return Err(api_error(StatusCode::NOT_FOUND, format!("unknown webhook: {}", id)));
+                }
+                Ok(Json(DeleteResponse { status: "deleted" }))
+            }
+        ))
+}
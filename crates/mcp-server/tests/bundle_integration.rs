@@ -1,8 +1,5 @@
-use std::{sync::{Arc, atomic::AtomicBool}};
-
 use axum::{Router, body::{Body, to_bytes}};
 use index::{IndexPaths, SearchIndex};
-use tokio::task::JoinHandle;
 use tower::ServiceExt; // for oneshot
 use hyper::{Request, StatusCode};
 
@@ -21,11 +18,8 @@ async fn context_bundle_endpoint_smoke() {
     let paths = IndexPaths { root: root.clone(), data_dir: data.clone() };
     let mut idx = SearchIndex::open(&paths).unwrap();
     let _ = idx.scan_and_index(&root).unwrap();
-    let shared_index: Arc<tokio::sync::Mutex<SearchIndex>> = Arc::new(tokio::sync::Mutex::new(idx));
-    let watcher_handle: Arc<tokio::sync::Mutex<Option<JoinHandle<()>>>> = Arc::new(tokio::sync::Mutex::new(None));
-    let watcher_shutdown: Arc<AtomicBool> = Arc::new(AtomicBool::new(true));
 
-    let app: Router = mcp_server::build_router(shared_index.clone(), watcher_handle, watcher_shutdown, root.clone());
+    let app: Router = mcp_server::build_router(idx, root.clone());
 
     // call endpoint directly against the router
     let body = serde_json::to_vec(&serde_json::json!({"q":"banana","limit":10, "cap_bytes": 4096})).unwrap();
@@ -43,6 +37,12 @@ async fn context_bundle_endpoint_smoke() {
     let size = v["size_bytes"].as_u64().unwrap();
     assert!(size > 0);
     assert!(size <= 4096, "bundle size should respect cap");
+    // Per-item measurements add up to the bundle totals
+    let sum = |field: &str| items.iter().map(|it| it[field].as_u64().unwrap()).sum::<u64>();
+    assert_eq!(sum("bytes"), items.iter().map(|it| it["content"].as_str().unwrap().len() as u64).sum::<u64>());
+    assert_eq!(v["content_bytes"].as_u64().unwrap(), sum("bytes"));
+    assert_eq!(v["tokens_estimate"].as_u64().unwrap(), sum("tokens_estimate"));
+    assert!(v["content_bytes"].as_u64().unwrap() < size);
     // Deterministic ordering: non-increasing score, then path asc
     for w in items.windows(2) {
         let a = &w[0];
@@ -74,11 +74,8 @@ async fn context_bundle_kind_filter() {
     let paths = IndexPaths { root: root.clone(), data_dir: data.clone() };
     let mut idx = SearchIndex::open(&paths).unwrap();
     let _ = idx.scan_and_index(&root).unwrap();
-    let shared_index: Arc<tokio::sync::Mutex<SearchIndex>> = Arc::new(tokio::sync::Mutex::new(idx));
-    let watcher_handle: Arc<tokio::sync::Mutex<Option<JoinHandle<()>>>> = Arc::new(tokio::sync::Mutex::new(None));
-    let watcher_shutdown: Arc<AtomicBool> = Arc::new(AtomicBool::new(true));
 
-    let app: Router = mcp_server::build_router(shared_index.clone(), watcher_handle, watcher_shutdown, root.clone());
+    let app: Router = mcp_server::build_router(idx, root.clone());
 
     // request with kind filter = gdscript
     let body = serde_json::to_vec(&serde_json::json!({"q":"banana","limit":10, "cap_bytes": 4096, "kind": "gdscript"})).unwrap();
@@ -110,11 +107,8 @@ async fn context_bundle_enforces_small_cap() {
     let paths = IndexPaths { root: root.clone(), data_dir: data.clone() };
     let mut idx = SearchIndex::open(&paths).unwrap();
     let _ = idx.scan_and_index(&root).unwrap();
-    let shared_index: Arc<tokio::sync::Mutex<SearchIndex>> = Arc::new(tokio::sync::Mutex::new(idx));
-    let watcher_handle: Arc<tokio::sync::Mutex<Option<JoinHandle<()>>>> = Arc::new(tokio::sync::Mutex::new(None));
-    let watcher_shutdown: Arc<AtomicBool> = Arc::new(AtomicBool::new(true));
 
-    let app: Router = mcp_server::build_router(shared_index.clone(), watcher_handle, watcher_shutdown, root.clone());
+    let app: Router = mcp_server::build_router(idx, root.clone());
 
     let cap = 512u64;
     let body = serde_json::to_vec(&serde_json::json!({"q":"banana","limit":10, "cap_bytes": cap})).unwrap();
@@ -129,3 +123,569 @@ async fn context_bundle_enforces_small_cap() {
     let size = v["size_bytes"].as_u64().unwrap();
     assert!(size <= cap, "bundle size {} should be <= cap {}", size, cap);
 }
+
+#[tokio::test]
+async fn context_bundle_get_with_query_string() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    let data = tmp.path().join("data");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("a.gd"), "func _ready():\n\tprint(\"apple banana\")").unwrap();
+    std::fs::write(root.join("b.rs"), "fn main(){ println!(\"banana\"); }").unwrap();
+
+    let paths = IndexPaths { root: root.clone(), data_dir: data.clone() };
+    let mut idx = SearchIndex::open(&paths).unwrap();
+    let _ = idx.scan_and_index(&root).unwrap();
+
+    let app: Router = mcp_server::build_router(idx, root.clone());
+
+    let req = Request::get("/context/bundle?q=banana&limit=5&kind=rust").body(Body::empty()).unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    let v: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(v["query"], "banana");
+    let items = v["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["kind"], "rust");
+
+    // q is required
+    let req = Request::get("/context/bundle?limit=5").body(Body::empty()).unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn context_bundle_is_compressed_when_accepted() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    let data = tmp.path().join("data");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("a.gd"), "func _ready():\n\tprint(\"banana\")\n".repeat(200)).unwrap();
+
+    let paths = IndexPaths { root: root.clone(), data_dir: data.clone() };
+    let mut idx = SearchIndex::open(&paths).unwrap();
+    let _ = idx.scan_and_index(&root).unwrap();
+
+    let app: Router = mcp_server::build_router(idx, root.clone());
+
+    for encoding in ["gzip", "br"] {
+        let req = Request::get("/context/bundle?q=banana").header("accept-encoding", encoding).body(Body::empty()).unwrap();
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get("content-encoding").unwrap(), encoding);
+        let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+        assert!(bytes.len() < 2000, "compressed body should be much smaller, got {}", bytes.len());
+    }
+
+    // No Accept-Encoding → identity
+    let req = Request::get("/context/bundle?q=banana").body(Body::empty()).unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert!(resp.headers().get("content-encoding").is_none());
+}
+
+#[tokio::test]
+async fn context_bundle_stream_matches_buffered_bundle() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    let data = tmp.path().join("data");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("a.gd"), "func _ready():\n\tprint(\"apple banana\")").unwrap();
+    std::fs::write(root.join("b.rs"), "fn main(){ println!(\"banana\"); }").unwrap();
+    std::fs::write(root.join("doc.txt"), "banana in docs").unwrap();
+
+    let paths = IndexPaths { root: root.clone(), data_dir: data.clone() };
+    let mut idx = SearchIndex::open(&paths).unwrap();
+    let _ = idx.scan_and_index(&root).unwrap();
+
+    let app: Router = mcp_server::build_router(idx, root.clone());
+
+    let req = Request::get("/context/bundle?q=banana").body(Body::empty()).unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let bundle: serde_json::Value = serde_json::from_slice(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap();
+
+    let req = Request::get("/context/bundle/stream?q=banana").body(Body::empty()).unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers().get("content-type").unwrap(), "application/x-ndjson");
+    let text = String::from_utf8(to_bytes(resp.into_body(), 1024 * 1024).await.unwrap().to_vec()).unwrap();
+    let lines: Vec<serde_json::Value> = text.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    let (end, items) = lines.split_last().unwrap();
+    assert_eq!(end["type"], "end");
+    assert_eq!(end["count"].as_u64().unwrap() as usize, items.len());
+    assert_eq!(end["size_bytes"], bundle["size_bytes"]);
+    let streamed: Vec<&serde_json::Value> = items.iter().map(|i| { assert_eq!(i["type"], "item"); &i["path"] }).collect();
+    let buffered: Vec<&serde_json::Value> = bundle["items"].as_array().unwrap().iter().map(|i| &i["path"]).collect();
+    assert_eq!(streamed, buffered);
+    assert!(!streamed.is_empty());
+}
+
+#[tokio::test]
+async fn context_bundle_content_modes() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    let script: String = (0..30).map(|i| format!("func f{i}():\n\tprint(\"banana\")\n")).collect();
+    std::fs::write(root.join("a.gd"), &script).unwrap();
+
+    let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") }).unwrap();
+    let _ = idx.scan_and_index(&root).unwrap();
+    let app: Router = mcp_server::build_router(idx, root.clone());
+
+    let get = |uri: &str| app.clone().oneshot(Request::get(uri).body(Body::empty()).unwrap());
+    let resp = get("/context/bundle?q=banana&content_mode=full").await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let v: serde_json::Value = serde_json::from_slice(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap();
+    assert_eq!(v["items"][0]["content"], script);
+
+    let resp = get("/context/bundle/stream?q=banana&content_mode=head(4)").await.unwrap();
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    let first: serde_json::Value = serde_json::from_slice(bytes.split(|b| *b == b'\n').next().unwrap()).unwrap();
+    assert_eq!(first["type"], "item");
+    assert_eq!(first["content"].as_str().unwrap().lines().count(), 4);
+
+    let resp = get("/context/bundle?q=banana&content_mode=everything").await.unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn context_bundle_renders_markdown_and_xml() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("a.gd"), "func _ready():\n\tprint(\"banana\")\n").unwrap();
+
+    let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") }).unwrap();
+    let _ = idx.scan_and_index(&root).unwrap();
+    let app: Router = mcp_server::build_router(idx, root.clone());
+
+    let body = serde_json::json!({"q": "banana", "format": "markdown", "content_mode": "full"});
+    let req = Request::post("/context/bundle")
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_vec(&body).unwrap()))
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers().get("content-type").unwrap(), "text/markdown; charset=utf-8");
+    let md = String::from_utf8(to_bytes(resp.into_body(), 1024 * 1024).await.unwrap().to_vec()).unwrap();
+    assert_eq!(md, "# Context: banana\n\n## `./a.gd` (lines 1-2)\n\n```gdscript\nfunc _ready():\n\tprint(\"banana\")\n```\n");
+
+    let resp = app.clone().oneshot(Request::get("/context/bundle?q=banana&format=xml").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.headers().get("content-type").unwrap(), "text/plain; charset=utf-8");
+    let xml = String::from_utf8(to_bytes(resp.into_body(), 1024 * 1024).await.unwrap().to_vec()).unwrap();
+    assert!(xml.starts_with("<documents query=\"banana\">\n<document index=\"1\" path=\"./a.gd\" kind=\"gdscript\">\n<document_content>\n"));
+    assert!(xml.ends_with("</document_content>\n</document>\n</documents>\n"));
+
+    let resp = app.oneshot(Request::get("/context/bundle?q=banana&format=pdf").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn context_bundle_chunks_carry_line_ranges() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    let mut lines: Vec<String> = (1..=40).map(|i| format!("var v{i} = {i}")).collect();
+    lines[2] = "var banana = 1".into();
+    lines[30] = "var banana_two = 2".into();
+    std::fs::write(root.join("a.gd"), lines.join("\n")).unwrap();
+
+    let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") }).unwrap();
+    let _ = idx.scan_and_index(&root).unwrap();
+    let app: Router = mcp_server::build_router(idx, root.clone());
+
+    let resp = app.oneshot(Request::get("/context/bundle?q=banana&content_mode=chunks").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let v: serde_json::Value = serde_json::from_slice(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap();
+    let ranges: Vec<(u64, u64)> = v["items"].as_array().unwrap().iter()
+        .map(|it| (it["start_line"].as_u64().unwrap(), it["end_line"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(ranges, vec![(1, 8), (26, 36)]);
+}
+
+#[tokio::test]
+async fn context_bundle_diversity_spreads_directories() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(root.join("enemies")).unwrap();
+    std::fs::create_dir_all(root.join("ui")).unwrap();
+    for name in ["goblin", "orc", "troll"] {
+        std::fs::write(root.join(format!("enemies/{name}.gd")), "func hit(): banana banana banana health -= 1").unwrap();
+    }
+    // One mention in a longer file: relevant, but ranked below the enemy scripts
+    let filler = "var label_text = tr(title) ".repeat(20);
+    std::fs::write(root.join("ui/bar.gd"), format!("func show(): label.text = banana\n{filler}")).unwrap();
+
+    let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") }).unwrap();
+    let _ = idx.scan_and_index(&root).unwrap();
+    let app: Router = mcp_server::build_router(idx, root.clone());
+
+    let paths = |v: serde_json::Value| -> Vec<String> {
+        v["items"].as_array().unwrap().iter().map(|it| it["path"].as_str().unwrap().to_string()).collect()
+    };
+    let get = |uri: &str| app.clone().oneshot(Request::get(uri).body(Body::empty()).unwrap());
+    let body = |resp: axum::response::Response| async move {
+        serde_json::from_slice::<serde_json::Value>(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap()
+    };
+
+    let plain = paths(body(get("/context/bundle?q=banana").await.unwrap()).await);
+    assert_eq!(plain.last().unwrap(), "./ui/bar.gd");
+    let diverse = paths(body(get("/context/bundle?q=banana&diversity=0.6").await.unwrap()).await);
+    assert_eq!(diverse[1], "./ui/bar.gd");
+
+    let resp = get("/context/bundle?q=banana&diversity=1.5").await.unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn context_bundle_quotas_mix_kinds() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    for name in ["a", "b", "c", "d"] {
+        std::fs::write(root.join(format!("{name}.gd")), format!("func hit_{name}(): mango mango mango")).unwrap();
+    }
+    // One mention in a longer file ranks the doc last
+    let filler = "Notes on the combat loop and its tuning. ".repeat(10);
+    std::fs::write(root.join("guide.md"), format!("mango\n{filler}")).unwrap();
+
+    let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") }).unwrap();
+    let _ = idx.scan_and_index(&root).unwrap();
+    let app: Router = mcp_server::build_router(idx, root.clone());
+
+    let kinds = |v: serde_json::Value| -> Vec<String> {
+        v["items"].as_array().unwrap().iter().map(|it| it["kind"].as_str().unwrap().to_string()).collect()
+    };
+    let get = |uri: &str| app.clone().oneshot(Request::get(uri).body(Body::empty()).unwrap());
+    let body = |resp: axum::response::Response| async move {
+        serde_json::from_slice::<serde_json::Value>(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap()
+    };
+
+    let plain = kinds(body(get("/context/bundle?q=mango&cap_bytes=300").await.unwrap()).await);
+    assert!(!plain.contains(&"docs".to_string()));
+    let mixed = kinds(body(get("/context/bundle?q=mango&cap_bytes=300&quotas=gdscript:50,docs:50").await.unwrap()).await);
+    assert!(mixed.contains(&"docs".to_string()));
+    assert!(mixed.contains(&"gdscript".to_string()));
+
+    let resp = get("/context/bundle?q=mango&quotas=gdscript:80,docs:40").await.unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn context_bundle_exclude_drops_matching_paths() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(root.join("addons/gut")).unwrap();
+    std::fs::write(root.join("addons/gut/gut.gd"), "func run(): papaya papaya papaya").unwrap();
+    std::fs::write(root.join("player.gd"), "func move(): papaya").unwrap();
+    std::fs::write(root.join("notes.md"), "papaya notes").unwrap();
+    for name in ["a", "b", "c"] {
+        std::fs::write(root.join(format!("addons/gut/{}.gd", name)), "papaya").unwrap();
+    }
+
+    let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") }).unwrap();
+    let _ = idx.scan_and_index(&root).unwrap();
+    let app: Router = mcp_server::build_router(idx, root.clone());
+
+    let req = Request::post("/context/bundle")
+        .header("content-type", "application/json")
+        .body(Body::from(r#"{"q":"papaya","exclude":["addons/**","*.md"]}"#))
+        .unwrap();
+    let v: serde_json::Value = serde_json::from_slice(&to_bytes(app.clone().oneshot(req).await.unwrap().into_body(), 1024 * 1024).await.unwrap()).unwrap();
+    let paths: Vec<&str> = v["items"].as_array().unwrap().iter().map(|it| it["path"].as_str().unwrap()).collect();
+    assert_eq!(paths, ["./player.gd"]);
+
+    // Excluded hits that outrank the wanted files don't use up `limit`
+    let req = Request::post("/context/bundle")
+        .header("content-type", "application/json")
+        .body(Body::from(r#"{"q":"papaya","limit":2,"exclude":["addons/**"]}"#))
+        .unwrap();
+    let v: serde_json::Value = serde_json::from_slice(&to_bytes(app.clone().oneshot(req).await.unwrap().into_body(), 1024 * 1024).await.unwrap()).unwrap();
+    let mut paths: Vec<&str> = v["items"].as_array().unwrap().iter().map(|it| it["path"].as_str().unwrap()).collect();
+    paths.sort();
+    assert_eq!(paths, ["./notes.md", "./player.gd"]);
+
+    let resp = app.oneshot(Request::get("/context/bundle?q=papaya&exclude=addons/%5B").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn context_bundle_merges_weighted_queries() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("jump.gd"), "func jump(): velocity.y = jump_force").unwrap();
+    std::fs::write(root.join("dash.gd"), "func dash(): velocity.x = dash_speed").unwrap();
+    std::fs::write(root.join("both.gd"), "func air(): jump dash").unwrap();
+
+    let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") }).unwrap();
+    let _ = idx.scan_and_index(&root).unwrap();
+    let app: Router = mcp_server::build_router(idx, root.clone());
+
+    let post = |json: &'static str| {
+        app.clone().oneshot(
+            Request::post("/context/bundle").header("content-type", "application/json").body(Body::from(json)).unwrap(),
+        )
+    };
+    let body = |resp: axum::response::Response| async move {
+        serde_json::from_slice::<serde_json::Value>(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap()
+    };
+
+    let v = body(post(r#"{"queries":["jump",{"q":"dash","weight":2}]}"#).await.unwrap()).await;
+    assert_eq!(v["query"], "jump | dash");
+    let paths: Vec<&str> = v["items"].as_array().unwrap().iter().map(|it| it["path"].as_str().unwrap()).collect();
+    // Each file appears once, whichever queries found it
+    assert_eq!(paths.len(), 3);
+    let mut unique = paths.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), 3);
+
+    let resp = post(r#"{"queries":[{"q":"jump","weight":0}]}"#).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn context_bundle_manifest_sends_only_changes() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("a.gd"), "func a(): lychee").unwrap();
+    std::fs::write(root.join("b.gd"), "func b(): lychee lychee").unwrap();
+
+    let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") }).unwrap();
+    let _ = idx.scan_and_index(&root).unwrap();
+    let app: Router = mcp_server::build_router(idx, root.clone());
+
+    let post = |json: String| {
+        app.clone().oneshot(
+            Request::post("/context/bundle").header("content-type", "application/json").body(Body::from(json)).unwrap(),
+        )
+    };
+    let body = |resp: axum::response::Response| async move {
+        serde_json::from_slice::<serde_json::Value>(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap()
+    };
+
+    let first = body(post(r#"{"q":"lychee","content_mode":"full"}"#.to_string()).await.unwrap()).await;
+    let items = first["items"].as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert!(first["generation"].is_u64());
+    for it in items {
+        assert_eq!(it["hash"].as_str().unwrap(), context::content_hash(it["content"].as_str().unwrap()));
+    }
+    // The client holds a.gd as delivered and an outdated copy of b.gd
+    let entries: Vec<serde_json::Value> = items.iter()
+        .map(|it| {
+            let content = if it["path"] == "./a.gd" { it["content"].as_str().unwrap() } else { "stale" };
+            serde_json::json!({
+                "path": it["path"], "start_line": it["start_line"], "end_line": it["end_line"],
+                "hash": context::content_hash(content),
+            })
+        })
+        .collect();
+    let req = serde_json::json!({ "q": "lychee", "content_mode": "full", "manifest": { "items": entries } });
+    let next = body(post(req.to_string()).await.unwrap()).await;
+    assert_eq!(next["skipped"], serde_json::json!(["./a.gd"]));
+    let items = next["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!((items[0]["path"].as_str(), items[0]["status"].as_str()), (Some("./b.gd"), Some("changed")));
+}
+
+#[tokio::test]
+async fn context_bundle_recency_lifts_recent_edits() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("old.gd"), "func a(): guava guava guava").unwrap();
+    std::fs::write(root.join("new.gd"), "func b(): guava\nvar speed = 10\nvar jump_height = 4\n").unwrap();
+    let month_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(30 * 24 * 60 * 60);
+    std::fs::File::options().write(true).open(root.join("old.gd")).unwrap().set_modified(month_ago).unwrap();
+
+    let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") }).unwrap();
+    let _ = idx.scan_and_index(&root).unwrap();
+    let app: Router = mcp_server::build_router(idx, root.clone());
+
+    let first = |uri: &'static str| {
+        let app = app.clone();
+        async move {
+            let resp = app.oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+            let v: serde_json::Value = serde_json::from_slice(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap();
+            v["items"][0]["path"].as_str().unwrap().to_string()
+        }
+    };
+    assert_eq!(first("/context/bundle?q=guava").await, "./old.gd");
+    assert_eq!(first("/context/bundle?q=guava&recency=0.8").await, "./new.gd");
+}
+
+#[tokio::test]
+async fn context_bundle_scene_packs_scripts() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(
+        root.join("main.tscn"),
+        "[ext_resource type=\"Script\" path=\"res://main.gd\" id=\"1_m\"]\n\n[node name=\"Main\" type=\"Node\"]\nscript = ExtResource(\"1_m\")\n",
+    )
+    .unwrap();
+    std::fs::write(root.join("main.gd"), "extends Node\n").unwrap();
+
+    let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") }).unwrap();
+    let _ = idx.scan_and_index(&root).unwrap();
+    let app: Router = mcp_server::build_router(idx, root.clone());
+
+    let get = |uri: &str| app.clone().oneshot(Request::get(uri).body(Body::empty()).unwrap());
+    let resp = get("/context/bundle/scene?scene=res://main.tscn").await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let v: serde_json::Value = serde_json::from_slice(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap();
+    let paths: Vec<&str> = v["items"].as_array().unwrap().iter().map(|it| it["path"].as_str().unwrap()).collect();
+    assert_eq!(paths, ["./main.tscn", "./main.gd"]);
+
+    assert_eq!(get("/context/bundle/scene?scene=missing.tscn").await.unwrap().status(), StatusCode::NOT_FOUND);
+    assert_eq!(get("/context/bundle/scene?scene=main.gd").await.unwrap().status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn context_bundle_symbol_finds_definition() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("player.gd"), "func take_damage(amount):\n\tpass\n").unwrap();
+    std::fs::write(root.join("enemy.gd"), "func hit(p):\n\tp.take_damage(3)\n").unwrap();
+
+    let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") }).unwrap();
+    let _ = idx.scan_and_index(&root).unwrap();
+    let app: Router = mcp_server::build_router(idx, root.clone());
+
+    let get = |uri: &str| app.clone().oneshot(Request::get(uri).body(Body::empty()).unwrap());
+    let resp = get("/context/bundle/symbol?name=take_damage").await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let v: serde_json::Value = serde_json::from_slice(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap();
+    let paths: Vec<&str> = v["items"].as_array().unwrap().iter().map(|it| it["path"].as_str().unwrap()).collect();
+    assert_eq!(paths, ["./player.gd", "./enemy.gd"]);
+
+    assert_eq!(get("/context/bundle/symbol?name=take%20damage").await.unwrap().status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn context_render_fills_template() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    let config_dir = tmp.path().join("config");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::create_dir_all(config_dir.join("templates")).unwrap();
+    std::fs::write(root.join("jump.gd"), "func jump():\n\tpass # trampoline\n").unwrap();
+    std::fs::write(config_dir.join("templates/explain.md"), "Explain {{ query }}.\n\n{{bundle}}\nIssues:\n{{issues}}\n").unwrap();
+
+    let project = mcp_server::ProjectState::open("default", root.clone(), tmp.path().join("data")).unwrap();
+    project.index.scan(root.clone()).await.unwrap();
+    let mut state = mcp_server::ServerState::new(mcp_server::ProjectRegistry::new(project));
+    state.set_config_dir(config_dir);
+    let app: Router = mcp_server::build_router_with_state(state);
+
+    let post = |body: &str| {
+        app.clone().oneshot(
+            Request::post("/context/render").header("content-type", "application/json").body(Body::from(body.to_string())).unwrap(),
+        )
+    };
+    let resp = post(r#"{"template":"explain","q":"trampoline","content_mode":"full"}"#).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let text = String::from_utf8(to_bytes(resp.into_body(), 1024 * 1024).await.unwrap().to_vec()).unwrap();
+    assert!(text.starts_with("Explain trampoline.\n\n# Context: trampoline\n"), "{}", text);
+    assert!(text.contains("## `./jump.gd`"));
+    assert!(text.contains("\tpass # trampoline\n"));
+    assert!(!text.contains("{{"));
+
+    assert_eq!(post(r#"{"template":"missing","q":"trampoline"}"#).await.unwrap().status(), StatusCode::NOT_FOUND);
+    assert_eq!(post(r#"{"template":"../explain","q":"trampoline"}"#).await.unwrap().status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn context_bundle_expand_finds_synonyms() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("mob.gd"), "extends CharacterBody2D\n# mob that chases the player\n").unwrap();
+    std::fs::write(root.join("overlay.gd"), "extends CanvasLayer\n# overlay drawing the score\n").unwrap();
+
+    let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") }).unwrap();
+    let _ = idx.scan_and_index(&root).unwrap();
+    let app: Router = mcp_server::build_router(idx, root.clone());
+
+    let paths = |uri: &str| {
+        let app = app.clone();
+        let uri = uri.to_string();
+        async move {
+            let resp = app.oneshot(Request::get(uri.as_str()).body(Body::empty()).unwrap()).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            let v: serde_json::Value = serde_json::from_slice(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap();
+            assert_eq!(v["query"], "enemy");
+            v["items"].as_array().unwrap().iter().map(|it| it["path"].as_str().unwrap().to_string()).collect::<Vec<_>>()
+        }
+    };
+    assert!(paths("/context/bundle?q=enemy").await.is_empty());
+    assert_eq!(paths("/context/bundle?q=enemy&expand=true").await, ["./mob.gd"]);
+}
+
+#[tokio::test]
+async fn context_bundle_known_files_skip_unchanged() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    let held = "func a(): pass # walnut\n";
+    std::fs::write(root.join("a.gd"), held).unwrap();
+    std::fs::write(root.join("b.gd"), "func b(): pass # walnut walnut\n").unwrap();
+    std::fs::write(root.join("c.gd"), "func c(): pass # walnut walnut walnut\n").unwrap();
+
+    let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") }).unwrap();
+    let _ = idx.scan_and_index(&root).unwrap();
+    let app: Router = mcp_server::build_router(idx, root.clone());
+
+    let body = serde_json::json!({
+        "q": "walnut",
+        "content_mode": "full",
+        "known_files": [
+            { "path": "res://a.gd", "hash": context::content_hash(held) },
+            { "path": "b.gd", "hash": context::content_hash("func b(): pass # old\n") },
+        ],
+    });
+    let req = Request::post("/context/bundle")
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let resp = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let v: serde_json::Value = serde_json::from_slice(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap();
+    let mut got: Vec<(&str, &str)> = v["items"].as_array().unwrap().iter()
+        .map(|it| (it["path"].as_str().unwrap(), it["status"].as_str().unwrap()))
+        .collect();
+    got.sort();
+    assert_eq!(got, [("./b.gd", "changed"), ("./c.gd", "new")]);
+    assert_eq!(v["skipped"], serde_json::json!(["./a.gd"]));
+}
+
+#[tokio::test]
+async fn context_bundle_error_follows_stack_trace() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    let script: String = (1..=30).map(|i| format!("var v{} = {}\n", i, i)).collect();
+    std::fs::write(root.join("player.gd"), &script).unwrap();
+
+    let mut idx = SearchIndex::open(&IndexPaths { root: root.clone(), data_dir: tmp.path().join("data") }).unwrap();
+    let _ = idx.scan_and_index(&root).unwrap();
+    let app: Router = mcp_server::build_router(idx, root.clone());
+
+    let post = |log: &str| {
+        let body = serde_json::json!({ "log": log }).to_string();
+        app.clone().oneshot(Request::post("/context/bundle/error").header("content-type", "application/json").body(Body::from(body)).unwrap())
+    };
+    let resp = post("SCRIPT ERROR: Invalid call.\n   at: _ready (res://player.gd:20)\n").await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let v: serde_json::Value = serde_json::from_slice(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap();
+    let item = &v["items"][0];
+    assert_eq!(item["path"], "./player.gd");
+    assert_eq!((item["start_line"].as_u64(), item["end_line"].as_u64()), (Some(15), Some(25)));
+    assert!(item["content"].as_str().unwrap().contains("var v20 = 20\n"));
+
+    assert_eq!(post("  ").await.unwrap().status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,39 @@
+// Helpers shared by the route tests; each test binary uses some of them.
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use axum::{Router, body::{Body, to_bytes}};
+use mcp_server::{ProjectRegistry, ProjectState};
+use tower::ServiceExt; // for oneshot
+use hyper::{Request, StatusCode};
+
+/// The default project at `<tmp>/root`, holding `files` (path, contents), scanned.
+pub async fn project(tmp: &Path, files: &[(&str, &str)]) -> (ProjectState, PathBuf) {
+    let root = tmp.join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    for (path, contents) in files {
+        std::fs::write(root.join(path), contents).unwrap();
+    }
+    let project = ProjectState::open("default", root.clone(), tmp.join("data")).unwrap();
+    project.index.scan(root.clone()).await.unwrap();
+    (project, root)
+}
+
+/// A router serving just `project(tmp, files)`, and the project root.
+pub async fn app(tmp: &Path, files: &[(&str, &str)]) -> (Router, PathBuf) {
+    let (project, root) = project(tmp, files).await;
+    (mcp_server::build_router_with_registry(ProjectRegistry::new(project)), root)
+}
+
+/// Status and JSON body (`Null` when the body isn't JSON).
+pub async fn send(app: &Router, req: Request<Body>) -> (StatusCode, serde_json::Value) {
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+}
+
+pub fn post_json(uri: &str, body: serde_json::Value) -> Request<Body> {
+    Request::post(uri).header("content-type", "application/json").body(Body::from(serde_json::to_vec(&body).unwrap())).unwrap()
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use axum::body::Body;
+use mcp_server::{ProjectRegistry, ServerState};
+use hyper::{Request, StatusCode};
+
+use common::send;
+
+fn reload() -> Request<Body> {
+    Request::post("/config/reload").body(Body::empty()).unwrap()
+}
+
+#[tokio::test]
+async fn reload_applies_tunables_without_restart() {
+    let tmp = tempfile::tempdir().unwrap();
+    let config_dir = tmp.path().join("config");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    let (project, root) = common::project(tmp.path(), &[("a.gd", "func alpha(): pass # banana")]).await;
+    let index = project.index.clone();
+    let mut state = ServerState::new(ProjectRegistry::new(project));
+    state.set_config_dir(config_dir.clone());
+    let app = mcp_server::build_router_with_state(state);
+
+    let (status, _) = send(&app, Request::get("/context/bundle?q=banana&cap_bytes=2048").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    std::fs::write(config_dir.join("default.yaml"), "\
+server:
+  host: 127.0.0.1
+  port: 8080
+  auto_start_watchers: false
+bundle:
+  default_cap_bytes: 512
+  max_cap_bytes: 1024
+index:
+  skip: [\"/exports/\"]
+").unwrap();
+    let (status, v) = send(&app, reload()).await;
+    assert_eq!(status, StatusCode::OK, "{}", v);
+    assert_eq!(v["changed"], serde_json::json!(["bundle.default_cap_bytes", "bundle.max_cap_bytes", "index.skip"]));
+
+    // New max applies immediately; the index is untouched
+    let (status, v) = send(&app, Request::get("/context/bundle?q=banana&cap_bytes=2048").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(v["error"], "cap_bytes must be between 1 and 1024 (got 2048)");
+    let (_, v) = send(&app, Request::get("/context/bundle?q=banana").body(Body::empty()).unwrap()).await;
+    assert_eq!(v["items"].as_array().unwrap().len(), 1);
+    // Later scans honour the new skip fragments
+    std::fs::create_dir_all(root.join("exports")).unwrap();
+    std::fs::write(root.join("exports/x.gd"), "func beta(): pass # banana").unwrap();
+    index.scan(root.clone()).await.unwrap();
+    let (_, v) = send(&app, Request::get("/context/bundle?q=banana").body(Body::empty()).unwrap()).await;
+    assert_eq!(v["items"].as_array().unwrap().len(), 1);
+
+    // Turning auto-watch on starts watchers; reloading again reports no changes
+    let yaml = std::fs::read_to_string(config_dir.join("default.yaml")).unwrap();
+    std::fs::write(config_dir.join("default.yaml"), yaml.replace("auto_start_watchers: false", "auto_start_watchers: true")).unwrap();
+    let (_, v) = send(&app, reload()).await;
+    assert_eq!(v["changed"], serde_json::json!(["server.auto_start_watchers"]));
+    assert_eq!(v["watchers_started"], serde_json::json!(["default"]));
+    let (_, v) = send(&app, reload()).await;
+    assert_eq!(v["changed"], serde_json::json!([]));
+    send(&app, Request::post("/index/watch/stop").body(Body::empty()).unwrap()).await;
+
+    // Contradicting caps are rejected and leave the current settings alone
+    let yaml = std::fs::read_to_string(config_dir.join("default.yaml")).unwrap();
+    std::fs::write(config_dir.join("default.yaml"), yaml.replace("default_cap_bytes: 512", "default_cap_bytes: 4096")).unwrap();
+    let (status, v) = send(&app, reload()).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(v["error"].as_str().unwrap().contains("max_cap_bytes (1024) is below bundle.default_cap_bytes (4096)"), "{}", v);
+    let (status, _) = send(&app, Request::get("/context/bundle?q=banana&cap_bytes=1024").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    std::fs::write(config_dir.join("default.yaml"), "server: [not, a, map]").unwrap();
+    let (status, v) = send(&app, reload()).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(v["error"].as_str().unwrap().starts_with("config reload failed"));
+}
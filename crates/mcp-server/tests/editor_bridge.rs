@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use axum::{Router, body::{Body, to_bytes}};
+use mcp_server::{ProjectRegistry, ProjectState, ServerState};
+use tokio::io::AsyncWriteExt;
+use tower::ServiceExt; // for oneshot
+use hyper::{Request, StatusCode};
+
+async fn editor_state(app: &Router) -> serde_json::Value {
+    let resp = app.clone().oneshot(Request::get("/editor/state").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    serde_json::from_slice(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap()
+}
+
+async fn wait_for(app: &Router, pred: impl Fn(&serde_json::Value) -> bool) -> serde_json::Value {
+    for _ in 0..100 {
+        let v = editor_state(app).await;
+        if pred(&v) { return v; }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("editor state never matched: {}", editor_state(app).await);
+}
+
+#[tokio::test]
+async fn plugin_messages_update_editor_state() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    let state = ServerState::new(ProjectRegistry::new(ProjectState::open("default", root, tmp.path().join("data")).unwrap()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(mcp_server::editor_bridge::serve_editor_bridge(listener, state.editor().clone()));
+    let app: Router = mcp_server::build_router_with_state(state);
+
+    let v = editor_state(&app).await;
+    assert_eq!(v["connected"], false);
+
+    let mut plugin = tokio::net::TcpStream::connect(addr).await.unwrap();
+    let msgs = [
+        r#"{"type":"hello","project":"Demo","godot_version":"4.5"}"#,
+        r#"{"type":"scene_changed","scene":"res://scenes/main.tscn"}"#,
+        r#"{"type":"selection","nodes":["Player","Player/Camera2D"]}"#,
+        "not json",
+        r#"{"type":"runtime_error","message":"Invalid call","file":"res://player.gd","line":12}"#,
+    ];
+    for m in msgs { plugin.write_all(format!("{m}\n").as_bytes()).await.unwrap(); }
+
+    let v = wait_for(&app, |v| v["errors"].as_array().map(|e| e.len() == 1).unwrap_or(false)).await;
+    assert_eq!(v["connected"], true);
+    assert_eq!(v["project"], "Demo");
+    assert_eq!(v["current_scene"], "res://scenes/main.tscn");
+    assert_eq!(v["selected_nodes"], serde_json::json!(["Player", "Player/Camera2D"]));
+    assert_eq!(v["errors"][0]["line"], 12);
+
+    drop(plugin);
+    let v = wait_for(&app, |v| v["connected"] == false).await;
+    assert!(v["current_scene"].is_null());
+    // Errors survive a disconnect so clients can still read them
+    assert_eq!(v["errors"].as_array().unwrap().len(), 1);
+}
@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use axum::{Router, body::{Body, to_bytes}};
+use http_body_util::BodyExt;
+use mcp_server::{ProjectRegistry, ProjectState};
+use tower::ServiceExt; // for oneshot
+use hyper::{Request, StatusCode};
+
+fn app(tmp: &std::path::Path) -> (Router, std::path::PathBuf) {
+    let root = tmp.join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    let project = ProjectState::open("default", root.clone(), tmp.join("data")).unwrap();
+    (mcp_server::build_router_with_registry(ProjectRegistry::new(project)), root)
+}
+
+#[tokio::test]
+async fn watcher_commits_stream_as_sse() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (app, root) = app(tmp.path());
+
+    let resp = app.clone().oneshot(Request::get("/events?project=default").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "text/event-stream");
+    let mut body = resp.into_body();
+
+    app.clone().oneshot(Request::post("/index/watch/start").body(Body::empty()).unwrap()).await.unwrap();
+    // Give the watcher a moment to register before touching the tree
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    std::fs::write(root.join("player.gd"), "extends Node").unwrap();
+
+    let mut text = String::new();
+    let read = async {
+        while !text.contains("\n\n") {
+            let frame = body.frame().await.unwrap().unwrap();
+            if let Some(data) = frame.data_ref() {
+                text.push_str(std::str::from_utf8(data).unwrap());
+            }
+        }
+    };
+    tokio::time::timeout(Duration::from_secs(10), read).await.expect("no event streamed");
+    app.clone().oneshot(Request::post("/index/watch/stop").body(Body::empty()).unwrap()).await.unwrap();
+
+    assert!(text.starts_with("event: index.commit\n"), "{}", text);
+    let data = text.lines().find_map(|l| l.strip_prefix("data: ")).unwrap();
+    let event: serde_json::Value = serde_json::from_str(data).unwrap();
+    assert_eq!(event["project"], "default");
+    assert!(event["changes"].as_array().unwrap().contains(&serde_json::json!({"path": "./player.gd", "kind": "gdscript", "change": "indexed"})));
+}
+
+#[tokio::test]
+async fn unknown_project_is_404() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (app, _) = app(tmp.path());
+    let resp = app.oneshot(Request::get("/events?project=nope").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let v: serde_json::Value = serde_json::from_slice(&to_bytes(resp.into_body(), 1024).await.unwrap()).unwrap();
+    assert_eq!(v["error"], "unknown project: nope");
+}
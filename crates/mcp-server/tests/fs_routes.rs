@@ -0,0 +1,127 @@
+use std::path::Path;
+
+use axum::{Router, body::{Body, to_bytes}};
+use mcp_server::{ProjectRegistry, ProjectState};
+use tower::ServiceExt; // for oneshot
+use hyper::{Request, StatusCode};
+
+fn app_for(root: &Path, data: &Path) -> Router {
+    let project = ProjectState::open("default", root.to_path_buf(), data.to_path_buf()).unwrap();
+    mcp_server::build_router_with_registry(ProjectRegistry::new(project))
+}
+
+async fn get_json(app: &Router, uri: &str) -> (StatusCode, serde_json::Value) {
+    let resp = app.clone().oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+    let status = resp.status();
+    let bytes = to_bytes(resp.into_body(), 4 * 1024 * 1024).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn read_supports_byte_and_line_ranges() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(root.join("scripts")).unwrap();
+    std::fs::write(root.join("scripts/a.gd"), "extends Node\nfunc a():\n    pass\n").unwrap();
+    let app = app_for(&root, &tmp.path().join("data"));
+
+    let (status, v) = get_json(&app, "/fs/read?path=./scripts/a.gd").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(v["content"], "extends Node\nfunc a():\n    pass\n");
+    assert_eq!(v["size"], 32);
+    assert_eq!(v["truncated"], false);
+
+    let (_, v) = get_json(&app, "/fs/read?path=res://scripts/a.gd&start_byte=0&end_byte=7").await;
+    assert_eq!(v["content"], "extends");
+
+    let (_, v) = get_json(&app, "/fs/read?path=scripts/a.gd&start_line=2&end_line=3").await;
+    assert_eq!(v["content"], "func a():\n    pass\n");
+
+    let (status, _) = get_json(&app, "/fs/read?path=scripts/a.gd&start_line=1&end_byte=3").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn read_returns_only_the_selection_of_large_files() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    // 20,000 numbered lines of 100 bytes: ~2 MiB, past the 1 MiB read limit
+    let big: String = (1..=20_000).map(|n| format!("{:<99}\n", format!("line {n}"))).collect();
+    std::fs::write(root.join("big.txt"), &big).unwrap();
+    let app = app_for(&root, &tmp.path().join("data"));
+
+    let (_, v) = get_json(&app, "/fs/read?path=big.txt").await;
+    assert_eq!(v["size"], 2_000_000);
+    assert_eq!(v["content"].as_str().unwrap().len(), 1024 * 1024);
+    assert_eq!(v["truncated"], true);
+
+    let (_, v) = get_json(&app, "/fs/read?path=big.txt&start_byte=1999900").await;
+    assert_eq!(v["content"].as_str().unwrap().trim_end(), "line 20000");
+    assert_eq!(v["truncated"], false);
+
+    let (_, v) = get_json(&app, "/fs/read?path=big.txt&start_line=15000&end_line=15001").await;
+    assert_eq!(v["content"], big[1_499_900..1_500_100]);
+    assert_eq!(v["truncated"], false);
+
+    let (_, v) = get_json(&app, "/fs/read?path=big.txt&start_line=19990").await;
+    assert_eq!(v["content"].as_str().unwrap().lines().count(), 11);
+}
+
+#[tokio::test]
+async fn read_is_confined_to_project_root() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(tmp.path().join("secret.txt"), "nope").unwrap();
+    let app = app_for(&root, &tmp.path().join("data"));
+
+    let (status, _) = get_json(&app, "/fs/read?path=../secret.txt").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let abs = tmp.path().join("secret.txt");
+    let (status, _) = get_json(&app, &format!("/fs/read?path={}", abs.display())).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = get_json(&app, "/fs/read?path=missing.gd").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(tmp.path().join("secret.txt"), root.join("link.txt")).unwrap();
+        let (status, v) = get_json(&app, "/fs/read?path=link.txt").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert!(v["error"].as_str().unwrap().contains("escapes"));
+    }
+}
+
+#[tokio::test]
+async fn list_respects_depth_and_skip_rules() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(root.join("scenes/levels")).unwrap();
+    std::fs::create_dir_all(root.join(".godot/imported")).unwrap();
+    std::fs::write(root.join("project.godot"), "config_version=5\n").unwrap();
+    std::fs::write(root.join("scenes/main.tscn"), "[gd_scene]\n").unwrap();
+    std::fs::write(root.join("scenes/levels/one.tscn"), "[gd_scene]\n").unwrap();
+    std::fs::write(root.join(".godot/imported/x.ctex"), "x").unwrap();
+    let app = app_for(&root, &tmp.path().join("data"));
+
+    let (status, v) = get_json(&app, "/fs/list").await;
+    assert_eq!(status, StatusCode::OK);
+    let entries = v["entries"].as_array().unwrap();
+    let paths: Vec<&str> = entries.iter().map(|e| e["path"].as_str().unwrap()).collect();
+    assert_eq!(paths, vec!["project.godot", "scenes"]);
+    assert_eq!(entries[0]["kind"], "file");
+    assert_eq!(entries[0]["size"], 17);
+    assert!(entries[0]["mtime"].as_u64().is_some());
+    assert_eq!(entries[1]["kind"], "dir");
+
+    let (_, v) = get_json(&app, "/fs/list?path=scenes&depth=2").await;
+    let paths: Vec<&str> = v["entries"].as_array().unwrap().iter().map(|e| e["path"].as_str().unwrap()).collect();
+    assert_eq!(paths, vec!["scenes/levels", "scenes/levels/one.tscn", "scenes/main.tscn"]);
+    assert_eq!(v["truncated"], false);
+
+    let (status, _) = get_json(&app, "/fs/list?path=..").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = get_json(&app, "/fs/list?path=project.godot").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,112 @@
+#![cfg(unix)]
+use std::{os::unix::fs::PermissionsExt, path::{Path, PathBuf}};
+
+use axum::{Router, body::{Body, to_bytes}};
+use common::GodotConfig;
+use mcp_server::{ProjectRegistry, ProjectState, ServerState};
+use tower::ServiceExt; // for oneshot
+use hyper::{Request, StatusCode};
+
+/// Stand-in for the Godot binary: a shell script with the given body.
+fn fake_godot(dir: &Path, body: &str) -> PathBuf {
+    let path = dir.join("fake-godot.sh");
+    std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+fn app_with_godot(tmp: &Path, binary: Option<PathBuf>, timeout_secs: u64) -> Router {
+    let root = tmp.join("root");
+    std::fs::create_dir_all(root.join("scripts")).unwrap();
+    std::fs::write(root.join("scripts/player.gd"), "extends Node\nfunc _ready(): foo()\n").unwrap();
+    let mut state = ServerState::new(ProjectRegistry::new(ProjectState::open("default", root, tmp.join("data")).unwrap()));
+    state.set_godot_config(GodotConfig { binary, timeout_secs });
+    mcp_server::build_router_with_state(state)
+}
+
+async fn post_json(app: &Router, uri: &str, v: serde_json::Value) -> (StatusCode, serde_json::Value) {
+    let req = Request::post(uri).header("content-type", "application/json").body(Body::from(serde_json::to_vec(&v).unwrap())).unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    (status, serde_json::from_slice(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap())
+}
+
+#[tokio::test]
+async fn check_maps_engine_errors_to_issues() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let bin = fake_godot(tmp.path(), r#"echo "args: $*"
+echo 'SCRIPT ERROR: Parse Error: Function "foo()" not found in base self.' >&2
+echo '          at: GDScript::reload (res://scripts/player.gd:2)' >&2
+exit 1"#);
+    let app = app_with_godot(tmp.path(), Some(bin), 30);
+
+    let (status, v) = post_json(&app, "/godot/check", serde_json::json!({"script": "scripts/player.gd"})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(v["exit_code"], 1);
+    assert_eq!(v["timed_out"], false);
+    let cmd: Vec<&str> = v["command"].as_array().unwrap().iter().map(|a| a.as_str().unwrap()).collect();
+    assert_eq!(&cmd[cmd.len() - 3..], ["--check-only", "--script", "res://scripts/player.gd"]);
+    let issues = v["issues"].as_array().unwrap();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0]["severity"], "error");
+    assert_eq!(issues[0]["file"], "scripts/player.gd");
+    assert!(issues[0]["message"].as_str().unwrap().ends_with("(line 2)"));
+
+    // Whole-project run uses --import
+    let (_, v) = post_json(&app, "/godot/check", serde_json::json!({})).await;
+    assert_eq!(v["command"].as_array().unwrap().last().unwrap(), "--import");
+
+    let (status, _) = post_json(&app, "/godot/check", serde_json::json!({"script": "missing.gd"})).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn check_requires_binary_and_enforces_timeout() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let app = app_with_godot(tmp.path(), None, 30);
+    let (status, _) = post_json(&app, "/godot/check", serde_json::json!({})).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let bin = fake_godot(tmp.path(), "echo 'Loading project'\necho 'ERROR: stuck importing' >&2\nsleep 10");
+    let app = app_with_godot(tmp.path(), Some(bin), 1);
+    let (status, v) = post_json(&app, "/godot/check", serde_json::json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(v["timed_out"], true);
+    assert!(v["exit_code"].is_null());
+    // Output printed before the timeout is kept
+    assert_eq!(v["output"], "Loading project\nERROR: stuck importing\n");
+}
+
+#[tokio::test]
+async fn export_streams_logs_and_reports_artifact() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    // Prints a log line on each stream and writes the artifact (last argument)
+    let bin = fake_godot(tmp.path(), r#"echo "exporting $5"
+echo "warning: something" >&2
+for last; do :; done
+printf 'binary' > "$last""#);
+    let app = app_with_godot(tmp.path(), Some(bin), 30);
+    std::fs::write(tmp.path().join("root/export_presets.cfg"), "[preset.0]\n\nname=\"Linux\"\nplatform=\"Linux\"\nexport_path=\"build/game.x86_64\"\n").unwrap();
+
+    let req = Request::post("/godot/export").header("content-type", "application/json")
+        .body(Body::from(r#"{"preset":"Linux"}"#)).unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers().get("content-type").unwrap(), "application/x-ndjson");
+    let text = String::from_utf8(to_bytes(resp.into_body(), 1024 * 1024).await.unwrap().to_vec()).unwrap();
+    let lines: Vec<serde_json::Value> = text.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    let (end, logs) = lines.split_last().unwrap();
+    assert!(logs.iter().any(|l| l["stream"] == "stdout" && l["line"] == "exporting Linux"));
+    assert!(logs.iter().any(|l| l["stream"] == "stderr" && l["line"] == "warning: something"));
+    assert_eq!(end["type"], "end");
+    assert_eq!(end["exit_code"], 0);
+    assert_eq!(end["artifact"], "build/game.x86_64");
+    assert_eq!(end["artifact_exists"], true);
+    assert_eq!(end["artifact_bytes"], 6);
+
+    let (status, _) = post_json(&app, "/godot/export", serde_json::json!({"preset": "Android"})).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = post_json(&app, "/godot/export", serde_json::json!({"preset": "Linux", "output": "../escape.bin"})).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
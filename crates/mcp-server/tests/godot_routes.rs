@@ -0,0 +1,184 @@
+use std::path::Path;
+
+use axum::{Router, body::{Body, to_bytes}};
+use index::{IndexPaths, SearchIndex};
+use tower::ServiceExt; // for oneshot
+use hyper::{Request, StatusCode};
+
+fn app_for(root: &Path, data: &Path) -> Router {
+    let paths = IndexPaths { root: root.to_path_buf(), data_dir: data.to_path_buf() };
+    let idx = SearchIndex::open(&paths).unwrap();
+    mcp_server::build_router(idx, root.to_path_buf())
+}
+
+async fn json_of(app: &Router, req: Request<Body>) -> (StatusCode, serde_json::Value) {
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let bytes = to_bytes(resp.into_body(), 4 * 1024 * 1024).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn analyze_returns_report_and_optional_sarif() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("project.godot"), "[application]\nconfig_version=5\n").unwrap();
+    let app = app_for(&root, &tmp.path().join("data"));
+
+    let (status, v) = json_of(&app, Request::get("/godot/analyze").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(v["report"]["project_format_version"], 5);
+    assert!(v["report"]["issues"].as_array().unwrap().iter().any(|i| i["message"].as_str().unwrap().contains("No main scene configured")));
+    assert!(v.get("sarif").is_none());
+
+    let body = serde_json::to_vec(&serde_json::json!({"sarif": true})).unwrap();
+    let req = Request::post("/godot/analyze").header("content-type", "application/json").body(Body::from(body)).unwrap();
+    let (status, v) = json_of(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(v["sarif"]["version"], "2.1.0");
+}
+
+#[tokio::test]
+async fn scene_validate_reports_lines_and_filters_checks() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(root.join("scenes")).unwrap();
+    std::fs::write(root.join("project.godot"), "[application]\nconfig_version=5\n").unwrap();
+    let scene = r#"[gd_scene load_steps=2 format=2]
+
+[node name="Root" type="Node" path="/root"]
+script="res://scripts/missing.gd"
+_p = preload("res://missing/thing.tscn")
+"#;
+    std::fs::write(root.join("scenes/main.tscn"), scene).unwrap();
+    let app = app_for(&root, &tmp.path().join("data"));
+
+    let body = serde_json::to_vec(&serde_json::json!({"scene": "res://scenes/main.tscn"})).unwrap();
+    let req = Request::post("/godot/scenes/validate").header("content-type", "application/json").body(Body::from(body)).unwrap();
+    let (status, v) = json_of(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    let issues = v["issues"].as_array().unwrap();
+    let script = issues.iter().find(|i| i["message"] == "Missing script: res://scripts/missing.gd").expect("missing script issue");
+    assert_eq!(script["line"], 4);
+    assert_eq!(script["node_path"], "/root");
+    assert_eq!(script["file"], "scenes/main.tscn");
+
+    // Only preload check selected
+    let body = serde_json::to_vec(&serde_json::json!({"checks": ["preload"]})).unwrap();
+    let req = Request::post("/godot/scenes/validate").header("content-type", "application/json").body(Body::from(body)).unwrap();
+    let (_, v) = json_of(&app, req).await;
+    let issues = v["issues"].as_array().unwrap();
+    assert!(!issues.is_empty());
+    assert!(issues.iter().all(|i| i["message"].as_str().unwrap().starts_with("Preload missing file:")));
+
+    // Bad inputs
+    let body = serde_json::to_vec(&serde_json::json!({"checks": ["bogus"]})).unwrap();
+    let req = Request::post("/godot/scenes/validate").header("content-type", "application/json").body(Body::from(body)).unwrap();
+    assert_eq!(json_of(&app, req).await.0, StatusCode::BAD_REQUEST);
+    let body = serde_json::to_vec(&serde_json::json!({"scene": "../outside.tscn"})).unwrap();
+    let req = Request::post("/godot/scenes/validate").header("content-type", "application/json").body(Body::from(body)).unwrap();
+    assert_eq!(json_of(&app, req).await.0, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn lint_filters_by_severity_and_emits_sarif() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    // Default-severity (warn) file and an error-level file
+    std::fs::write(root.join("a.gd"), "extends Node\nfunc _ready():\n    print(\"hi\")\n").unwrap();
+    std::fs::write(root.join("b.gd"), "# gd-lint: level=error\nfunc _ready():\n    pass\n").unwrap();
+    let app = app_for(&root, &tmp.path().join("data"));
+
+    let (status, v) = json_of(&app, Request::get("/godot/lint").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+    let codes: Vec<&str> = v["findings"].as_array().unwrap().iter().map(|f| f["code"].as_str().unwrap()).collect();
+    assert_eq!(codes, vec!["debug-print", "missing-extends"]);
+    assert!(v.get("sarif").is_none());
+
+    let req = Request::get("/godot/lint?min_severity=error&sarif=true").body(Body::empty()).unwrap();
+    let (status, v) = json_of(&app, req).await;
+    assert_eq!(status, StatusCode::OK);
+    let findings = v["findings"].as_array().unwrap();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0]["severity"], "error");
+    assert_eq!(findings[0]["file"], "b.gd");
+    let results = v["sarif"]["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["ruleId"], "script-lint");
+    assert_eq!(results[0]["level"], "error");
+}
+
+#[tokio::test]
+async fn structure_fix_apply_requires_matching_token() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("player.gd"), "extends Node\n").unwrap();
+    let app = app_for(&root, &tmp.path().join("data"));
+
+    let post_json = |uri: &str, v: serde_json::Value| {
+        Request::post(uri).header("content-type", "application/json").body(Body::from(serde_json::to_vec(&v).unwrap())).unwrap()
+    };
+
+    let (status, v) = json_of(&app, post_json("/godot/structure-fix/plan", serde_json::json!({}))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(v["plan"]["moves"][0]["to"], "res://scripts/player.gd");
+    let token = v["token"].as_str().unwrap().to_string();
+    // Plan is a dry run
+    assert!(root.join("player.gd").exists());
+
+    let (status, _) = json_of(&app, post_json("/godot/structure-fix/apply", serde_json::json!({"token": "bogus"}))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert!(root.join("player.gd").exists());
+
+    let (status, v) = json_of(&app, post_json("/godot/structure-fix/apply", serde_json::json!({"token": token}))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(v["moved"].as_array().unwrap().len(), 1);
+    assert!(root.join("scripts/player.gd").exists());
+}
+
+#[tokio::test]
+async fn structure_fix_apply_takes_a_selection_of_moves() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("player.gd"), "extends Node\n").unwrap();
+    std::fs::write(root.join("main.tscn"), "[gd_scene format=3]\n").unwrap();
+    let app = app_for(&root, &tmp.path().join("data"));
+
+    let post_json = |uri: &str, v: serde_json::Value| {
+        Request::post(uri).header("content-type", "application/json").body(Body::from(serde_json::to_vec(&v).unwrap())).unwrap()
+    };
+    let (_, v) = json_of(&app, post_json("/godot/structure-fix/plan", serde_json::json!({}))).await;
+    let token = v["token"].as_str().unwrap().to_string();
+
+    let (status, _) = json_of(&app, post_json("/godot/structure-fix/apply", serde_json::json!({"token": token, "include": ["["]}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, v) = json_of(&app, post_json("/godot/structure-fix/apply", serde_json::json!({"token": token, "include": ["scenes/**"]}))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(v["moved"][0]["to"], "res://scenes/main.tscn");
+    assert_eq!(v["moved"].as_array().unwrap().len(), 1);
+    assert!(root.join("player.gd").exists());
+}
+
+#[tokio::test]
+async fn signal_graph_lists_connections_with_status() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    let scene = "[gd_scene format=3]\n\n[node name=\"Root\" type=\"Node\"]\n\n[connection signal=\"ready\" from=\".\" to=\"Missing\" method=\"_on_ready\" flags=3]\n";
+    std::fs::write(root.join("main.tscn"), scene).unwrap();
+    let app = app_for(&root, &tmp.path().join("data"));
+
+    let (status, v) = json_of(&app, Request::get("/godot/signals/graph").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+    let edges = v["edges"].as_array().unwrap();
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0]["line"], 5);
+    assert_eq!(edges[0]["deferred"], true);
+    assert_eq!(edges[0]["one_shot"], false);
+    assert_eq!(edges[0]["to_status"], "missing");
+}
@@ -0,0 +1,71 @@
+use axum::{Router, body::{Body, to_bytes}};
+use mcp_server::{ProjectRegistry, ProjectState};
+use tower::ServiceExt; // for oneshot
+use hyper::{Request, StatusCode};
+
+async fn app(tmp: &std::path::Path) -> Router {
+    let root = tmp.join("root");
+    std::fs::create_dir_all(root.join("scripts")).unwrap();
+    std::fs::write(root.join("scripts/player.gd"), "extends Node\nfunc jump(): pass # banana\n\tprint(\"hi\")\n").unwrap();
+    std::fs::write(root.join("notes.txt"), "banana notes").unwrap();
+    let project = ProjectState::open("default", root.clone(), tmp.join("data")).unwrap();
+    project.index.scan(root.clone()).await.unwrap();
+    mcp_server::build_router_with_registry(ProjectRegistry::new(project))
+}
+
+async fn graphql(app: &Router, query: &str) -> serde_json::Value {
+    let body = serde_json::json!({ "query": query });
+    let req = Request::post("/graphql").header("content-type", "application/json").body(Body::from(body.to_string())).unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+#[tokio::test]
+async fn search_contents_and_issues_in_one_request() {
+    let tmp = tempfile::tempdir().unwrap();
+    let app = app(tmp.path()).await;
+    let v = graphql(&app, r#"{
+        search(q: "banana", kind: "gdscript") {
+            path
+            kind
+            content(startLine: 2, endLine: 2) { content size truncated }
+            issues { severity code file }
+        }
+    }"#).await;
+    assert!(v.get("errors").is_none(), "{}", v);
+    let hits = v["data"]["search"].as_array().unwrap();
+    assert_eq!(hits.len(), 1);
+    let hit = &hits[0];
+    assert_eq!(hit["path"], "./scripts/player.gd");
+    assert_eq!(hit["content"]["content"], "func jump(): pass # banana\n");
+    assert_eq!(hit["content"]["truncated"], false);
+    let codes: Vec<&str> = hit["issues"].as_array().unwrap().iter().map(|i| i["code"].as_str().unwrap()).collect();
+    assert!(codes.contains(&"debug-print"), "{:?}", codes);
+    assert!(hit["issues"].as_array().unwrap().iter().all(|i| i["file"] == "scripts/player.gd"));
+}
+
+#[tokio::test]
+async fn errors_carry_http_status() {
+    let tmp = tempfile::tempdir().unwrap();
+    let app = app(tmp.path()).await;
+    let v = graphql(&app, r#"{ search(q: "banana", limit: 500) { path } }"#).await;
+    assert_eq!(v["errors"][0]["message"], "limit must be between 1 and 100 (got 500)");
+    assert_eq!(v["errors"][0]["extensions"]["status"], 400);
+
+    let v = graphql(&app, r#"{ search(q: "banana", project: "nope") { path } }"#).await;
+    assert_eq!(v["errors"][0]["extensions"]["status"], 404);
+
+    let v = graphql(&app, "{ projects { id isDefault } }").await;
+    assert_eq!(v["data"]["projects"], serde_json::json!([{ "id": "default", "isDefault": true }]));
+}
+
+#[tokio::test]
+async fn get_serves_the_schema() {
+    let tmp = tempfile::tempdir().unwrap();
+    let app = app(tmp.path()).await;
+    let resp = app.oneshot(Request::get("/graphql").body(Body::empty()).unwrap()).await.unwrap();
+    let sdl = String::from_utf8(to_bytes(resp.into_body(), 1024 * 1024).await.unwrap().to_vec()).unwrap();
+    assert!(sdl.contains("search(q: String!"), "{}", sdl);
+}
@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use mcp_server::{
+    grpc::proto::{
+        bundle_event, context_service_client::ContextServiceClient, godot_service_client::GodotServiceClient,
+        index_service_client::IndexServiceClient, BundleRequest, JobState, LintRequest, QueryRequest, ScanRequest,
+    },
+    ProjectRegistry, ProjectState, ServerState,
+};
+use tonic::{transport::Channel, Code};
+
+async fn serve(tmp: &std::path::Path) -> (Channel, std::path::PathBuf) {
+    let root = tmp.join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("a.gd"), "func alpha(): pass # banana\n\tprint(\"x\")\n").unwrap();
+    std::fs::write(root.join("b.txt"), "banana notes").unwrap();
+    let project = ProjectState::open("default", root.clone(), tmp.join("data")).unwrap();
+    project.index.scan(root.clone()).await.unwrap();
+    let state = Arc::new(ServerState::new(ProjectRegistry::new(project)));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(mcp_server::grpc::serve_grpc(listener, state));
+    let channel = Channel::from_shared(format!("http://{}", addr)).unwrap().connect().await.unwrap();
+    (channel, root)
+}
+
+#[tokio::test]
+async fn query_and_bundle_over_grpc() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (channel, _) = serve(tmp.path()).await;
+
+    let mut index = IndexServiceClient::new(channel.clone());
+    let resp = index.query(QueryRequest { q: "banana".into(), limit: Some(5), project: None }).await.unwrap().into_inner();
+    let mut paths: Vec<_> = resp.hits.iter().map(|h| h.path.as_str()).collect();
+    paths.sort();
+    assert_eq!(paths, vec!["./a.gd", "./b.txt"]);
+
+    let err = index.query(QueryRequest { q: "banana".into(), limit: Some(0), project: None }).await.unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+    assert_eq!(err.message(), "limit must be between 1 and 100 (got 0)");
+    let err = index.query(QueryRequest { q: "banana".into(), limit: None, project: Some("nope".into()) }).await.unwrap_err();
+    assert_eq!(err.code(), Code::NotFound);
+
+    let mut context = ContextServiceClient::new(channel);
+    let req = BundleRequest { q: "banana".into(), ..Default::default() };
+    let bundle = context.bundle(req.clone()).await.unwrap().into_inner();
+    assert_eq!(bundle.items.len(), 2);
+
+    let mut stream = context.bundle_stream(req).await.unwrap().into_inner();
+    let mut events = Vec::new();
+    while let Some(ev) = stream.message().await.unwrap() {
+        events.push(ev.event.unwrap());
+    }
+    assert_eq!(events.len(), 3);
+    match events.last().unwrap() {
+        bundle_event::Event::End(end) => {
+            assert_eq!(end.count, 2);
+            assert_eq!(end.size_bytes, bundle.size_bytes);
+        }
+        other => panic!("expected end event, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn scan_streams_job_progress() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (channel, root) = serve(tmp.path()).await;
+    std::fs::write(root.join("c.gd"), "func gamma(): pass # cherry").unwrap();
+
+    let mut index = IndexServiceClient::new(channel);
+    let mut stream = index.scan(ScanRequest::default()).await.unwrap().into_inner();
+    let mut last = None;
+    while let Some(job) = stream.message().await.unwrap() {
+        last = Some(job);
+    }
+    let job = last.unwrap();
+    assert_eq!(job.state(), JobState::Completed);
+    assert_eq!(job.total, Some(3));
+    assert!(job.finished_at.is_some());
+
+    let got = index.get_job(mcp_server::grpc::proto::GetJobRequest { id: job.id }).await.unwrap().into_inner();
+    assert_eq!(got.processed, 3);
+    let resp = index.query(QueryRequest { q: "cherry".into(), limit: None, project: None }).await.unwrap().into_inner();
+    assert_eq!(resp.hits.len(), 1);
+}
+
+#[tokio::test]
+async fn lint_maps_findings() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (channel, _) = serve(tmp.path()).await;
+    let mut godot = GodotServiceClient::new(channel);
+    let resp = godot.lint(LintRequest::default()).await.unwrap().into_inner();
+    assert!(!resp.findings.is_empty());
+    assert!(resp.findings.iter().all(|f| f.code.is_some() && f.file.as_deref().is_some_and(|p| p.ends_with("a.gd"))));
+}
@@ -0,0 +1,34 @@
+use axum::body::{Body, to_bytes};
+use mcp_server::{ProjectRegistry, ProjectState, ServerState};
+use tower::ServiceExt; // for oneshot
+use hyper::{Request, StatusCode};
+
+#[tokio::test]
+async fn health_reports_projects_and_config() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("a.gd"), "func alpha(): pass").unwrap();
+    let project = ProjectState::open("default", root.clone(), tmp.path().join("data")).unwrap();
+    project.index.scan(root.clone()).await.unwrap();
+    let mut state = ServerState::new(ProjectRegistry::new(project));
+    state.set_server_config(common::ServerConfig {
+        port: 9000, auto_start_watchers: false, editor_port: Some(8091), allow_fs_write: false, ..Default::default()
+    });
+    let app = mcp_server::build_router_with_state(state);
+
+    let resp = app.oneshot(Request::get("/health").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let v: serde_json::Value = serde_json::from_slice(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap();
+    assert_eq!(v["status"], "ok");
+    assert_eq!(v["version"], env!("CARGO_PKG_VERSION"));
+    assert!(v["uptime_secs"].is_u64());
+    assert_eq!(v["workspace_root"], root.display().to_string());
+    assert_eq!(v["projects"][0]["id"], "default");
+    assert_eq!(v["projects"][0]["watching"], false);
+    assert_eq!(v["projects"][0]["docs"], 1);
+    assert_eq!(v["config"]["port"], 9000);
+    assert_eq!(v["config"]["editor_port"], 8091);
+    assert_eq!(v["config"]["godot_binary"], serde_json::Value::Null);
+    assert_eq!(v["config"]["webhooks"], 0);
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use axum::{Router, body::Body};
+use mcp_server::{ProjectRegistry, ServerState};
+use hyper::{Request, StatusCode};
+
+use common::{post_json, send};
+
+async fn read_only_app(tmp: &std::path::Path) -> Router {
+    let (project, _) = common::project(tmp, &[("a.gd", "func alpha(): pass # banana")]).await;
+    let mut state = ServerState::new(ProjectRegistry::new(project));
+    state.set_server_config(::common::ServerConfig {
+        port: 0,
+        auto_start_watchers: false,
+        allow_scan: false,
+        allow_watch_control: false,
+        allow_fs_write: false,
+        allow_config_write: false,
+        ..Default::default()
+    });
+    mcp_server::build_router_with_state(state)
+}
+
+#[tokio::test]
+async fn disabled_routes_return_403() {
+    let tmp = tempfile::tempdir().unwrap();
+    let app = read_only_app(tmp.path()).await;
+
+    for (req, key) in [
+        (post_json("/index/scan", serde_json::json!({})), "server.allow_scan"),
+        (post_json("/index/reset", serde_json::json!({})), "server.allow_scan"),
+        (Request::post("/index/watch/start").body(Body::empty()).unwrap(), "server.allow_watch_control"),
+        (Request::post("/index/watch/stop").body(Body::empty()).unwrap(), "server.allow_watch_control"),
+        (post_json("/godot/structure-fix/apply", serde_json::json!({"token": "x"})), "server.allow_fs_write"),
+        (post_json("/tools/autodoc", serde_json::json!({"apply": true})), "server.allow_fs_write"),
+        (post_json("/webhooks", serde_json::json!({"url": "http://127.0.0.1:9/hook"})), "server.allow_config_write"),
+        (Request::delete("/webhooks/1").body(Body::empty()).unwrap(), "server.allow_config_write"),
+        (Request::post("/config/reload").body(Body::empty()).unwrap(), "server.allow_config_write"),
+    ] {
+        let uri = req.uri().to_string();
+        let (status, v) = send(&app, req).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "{}", uri);
+        assert_eq!(v["error"], format!("disabled by {}", key));
+    }
+}
+
+#[tokio::test]
+async fn read_only_mode_still_serves_queries() {
+    let tmp = tempfile::tempdir().unwrap();
+    let app = read_only_app(tmp.path()).await;
+
+    let (status, v) = send(&app, Request::get("/index/query?q=banana").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(v["hits"].as_array().unwrap().len(), 1);
+    let (status, _) = send(&app, Request::get("/context/bundle?q=banana").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+    // Autodoc dry runs don't write, so they stay available
+    let (status, _) = send(&app, post_json("/tools/autodoc", serde_json::json!({}))).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(&app, Request::get("/webhooks").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, health) = send(&app, Request::get("/health").body(Body::empty()).unwrap()).await;
+    assert_eq!(health["config"]["permissions"], serde_json::json!({"scan": false, "watch_control": false, "fs_write": false, "config_write": false}));
+}
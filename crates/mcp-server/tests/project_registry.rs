@@ -0,0 +1,58 @@
+use axum::{Router, body::{Body, to_bytes}};
+use mcp_server::{ProjectRegistry, ProjectState};
+use tower::ServiceExt; // for oneshot
+use hyper::{Request, StatusCode};
+
+async fn seeded_project(tmp: &std::path::Path, id: &str, file: &str, content: &str) -> ProjectState {
+    let root = tmp.join(id);
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join(file), content).unwrap();
+    let project = ProjectState::open(id, root.clone(), tmp.join(format!("{id}_data"))).unwrap();
+    project.index.scan(root.clone()).await.unwrap();
+    project
+}
+
+#[tokio::test]
+async fn queries_are_routed_per_project() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let mut registry = ProjectRegistry::new(seeded_project(tmp.path(), "default", "a.gd", "func alpha(): pass # banana").await);
+    registry.register(seeded_project(tmp.path(), "other", "b.gd", "func beta(): pass # banana").await).unwrap();
+    let app: Router = mcp_server::build_router_with_registry(registry);
+
+    // Default project when no id is given
+    let req = Request::get("/index/query?q=banana").body(Body::empty()).unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let v: serde_json::Value = serde_json::from_slice(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap();
+    let paths: Vec<&str> = v["hits"].as_array().unwrap().iter().map(|h| h["path"].as_str().unwrap()).collect();
+    assert_eq!(paths, vec!["./a.gd"]);
+
+    // Explicit project id
+    let body = serde_json::to_vec(&serde_json::json!({"q":"banana", "project":"other"})).unwrap();
+    let req = Request::post("/index/query").header("content-type", "application/json").body(Body::from(body)).unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let v: serde_json::Value = serde_json::from_slice(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap();
+    let paths: Vec<&str> = v["hits"].as_array().unwrap().iter().map(|h| h["path"].as_str().unwrap()).collect();
+    assert_eq!(paths, vec!["./b.gd"]);
+
+    // Project listing
+    let req = Request::get("/projects").body(Body::empty()).unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let v: serde_json::Value = serde_json::from_slice(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap();
+    let ids: Vec<&str> = v.as_array().unwrap().iter().map(|p| p["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, vec!["default", "other"]);
+}
+
+#[tokio::test]
+async fn unknown_project_is_404() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let registry = ProjectRegistry::new(seeded_project(tmp.path(), "default", "a.gd", "func alpha(): pass").await);
+    let app: Router = mcp_server::build_router_with_registry(registry);
+
+    let req = Request::get("/index/health?project=nope").body(Body::empty()).unwrap();
+    let resp = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let v: serde_json::Value = serde_json::from_slice(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap();
+    assert_eq!(v["error"], "unknown project: nope");
+}
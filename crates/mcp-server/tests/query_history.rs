@@ -0,0 +1,48 @@
+use axum::{Router, body::{Body, to_bytes}};
+use mcp_server::{ProjectRegistry, ProjectState};
+use tower::ServiceExt; // for oneshot
+use hyper::{Request, StatusCode};
+
+async fn get_json(app: &Router, uri: &str) -> (StatusCode, serde_json::Value) {
+    let resp = app.clone().oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+    let status = resp.status();
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn queries_are_recorded_newest_first() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("a.gd"), "func alpha(): pass # banana").unwrap();
+    let project = ProjectState::open("default", root.clone(), tmp.path().join("data")).unwrap();
+    project.index.scan(root.clone()).await.unwrap();
+    let app = mcp_server::build_router_with_registry(ProjectRegistry::new(project));
+
+    get_json(&app, "/index/query?q=banana").await;
+    get_json(&app, "/index/query?q=missing").await;
+    get_json(&app, "/index/query?q=banana").await;
+    get_json(&app, "/context/bundle?q=banana").await;
+
+    let (status, v) = get_json(&app, "/index/query/history").await;
+    assert_eq!(status, StatusCode::OK);
+    let entries = v["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 4);
+    assert_eq!(entries[0]["route"], "bundle");
+    assert_eq!(entries[1]["q"], "banana");
+    assert_eq!(entries[2]["q"], "missing");
+    assert_eq!(entries[2]["hits"], 0);
+    assert_eq!(entries[3]["hits"], 1);
+    assert!(entries[3]["latency_ms"].as_f64().unwrap() >= 0.0);
+
+    let summary = &v["summary"];
+    assert_eq!(summary["queries"], 4);
+    assert_eq!(summary["top"][0], serde_json::json!({"q": "banana", "count": 3}));
+    assert_eq!(summary["zero_hit"], serde_json::json!([{"q": "missing", "count": 1}]));
+
+    let (_, v) = get_json(&app, "/index/query/history?limit=1").await;
+    assert_eq!(v["entries"].as_array().unwrap().len(), 1);
+    let (status, _) = get_json(&app, "/index/query/history?project=nope").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
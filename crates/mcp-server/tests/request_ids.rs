@@ -0,0 +1,26 @@
+use axum::body::Body;
+use mcp_server::{ProjectRegistry, ProjectState};
+use tower::ServiceExt; // for oneshot
+use hyper::{Request, StatusCode};
+
+#[tokio::test]
+async fn responses_carry_a_request_id() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    let project = ProjectState::open("default", root, tmp.path().join("data")).unwrap();
+    let app = mcp_server::build_router_with_registry(ProjectRegistry::new(project));
+
+    let resp = app.clone().oneshot(Request::get("/index/health").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let first = resp.headers()["x-request-id"].to_str().unwrap().to_string();
+    assert!(!first.is_empty());
+    let resp = app.clone().oneshot(Request::get("/index/health").body(Body::empty()).unwrap()).await.unwrap();
+    assert_ne!(resp.headers()["x-request-id"], first.as_str());
+
+    // Client-supplied ids are kept so agents can correlate their own steps; errors carry one too
+    let req = Request::get("/index/health?project=nope").header("x-request-id", "agent-step-7").body(Body::empty()).unwrap();
+    let resp = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert_eq!(resp.headers()["x-request-id"], "agent-step-7");
+}
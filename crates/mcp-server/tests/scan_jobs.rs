@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use axum::{Router, body::{Body, to_bytes}};
+use mcp_server::{ProjectRegistry, ProjectState};
+use tower::ServiceExt; // for oneshot
+use hyper::{Request, StatusCode};
+
+async fn json_of(app: &Router, req: Request<Body>) -> (StatusCode, serde_json::Value) {
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn scan_runs_as_background_job() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    for i in 0..5 {
+        std::fs::write(root.join(format!("f{i}.gd")), format!("func f{i}(): pass # kiwi")).unwrap();
+    }
+    let project = ProjectState::open("default", root.clone(), tmp.path().join("data")).unwrap();
+    let app: Router = mcp_server::build_router_with_registry(ProjectRegistry::new(project));
+
+    let req = Request::post("/index/scan").header("content-type", "application/json").body(Body::from("{}")).unwrap();
+    let (status, v) = json_of(&app, req).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let status_url = v["status_url"].as_str().unwrap().to_string();
+    assert_eq!(status_url, format!("/jobs/{}", v["job_id"]));
+
+    let mut job = serde_json::Value::Null;
+    for _ in 0..100 {
+        let (status, v) = json_of(&app, Request::get(&status_url).body(Body::empty()).unwrap()).await;
+        assert_eq!(status, StatusCode::OK);
+        job = v;
+        if job["state"] != "running" { break; }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(job["state"], "completed");
+    assert_eq!(job["kind"], "scan");
+    assert_eq!(job["total"], 5);
+    assert_eq!(job["processed"], 5);
+    assert_eq!(job["indexed"], 5);
+    assert!(job["finished_at"].as_u64().is_some());
+
+    let (_, v) = json_of(&app, Request::get("/index/query?q=kiwi").body(Body::empty()).unwrap()).await;
+    assert_eq!(v["hits"].as_array().unwrap().len(), 5);
+
+    let (status, _) = json_of(&app, Request::get("/jobs/999").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn reset_wipes_index_and_optionally_rescans() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("a.gd"), "func a(): pass # kiwi").unwrap();
+    let project = ProjectState::open("default", root.clone(), tmp.path().join("data")).unwrap();
+    project.index.scan(root.clone()).await.unwrap();
+    let app: Router = mcp_server::build_router_with_registry(ProjectRegistry::new(project));
+
+    let post_json = |v: serde_json::Value| {
+        Request::post("/index/reset").header("content-type", "application/json").body(Body::from(serde_json::to_vec(&v).unwrap())).unwrap()
+    };
+
+    let (status, v) = json_of(&app, post_json(serde_json::json!({}))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(v["status"], "reset");
+    assert!(v.get("scan_job").is_none());
+    let (_, v) = json_of(&app, Request::get("/index/health").body(Body::empty()).unwrap()).await;
+    assert_eq!(v["docs"], 0);
+
+    let (_, v) = json_of(&app, post_json(serde_json::json!({"scan": true}))).await;
+    let status_url = format!("/jobs/{}", v["scan_job"]);
+    for _ in 0..100 {
+        let (_, job) = json_of(&app, Request::get(&status_url).body(Body::empty()).unwrap()).await;
+        if job["state"] != "running" { assert_eq!(job["state"], "completed"); break; }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    let (_, v) = json_of(&app, Request::get("/index/query?q=kiwi").body(Body::empty()).unwrap()).await;
+    assert_eq!(v["hits"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn scans_and_queries_proceed_while_watching() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("a.gd"), "func a(): pass # kiwi").unwrap();
+    let project = ProjectState::open("default", root.clone(), tmp.path().join("data")).unwrap();
+    let app: Router = mcp_server::build_router_with_registry(ProjectRegistry::new(project));
+
+    let (_, v) = json_of(&app, Request::post("/index/watch/start").body(Body::empty()).unwrap()).await;
+    assert_eq!(v["status"], "started");
+
+    let req = Request::post("/index/scan").header("content-type", "application/json").body(Body::from("{}")).unwrap();
+    let (_, v) = json_of(&app, req).await;
+    let status_url = v["status_url"].as_str().unwrap().to_string();
+    let mut job = serde_json::Value::Null;
+    for _ in 0..100 {
+        job = json_of(&app, Request::get(&status_url).body(Body::empty()).unwrap()).await.1;
+        if job["state"] != "running" { break; }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(job["state"], "completed");
+    let (_, v) = json_of(&app, Request::get("/index/health").body(Body::empty()).unwrap()).await;
+    assert_eq!(v["docs"], 1);
+
+    // The watcher still commits alongside the scan's writes
+    std::fs::write(root.join("b.gd"), "func b(): pass # kiwi").unwrap();
+    let mut hits = 0;
+    for _ in 0..100 {
+        let (_, v) = json_of(&app, Request::get("/index/query?q=kiwi").body(Body::empty()).unwrap()).await;
+        hits = v["hits"].as_array().unwrap().len();
+        if hits == 2 { break; }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(hits, 2);
+
+    let (_, v) = json_of(&app, Request::post("/index/watch/stop").body(Body::empty()).unwrap()).await;
+    assert_eq!(v["status"], "stopped");
+}
+
+#[test]
+fn finished_jobs_are_evicted_past_the_cap() {
+    use mcp_server::jobs::{JobTable, MAX_FINISHED_JOBS};
+    let jobs = JobTable::default();
+    let running = jobs.create("scan", "default");
+    let ids: Vec<u64> = (0..MAX_FINISHED_JOBS + 5).map(|_| jobs.create("scan", "default")).collect();
+    for &id in &ids {
+        jobs.finish(id, None);
+    }
+    assert!(jobs.get(running).is_some());
+    assert!(ids[..5].iter().all(|&id| jobs.get(id).is_none()));
+    assert!(ids[5..].iter().all(|&id| jobs.get(id).is_some()));
+}
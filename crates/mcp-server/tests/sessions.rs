@@ -0,0 +1,100 @@
+mod common;
+
+use axum::{Router, body::{Body, to_bytes}};
+use tower::ServiceExt; // for oneshot
+use hyper::{Request, StatusCode};
+
+use common::{post_json, send};
+
+async fn app(tmp: &std::path::Path) -> (Router, std::path::PathBuf) {
+    common::app(tmp, &[("a.gd", "func alpha(): pass # banana"), ("b.txt", "banana notes")]).await
+}
+
+fn paths(v: &serde_json::Value) -> Vec<String> {
+    let mut p: Vec<String> = v["items"].as_array().unwrap().iter().map(|i| i["path"].as_str().unwrap().to_string()).collect();
+    p.sort();
+    p
+}
+
+#[tokio::test]
+async fn session_bundles_skip_served_items() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (app, _) = app(tmp.path()).await;
+
+    let (status, v) = send(&app, post_json("/sessions", serde_json::json!({}))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let id = v["id"].as_u64().unwrap();
+
+    let (status, first) = send(&app, post_json("/context/bundle", serde_json::json!({"q":"banana", "session": id}))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(paths(&first), vec!["./a.gd", "./b.txt"]);
+    assert!(first["items"].as_array().unwrap().iter().all(|i| i["status"] == "new"));
+
+    let (_, second) = send(&app, post_json("/context/bundle", serde_json::json!({"q":"banana", "session": id}))).await;
+    assert!(paths(&second).is_empty());
+    assert_eq!(second["skipped"].as_array().unwrap().len(), 2);
+
+    // Without a session nothing is filtered
+    let (_, plain) = send(&app, post_json("/context/bundle", serde_json::json!({"q":"banana"}))).await;
+    assert_eq!(paths(&plain).len(), 2);
+    assert!(plain.get("skipped").is_none());
+
+    let (status, info) = send(&app, Request::get(format!("/sessions/{}", id)).body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(info["served"], serde_json::json!(["./a.gd", "./b.txt"]));
+
+    let (status, _) = send(&app, Request::delete(format!("/sessions/{}", id)).body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(&app, post_json("/context/bundle", serde_json::json!({"q":"banana", "session": id}))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn diff_mode_resends_changed_content() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (app, root) = app(tmp.path()).await;
+    let (_, v) = send(&app, post_json("/sessions", serde_json::json!({}))).await;
+    let id = v["id"].as_u64().unwrap();
+
+    let bundle = serde_json::json!({"q":"banana", "session": id, "session_mode": "diff"});
+    send(&app, post_json("/context/bundle", bundle.clone())).await;
+
+    std::fs::write(root.join("b.txt"), "banana notes, revised").unwrap();
+    send(&app, post_json("/index/scan", serde_json::json!({}))).await;
+    // Scans run as background jobs; poll until the new content is indexed
+    let mut changed = serde_json::Value::Null;
+    for _ in 0..50 {
+        let (_, v) = send(&app, post_json("/context/bundle", bundle.clone())).await;
+        if !v["items"].as_array().unwrap().is_empty() { changed = v; break; }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    assert_eq!(paths(&changed), vec!["./b.txt"]);
+    assert_eq!(changed["items"][0]["status"], "changed");
+    assert_eq!(changed["skipped"], serde_json::json!(["./a.gd"]));
+}
+
+#[tokio::test]
+async fn stream_records_session_items() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (app, _) = app(tmp.path()).await;
+    let (_, v) = send(&app, post_json("/sessions", serde_json::json!({}))).await;
+    let id = v["id"].as_u64().unwrap();
+
+    let req = Request::get(format!("/context/bundle/stream?q=banana&session={}", id)).body(Body::empty()).unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let body = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    let lines: Vec<serde_json::Value> = body.split(|b| *b == b'\n').filter(|l| !l.is_empty()).map(|l| serde_json::from_slice(l).unwrap()).collect();
+    assert_eq!(lines.last().unwrap()["count"], 2);
+
+    let (_, again) = send(&app, Request::get(format!("/context/bundle?q=banana&session={}", id)).body(Body::empty()).unwrap()).await;
+    assert!(paths(&again).is_empty());
+}
+
+#[tokio::test]
+async fn unknown_session_is_404() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (app, _) = app(tmp.path()).await;
+    let (status, v) = send(&app, Request::get("/sessions/42").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(v["error"], "unknown session: 42");
+}
@@ -0,0 +1,78 @@
+use std::path::Path;
+
+use axum::{Router, body::{Body, to_bytes}};
+use mcp_server::{ProjectRegistry, ProjectState};
+use tower::ServiceExt; // for oneshot
+use hyper::{Request, StatusCode};
+
+fn app_for(root: &Path, data: &Path) -> Router {
+    let project = ProjectState::open("default", root.to_path_buf(), data.to_path_buf()).unwrap();
+    mcp_server::build_router_with_registry(ProjectRegistry::new(project))
+}
+
+async fn body_of(app: &Router, req: Request<Body>) -> (StatusCode, Option<String>, Vec<u8>) {
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let ctype = resp.headers().get("content-type").map(|v| v.to_str().unwrap().to_string());
+    (status, ctype, to_bytes(resp.into_body(), 4 * 1024 * 1024).await.unwrap().to_vec())
+}
+
+#[tokio::test]
+async fn metatagger_is_read_only_and_supports_formats() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("foo.tmp"), b"x").unwrap();
+    let app = app_for(&root, &tmp.path().join("data"));
+
+    let (status, _, bytes) = body_of(&app, Request::get("/tools/metatagger").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+    let v: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert!(v["findings"].as_array().unwrap().iter().any(|f| f["kind"] == "temp" && f["path"] == "foo.tmp"));
+    assert!(!root.join("docs/PROJECT_INDEX.md").exists());
+
+    // temp findings are info-level
+    let (_, _, bytes) = body_of(&app, Request::get("/tools/metatagger?min_severity=warn").body(Body::empty()).unwrap()).await;
+    let v: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert!(v["findings"].as_array().unwrap().iter().all(|f| f["kind"] != "temp"));
+
+    let (_, _, bytes) = body_of(&app, Request::get("/tools/metatagger?format=sarif").body(Body::empty()).unwrap()).await;
+    let v: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(v["version"], "2.1.0");
+
+    let (status, ctype, bytes) = body_of(&app, Request::get("/tools/metatagger?format=junit").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(ctype.as_deref(), Some("application/xml"));
+    assert!(String::from_utf8(bytes).unwrap().contains("<testsuite name=\"metatagger\""));
+
+    let (status, _, _) = body_of(&app, Request::get("/tools/metatagger?format=yaml").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn autodoc_dry_runs_by_default_and_applies_on_request() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    let app = app_for(&root, &tmp.path().join("data"));
+
+    let post_json = |v: serde_json::Value| {
+        Request::post("/tools/autodoc").header("content-type", "application/json").body(Body::from(serde_json::to_vec(&v).unwrap())).unwrap()
+    };
+
+    let (status, _, bytes) = body_of(&app, post_json(serde_json::json!({}))).await;
+    assert_eq!(status, StatusCode::OK);
+    let v: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert!(v["created"].as_array().unwrap().iter().any(|p| p == "docs/DEV_LOG.md"));
+    assert!(!root.join("docs/DEV_LOG.md").exists());
+
+    let (status, _, _) = body_of(&app, post_json(serde_json::json!({"apply": true}))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(root.join("docs/DEV_LOG.md").exists());
+
+    // Second run only verifies
+    let (_, _, bytes) = body_of(&app, post_json(serde_json::json!({}))).await;
+    let v: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert!(v["created"].as_array().unwrap().is_empty());
+    assert!(v["updated"].as_array().unwrap().is_empty());
+}
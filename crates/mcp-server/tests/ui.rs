@@ -0,0 +1,24 @@
+use axum::body::{Body, to_bytes};
+use mcp_server::{ProjectRegistry, ProjectState};
+use tower::ServiceExt; // for oneshot
+use hyper::{Request, StatusCode};
+
+#[tokio::test]
+async fn dashboard_is_served() {
+    let tmp = tempfile::tempdir().unwrap();
+    let project = ProjectState::open("default", tmp.path().to_path_buf(), tmp.path().join("data")).unwrap();
+    let app = mcp_server::build_router_with_registry(ProjectRegistry::new(project));
+
+    let resp = app.clone().oneshot(Request::get("/ui").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(resp.headers()["content-type"].to_str().unwrap().starts_with("text/html"));
+    let html = String::from_utf8(to_bytes(resp.into_body(), 1024 * 1024).await.unwrap().to_vec()).unwrap();
+    // The page drives these routes; keep them in sync if they move
+    for route in ["/health", "/index/query/advanced", "/godot/analyze", "/events"] {
+        assert!(html.contains(route), "dashboard no longer calls {}", route);
+    }
+
+    let resp = app.oneshot(Request::get("/ui/").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(resp.headers()["location"], "/ui");
+}
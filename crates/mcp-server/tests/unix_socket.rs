@@ -0,0 +1,38 @@
+#![cfg(unix)]
+use std::time::Duration;
+
+use axum::body::{Body, to_bytes};
+use hyper::{Request, StatusCode};
+use hyper_util::rt::TokioIo;
+use mcp_server::{ProjectRegistry, ProjectState};
+
+#[tokio::test]
+async fn serves_routes_over_unix_socket() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    let project = ProjectState::open("default", root, tmp.path().join("data")).unwrap();
+    let app = mcp_server::build_router_with_registry(ProjectRegistry::new(project));
+
+    let socket = tmp.path().join("run/mcp.sock");
+    // A stale file at the socket path is replaced
+    std::fs::create_dir_all(socket.parent().unwrap()).unwrap();
+    std::fs::write(&socket, b"stale").unwrap();
+    let path = socket.clone();
+    tokio::spawn(async move { mcp_server::unix_socket::serve_unix(&path, app).await });
+
+    let mut stream = None;
+    for _ in 0..50 {
+        if let Ok(s) = tokio::net::UnixStream::connect(&socket).await { stream = Some(s); break; }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream.expect("connect"))).await.unwrap();
+    tokio::spawn(conn);
+
+    let req = Request::get("/projects").header("host", "localhost").body(Body::empty()).unwrap();
+    let resp = sender.send_request(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let bytes = to_bytes(Body::new(resp.into_body()), 1024 * 1024).await.unwrap();
+    let v: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(v[0]["id"], "default");
+}
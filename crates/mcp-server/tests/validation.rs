@@ -0,0 +1,45 @@
+mod common;
+
+use axum::body::Body;
+use hyper::{Request, StatusCode};
+
+use common::{app, post_json, send};
+
+#[tokio::test]
+async fn rejects_out_of_range_limits_and_bad_scan_paths() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let (app, root) = app(tmp.path(), &[]).await;
+    std::fs::create_dir_all(root.join("sub")).unwrap();
+
+    let (status, v) = send(&app, Request::get("/index/query?q=x&limit=0").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(v["error"].as_str().unwrap().contains("limit must be between 1 and 100"));
+    let (status, _) = send(&app, post_json("/index/query/advanced", serde_json::json!({"q": "x", "limit": 101}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, v) = send(&app, post_json("/context/bundle", serde_json::json!({"q": "x", "cap_bytes": 0}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(v["error"].as_str().unwrap().contains("cap_bytes"));
+    let long_q = "a".repeat(mcp_server::MAX_QUERY_LEN + 1);
+    let (status, _) = send(&app, post_json("/index/query", serde_json::json!({"q": long_q}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(&app, Request::get("/index/query?q=x&limit=100").body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let outside = tmp.path().to_string_lossy().to_string();
+    let (status, _) = send(&app, post_json("/index/scan", serde_json::json!({"path": outside}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(&app, post_json("/index/scan", serde_json::json!({"path": "../"}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(&app, post_json("/index/scan", serde_json::json!({"path": "sub"}))).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+}
+
+#[tokio::test]
+async fn rejects_oversized_bodies() {
+    let tmp = tempfile::tempdir().expect("tempdir");
+    let (app, _) = app(tmp.path(), &[]).await;
+
+    let big = "a".repeat(mcp_server::MAX_BODY_BYTES + 1);
+    let (status, _) = send(&app, post_json("/index/query", serde_json::json!({"q": big}))).await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+}
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+mod common;
+
+use axum::{Router, body::Body, routing::post, Json};
+use tokio::sync::mpsc;
+use hyper::{Request, StatusCode};
+
+use common::{app, post_json, send};
+
+#[tokio::test]
+async fn watcher_commits_are_posted_to_webhooks() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (app, root) = app(tmp.path(), &[]).await;
+
+    // Receiver forwarding every delivery to the test
+    let (tx, mut rx) = mpsc::unbounded_channel::<serde_json::Value>();
+    let receiver = Router::new().route("/hook", post(move |Json(v): Json<serde_json::Value>| async move {
+        let _ = tx.send(v);
+    }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, receiver).await.unwrap() });
+
+    let (status, hook) = send(&app, post_json("/webhooks", serde_json::json!({"url": format!("http://{}/hook", addr)}))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (_, list) = send(&app, Request::get("/webhooks").body(Body::empty()).unwrap()).await;
+    assert_eq!(list.as_array().unwrap().len(), 1);
+
+    send(&app, Request::post("/index/watch/start").body(Body::empty()).unwrap()).await;
+    // Give the watcher a moment to register before touching the tree
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    std::fs::write(root.join("player.gd"), "extends Node").unwrap();
+
+    let event = tokio::time::timeout(Duration::from_secs(10), rx.recv()).await.expect("webhook not delivered").unwrap();
+    send(&app, Request::post("/index/watch/stop").body(Body::empty()).unwrap()).await;
+    assert_eq!(event["event"], "index.commit");
+    assert_eq!(event["project"], "default");
+    assert!(event["changes"].as_array().unwrap().contains(&serde_json::json!({"path": "./player.gd", "kind": "gdscript", "change": "indexed"})));
+
+    let (status, _) = send(&app, Request::delete(format!("/webhooks/{}", hook["id"])).body(Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn only_http_urls_are_accepted() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (app, _) = app(tmp.path(), &[]).await;
+    let (status, v) = send(&app, post_json("/webhooks", serde_json::json!({"url": "ftp://example.com/x"}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(v["error"].as_str().unwrap().contains("http://"));
+    let (status, _) = send(&app, post_json("/webhooks", serde_json::json!({"url": "http://localhost/x", "project": "nope"}))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
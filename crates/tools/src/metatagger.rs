@@ -20,11 +20,9 @@ pub struct Finding {
     pub severity: Severity,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Default)]
 #[serde(rename_all = "lowercase")]
-pub enum Severity { Info, Warn, Error }
-
-impl Default for Severity { fn default() -> Self { Severity::Warn } }
+pub enum Severity { Info, #[default] Warn, Error }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct Report {
@@ -65,6 +63,13 @@ fn load_baseline(root: &Path) -> Result<Vec<BaselineEntry>> {
 }
 
 pub fn run(root: &Path) -> Result<Report> {
+    let mut report = scan(root)?;
+    report.updated = update_project_index(root, &report.findings)?;
+    Ok(report)
+}
+
+/// Classify the tree (ignores + baseline applied) without touching PROJECT_INDEX.md.
+pub fn scan(root: &Path) -> Result<Report> {
     let cfg = load_ignores(root)?;
     let mut findings = classify(root, &cfg)?;
     // Apply baseline suppression if present
@@ -74,8 +79,7 @@ pub fn run(root: &Path) -> Result<Report> {
         for b in baseline { allow.insert((b.kind, b.path)); }
         findings.retain(|f| !allow.contains(&(f.kind.clone(), f.path.clone())));
     }
-    let updated = update_project_index(root, &findings)?;
-    Ok(Report { schema_version: "1.1".into(), findings, updated })
+    Ok(Report { schema_version: "1.1".into(), findings, updated: None })
 }
 
 pub fn classify(root: &Path, ignores: &IgnoreConfig) -> Result<Vec<Finding>> {
@@ -110,7 +114,7 @@ pub fn classify(root: &Path, ignores: &IgnoreConfig) -> Result<Vec<Finding>> {
         // Collect image sources and imports
         match ext {
             "png" | "jpg" | "jpeg" | "webp" | "svg" | "gif" => { image_sources.insert(rel.clone()); },
-            "import" => if name.ends_with(".png.import") || name.ends_with(".jpg.import") || name.ends_with(".jpeg.import") || name.ends_with(".webp.import") || name.ends_with(".svg.import") || name.ends_with(".gif.import") { image_imports.insert(rel.clone()); },
+            "import" if name.ends_with(".png.import") || name.ends_with(".jpg.import") || name.ends_with(".jpeg.import") || name.ends_with(".webp.import") || name.ends_with(".svg.import") || name.ends_with(".gif.import") => { image_imports.insert(rel.clone()); },
             _ => {}
         }
 
@@ -135,10 +139,8 @@ pub fn classify(root: &Path, ignores: &IgnoreConfig) -> Result<Vec<Finding>> {
         // Large files (> 5 MiB) outside known docs content
         if let Ok(meta) = entry.metadata() {
             let len = meta.len();
-            if len > 5 * 1024 * 1024 {
-                if !(path.components().any(|c| c.as_os_str() == "rust-book") || path.components().any(|c| c.as_os_str() == "docs")) {
-                    out.push(Finding { kind: "large".into(), path: rel.clone(), reason: "Large file (>5MiB)".into(), bytes: Some(len), severity: Severity::Warn });
-                }
+            if len > 5 * 1024 * 1024 && !(path.components().any(|c| c.as_os_str() == "rust-book") || path.components().any(|c| c.as_os_str() == "docs")) {
+                out.push(Finding { kind: "large".into(), path: rel.clone(), reason: "Large file (>5MiB)".into(), bytes: Some(len), severity: Severity::Warn });
             }
         }
 
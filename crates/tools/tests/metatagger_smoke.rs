@@ -13,7 +13,7 @@ fn classifies_and_updates_cleanup_section() {
 
     // Run
     let report = tools::metatagger::run(root).unwrap();
-    assert!(report.findings.len() >= 1);
+    assert!(!report.findings.is_empty());
 
     // PROJECT_INDEX must exist and contain cleanup region
     let proj = root.join("docs/PROJECT_INDEX.md");